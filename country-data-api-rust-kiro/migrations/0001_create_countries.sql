@@ -0,0 +1,32 @@
+-- Countries in insertion order (by id), stored as JSON next to their lookup keys
+CREATE TABLE countries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- `name_key` of the name: folded case, accents and whitespace
+    name_key TEXT NOT NULL,
+    -- Uppercased ISO codes
+    alpha2 TEXT,
+    alpha3 TEXT,
+    -- Lowercased region
+    region_key TEXT NOT NULL,
+    -- `name_key` of the capital
+    capital_key TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+
+CREATE INDEX countries_name_key ON countries (name_key);
+CREATE INDEX countries_alpha2 ON countries (alpha2);
+CREATE INDEX countries_alpha3 ON countries (alpha3);
+CREATE INDEX countries_region_key ON countries (region_key);
+CREATE INDEX countries_capital_key ON countries (capital_key);
+
+CREATE TABLE snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT
+);
+
+-- The countries a snapshot saved, in their order at the time
+CREATE TABLE snapshot_countries (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (snapshot_id, position)
+);
@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::api::routes::error_response;
+
+/// Header carrying the key for mutating requests
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Who may call the mutating (non-GET) endpoints
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum WriteAccess {
+    /// Anyone; the default for `create_routes`
+    #[default]
+    Open,
+    /// Requests with an `X-Api-Key` matching one of the keys
+    Keys(Vec<String>),
+    /// Nobody
+    Disabled,
+}
+
+/// Middleware enforcing `WriteAccess` on everything but GET and HEAD:
+/// `403 Forbidden` when writes are disabled, `401 Unauthorized` for a missing
+/// or unknown key
+pub async fn require_api_key(
+    State(access): State<Arc<WriteAccess>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if matches!(*request.method(), Method::GET | Method::HEAD) {
+        return next.run(request).await;
+    }
+
+    match access.as_ref() {
+        WriteAccess::Open => next.run(request).await,
+        WriteAccess::Disabled => error_response(
+            StatusCode::FORBIDDEN,
+            "Writes are disabled: no API keys are configured".to_string(),
+        ),
+        WriteAccess::Keys(keys) => {
+            if has_valid_key(request.headers(), keys) {
+                next.run(request).await
+            } else {
+                error_response(
+                    StatusCode::UNAUTHORIZED,
+                    "A valid X-Api-Key header is required".to_string(),
+                )
+            }
+        }
+    }
+}
+
+/// Compares against every key, each in constant time, so timing doesn't
+/// reveal which key or how much of it matched
+fn has_valid_key(headers: &HeaderMap, keys: &[String]) -> bool {
+    let Some(given) = headers.get(API_KEY_HEADER) else {
+        return false;
+    };
+    keys.iter().fold(false, |found, key| {
+        found | constant_time_eq(given.as_bytes(), key.as_bytes())
+    })
+}
+
+/// Byte equality whose running time depends only on the lengths
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
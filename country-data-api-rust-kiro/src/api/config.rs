@@ -0,0 +1,132 @@
+use axum::http::HeaderValue;
+
+use crate::api::WriteAccess;
+
+/// Comma-separated origins allowed to call the API from a browser; unset or
+/// `*` allows any origin
+pub const CORS_ORIGINS_ENV: &str = "CORS_ALLOWED_ORIGINS";
+
+/// Requests allowed per client IP and minute; unset disables rate limiting
+pub const RATE_LIMIT_ENV: &str = "RATE_LIMIT_PER_MINUTE";
+
+/// Set (to any value) to identify clients by `X-Forwarded-For`, when running
+/// behind a proxy that sets it
+pub const TRUST_FORWARDED_ENV: &str = "TRUST_FORWARDED_FOR";
+
+/// Comma-separated keys accepted in `X-Api-Key` for mutating requests; unset
+/// (or blank) disables writes
+pub const API_KEYS_ENV: &str = "API_KEYS";
+
+/// Set (to any value) to reject query parameters an endpoint doesn't know
+pub const STRICT_QUERY_ENV: &str = "STRICT_QUERY_PARAMS";
+
+/// Settings for the layers around the routes. The default allows any origin,
+/// doesn't rate limit and leaves writes open; `from_env` reads the
+/// deployment's configuration, where writes need an API key.
+#[derive(Debug, Clone, Default)]
+pub struct ApiConfig {
+    /// `None` allows any origin
+    cors_origins: Option<Vec<HeaderValue>>,
+    rate_limit: Option<RateLimitConfig>,
+    write_access: WriteAccess,
+    strict_query: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    pub requests_per_minute: u32,
+    pub trust_forwarded: bool,
+}
+
+impl ApiConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(origins) = std::env::var(CORS_ORIGINS_ENV)
+            && origins.trim() != "*"
+        {
+            config = config.with_cors_origins(origins.split(','));
+        }
+        let keys = std::env::var(API_KEYS_ENV).unwrap_or_default();
+        config = config.with_api_keys(keys.split(','));
+        if std::env::var_os(STRICT_QUERY_ENV).is_some() {
+            config = config.with_strict_query();
+        }
+        if let Ok(limit) = std::env::var(RATE_LIMIT_ENV) {
+            match limit.trim().parse::<u32>() {
+                Ok(limit) if limit > 0 => {
+                    let trust_forwarded = std::env::var_os(TRUST_FORWARDED_ENV).is_some();
+                    config = config.with_rate_limit(limit, trust_forwarded);
+                }
+                _ => tracing::warn!("Ignoring invalid {}={:?}", RATE_LIMIT_ENV, limit),
+            }
+        }
+        config
+    }
+
+    /// Only allows cross-origin requests from `origins`, e.g.
+    /// `"https://example.com"`. Blank and malformed entries are skipped.
+    pub fn with_cors_origins<'a>(mut self, origins: impl IntoIterator<Item = &'a str>) -> Self {
+        let origins = origins
+            .into_iter()
+            .map(str::trim)
+            .filter(|origin| !origin.is_empty())
+            .filter_map(|origin| match HeaderValue::from_str(origin) {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    tracing::warn!("Ignoring invalid CORS origin {:?}", origin);
+                    None
+                }
+            })
+            .collect();
+        self.cors_origins = Some(origins);
+        self
+    }
+
+    /// Limits each client IP to `requests_per_minute`; see `RateLimiter`
+    pub fn with_rate_limit(mut self, requests_per_minute: u32, trust_forwarded: bool) -> Self {
+        self.rate_limit = Some(RateLimitConfig {
+            requests_per_minute,
+            trust_forwarded,
+        });
+        self
+    }
+
+    /// Requires one of `keys` in `X-Api-Key` for non-GET requests. Blank keys
+    /// are skipped; with none left, writes are disabled.
+    pub fn with_api_keys<'a>(mut self, keys: impl IntoIterator<Item = &'a str>) -> Self {
+        let keys: Vec<String> = keys
+            .into_iter()
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(String::from)
+            .collect();
+        self.write_access = if keys.is_empty() {
+            WriteAccess::Disabled
+        } else {
+            WriteAccess::Keys(keys)
+        };
+        self
+    }
+
+    /// Answers 400 for unknown query parameters instead of ignoring them
+    pub fn with_strict_query(mut self) -> Self {
+        self.strict_query = true;
+        self
+    }
+
+    pub fn cors_origins(&self) -> Option<&[HeaderValue]> {
+        self.cors_origins.as_deref()
+    }
+
+    pub fn rate_limit(&self) -> Option<RateLimitConfig> {
+        self.rate_limit
+    }
+
+    pub fn write_access(&self) -> &WriteAccess {
+        &self.write_access
+    }
+
+    pub fn strict_query(&self) -> bool {
+        self.strict_query
+    }
+}
@@ -0,0 +1,88 @@
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema};
+use axum::{
+    extract::State,
+    response::{Html, Json},
+    routing::get,
+    Router,
+};
+
+use crate::api::routes::MAX_PAGE_LIMIT;
+use crate::models::{Country, CountryStats, SearchFields};
+use crate::services::{parse_region_list, search_countries, IntoSharedStore, SharedStore};
+
+/// Read-only schema; the store is available to resolvers as context data
+pub type CountrySchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Countries in insertion order. `region` is a comma-separated list matched
+    /// case-insensitively and `search` a name substring; both narrow before
+    /// `offset` and `limit` (at most 500) are applied.
+    async fn countries(
+        &self,
+        ctx: &Context<'_>,
+        region: Option<String>,
+        search: Option<String>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Vec<Country> {
+        let store = ctx.data_unchecked::<SharedStore>();
+        let mut countries = match region.as_deref() {
+            Some(region) if !parse_region_list(region).is_empty() => {
+                store.filter_by_region(region).await
+            }
+            _ => store.get_all().await,
+        };
+        if let Some(search) = &search {
+            countries = search_countries(countries, search, SearchFields::Name, None);
+        }
+
+        let limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+        countries
+            .into_iter()
+            .skip(offset.unwrap_or(0))
+            .take(limit)
+            .collect()
+    }
+
+    /// The country called `name`, ignoring case, accents and extra whitespace
+    async fn country(&self, ctx: &Context<'_>, name: String) -> Option<Country> {
+        ctx.data_unchecked::<SharedStore>()
+            .get_by_name(&name)
+            .await
+    }
+
+    /// Aggregate statistics, over the comma-separated `region`s when given
+    async fn stats(&self, ctx: &Context<'_>, region: Option<String>) -> CountryStats {
+        ctx.data_unchecked::<SharedStore>()
+            .stats(region.as_deref())
+            .await
+    }
+}
+
+pub fn build_schema(store: impl IntoSharedStore) -> CountrySchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(store.into_shared())
+        .finish()
+}
+
+/// `POST /graphql` runs queries against `store`; `GET /graphql` serves GraphiQL
+pub(crate) fn graphql_routes(store: SharedStore) -> Router {
+    Router::new()
+        .route("/graphql", get(graphiql).post(execute))
+        .with_state(build_schema(store))
+}
+
+async fn execute(
+    State(schema): State<CountrySchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request).await)
+}
+
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
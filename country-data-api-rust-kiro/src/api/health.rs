@@ -0,0 +1,80 @@
+use axum::{extract::State, http::StatusCode, response::Json, routing::get, Router};
+use serde::Serialize;
+use std::time::Instant;
+use utoipa::ToSchema;
+
+use crate::services::SharedStore;
+
+/// Liveness report
+#[derive(Serialize, ToSchema)]
+pub struct HealthResponse {
+    /// Always "ok"
+    pub status: &'static str,
+    /// Crate version of the running server
+    pub version: &'static str,
+    /// Whole seconds since the router was built
+    pub uptime_seconds: u64,
+}
+
+/// Readiness report
+#[derive(Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// "ready" once the initial data is loaded, "loading" before
+    pub status: &'static str,
+    /// Countries in the store
+    pub countries: usize,
+}
+
+#[derive(Clone)]
+struct HealthState {
+    store: SharedStore,
+    started: Instant,
+}
+
+/// `/health` and `/ready`; the uptime counts from this call
+pub(crate) fn health_routes(store: SharedStore) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/ready", get(ready))
+        .with_state(HealthState {
+            store,
+            started: Instant::now(),
+        })
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "The server is up", body = HealthResponse)),
+)]
+async fn health(State(state): State<HealthState>) -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "ok",
+        version: env!("CARGO_PKG_VERSION"),
+        uptime_seconds: state.started.elapsed().as_secs(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "The initial data is loaded", body = ReadinessResponse),
+        (status = 503, description = "Still loading", body = ReadinessResponse),
+    ),
+)]
+async fn ready(State(state): State<HealthState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let loaded = state.store.is_loaded();
+    let body = ReadinessResponse {
+        status: if loaded { "ready" } else { "loading" },
+        countries: state.store.count().await,
+    };
+    let status = if loaded {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(body))
+}
@@ -1,3 +1,16 @@
+pub mod auth;
+pub mod config;
+pub mod graphql;
+pub mod health;
+pub mod metrics;
+pub mod openapi;
+pub mod query;
+pub mod rate_limit;
 pub mod routes;
-pub use routes::create_routes;
-
+pub mod trace;
+pub use auth::WriteAccess;
+pub use config::ApiConfig;
+pub use graphql::{build_schema, CountrySchema};
+pub use openapi::ApiDoc;
+pub use rate_limit::RateLimiter;
+pub use routes::{create_routes, create_routes_with_config};
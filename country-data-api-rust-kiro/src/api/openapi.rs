@@ -0,0 +1,57 @@
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::api::auth::API_KEY_HEADER;
+use crate::api::{health, metrics, routes};
+
+/// OpenAPI description of the API, served at `/api/openapi.json`
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Country Data API"),
+    paths(
+        routes::get_countries,
+        routes::create_country,
+        routes::bulk_create_countries,
+        routes::get_country_by_code,
+        routes::batch_lookup,
+        routes::get_countries_by_capital,
+        routes::get_random_countries,
+        routes::get_stats,
+        routes::get_languages,
+        routes::get_regions,
+        routes::get_countries_by_region,
+        routes::get_country_by_name,
+        routes::replace_country,
+        routes::patch_country,
+        routes::update_population,
+        routes::get_population_history,
+        routes::delete_country,
+        routes::get_neighbors,
+        routes::create_snapshot,
+        routes::rollback_snapshot,
+        health::health,
+        health::ready,
+        metrics::render_metrics,
+    ),
+    modifiers(&ApiKeySecurity),
+    tags(
+        (name = "countries", description = "Country lookup, filtering and editing"),
+        (name = "regions", description = "Regions and subregions"),
+        (name = "admin", description = "Snapshots and rollback"),
+        (name = "health", description = "Probes and metrics"),
+    ),
+)]
+pub struct ApiDoc;
+
+/// Registers the `api_key` scheme referenced by the mutating endpoints
+struct ApiKeySecurity;
+
+impl Modify for ApiKeySecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::request::Parts,
+    response::Response,
+};
+use serde::de::DeserializeOwned;
+use utoipa::openapi::path::ParameterIn;
+use utoipa::IntoParams;
+
+use crate::api::routes::query_error_response;
+
+/// Marks a router whose query strings may only use the keys each endpoint
+/// declares; see `ApiConfig::with_strict_query`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StrictQuery;
+
+/// Query parameters read with `ValidatedQuery`. The accepted keys are the ones
+/// documented by `IntoParams`, so the OpenAPI spec and the checks can't drift.
+pub trait QueryParams: DeserializeOwned + IntoParams {
+    /// Keys whose empty value means something, like `search=` matching
+    /// everything; any other key given without a value is rejected
+    const MAY_BE_EMPTY: &'static [&'static str] = &[];
+
+    fn keys() -> Vec<String> {
+        Self::into_params(|| Some(ParameterIn::Query))
+            .into_iter()
+            .map(|param| param.name)
+            .collect()
+    }
+}
+
+/// Like `Query`, but every problem is a 400 in the usual error shape, keyed
+/// by the parameter:
+/// - a key given without a value, unless the endpoint allows it
+/// - a value that doesn't parse, e.g. `limit=abc`
+/// - with `StrictQuery`, a key the endpoint doesn't know; otherwise those are
+///   ignored
+pub struct ValidatedQuery<T>(pub T);
+
+#[async_trait]
+impl<T: QueryParams, S: Send + Sync> FromRequestParts<S> for ValidatedQuery<T> {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Response> {
+        let query = parts.uri.query().unwrap_or_default();
+        let pairs: Vec<(String, String)> =
+            form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+
+        let keys = T::keys();
+        let strict = parts.extensions.get::<StrictQuery>().is_some();
+        for (key, value) in &pairs {
+            if !keys.contains(key) {
+                if strict {
+                    let message = format!("unknown parameter; expected one of {}", keys.join(", "));
+                    return Err(query_error_response(key, message));
+                }
+                continue;
+            }
+            if value.trim().is_empty() && !T::MAY_BE_EMPTY.contains(&key.as_str()) {
+                return Err(query_error_response(key, "must not be empty".to_string()));
+            }
+        }
+
+        let deserializer =
+            serde_urlencoded::Deserializer::new(form_urlencoded::parse(query.as_bytes()));
+        serde_path_to_error::deserialize(deserializer)
+            .map(ValidatedQuery)
+            .map_err(|err| {
+                let key = err.path().to_string();
+                let message = match pairs.iter().find(|(k, _)| *k == key) {
+                    Some((_, value)) => format!("invalid value {:?}: {}", value, err.inner()),
+                    None => err.inner().to_string(),
+                };
+                query_error_response(if key == "." { "query" } else { &key }, message)
+            })
+    }
+}
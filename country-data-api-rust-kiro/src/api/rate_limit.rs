@@ -0,0 +1,142 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::api::routes::error_response;
+
+/// How often idle buckets are dropped from the limiter
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Key for requests whose client address is unknown (no connection info and
+/// no trusted `X-Forwarded-For`); they share one bucket
+const UNKNOWN_CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+
+/// Token-bucket limiter keyed by client IP. Each client starts with a full
+/// bucket of `requests_per_minute` tokens, spends one per request and regains
+/// them continuously at the same rate.
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    trust_forwarded: bool,
+    state: Mutex<LimiterState>,
+}
+
+struct LimiterState {
+    buckets: HashMap<IpAddr, Bucket>,
+    last_cleanup: Instant,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// `requests_per_minute` is raised to at least 1. With `trust_forwarded`,
+    /// the client is the first address in `X-Forwarded-For`, which is only
+    /// safe behind a proxy that sets it.
+    pub fn new(requests_per_minute: u32, trust_forwarded: bool) -> Self {
+        let capacity = f64::from(requests_per_minute.max(1));
+        Self {
+            capacity,
+            refill_per_sec: capacity / 60.0,
+            trust_forwarded,
+            state: Mutex::new(LimiterState {
+                buckets: HashMap::new(),
+                last_cleanup: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token for `client` at `now`, or returns how long until one is available.
+    /// Buckets that have refilled completely are dropped every `CLEANUP_INTERVAL`.
+    pub fn check(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if now.saturating_duration_since(state.last_cleanup) >= CLEANUP_INTERVAL {
+            state
+                .buckets
+                .retain(|_, bucket| self.refilled(bucket, now) < self.capacity);
+            state.last_cleanup = now;
+        }
+
+        let bucket = state.buckets.entry(client).or_insert(Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+        bucket.tokens = self.refilled(bucket, now);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.refill_per_sec))
+        }
+    }
+
+    /// Number of clients with a bucket, i.e. seen since they last refilled completely
+    pub fn tracked_clients(&self) -> usize {
+        let state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.buckets.len()
+    }
+
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity)
+    }
+
+    fn client_ip(&self, request: &Request) -> IpAddr {
+        if self.trust_forwarded
+            && let Some(ip) = forwarded_for(request.headers())
+        {
+            return ip;
+        }
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+            .unwrap_or(UNKNOWN_CLIENT)
+    }
+}
+
+/// The originating client in `X-Forwarded-For` (its first entry)
+fn forwarded_for(headers: &HeaderMap) -> Option<IpAddr> {
+    headers
+        .get("x-forwarded-for")?
+        .to_str()
+        .ok()?
+        .split(',')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Middleware answering `429 Too Many Requests`, with `Retry-After` in whole
+/// seconds, once a client has used up its bucket
+pub async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = limiter.client_ip(&request);
+    match limiter.check(client, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            let body = error_response(
+                StatusCode::TOO_MANY_REQUESTS,
+                format!("Too many requests, retry in {} seconds", seconds),
+            );
+            ([(header::RETRY_AFTER, seconds.to_string())], body).into_response()
+        }
+    }
+}
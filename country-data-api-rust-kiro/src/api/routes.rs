@@ -1,59 +1,1347 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Json},
-    routing::get,
+    body::{to_bytes, Body, Bytes},
+    extract::{
+        rejection::PathRejection,
+        DefaultBodyLimit, Extension, Path, Request, State,
+    },
+    handler::Handler,
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    response::{IntoResponse, Json, Response},
+    middleware::{self, Next},
+    routing::{get, patch, post},
     Router,
 };
+use futures_util::stream;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
 
-use crate::services::CountryDataStore;
+use crate::api::auth::{require_api_key, API_KEY_HEADER};
+use crate::api::graphql::graphql_routes;
+use crate::api::health::health_routes;
+use crate::api::metrics::{metrics_routes, track_metrics};
+use crate::api::openapi::ApiDoc;
+use crate::api::query::{QueryParams, StrictQuery, ValidatedQuery};
+use crate::api::rate_limit::{rate_limit, RateLimiter};
+use crate::api::trace::{trace_request, REQUEST_ID_HEADER};
+use crate::api::{ApiConfig, WriteAccess};
+use crate::models::{
+    BatchLookup, BatchLookupRequest, BulkInsertReport, LoadMode, Country, CountryField,
+    CountryStats, LanguageSummary, Neighbors, PopulationRevision, RegionGroup, RegionSummary,
+    ResponseFormat, SearchFields, SnapshotId, SnapshotInfo, SortField, SortOrder,
+    UpdateCountryRequest, ValidationError,
+};
+use crate::models::validation::{describe_errors, errors_by_field};
+use crate::services::{
+    countries_to_csv, countries_to_xml, country_to_xml, error_to_xml, parse_region_list,
+    project_country, search_countries, sort_countries, DensityRange, IntoSharedStore,
+    PopulationRange, SharedStore, StoreError,
+};
+use crate::utils::{name_key, normalize_for_match};
 
-#[derive(Serialize)]
-struct ErrorResponse {
+/// Characters that must be escaped inside a single URL path segment
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// Largest accepted bulk request body, in bytes
+const BULK_MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Largest number of countries accepted in one bulk request
+const BULK_MAX_COUNTRIES: usize = 5_000;
+
+/// Most names accepted in one batch lookup
+const BATCH_MAX_NAMES: usize = 100;
+
+/// Largest page size; bigger `limit` values are clamped to this
+pub(crate) const MAX_PAGE_LIMIT: usize = 500;
+
+/// Most search matches `search_limit` keeps; bigger values are clamped to this
+const MAX_SEARCH_LIMIT: usize = 50;
+
+/// Media type of `ResponseFormat::Ndjson` lists
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Media type of `ResponseFormat::Xml` responses; `text/xml` is accepted too
+const XML_CONTENT_TYPE: &str = "application/xml";
+
+/// Largest error body `xml_errors` converts
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// Request header that asks for an `Envelope`, like `envelope=true`
+const ENVELOPE_HEADER: &str = "x-response-envelope";
+
+/// Largest number of countries returned by one random request
+const MAX_RANDOM_COUNT: usize = 10;
+
+/// Body of every error response
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(crate) struct ErrorResponse {
     error: String,
+    /// Closest country name, on a by-name 404 that looks like a typo
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestion: Option<String>,
+    /// Validation messages keyed by country field, on a 400 for an invalid country
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<BTreeMap<String, Vec<String>>>,
+}
+
+pub(crate) fn error_response(status: StatusCode, error: String) -> Response {
+    let body = ErrorResponse {
+        error,
+        suggestion: None,
+        fields: None,
+    };
+    (status, Json(body)).into_response()
+}
+
+/// 400 listing every validation error, both as one message and by field
+fn validation_error_response(errors: &[ValidationError]) -> Response {
+    let body = ErrorResponse {
+        error: describe_errors(errors),
+        suggestion: None,
+        fields: Some(errors_by_field(errors)),
+    };
+    (StatusCode::BAD_REQUEST, Json(body)).into_response()
+}
+
+/// 400 for one bad query parameter, reported under its key in `fields`
+pub(crate) fn query_error_response(key: &str, message: String) -> Response {
+    let body = ErrorResponse {
+        error: format!("{}: {}", key, message),
+        suggestion: None,
+        fields: Some(BTreeMap::from([(key.to_string(), vec![message])])),
+    };
+    (StatusCode::BAD_REQUEST, Json(body)).into_response()
+}
+
+/// A single country, projected to `fields` when given
+fn country_response(country: Country, fields: Option<&[CountryField]>) -> Response {
+    match fields {
+        Some(fields) => Json(project_country(&country, fields)).into_response(),
+        None => Json(country).into_response(),
+    }
+}
+
+/// An `Envelope` when requested, otherwise a `Page` when a `limit` was
+/// given, otherwise a bare array
+fn list_response<T: Serialize>(
+    items: Vec<T>,
+    total: usize,
+    limit: Option<usize>,
+    offset: usize,
+    envelope: bool,
+) -> Response {
+    if envelope {
+        return Json(Envelope {
+            count: items.len(),
+            total,
+            data: items,
+        })
+        .into_response();
+    }
+
+    match limit {
+        Some(limit) => Json(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })
+        .into_response(),
+        None => Json(items).into_response(),
+    }
+}
+
+/// Countries as a CSV download
+fn csv_response(countries: &[Country]) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"countries.csv\""),
+        ],
+        countries_to_csv(countries),
+    )
+        .into_response()
 }
 
-#[derive(Deserialize)]
+/// One country per line, serialized as the body is sent so a large list is
+/// never held in memory as JSON. The iterator is dropped, ending the work,
+/// when the client disconnects.
+fn ndjson_response<I>(countries: I) -> Response
+where
+    I: Iterator<Item = serde_json::Result<Vec<u8>>> + Send + 'static,
+{
+    let lines = countries.map(|line| {
+        line.map(|mut line| {
+            line.push(b'\n');
+            Bytes::from(line)
+        })
+    });
+    (
+        [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        Body::from_stream(stream::iter(lines)),
+    )
+        .into_response()
+}
+
+/// One NDJSON line, without the newline, projected to `fields` when given
+fn ndjson_line(country: &Country, fields: Option<&[CountryField]>) -> serde_json::Result<Vec<u8>> {
+    match fields {
+        Some(fields) => serde_json::to_vec(&project_country(country, fields)),
+        None => serde_json::to_vec(country),
+    }
+}
+
+/// Countries, or one country, as XML
+fn xml_response(xml: String) -> Response {
+    ([(header::CONTENT_TYPE, XML_CONTENT_TYPE)], xml).into_response()
+}
+
+/// Strong ETag for one representation of the store at `version`
+fn etag(version: u64, representation: &str) -> String {
+    format!("\"{}-{}\"", version, representation)
+}
+
+/// 304 with no body when `If-None-Match` lists `etag` (or `*`), otherwise
+/// `response` with the tag attached. Weak tags compare by their opaque value.
+fn with_etag(headers: &HeaderMap, etag: String, response: Response) -> Response {
+    let not_modified = headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag);
+
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+    ([(header::ETAG, etag)], response).into_response()
+}
+
+/// The list format named by the first `Accept` media range that is CSV, NDJSON
+/// or XML (parameters and quality are ignored), otherwise JSON
+fn accepted_format(headers: &HeaderMap) -> ResponseFormat {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find_map(|range| {
+            let media_type = range.split(';').next().unwrap_or_default().trim();
+            if media_type.eq_ignore_ascii_case("text/csv") {
+                Some(ResponseFormat::Csv)
+            } else if media_type.eq_ignore_ascii_case(NDJSON_CONTENT_TYPE) {
+                Some(ResponseFormat::Ndjson)
+            } else if media_type.eq_ignore_ascii_case(XML_CONTENT_TYPE)
+                || media_type.eq_ignore_ascii_case("text/xml")
+            {
+                Some(ResponseFormat::Xml)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(ResponseFormat::Json)
+}
+
+/// Whether the request asks for an `Envelope` through `ENVELOPE_HEADER`
+fn envelope_requested(headers: &HeaderMap) -> bool {
+    headers
+        .get(ENVELOPE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("true"))
+}
+
+fn store_error_response(err: StoreError) -> Response {
+    let status = match &err {
+        StoreError::DuplicateName(_) | StoreError::DuplicateCode(_) => StatusCode::CONFLICT,
+        StoreError::NotFound(_) | StoreError::SnapshotNotFound(_) => StatusCode::NOT_FOUND,
+        StoreError::Invalid(errors) => return validation_error_response(errors),
+        StoreError::Storage(_) => {
+            tracing::error!("{}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    };
+    error_response(status, err.to_string())
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct CountryQuery {
+    /// Comma-separated regions, matched case-insensitively
     region: Option<String>,
+    /// Comma-separated subregions, matched case-insensitively
+    subregion: Option<String>,
+    /// Accent- and case-insensitive substring to look for
     search: Option<String>,
+    #[serde(default)]
+    #[param(inline)]
+    search_fields: SearchFields,
+    /// Keep only this many of the best `search` matches, clamped to 50; applied
+    /// before sorting and pagination. Requires `search`.
+    search_limit: Option<usize>,
+    min_population: Option<i64>,
+    max_population: Option<i64>,
+    /// Minimum people per km²
+    min_density: Option<f64>,
+    /// Maximum people per km²
+    max_density: Option<f64>,
+    /// ISO 4217 currency code, e.g. "EUR"
+    currency: Option<String>,
+    /// Capital city, matched ignoring case, accents and extra whitespace
+    capital: Option<String>,
+    #[param(inline)]
+    sort: Option<SortField>,
+    #[serde(default)]
+    #[param(inline)]
+    order: SortOrder,
+    /// Page size, clamped to 500; returns a `Page` object instead of an array
+    limit: Option<usize>,
+    /// Number of countries to skip
+    offset: Option<usize>,
+    /// Comma-separated keys to keep in each country; `name` is always kept
+    fields: Option<String>,
+    /// Wrap the list in an `Envelope`, like the `X-Response-Envelope: true` header
+    #[serde(default)]
+    envelope: bool,
+    /// Takes precedence over the `Accept` header
+    #[param(inline)]
+    format: Option<ResponseFormat>,
 }
 
-pub fn create_routes(store: CountryDataStore) -> Router {
-    Router::new()
-        .route("/api/countries", get(get_countries))
-        .route("/api/countries/:name", get(get_country_by_name))
-        .with_state(store)
+/// The `CountryQuery` filters that keep or drop each country on its own,
+/// i.e. all but `search`, which also reorders
+struct ListFilter {
+    regions: Option<Vec<String>>,
+    subregions: Option<Vec<String>>,
+    population: PopulationRange,
+    density: DensityRange,
+    currency: Option<String>,
+    /// As a `name_key`
+    capital: Option<String>,
 }
 
+impl ListFilter {
+    fn matches(&self, country: &Country) -> bool {
+        self.regions
+            .as_ref()
+            .is_none_or(|regions| regions.contains(&normalize_for_match(&country.region)))
+            && self.subregions.as_ref().is_none_or(|subregions| {
+                subregions.contains(&normalize_for_match(country.subregion.trim()))
+            })
+            && self.population.contains(country.population)
+            && self.density.contains(country.population_density())
+            && self
+                .currency
+                .as_ref()
+                .is_none_or(|currency| country.uses_currency(currency))
+            && self
+                .capital
+                .as_ref()
+                .is_none_or(|capital| name_key(&country.capital) == *capital)
+    }
+}
+
+/// One page of a list, returned when `limit` is given
+#[derive(Serialize)]
+struct Page<T> {
+    items: Vec<T>,
+    total: usize,
+    limit: usize,
+    offset: usize,
+}
+
+/// A list with its size and the size before pagination, returned when
+/// `envelope=true` or the `X-Response-Envelope: true` header is given
+#[derive(Serialize)]
+struct Envelope<T> {
+    count: usize,
+    total: usize,
+    data: Vec<T>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LookupQuery {
+    /// Return the closest match instead of a 404 when the name is a likely typo
+    #[serde(default)]
+    fuzzy: bool,
+    /// Comma-separated keys to keep; `name` is always kept
+    fields: Option<String>,
+    /// `json` or `xml`; takes precedence over the `Accept` header
+    #[param(inline)]
+    format: Option<ResponseFormat>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FieldsQuery {
+    /// Comma-separated keys to keep; `name` is always kept
+    fields: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RandomQuery {
+    /// Comma-separated regions, matched case-insensitively
+    region: Option<String>,
+    min_population: Option<i64>,
+    /// Return this many distinct countries as a list instead of a single country
+    count: Option<usize>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    /// Restrict the statistics to this region
+    region: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BulkQuery {
+    #[serde(default)]
+    #[param(inline)]
+    mode: LoadMode,
+}
+
+impl QueryParams for CountryQuery {
+    const MAY_BE_EMPTY: &'static [&'static str] = &["search"];
+}
+impl QueryParams for LookupQuery {}
+impl QueryParams for FieldsQuery {}
+impl QueryParams for RandomQuery {}
+impl QueryParams for StatsQuery {}
+impl QueryParams for BulkQuery {}
+
+/// The API with the default `ApiConfig`
+pub fn create_routes(store: impl IntoSharedStore) -> Router {
+    create_routes_with_config(store, &ApiConfig::default())
+}
+
+/// The API serving `store`, which may be any `CountryStore`
+pub fn create_routes_with_config(store: impl IntoSharedStore, config: &ApiConfig) -> Router {
+    let store = store.into_shared();
+    let mut router = Router::new()
+        .route(
+            "/api/countries",
+            get(get_countries.layer(middleware::from_fn(xml_errors))).post(create_country),
+        )
+        .route(
+            "/api/countries/bulk",
+            post(bulk_create_countries).layer(DefaultBodyLimit::max(BULK_MAX_BODY_BYTES)),
+        )
+        .route("/api/countries/code/:code", get(get_country_by_code))
+        .route("/api/countries/random", get(get_random_countries))
+        .route("/api/countries/stats", get(get_stats))
+        .route("/api/countries/by-region", get(get_countries_by_region))
+        .route("/api/regions", get(get_regions))
+        .route("/api/languages", get(get_languages))
+        .route(
+            "/api/countries/:name",
+            get(get_country_by_name.layer(middleware::from_fn(xml_errors)))
+                .put(replace_country)
+                .patch(patch_country)
+                .delete(delete_country),
+        )
+        .route("/api/countries/:name/neighbors", get(get_neighbors))
+        .route("/api/countries/:name/population", patch(update_population))
+        .route("/api/countries/:name/population/history", get(get_population_history))
+        .route("/api/capitals/:name", get(get_countries_by_capital))
+        .route("/api/admin/snapshots", post(create_snapshot))
+        .route("/api/admin/snapshots/:id/rollback", post(rollback_snapshot))
+        .with_state(store.clone())
+        .merge(SwaggerUi::new("/docs").url("/api/openapi.json", ApiDoc::openapi()));
+
+    if config.strict_query() {
+        router = router.layer(Extension(StrictQuery));
+    }
+    if *config.write_access() != WriteAccess::Open {
+        let access = Arc::new(config.write_access().clone());
+        router = router.layer(middleware::from_fn_with_state(access, require_api_key));
+    }
+    // GraphQL and batch lookups only read, so their POSTs don't need an API key;
+    // they are rate limited
+    router = router.merge(graphql_routes(store.clone())).route(
+        "/api/countries/batch",
+        post(batch_lookup).with_state(store.clone()),
+    );
+    if let Some(rate_limit_config) = config.rate_limit() {
+        let limiter = Arc::new(RateLimiter::new(
+            rate_limit_config.requests_per_minute,
+            rate_limit_config.trust_forwarded,
+        ));
+        router = router.layer(middleware::from_fn_with_state(limiter, rate_limit));
+    }
+    let metrics = store.metrics().clone();
+    // Probes and metrics are added after the auth and rate limit layers so
+    // neither applies to them; every request is measured, including rejected ones
+    router
+        .merge(health_routes(store))
+        .merge(metrics_routes(metrics.clone()))
+        .layer(middleware::from_fn(method_not_allowed))
+        .layer(middleware::from_fn_with_state(metrics, track_metrics))
+        // Throttled responses still carry CORS headers
+        .layer(cors_layer(config))
+        // Outermost, so every response gets a request id and a span
+        .layer(middleware::from_fn(trace_request))
+}
+
+/// Gives the `405 Method Not Allowed` axum answers for a known path the usual
+/// error body; axum adds the `Allow` header listing the path's methods after
+/// this layer runs
+async fn method_not_allowed(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let response = next.run(request).await;
+    if response.status() != StatusCode::METHOD_NOT_ALLOWED || method == Method::HEAD {
+        return response;
+    }
+    error_response(
+        StatusCode::METHOD_NOT_ALLOWED,
+        format!("{} is not allowed on {}; see the Allow header", method, path),
+    )
+}
+
+/// Whether the request asks for XML, through `format` or else `Accept`
+fn xml_requested(request: &Request) -> bool {
+    let query = request.uri().query().unwrap_or_default();
+    match form_urlencoded::parse(query.as_bytes()).find(|(key, _)| key == "format") {
+        Some((_, format)) => format.trim().eq_ignore_ascii_case("xml"),
+        None => accepted_format(request.headers()) == ResponseFormat::Xml,
+    }
+}
+
+/// Re-renders the JSON error bodies of a handler that can answer in XML as
+/// XML when the request asks for it, including the query rejections raised
+/// before the handler runs
+async fn xml_errors(request: Request, next: Next) -> Response {
+    let xml = xml_requested(&request);
+    let response = next.run(request).await;
+    if !xml || !(response.status().is_client_error() || response.status().is_server_error()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_ERROR_BODY_BYTES).await else {
+        return (parts.status, Body::empty()).into_response();
+    };
+    let Ok(error) = serde_json::from_slice::<ErrorResponse>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    let xml = error_to_xml(&error.error, error.suggestion.as_deref(), error.fields.as_ref());
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(XML_CONTENT_TYPE));
+    Response::from_parts(parts, Body::from(xml))
+}
+
+/// Answers preflight requests itself; only simple headers, `Content-Type`,
+/// `X-Api-Key` and `X-Request-Id` may be sent, and `ETag`, `Retry-After` and
+/// `X-Request-Id` are readable by scripts
+fn cors_layer(config: &ApiConfig) -> CorsLayer {
+    let origins = match config.cors_origins() {
+        Some(origins) => AllowOrigin::list(origins.iter().cloned()),
+        None => AllowOrigin::any(),
+    };
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
+        .allow_headers([
+            header::CONTENT_TYPE,
+            HeaderName::from_static(API_KEY_HEADER),
+            HeaderName::from_static(REQUEST_ID_HEADER),
+        ])
+        .expose_headers([
+            header::ETAG,
+            header::RETRY_AFTER,
+            HeaderName::from_static(REQUEST_ID_HEADER),
+        ])
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/countries",
+    tag = "countries",
+    params(CountryQuery),
+    responses(
+        (
+            status = 200,
+            description = "Matching countries; a `Page` with `limit`, an `Envelope` with \
+                `envelope=true`, one per line with `format=ndjson`, `<countries>` with \
+                `format=xml`",
+            content(
+                (Vec<Country> = "application/json"),
+                (String = "text/csv"),
+                (String = "application/x-ndjson"),
+                (String = "application/xml"),
+            ),
+            headers(("ETag" = String)),
+        ),
+        (status = 304, description = "Not modified since the `If-None-Match` tag"),
+        (status = 400, description = "Invalid query parameter", body = ErrorResponse),
+    ),
+)]
 async fn get_countries(
-    State(store): State<CountryDataStore>,
-    Query(params): Query<CountryQuery>,
+    State(store): State<SharedStore>,
+    headers: HeaderMap,
+    ValidatedQuery(params): ValidatedQuery<CountryQuery>,
+) -> impl IntoResponse {
+    if let Some(region) = &params.region
+        && parse_region_list(region).is_empty()
+    {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "region must name at least one region".to_string(),
+        );
+    }
+    let subregions = params.subregion.as_deref().map(parse_region_list);
+    if subregions.as_ref().is_some_and(Vec::is_empty) {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "subregion must name at least one subregion".to_string(),
+        );
+    }
+
+    if let Some(search_limit) = params.search_limit {
+        if params.search.is_none() {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "search_limit requires search".to_string(),
+            );
+        }
+        if search_limit == 0 {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "search_limit must be at least 1".to_string(),
+            );
+        }
+    }
+
+    let population = match PopulationRange::new(params.min_population, params.max_population) {
+        Ok(range) => range,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+    let density = match DensityRange::new(params.min_density, params.max_density) {
+        Ok(range) => range,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+    let fields = match params.fields.as_deref().map(CountryField::parse_list).transpose() {
+        Ok(fields) => fields,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+    let format = params.format.unwrap_or_else(|| accepted_format(&headers));
+    let envelope = params.envelope || envelope_requested(&headers);
+
+    // The same URL has CSV, NDJSON, XML, enveloped and plain JSON representations
+    let representation = match (format, envelope) {
+        (ResponseFormat::Csv, _) => "csv",
+        (ResponseFormat::Ndjson, _) => "ndjson",
+        (ResponseFormat::Xml, _) => "xml",
+        (ResponseFormat::Json, true) => "envelope",
+        (ResponseFormat::Json, false) => "json",
+    };
+    let etag = etag(store.version(), representation);
+
+    let filter = ListFilter {
+        regions: params.region.as_deref().map(parse_region_list),
+        subregions,
+        population,
+        density,
+        currency: params.currency.as_deref().map(|currency| currency.trim().to_string()),
+        capital: params.capital.as_deref().map(name_key),
+    };
+    let offset = params.offset.unwrap_or(0);
+    let limit = params.limit.map(|limit| limit.min(MAX_PAGE_LIMIT));
+
+    // Without search or sort the order is the store's, so NDJSON can filter
+    // and serialize lazily from the current table
+    if format == ResponseFormat::Ndjson && params.search.is_none() && params.sort.is_none() {
+        let countries = store.shared_countries().await;
+        let rows = Arc::clone(&countries);
+        let lines = (0..(*countries).as_ref().len())
+            .filter(move |&index| filter.matches(&(*rows).as_ref()[index]))
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .map(move |index| ndjson_line(&(*countries).as_ref()[index], fields.as_deref()));
+        return with_etag(&headers, etag, ndjson_response(lines));
+    }
+
+    let mut countries = match &params.region {
+        Some(region) => store.filter_by_region(region).await,
+        None => store.get_all().await,
+    };
+
+    // Region narrows first; the remaining filters compose on top of it
+    countries.retain(|c| filter.matches(c));
+    if let Some(search) = &params.search {
+        let search_limit = params.search_limit.map(|limit| limit.min(MAX_SEARCH_LIMIT));
+        countries = search_countries(countries, search, params.search_fields, search_limit);
+    }
+
+    if let Some(field) = params.sort {
+        sort_countries(&mut countries, field, params.order);
+    }
+
+    let total = countries.len();
+    let items: Vec<Country> = countries
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+
+    let response = match format {
+        ResponseFormat::Csv => csv_response(&items),
+        ResponseFormat::Ndjson => ndjson_response(
+            items
+                .into_iter()
+                .map(move |country| ndjson_line(&country, fields.as_deref())),
+        ),
+        ResponseFormat::Xml => match fields {
+            Some(fields) => {
+                let items: Vec<_> = items.iter().map(|c| project_country(c, &fields)).collect();
+                xml_response(countries_to_xml(&items))
+            }
+            None => xml_response(countries_to_xml(&items)),
+        },
+        ResponseFormat::Json => match fields {
+            Some(fields) => {
+                let items = items.iter().map(|c| project_country(c, &fields)).collect();
+                list_response::<serde_json::Value>(items, total, limit, offset, envelope)
+            }
+            None => list_response(items, total, limit, offset, envelope),
+        },
+    };
+    with_etag(&headers, etag, response)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/countries/random",
+    tag = "countries",
+    params(RandomQuery),
+    responses(
+        (
+            status = 200,
+            description = "One random country, or an array of `count` distinct ones",
+            body = Country,
+        ),
+        (status = 400, description = "Invalid query parameter", body = ErrorResponse),
+        (status = 404, description = "No country matches the filters", body = ErrorResponse),
+    ),
+)]
+async fn get_random_countries(
+    State(store): State<SharedStore>,
+    ValidatedQuery(params): ValidatedQuery<RandomQuery>,
 ) -> impl IntoResponse {
-    let countries = if let Some(region) = params.region {
-        store.filter_by_region(&region).await
-    } else if let Some(search) = params.search {
-        store.search_by_name(&search).await
-    } else {
-        store.get_all().await
+    let regions = params.region.as_deref().map(parse_region_list);
+    if regions.as_ref().is_some_and(Vec::is_empty) {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "region must name at least one region".to_string(),
+        );
+    }
+    let population = match PopulationRange::new(params.min_population, None) {
+        Ok(range) => range,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
     };
+    if let Some(count) = params.count
+        && !(1..=MAX_RANDOM_COUNT).contains(&count)
+    {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            format!("count must be between 1 and {}, got {}", MAX_RANDOM_COUNT, count),
+        );
+    }
+
+    let countries = store
+        .random_countries(params.count.unwrap_or(1), &|c| {
+            regions
+                .as_ref()
+                .is_none_or(|regions| regions.contains(&normalize_for_match(&c.region)))
+                && population.contains(c.population)
+        })
+        .await;
+
+    if countries.is_empty() {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            "No countries match the given filters".to_string(),
+        );
+    }
+    match params.count {
+        Some(_) => Json(countries).into_response(),
+        None => Json(countries.into_iter().next()).into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/countries/stats",
+    tag = "countries",
+    params(StatsQuery),
+    responses(
+        (status = 200, description = "Aggregate statistics", body = CountryStats),
+        (status = 400, description = "Invalid query parameter", body = ErrorResponse),
+    ),
+)]
+async fn get_stats(
+    State(store): State<SharedStore>,
+    ValidatedQuery(params): ValidatedQuery<StatsQuery>,
+) -> impl IntoResponse {
+    if let Some(region) = &params.region
+        && parse_region_list(region).is_empty()
+    {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "region must name at least one region".to_string(),
+        );
+    }
+
+    Json(store.stats(params.region.as_deref()).await).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/languages",
+    tag = "countries",
+    params(StatsQuery),
+    responses(
+        (
+            status = 200,
+            description = "Languages with the countries speaking them, most countries first",
+            body = Vec<LanguageSummary>,
+        ),
+        (status = 400, description = "Invalid query parameter", body = ErrorResponse),
+    ),
+)]
+async fn get_languages(
+    State(store): State<SharedStore>,
+    ValidatedQuery(params): ValidatedQuery<StatsQuery>,
+) -> impl IntoResponse {
+    if let Some(region) = &params.region
+        && parse_region_list(region).is_empty()
+    {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "region must name at least one region".to_string(),
+        );
+    }
 
-    Json(countries)
+    Json(store.language_stats(params.region.as_deref()).await).into_response()
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/countries/{name}",
+    tag = "countries",
+    params(
+        ("name" = String, Path, description = "Country name, matched case-insensitively"),
+        LookupQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The country; a `<country>` element with `format=xml`",
+            content((Country = "application/json"), (String = "application/xml")),
+            headers(("ETag" = String)),
+        ),
+        (status = 304, description = "Not modified since the `If-None-Match` tag"),
+        (status = 400, description = "Invalid query parameter", body = ErrorResponse),
+        (
+            status = 404,
+            description = "No such country; `suggestion` names the closest match",
+            body = ErrorResponse,
+        ),
+    ),
+)]
 async fn get_country_by_name(
-    State(store): State<CountryDataStore>,
+    State(store): State<SharedStore>,
+    headers: HeaderMap,
     Path(name): Path<String>,
+    ValidatedQuery(params): ValidatedQuery<LookupQuery>,
 ) -> impl IntoResponse {
-    match store.get_by_name(&name).await {
-        Some(country) => (StatusCode::OK, Json(country)).into_response(),
-        None => (
+    let fields = match params.fields.as_deref().map(CountryField::parse_list).transpose() {
+        Ok(fields) => fields,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+    let xml = match params.format {
+        Some(ResponseFormat::Json) => false,
+        Some(ResponseFormat::Xml) => true,
+        Some(_) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "format must be json or xml for a single country".to_string(),
+            );
+        }
+        None => accepted_format(&headers) == ResponseFormat::Xml,
+    };
+    let respond = |country: Country| match (xml, fields.as_deref()) {
+        (true, Some(fields)) => xml_response(country_to_xml(&project_country(&country, fields))),
+        (true, None) => xml_response(country_to_xml(&country)),
+        (false, fields) => country_response(country, fields),
+    };
+
+    let etag = etag(store.version(), if xml { "xml" } else { "json" });
+    if let Some(country) = store.get_by_name(&name).await {
+        return with_etag(&headers, etag, respond(country));
+    }
+
+    let closest = store.closest_match(&name).await;
+    if params.fuzzy
+        && let Some(country) = closest
+    {
+        return with_etag(&headers, etag, respond(country));
+    }
+
+    store.metrics().record_not_found("name");
+    let body = ErrorResponse {
+        error: format!("Country \"{}\" not found", name),
+        suggestion: closest.map(|c| c.name),
+        fields: None,
+    };
+    (StatusCode::NOT_FOUND, Json(body)).into_response()
+}
+
+/// Resolves several names at once, without an API key
+#[utoipa::path(
+    post,
+    path = "/api/countries/batch",
+    tag = "countries",
+    request_body = BatchLookupRequest,
+    responses(
+        (status = 200, description = "Found countries and missing names", body = BatchLookup),
+        (status = 400, description = "More than 100 names", body = ErrorResponse),
+    ),
+)]
+async fn batch_lookup(
+    State(store): State<SharedStore>,
+    Json(request): Json<BatchLookupRequest>,
+) -> impl IntoResponse {
+    if request.names.len() > BATCH_MAX_NAMES {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Batch lookups are limited to {} names, got {}",
+                BATCH_MAX_NAMES,
+                request.names.len()
+            ),
+        );
+    }
+
+    Json(store.get_many(&request.names).await).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/countries/by-region",
+    tag = "regions",
+    params(FieldsQuery),
+    responses(
+        (status = 200, description = "Every country, grouped by region", body = Vec<RegionGroup>),
+        (status = 400, description = "Invalid query parameter", body = ErrorResponse),
+    ),
+)]
+async fn get_countries_by_region(
+    State(store): State<SharedStore>,
+    ValidatedQuery(params): ValidatedQuery<FieldsQuery>,
+) -> impl IntoResponse {
+    let fields = match params.fields.as_deref().map(CountryField::parse_list).transpose() {
+        Ok(fields) => fields,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+
+    let groups = store.countries_by_region().await;
+    match fields {
+        Some(fields) => {
+            let groups: Vec<_> = groups
+                .into_iter()
+                .map(|group| group.map_countries(|c| project_country(&c, &fields)))
+                .collect();
+            Json(groups).into_response()
+        }
+        None => Json(groups).into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/regions",
+    tag = "regions",
+    responses(
+        (status = 200, description = "Regions and their subregions", body = Vec<RegionSummary>),
+    ),
+)]
+async fn get_regions(State(store): State<SharedStore>) -> impl IntoResponse {
+    Json(store.regions().await)
+}
+
+/// Capitals aren't unique, so every country with the capital is returned
+#[utoipa::path(
+    get,
+    path = "/api/capitals/{name}",
+    tag = "countries",
+    params(("name" = String, Path, description = "Capital city, ignoring case and accents")),
+    responses(
+        (status = 200, description = "The countries with that capital", body = Vec<Country>),
+        (status = 404, description = "No country has that capital", body = ErrorResponse),
+    ),
+)]
+async fn get_countries_by_capital(
+    State(store): State<SharedStore>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let countries = store.get_by_capital(&name).await;
+    if countries.is_empty() {
+        store.metrics().record_not_found("capital");
+        return error_response(
             StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Country \"{}\" not found", name),
-            }),
-        )
-            .into_response(),
+            format!("No country with capital \"{}\"", name),
+        );
+    }
+    Json(countries).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/countries/code/{code}",
+    tag = "countries",
+    params(
+        ("code" = String, Path, description = "ISO 3166-1 alpha-2 or alpha-3 code"),
+        FieldsQuery,
+    ),
+    responses(
+        (status = 200, description = "The country", body = Country, headers(("ETag" = String))),
+        (status = 304, description = "Not modified since the `If-None-Match` tag"),
+        (status = 400, description = "Not a 2 or 3 letter code", body = ErrorResponse),
+        (status = 404, description = "No such country", body = ErrorResponse),
+    ),
+)]
+async fn get_country_by_code(
+    State(store): State<SharedStore>,
+    headers: HeaderMap,
+    Path(code): Path<String>,
+    ValidatedQuery(params): ValidatedQuery<FieldsQuery>,
+) -> impl IntoResponse {
+    let fields = match params.fields.as_deref().map(CountryField::parse_list).transpose() {
+        Ok(fields) => fields,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+    };
+
+    let is_code = matches!(code.len(), 2 | 3) && code.bytes().all(|b| b.is_ascii_alphabetic());
+    if !is_code {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            format!("code must be a 2 or 3 letter ISO code, got \"{}\"", code),
+        );
+    }
+
+    let etag = etag(store.version(), "json");
+    match store.get_by_code(&code).await {
+        Some(country) => with_etag(&headers, etag, country_response(country, fields.as_deref())),
+        None => {
+            store.metrics().record_not_found("code");
+            error_response(
+                StatusCode::NOT_FOUND,
+                format!("No country with code \"{}\"", code),
+            )
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/countries/{name}/neighbors",
+    tag = "countries",
+    params(("name" = String, Path, description = "Country name, matched case-insensitively")),
+    responses(
+        (status = 200, description = "The bordering countries", body = Neighbors),
+        (status = 404, description = "No such country", body = ErrorResponse),
+    ),
+)]
+async fn get_neighbors(
+    State(store): State<SharedStore>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    match store.neighbors(&name).await {
+        Some(neighbors) => (StatusCode::OK, Json(neighbors)).into_response(),
+        None => {
+            store.metrics().record_not_found("name");
+            store_error_response(StoreError::NotFound(name))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/countries",
+    tag = "countries",
+    request_body = Country,
+    security(("api_key" = [])),
+    responses(
+        (status = 201, description = "Created", body = Country, headers(("Location" = String))),
+        (status = 400, description = "Invalid country", body = ErrorResponse),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "Writes are disabled", body = ErrorResponse),
+        (status = 409, description = "Name or code already taken", body = ErrorResponse),
+    ),
+)]
+async fn create_country(
+    State(store): State<SharedStore>,
+    Json(country): Json<Country>,
+) -> impl IntoResponse {
+    match store.add_country(country).await {
+        Ok(country) => {
+            let location = format!(
+                "/api/countries/{}",
+                utf8_percent_encode(&country.name, PATH_SEGMENT)
+            );
+            (
+                StatusCode::CREATED,
+                [(header::LOCATION, location)],
+                Json(country),
+            )
+                .into_response()
+        }
+        Err(err) => store_error_response(err),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/countries/bulk",
+    tag = "countries",
+    params(BulkQuery),
+    request_body = Vec<Country>,
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "What was inserted and rejected", body = BulkInsertReport),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "Writes are disabled", body = ErrorResponse),
+        (status = 413, description = "Too many countries", body = ErrorResponse),
+    ),
+)]
+async fn bulk_create_countries(
+    State(store): State<SharedStore>,
+    ValidatedQuery(params): ValidatedQuery<BulkQuery>,
+    Json(countries): Json<Vec<Country>>,
+) -> impl IntoResponse {
+    if countries.len() > BULK_MAX_COUNTRIES {
+        return error_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "Bulk requests are limited to {} countries, got {}",
+                BULK_MAX_COUNTRIES,
+                countries.len()
+            ),
+        );
+    }
+
+    let report = store.bulk_insert(countries, params.mode).await;
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+/// Replaces the country named in the path; a different name in the body renames it
+#[utoipa::path(
+    put,
+    path = "/api/countries/{name}",
+    tag = "countries",
+    params(("name" = String, Path, description = "Country name, matched case-insensitively")),
+    request_body = Country,
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "The replaced country", body = Country),
+        (status = 400, description = "Invalid country", body = ErrorResponse),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "Writes are disabled", body = ErrorResponse),
+        (status = 404, description = "No such country", body = ErrorResponse),
+        (status = 409, description = "New name or code already taken", body = ErrorResponse),
+    ),
+)]
+async fn replace_country(
+    State(store): State<SharedStore>,
+    Path(name): Path<String>,
+    Json(country): Json<Country>,
+) -> impl IntoResponse {
+    match store.update(&name, country).await {
+        Ok(country) => (StatusCode::OK, Json(country)).into_response(),
+        Err(err) => store_error_response(err),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/countries/{name}",
+    tag = "countries",
+    params(("name" = String, Path, description = "Country name, matched case-insensitively")),
+    request_body = UpdateCountryRequest,
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "The updated country", body = Country),
+        (status = 400, description = "Invalid update", body = ErrorResponse),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "Writes are disabled", body = ErrorResponse),
+        (status = 404, description = "No such country", body = ErrorResponse),
+        (status = 409, description = "New name or code already taken", body = ErrorResponse),
+    ),
+)]
+async fn patch_country(
+    State(store): State<SharedStore>,
+    Path(name): Path<String>,
+    Json(update): Json<UpdateCountryRequest>,
+) -> impl IntoResponse {
+    match store.patch(&name, update).await {
+        Ok(country) => (StatusCode::OK, Json(country)).into_response(),
+        Err(err) => store_error_response(err),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/countries/{name}/population",
+    tag = "countries",
+    params(("name" = String, Path, description = "Country name, matched case-insensitively")),
+    request_body = PopulationRevision,
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "The country with its new population", body = Country),
+        (status = 400, description = "Invalid figure or date", body = ErrorResponse),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "Writes are disabled", body = ErrorResponse),
+        (status = 404, description = "No such country", body = ErrorResponse),
+    ),
+)]
+async fn update_population(
+    State(store): State<SharedStore>,
+    Path(name): Path<String>,
+    Json(revision): Json<PopulationRevision>,
+) -> impl IntoResponse {
+    match store.revise_population(&name, revision).await {
+        Ok(country) => (StatusCode::OK, Json(country)).into_response(),
+        Err(err) => store_error_response(err),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/countries/{name}/population/history",
+    tag = "countries",
+    params(("name" = String, Path, description = "Country name, matched case-insensitively")),
+    responses(
+        (status = 200, description = "Revisions, oldest first", body = [PopulationRevision]),
+        (status = 404, description = "No such country", body = ErrorResponse),
+    ),
+)]
+async fn get_population_history(
+    State(store): State<SharedStore>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    match store.get_by_name(&name).await {
+        Some(country) => (StatusCode::OK, Json(country.population_history)).into_response(),
+        None => {
+            store.metrics().record_not_found("name");
+            store_error_response(StoreError::NotFound(name))
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/countries/{name}",
+    tag = "countries",
+    params(("name" = String, Path, description = "Country name, matched case-insensitively")),
+    security(("api_key" = [])),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "Writes are disabled", body = ErrorResponse),
+        (status = 404, description = "No such country", body = ErrorResponse),
+    ),
+)]
+async fn delete_country(
+    State(store): State<SharedStore>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    match store.remove(&name).await {
+        Some(_) => StatusCode::NO_CONTENT.into_response(),
+        None => store_error_response(StoreError::NotFound(name)),
+    }
+}
+
+/// Saves the current countries so a later rollback can restore them
+#[utoipa::path(
+    post,
+    path = "/api/admin/snapshots",
+    tag = "admin",
+    security(("api_key" = [])),
+    responses(
+        (status = 201, description = "Snapshot taken", body = SnapshotInfo),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "Writes are disabled", body = ErrorResponse),
+    ),
+)]
+async fn create_snapshot(State(store): State<SharedStore>) -> impl IntoResponse {
+    let id = match store.snapshot().await {
+        Ok(id) => id,
+        Err(err) => return store_error_response(err),
+    };
+    let info = SnapshotInfo {
+        id,
+        countries: store.count().await,
+    };
+    (StatusCode::CREATED, Json(info)).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/snapshots/{id}/rollback",
+    tag = "admin",
+    params(("id" = u64, Path, description = "Id returned when the snapshot was taken")),
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "Countries restored", body = SnapshotInfo),
+        (status = 400, description = "Invalid snapshot id", body = ErrorResponse),
+        (status = 401, description = "Missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "Writes are disabled", body = ErrorResponse),
+        (status = 404, description = "Unknown or evicted snapshot", body = ErrorResponse),
+    ),
+)]
+async fn rollback_snapshot(
+    State(store): State<SharedStore>,
+    id: Result<Path<SnapshotId>, PathRejection>,
+) -> impl IntoResponse {
+    let Path(id) = match id {
+        Ok(id) => id,
+        Err(rejection) => return error_response(StatusCode::BAD_REQUEST, rejection.body_text()),
+    };
+
+    match store.rollback(id).await {
+        Ok(()) => {
+            let info = SnapshotInfo {
+                id,
+                countries: store.count().await,
+            };
+            (StatusCode::OK, Json(info)).into_response()
+        }
+        Err(err) => store_error_response(err),
     }
 }
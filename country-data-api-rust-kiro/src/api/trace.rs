@@ -0,0 +1,97 @@
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+use tracing::{field, Instrument};
+use uuid::Uuid;
+
+/// Header carrying the request id, in both directions
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied request id that is reused rather than replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Largest 5xx body copied into the error log, in bytes
+const MAX_LOGGED_BODY_BYTES: usize = 4096;
+
+/// Middleware running each request in a `request` span with its method, path
+/// and request id, then recording the status and latency. 5xx responses are
+/// logged at error level with their body. The request id is taken from an
+/// incoming `X-Request-Id` when it looks sane, generated otherwise, and
+/// returned in the `X-Request-Id` response header.
+pub async fn trace_request(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .filter(|value| is_usable_id(value))
+        .cloned()
+        .unwrap_or_else(|| {
+            HeaderValue::from_str(&Uuid::new_v4().to_string()).expect("UUIDs are valid headers")
+        });
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        request_id = request_id.to_str().unwrap_or_default(),
+        status = field::Empty,
+        latency_ms = field::Empty,
+    );
+
+    async move {
+        let started = Instant::now();
+        let response = next.run(request).await;
+        let latency = started.elapsed();
+
+        let span = tracing::Span::current();
+        span.record("status", response.status().as_u16());
+        span.record("latency_ms", latency.as_secs_f64() * 1000.0);
+
+        let mut response = if response.status().is_server_error() {
+            log_server_error(response).await
+        } else {
+            tracing::info!("request completed");
+            response
+        };
+        response.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+fn is_usable_id(value: &HeaderValue) -> bool {
+    let bytes = value.as_bytes();
+    !bytes.is_empty()
+        && bytes.len() <= MAX_REQUEST_ID_LEN
+        && bytes.iter().all(|b| b.is_ascii_graphic())
+}
+
+/// Logs the response body, when it is small and of known size, and hands
+/// back an equivalent response
+async fn log_server_error(response: Response) -> Response {
+    let small = response
+        .body()
+        .size_hint()
+        .upper()
+        .is_some_and(|len| len <= MAX_LOGGED_BODY_BYTES as u64);
+    if !small {
+        tracing::error!("request failed");
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    match to_bytes(body, MAX_LOGGED_BODY_BYTES).await {
+        Ok(bytes) => {
+            tracing::error!(body = %String::from_utf8_lossy(&bytes), "request failed");
+            Response::from_parts(parts, Body::from(bytes))
+        }
+        Err(err) => {
+            tracing::error!("request failed; body unreadable: {}", err);
+            Response::from_parts(parts, Body::empty())
+        }
+    }
+}
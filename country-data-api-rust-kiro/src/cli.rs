@@ -0,0 +1,85 @@
+use clap::Parser;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+
+/// Address to listen on, when neither `--host` nor the env var is given
+pub const HOST_ENV: &str = "HOST";
+
+/// Port to listen on, when `--port` isn't given; 0 picks a free port
+pub const PORT_ENV: &str = "PORT";
+
+/// JSON file of initial countries, when `--data` isn't given
+pub const COUNTRIES_FILE_ENV: &str = "COUNTRIES_FILE";
+
+const DEFAULT_HOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+const DEFAULT_PORT: u16 = 5000;
+
+/// Command-line arguments. Each falls back to an environment variable, then
+/// to a default; see `Cli::resolve`.
+#[derive(Debug, Clone, Default, Parser)]
+#[command(version, about = "Country Data API server")]
+pub struct Cli {
+    /// IP address to listen on [env: HOST] [default: 127.0.0.1]
+    #[arg(long)]
+    pub host: Option<IpAddr>,
+    /// Port to listen on, 0 for any free port [env: PORT] [default: 5000]
+    #[arg(long)]
+    pub port: Option<u16>,
+    /// JSON file of initial countries [env: COUNTRIES_FILE]
+    #[arg(long, value_name = "FILE")]
+    pub data: Option<PathBuf>,
+    /// Reload the data file whenever it changes
+    #[arg(long)]
+    pub watch: bool,
+}
+
+/// Startup settings resolved from the command line and environment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchConfig {
+    pub addr: SocketAddr,
+    pub countries_file: Option<PathBuf>,
+    /// Reload `countries_file` when it changes; only set when there is one
+    pub watch: bool,
+}
+
+impl Cli {
+    /// Applies the fallbacks: arguments win over the variables read through
+    /// `env`, which win over the defaults. Blank variables count as unset;
+    /// malformed ones are an error.
+    pub fn resolve(self, env: impl Fn(&str) -> Option<String>) -> Result<LaunchConfig, String> {
+        let env = |key: &str| env(key).filter(|value| !value.trim().is_empty());
+        let host = match self.host {
+            Some(host) => host,
+            None => match env(HOST_ENV) {
+                Some(host) => host.trim().parse().map_err(|_| {
+                    format!("{} must be an IP address, got {:?}", HOST_ENV, host)
+                })?,
+                None => DEFAULT_HOST,
+            },
+        };
+        let port = match self.port {
+            Some(port) => port,
+            None => match env(PORT_ENV) {
+                Some(port) => port.trim().parse().map_err(|_| {
+                    format!("{} must be a number from 0 to 65535, got {:?}", PORT_ENV, port)
+                })?,
+                None => DEFAULT_PORT,
+            },
+        };
+        let countries_file = self
+            .data
+            .or_else(|| env(COUNTRIES_FILE_ENV).map(PathBuf::from));
+        if self.watch && countries_file.is_none() {
+            return Err(format!(
+                "--watch needs a data file, from --data or {}",
+                COUNTRIES_FILE_ENV
+            ));
+        }
+
+        Ok(LaunchConfig {
+            addr: SocketAddr::new(host, port),
+            countries_file,
+            watch: self.watch,
+        })
+    }
+}
@@ -1,4 +1,7 @@
 pub mod api;
+pub mod cli;
 pub mod models;
 pub mod sample_data;
+pub mod server;
 pub mod services;
+pub mod utils;
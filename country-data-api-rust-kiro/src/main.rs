@@ -1,24 +1,162 @@
-mod api;
-mod models;
-mod sample_data;
-mod services;
+use clap::Parser;
+use country_data_api_rust_kiro::cli::Cli;
+use country_data_api_rust_kiro::models::Country;
+use country_data_api_rust_kiro::services::{
+    read_countries_file, watch_file, CountryDataStore, CountryStore, IntoSharedStore, SharedStore,
+};
+use country_data_api_rust_kiro::sample_data;
+use country_data_api_rust_kiro::server::{self, ServerConfig};
+use std::path::PathBuf;
+use std::sync::Arc;
 
-use services::CountryDataStore;
+/// Initial data: the `--data`/COUNTRIES_FILE file, live REST Countries data
+/// (with the `fetch` feature), or the embedded sample data.
+/// Exits if the file is configured but can't be read or parsed.
+async fn initial_countries(countries_file: Option<PathBuf>) -> Vec<Country> {
+    let Some(path) = countries_file else {
+        return default_countries().await;
+    };
+
+    match read_countries_file(&path) {
+        Ok(loaded) => {
+            tracing::info!(
+                "Loaded {} countries from file, rejected {}",
+                loaded.countries.len(),
+                loaded.rejected.len()
+            );
+            loaded.countries
+        }
+        Err(err) => {
+            tracing::error!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(feature = "fetch")]
+async fn default_countries() -> Vec<Country> {
+    use country_data_api_rust_kiro::services::import_countries;
+    use country_data_api_rust_kiro::services::rest_countries::HttpCountrySource;
+
+    match import_countries(&HttpCountrySource::default()).await {
+        Ok(loaded) => {
+            tracing::info!(
+                "Imported {} countries from REST Countries, rejected {}",
+                loaded.countries.len(),
+                loaded.rejected.len()
+            );
+            loaded.countries
+        }
+        Err(err) => {
+            tracing::warn!("{}; using sample data", err);
+            sample_data::get_sample_countries()
+        }
+    }
+}
+
+#[cfg(not(feature = "fetch"))]
+async fn default_countries() -> Vec<Country> {
+    sample_data::get_sample_countries()
+}
+
+/// Fills the store with the initial data, unless it already holds countries
+/// saved by an earlier run; `/ready` reports 503 until this finishes
+async fn load_initial_data(store: &dyn CountryStore, countries_file: Option<PathBuf>) {
+    if store.is_loaded() {
+        tracing::info!("Keeping the {} stored countries", store.count().await);
+        return;
+    }
+
+    let countries = initial_countries(countries_file).await;
+    let report = match store.data_file() {
+        Some(path) => store.load_from_file_or(path, countries).await,
+        None => store.load_countries(countries).await,
+    };
+    for rejected in &report.rejected {
+        tracing::warn!(
+            "Rejected country #{} {:?}: {}",
+            rejected.index,
+            rejected.name,
+            rejected.reason
+        );
+    }
+    tracing::info!(
+        "Loaded {} countries, rejected {}",
+        report.loaded,
+        report.rejected.len()
+    );
+}
+
+/// The SQLite database at DATABASE_FILE (with the `sqlite` feature), otherwise
+/// an in-memory store saved to DATA_FILE if that is set
+async fn open_store() -> SharedStore {
+    #[cfg(feature = "sqlite")]
+    if let Some(path) = std::env::var_os("DATABASE_FILE") {
+        return open_database(PathBuf::from(path)).await;
+    }
+
+    let mut store = CountryDataStore::new();
+    if std::env::var_os("STRICT_BORDERS").is_some() {
+        store = store.with_strict_borders();
+    }
+    if let Some(path) = std::env::var_os("DATA_FILE") {
+        store = store.with_data_file(PathBuf::from(path));
+    }
+    store.into_shared()
+}
+
+/// Opens the database at `path`; exits if it can't be opened or migrated
+#[cfg(feature = "sqlite")]
+async fn open_database(path: PathBuf) -> SharedStore {
+    use country_data_api_rust_kiro::services::SqliteCountryStore;
+
+    match SqliteCountryStore::open(&path).await {
+        Ok(mut store) => {
+            if std::env::var_os("STRICT_BORDERS").is_some() {
+                store = store.with_strict_borders();
+            }
+            tracing::info!("Storing countries in {}", path.display());
+            store.into_shared()
+        }
+        Err(err) => {
+            tracing::error!("Cannot open database {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+    }
+}
 
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt::init();
 
-    let store = CountryDataStore::new();
-    store.load_countries(sample_data::get_sample_countries()).await;
+    let launch = match Cli::parse().resolve(|key| std::env::var(key).ok()) {
+        Ok(launch) => launch,
+        Err(message) => {
+            tracing::error!("{}", message);
+            std::process::exit(2);
+        }
+    };
 
-    let app = api::create_routes(store);
+    let store = open_store().await;
 
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:5000")
-        .await
-        .unwrap();
-    
-    tracing::info!("Country Data API running on http://127.0.0.1:5000");
+    // Serve while loading, so probes can tell a starting server from a dead one
+    let watched = launch.countries_file.clone().filter(|_| launch.watch);
+    let loading = Arc::clone(&store);
+    tokio::spawn(async move {
+        load_initial_data(loading.as_ref(), launch.countries_file).await;
+        if let Some(path) = watched
+            && let Err(err) = watch_file(loading, path)
+        {
+            tracing::error!("Cannot watch the data file: {}", err);
+        }
+    });
 
-    axum::serve(listener, app).await.unwrap();
+    let config = ServerConfig {
+        addr: launch.addr,
+        ..ServerConfig::from_env()
+    };
+    if let Err(err) = server::run(config, store, server::shutdown_signal()).await {
+        tracing::error!("Server error: {}", err);
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,35 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::Country;
+use crate::utils::name_key;
+
+/// Body of `POST /api/countries/batch`
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct BatchLookupRequest {
+    /// Country names, matched like single lookups; repeats are looked up once
+    #[schema(example = json!(["France", "japan", "Atlantis"]))]
+    pub names: Vec<String>,
+}
+
+/// The countries a batch of names resolved to; see `CountryStore::get_many`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct BatchLookup {
+    /// In the order their names were given
+    pub found: Vec<Country>,
+    /// The names that matched no country, as given
+    pub missing: Vec<String>,
+}
+
+/// `names` without repeats, keeping the first spelling of each. Names are
+/// compared by `name_key`, so spellings of one name are one entry.
+pub fn distinct_names(names: &[String]) -> Vec<&str> {
+    let mut seen = HashSet::new();
+    names
+        .iter()
+        .filter(|name| seen.insert(name_key(name)))
+        .map(String::as_str)
+        .collect()
+}
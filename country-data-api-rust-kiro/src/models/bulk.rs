@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::validation::{ValidationError, describe_errors};
+
+/// How a load combines a batch with the existing store. The bulk endpoint
+/// defaults to `Append`; `CountryDataStore::load_countries` always replaces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LoadMode {
+    /// Add the batch to the existing countries; names already present are skipped
+    #[default]
+    Append,
+    /// Swap the whole store for the batch
+    Replace,
+    /// Update countries whose name (ignoring case) is already present, add
+    /// the others, and keep stored countries the batch doesn't mention
+    Merge,
+}
+
+/// A batch entry that was not inserted, and why
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RejectedCountry {
+    pub index: usize,
+    pub name: String,
+    pub reason: String,
+    /// The validation failures behind `reason`; empty for other rejections
+    #[serde(skip)]
+    pub errors: Vec<ValidationError>,
+}
+
+impl RejectedCountry {
+    pub fn new(index: usize, name: String, reason: String) -> Self {
+        RejectedCountry {
+            index,
+            name,
+            reason,
+            errors: Vec::new(),
+        }
+    }
+
+    /// A rejection for failed validation; `reason` lists every error
+    pub fn invalid(index: usize, name: String, errors: Vec<ValidationError>) -> Self {
+        RejectedCountry {
+            index,
+            name,
+            reason: describe_errors(&errors),
+            errors,
+        }
+    }
+}
+
+/// Outcome of a bulk load
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BulkInsertReport {
+    pub inserted: usize,
+    /// Existing countries overwritten in merge mode
+    #[serde(default)]
+    pub updated: usize,
+    pub rejected: Vec<RejectedCountry>,
+}
+
+/// Outcome of `CountryDataStore::load_countries`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadReport {
+    /// Countries added to the store
+    pub loaded: usize,
+    /// Existing countries overwritten in merge mode
+    pub updated: usize,
+    /// Invalid entries and repeated names, plus names already present in append mode
+    pub rejected: Vec<RejectedCountry>,
+}
@@ -1,18 +1,292 @@
-use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+use async_graphql::{ComplexObject, SimpleObject};
+use serde::{Deserialize, Serialize, Serializer};
+use utoipa::openapi::{RefOr, Schema};
+use utoipa::{PartialSchema, ToSchema};
+
+use crate::models::validation::{MAX_NAME_LEN, ValidationError};
+use crate::models::{Currency, PopulationRevision};
+use crate::utils::flag_emoji_from_alpha2;
+
+/// Serialized with a computed `population_density`; see the `Serialize` impl below
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct Country {
     pub name: String,
     pub capital: String,
     pub population: i64,
     pub region: String,
+    /// Finer-grained area within the region, e.g. "Western Europe"; may be empty
+    #[serde(default)]
+    pub subregion: String,
     pub languages: Vec<String>,
+    /// ISO 3166-1 alpha-2 code, e.g. "FR"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpha2: Option<String>,
+    /// ISO 3166-1 alpha-3 code, e.g. "FRA"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpha3: Option<String>,
+    #[serde(default)]
+    pub currencies: Vec<Currency>,
+    #[serde(default)]
+    pub area_km2: f64,
+    /// Alpha-3 codes of neighboring countries; empty for island nations
+    #[serde(default)]
+    pub borders: Vec<String>,
+    /// Derived from `alpha2` when left empty; see `derive_flag_emoji`
+    #[serde(default)]
+    pub flag_emoji: String,
+    /// http(s) URL of a flag image
+    #[serde(default)]
+    pub flag_url: Option<String>,
+    /// Revisions recorded by `CountryStore::revise_population`, oldest first.
+    /// Replacing or patching a country keeps them.
+    #[serde(default)]
+    #[graphql(skip)]
+    pub population_history: Vec<PopulationRevision>,
+}
+
+impl Country {
+    /// Checks every field, reporting all the problems found rather than the first
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        let name_len = self.name.chars().count();
+        if self.name.trim().is_empty() {
+            errors.push(ValidationError::EmptyName);
+        } else if name_len > MAX_NAME_LEN {
+            errors.push(ValidationError::NameTooLong { len: name_len });
+        }
+        if self.population < 0 {
+            errors.push(ValidationError::NegativePopulation(self.population));
+        }
+        for (index, language) in self.languages.iter().enumerate() {
+            if language.is_empty() {
+                errors.push(ValidationError::EmptyLanguage { index });
+            }
+        }
+        if let Some(code) = &self.alpha2
+            && !is_upper_code(code, 2)
+        {
+            errors.push(ValidationError::InvalidAlpha2(code.clone()));
+        }
+        if let Some(code) = &self.alpha3
+            && !is_upper_code(code, 3)
+        {
+            errors.push(ValidationError::InvalidAlpha3(code.clone()));
+        }
+        if self.area_km2 < 0.0 || !self.area_km2.is_finite() {
+            errors.push(ValidationError::InvalidArea(self.area_km2));
+        }
+        for (index, currency) in self.currencies.iter().enumerate() {
+            if !is_upper_code(&currency.code, 3) {
+                errors.push(ValidationError::InvalidCurrencyCode {
+                    index,
+                    code: currency.code.clone(),
+                });
+            }
+        }
+        if let Some(url) = &self.flag_url
+            && !is_http_url(url)
+        {
+            errors.push(ValidationError::InvalidFlagUrl(url.clone()));
+        }
+        for (index, code) in self.borders.iter().enumerate() {
+            if !is_upper_code(code, 3) {
+                errors.push(ValidationError::InvalidBorder {
+                    index,
+                    code: code.clone(),
+                });
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Fills in `flag_emoji` from `alpha2` if it is empty
+    pub fn derive_flag_emoji(&mut self) {
+        if self.flag_emoji.is_empty()
+            && let Some(emoji) = self.alpha2.as_deref().and_then(flag_emoji_from_alpha2)
+        {
+            self.flag_emoji = emoji;
+        }
+    }
+
+    /// The country's ISO codes that are set
+    pub fn codes(&self) -> impl Iterator<Item = &str> {
+        [&self.alpha2, &self.alpha3].into_iter().flatten().map(String::as_str)
+    }
+
+    /// Whether `code` is this country's alpha-2 or alpha-3 code (case-insensitive)
+    pub fn has_code(&self, code: &str) -> bool {
+        self.codes().any(|own| own.eq_ignore_ascii_case(code))
+    }
+
+    /// People per km², or `None` when the area is unknown (zero)
+    pub fn population_density(&self) -> Option<f64> {
+        (self.area_km2 > 0.0).then(|| self.population as f64 / self.area_km2)
+    }
+
+    /// Whether any of the country's currencies has `code` (case-insensitive)
+    pub fn uses_currency(&self, code: &str) -> bool {
+        self.currencies
+            .iter()
+            .any(|currency| currency.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Makes `revision` the current population and appends it to the history,
+    /// unless it is invalid or older than the latest revision
+    pub fn revise_population(
+        &mut self,
+        revision: PopulationRevision,
+    ) -> Result<(), Vec<ValidationError>> {
+        revision.validate(self.population_history.last())?;
+        self.population = revision.population;
+        self.population_history.push(revision);
+        Ok(())
+    }
 }
 
+#[ComplexObject]
 impl Country {
-    pub fn validate(&self) -> bool {
-        !self.name.trim().is_empty()
-            && self.population >= 0
-            && self.languages.iter().all(|lang| !lang.is_empty())
+    /// People per km², computed from `population` and `areaKm2`; null when the area is unknown
+    #[graphql(name = "populationDensity")]
+    async fn graphql_population_density(&self) -> Option<f64> {
+        self.population_density()
+    }
+}
+
+/// Borrowed view of a country as it goes over the wire; also its OpenAPI schema
+#[derive(Serialize, ToSchema)]
+#[schema(as = Country)]
+struct CountryOut<'a> {
+    name: &'a str,
+    capital: &'a str,
+    population: i64,
+    region: &'a str,
+    subregion: &'a str,
+    languages: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alpha2: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alpha3: Option<&'a str>,
+    currencies: &'a [Currency],
+    area_km2: f64,
+    /// People per km², computed from `population` and `area_km2`
+    #[schema(read_only)]
+    population_density: Option<f64>,
+    borders: &'a [String],
+    flag_emoji: &'a str,
+    flag_url: Option<&'a str>,
+    /// Population revisions, oldest first; omitted when there are none
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    population_history: &'a [PopulationRevision],
+}
+
+impl Serialize for Country {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CountryOut {
+            name: &self.name,
+            capital: &self.capital,
+            population: self.population,
+            region: &self.region,
+            subregion: &self.subregion,
+            languages: &self.languages,
+            alpha2: self.alpha2.as_deref(),
+            alpha3: self.alpha3.as_deref(),
+            currencies: &self.currencies,
+            area_km2: self.area_km2,
+            population_density: self.population_density(),
+            borders: &self.borders,
+            flag_emoji: &self.flag_emoji,
+            flag_url: self.flag_url.as_deref(),
+            population_history: &self.population_history,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl PartialSchema for Country {
+    fn schema() -> RefOr<Schema> {
+        CountryOut::schema()
+    }
+}
+
+impl ToSchema for Country {
+    fn name() -> Cow<'static, str> {
+        CountryOut::name()
+    }
+
+    fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        CountryOut::schemas(schemas);
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    ["http://", "https://"].iter().any(|scheme| {
+        url.get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+            && url.len() > scheme.len()
+    })
+}
+
+fn is_upper_code(code: &str, len: usize) -> bool {
+    code.len() == len && code.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+/// Partial update for a country; only the fields that are present are changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct UpdateCountryRequest {
+    pub name: Option<String>,
+    pub capital: Option<String>,
+    pub population: Option<i64>,
+    pub region: Option<String>,
+    pub subregion: Option<String>,
+    pub languages: Option<Vec<String>>,
+    pub alpha2: Option<String>,
+    pub alpha3: Option<String>,
+    pub currencies: Option<Vec<Currency>>,
+    pub area_km2: Option<f64>,
+    pub borders: Option<Vec<String>>,
+    pub flag_emoji: Option<String>,
+    pub flag_url: Option<String>,
+}
+
+impl UpdateCountryRequest {
+    /// Returns a copy of `country` with this update merged onto it
+    pub fn apply(&self, country: &Country) -> Country {
+        Country {
+            name: self.name.clone().unwrap_or_else(|| country.name.clone()),
+            capital: self.capital.clone().unwrap_or_else(|| country.capital.clone()),
+            population: self.population.unwrap_or(country.population),
+            region: self.region.clone().unwrap_or_else(|| country.region.clone()),
+            subregion: self
+                .subregion
+                .clone()
+                .unwrap_or_else(|| country.subregion.clone()),
+            languages: self
+                .languages
+                .clone()
+                .unwrap_or_else(|| country.languages.clone()),
+            alpha2: self.alpha2.clone().or_else(|| country.alpha2.clone()),
+            alpha3: self.alpha3.clone().or_else(|| country.alpha3.clone()),
+            currencies: self
+                .currencies
+                .clone()
+                .unwrap_or_else(|| country.currencies.clone()),
+            area_km2: self.area_km2.unwrap_or(country.area_km2),
+            borders: self.borders.clone().unwrap_or_else(|| country.borders.clone()),
+            flag_emoji: self
+                .flag_emoji
+                .clone()
+                .unwrap_or_else(|| country.flag_emoji.clone()),
+            flag_url: self.flag_url.clone().or_else(|| country.flag_url.clone()),
+            population_history: country.population_history.clone(),
+        }
     }
 }
@@ -0,0 +1,22 @@
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct Currency {
+    /// ISO 4217 code, e.g. "EUR"
+    pub code: String,
+    pub name: String,
+    #[serde(default)]
+    pub symbol: String,
+}
+
+impl Currency {
+    pub fn new(code: &str, name: &str, symbol: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+/// A top-level key of a serialized country, selectable with `?fields=`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountryField {
+    Name,
+    Capital,
+    Population,
+    Region,
+    Subregion,
+    Languages,
+    Alpha2,
+    Alpha3,
+    Currencies,
+    AreaKm2,
+    PopulationDensity,
+    Borders,
+    FlagEmoji,
+    FlagUrl,
+}
+
+impl CountryField {
+    pub const ALL: [CountryField; 14] = [
+        CountryField::Name,
+        CountryField::Capital,
+        CountryField::Population,
+        CountryField::Region,
+        CountryField::Subregion,
+        CountryField::Languages,
+        CountryField::Alpha2,
+        CountryField::Alpha3,
+        CountryField::Currencies,
+        CountryField::AreaKm2,
+        CountryField::PopulationDensity,
+        CountryField::Borders,
+        CountryField::FlagEmoji,
+        CountryField::FlagUrl,
+    ];
+
+    /// The JSON key this field is serialized under
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CountryField::Name => "name",
+            CountryField::Capital => "capital",
+            CountryField::Population => "population",
+            CountryField::Region => "region",
+            CountryField::Subregion => "subregion",
+            CountryField::Languages => "languages",
+            CountryField::Alpha2 => "alpha2",
+            CountryField::Alpha3 => "alpha3",
+            CountryField::Currencies => "currencies",
+            CountryField::AreaKm2 => "area_km2",
+            CountryField::PopulationDensity => "population_density",
+            CountryField::Borders => "borders",
+            CountryField::FlagEmoji => "flag_emoji",
+            CountryField::FlagUrl => "flag_url",
+        }
+    }
+
+    /// Parses a comma-separated, case-insensitive field list, ignoring empty
+    /// segments and repeats (`"name, Population,,name"` -> `[Name, Population]`)
+    pub fn parse_list(fields: &str) -> Result<Vec<CountryField>, String> {
+        let mut parsed = Vec::new();
+        for field in fields.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            let Some(field) = Self::ALL
+                .into_iter()
+                .find(|known| known.as_str().eq_ignore_ascii_case(field))
+            else {
+                let valid: Vec<&str> = Self::ALL.iter().map(|f| f.as_str()).collect();
+                return Err(format!(
+                    "unknown field \"{}\", expected one of: {}",
+                    field,
+                    valid.join(", ")
+                ));
+            };
+            if !parsed.contains(&field) {
+                parsed.push(field);
+            }
+        }
+
+        if parsed.is_empty() {
+            return Err("fields must name at least one field".to_string());
+        }
+        Ok(parsed)
+    }
+}
@@ -0,0 +1,14 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+/// Representation of a country list response; single countries are JSON or XML
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseFormat {
+    Json,
+    Csv,
+    /// Newline-delimited JSON, one country per line, streamed
+    Ndjson,
+    /// `<countries><country>…</country></countries>`, without pages or envelopes
+    Xml,
+}
@@ -1,3 +1,28 @@
+pub mod batch;
+pub mod bulk;
 pub mod country;
-pub use country::Country;
-
+pub mod currency;
+pub mod fields;
+pub mod format;
+pub mod neighbors;
+pub mod population;
+pub mod region;
+pub mod search;
+pub mod snapshot;
+pub mod sort;
+pub mod stats;
+pub mod validation;
+pub use batch::{distinct_names, BatchLookup, BatchLookupRequest};
+pub use bulk::{BulkInsertReport, LoadMode, LoadReport, RejectedCountry};
+pub use country::{Country, UpdateCountryRequest};
+pub use currency::Currency;
+pub use fields::CountryField;
+pub use format::ResponseFormat;
+pub use neighbors::Neighbors;
+pub use population::PopulationRevision;
+pub use region::{RegionGroup, RegionSummary, SubregionSummary};
+pub use search::SearchFields;
+pub use snapshot::{SnapshotId, SnapshotInfo};
+pub use sort::{SortField, SortOrder};
+pub use stats::{CountryStats, LanguageCount, LanguageSummary, RegionStats};
+pub use validation::ValidationError;
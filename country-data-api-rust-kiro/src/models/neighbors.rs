@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::Country;
+
+/// A country's border codes resolved against the store
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Neighbors {
+    pub neighbors: Vec<Country>,
+    /// Border codes that don't match any stored country
+    pub unresolved: Vec<String>,
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::ValidationError;
+
+/// A population figure and the date it applies to; a country's history lists
+/// them oldest first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PopulationRevision {
+    pub population: i64,
+    /// Date as `YYYY-MM-DD`, e.g. "2024-07-01"
+    #[schema(example = "2024-07-01")]
+    pub as_of: String,
+}
+
+impl PopulationRevision {
+    /// Checks the figure and the date, which must not precede `latest`, the
+    /// newest revision already recorded
+    pub fn validate(
+        &self,
+        latest: Option<&PopulationRevision>,
+    ) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        if self.population < 0 {
+            errors.push(ValidationError::NegativePopulation(self.population));
+        }
+        if !is_iso_date(&self.as_of) {
+            errors.push(ValidationError::InvalidDate(self.as_of.clone()));
+        } else if let Some(latest) = latest
+            && self.as_of < latest.as_of
+        {
+            errors.push(ValidationError::RevisionOutOfOrder {
+                as_of: self.as_of.clone(),
+                latest: latest.as_of.clone(),
+            });
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// Whether `date` is a real calendar date written `YYYY-MM-DD`. Such dates
+/// sort correctly as strings.
+fn is_iso_date(date: &str) -> bool {
+    let number = |part: &str, len: usize| {
+        let digits = part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+        digits.then_some(part).and_then(|part| part.parse::<u32>().ok())
+    };
+    let mut parts = date.split('-');
+    let (Some(year), Some(month), Some(day), None) = (
+        parts.next().and_then(|part| number(part, 4)),
+        parts.next().and_then(|part| number(part, 2)),
+        parts.next().and_then(|part| number(part, 2)),
+        parts.next(),
+    ) else {
+        return false;
+    };
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
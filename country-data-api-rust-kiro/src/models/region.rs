@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::Country;
+
+/// A region with its country count and the subregions it contains
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RegionSummary {
+    pub region: String,
+    pub count: usize,
+    pub subregions: Vec<SubregionSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SubregionSummary {
+    pub subregion: String,
+    pub count: usize,
+}
+
+/// A region with its countries, sorted by name, and their total population; see
+/// `CountryStore::countries_by_region`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RegionGroup<C = Country> {
+    pub region: String,
+    pub count: usize,
+    pub total_population: i64,
+    pub countries: Vec<C>,
+}
+
+impl<C> RegionGroup<C> {
+    /// The same group with `f` applied to each country
+    pub fn map_countries<D>(self, f: impl FnMut(C) -> D) -> RegionGroup<D> {
+        RegionGroup {
+            region: self.region,
+            count: self.count,
+            total_population: self.total_population,
+            countries: self.countries.into_iter().map(f).collect(),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+/// Which country fields a text search looks at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchFields {
+    #[default]
+    Name,
+    Capital,
+    Languages,
+    All,
+}
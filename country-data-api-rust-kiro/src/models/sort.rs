@@ -0,0 +1,21 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+/// Field to sort a country list by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortField {
+    Name,
+    Population,
+    Region,
+    /// Population per km²; countries without an area sort last in either order
+    Density,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
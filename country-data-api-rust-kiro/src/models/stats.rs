@@ -0,0 +1,40 @@
+use async_graphql::SimpleObject;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use std::collections::BTreeMap;
+
+/// Aggregate figures over a set of countries
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct CountryStats {
+    pub total_countries: usize,
+    pub total_population: i64,
+    /// Keyed by region name, using the first spelling seen
+    pub by_region: BTreeMap<String, RegionStats>,
+    pub most_populous: Option<String>,
+    pub least_populous: Option<String>,
+    /// The most common languages, most countries first, ties by name
+    pub top_languages: Vec<LanguageCount>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct RegionStats {
+    pub count: usize,
+    pub total_population: i64,
+    pub average_population: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct LanguageCount {
+    pub language: String,
+    pub count: usize,
+}
+
+/// The countries speaking one language; see `CountryDataStore::language_stats`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LanguageSummary {
+    pub language: String,
+    pub count: usize,
+    pub total_population: i64,
+    /// In insertion order
+    pub countries: Vec<String>,
+}
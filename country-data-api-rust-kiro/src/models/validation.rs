@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Longest country name accepted, in characters
+pub const MAX_NAME_LEN: usize = 100;
+
+/// One reason a country failed validation; see `Country::validate`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    EmptyName,
+    NameTooLong { len: usize },
+    NegativePopulation(i64),
+    EmptyLanguage { index: usize },
+    InvalidAlpha2(String),
+    InvalidAlpha3(String),
+    InvalidArea(f64),
+    InvalidCurrencyCode { index: usize, code: String },
+    InvalidFlagUrl(String),
+    InvalidBorder { index: usize, code: String },
+    /// A border code no stored country has, in strict border mode
+    UnknownBorder(String),
+    /// A population revision date that isn't `YYYY-MM-DD`
+    InvalidDate(String),
+    /// A population revision dated before the latest one recorded
+    RevisionOutOfOrder { as_of: String, latest: String },
+}
+
+impl ValidationError {
+    /// The field the error is about; population revisions share `population`
+    /// with `Country`
+    pub fn field(&self) -> &'static str {
+        match self {
+            ValidationError::EmptyName | ValidationError::NameTooLong { .. } => "name",
+            ValidationError::NegativePopulation(_) => "population",
+            ValidationError::EmptyLanguage { .. } => "languages",
+            ValidationError::InvalidAlpha2(_) => "alpha2",
+            ValidationError::InvalidAlpha3(_) => "alpha3",
+            ValidationError::InvalidArea(_) => "area_km2",
+            ValidationError::InvalidCurrencyCode { .. } => "currencies",
+            ValidationError::InvalidFlagUrl(_) => "flag_url",
+            ValidationError::InvalidBorder { .. } | ValidationError::UnknownBorder(_) => {
+                "borders"
+            }
+            ValidationError::InvalidDate(_) | ValidationError::RevisionOutOfOrder { .. } => {
+                "as_of"
+            }
+        }
+    }
+
+    /// What is wrong with the field, without the field name
+    pub fn message(&self) -> String {
+        match self {
+            ValidationError::EmptyName => "must not be empty".to_string(),
+            ValidationError::NameTooLong { len } => {
+                format!("must be at most {} characters, got {}", MAX_NAME_LEN, len)
+            }
+            ValidationError::NegativePopulation(population) => {
+                format!("must be non-negative, got {}", population)
+            }
+            ValidationError::EmptyLanguage { index } => {
+                format!("entries must not be empty, got one at index {}", index)
+            }
+            ValidationError::InvalidAlpha2(code) => {
+                format!("must be 2 uppercase letters, got \"{}\"", code)
+            }
+            ValidationError::InvalidAlpha3(code) => {
+                format!("must be 3 uppercase letters, got \"{}\"", code)
+            }
+            ValidationError::InvalidArea(area) => format!("must be non-negative, got {}", area),
+            ValidationError::InvalidCurrencyCode { code, .. } => {
+                format!("code must be 3 uppercase letters, got \"{}\"", code)
+            }
+            ValidationError::InvalidFlagUrl(url) => {
+                format!("must be an http(s) URL, got \"{}\"", url)
+            }
+            ValidationError::InvalidBorder { code, .. } => {
+                format!("codes must be 3 uppercase letters, got \"{}\"", code)
+            }
+            ValidationError::UnknownBorder(code) => {
+                format!("unknown country code \"{}\"", code)
+            }
+            ValidationError::InvalidDate(date) => {
+                format!("must be a date as YYYY-MM-DD, got \"{}\"", date)
+            }
+            ValidationError::RevisionOutOfOrder { as_of, latest } => {
+                format!("must not be before the latest revision ({}), got {}", latest, as_of)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field(), self.message())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// All errors on one line, e.g. "name: must not be empty; population: ..."
+pub fn describe_errors(errors: &[ValidationError]) -> String {
+    errors
+        .iter()
+        .map(ValidationError::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Error messages grouped by field, as returned in 400 bodies
+pub fn errors_by_field(errors: &[ValidationError]) -> BTreeMap<String, Vec<String>> {
+    let mut fields: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for error in errors {
+        fields
+            .entry(error.field().to_string())
+            .or_default()
+            .push(error.message());
+    }
+    fields
+}
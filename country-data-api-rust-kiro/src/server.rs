@@ -0,0 +1,172 @@
+use axum::Router;
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::api::{create_routes_with_config, ApiConfig};
+use crate::services::{IntoSharedStore, SharedStore};
+
+/// Seconds in-flight requests get to finish after a shutdown signal
+pub const SHUTDOWN_TIMEOUT_ENV: &str = "SHUTDOWN_TIMEOUT_SECS";
+
+const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where and how the server runs
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub addr: SocketAddr,
+    /// How long in-flight requests may take to finish once shutdown starts;
+    /// connections still open afterwards are dropped
+    pub drain_timeout: Duration,
+    pub api: ApiConfig,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            addr: SocketAddr::from(([127, 0, 0, 1], 5000)),
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            api: ApiConfig::default(),
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self {
+            api: ApiConfig::from_env(),
+            ..Self::default()
+        };
+        if let Ok(secs) = std::env::var(SHUTDOWN_TIMEOUT_ENV) {
+            match secs.trim().parse::<u64>() {
+                Ok(secs) => config.drain_timeout = Duration::from_secs(secs),
+                Err(_) => tracing::warn!("Ignoring invalid {}={:?}", SHUTDOWN_TIMEOUT_ENV, secs),
+            }
+        }
+        config
+    }
+}
+
+/// The router `serve` runs for `store`
+pub fn build_app(config: &ServerConfig, store: impl IntoSharedStore) -> Router {
+    create_routes_with_config(store, &config.api)
+}
+
+/// Binds `config.addr` and serves until `shutdown` completes; see `serve`.
+/// The bound address is logged, which tells the port picked for port 0.
+pub async fn run<F>(
+    config: ServerConfig,
+    store: impl IntoSharedStore,
+    shutdown: F,
+) -> io::Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let listener = TcpListener::bind(config.addr).await.map_err(|err| {
+        let message = match err.kind() {
+            io::ErrorKind::AddrInUse => format!(
+                "{} is already in use; stop the other server or pick another port",
+                config.addr
+            ),
+            _ => format!("Failed to listen on {}: {}", config.addr, err),
+        };
+        io::Error::new(err.kind(), message)
+    })?;
+    serve(listener, config, store, shutdown).await
+}
+
+/// Serves the API on `listener` until `shutdown` completes. New connections
+/// are then refused and in-flight requests get `config.drain_timeout` to
+/// finish. Finally the store is saved to its data file, if it has one and its
+/// initial data was loaded.
+pub async fn serve<F>(
+    listener: TcpListener,
+    config: ServerConfig,
+    store: impl IntoSharedStore,
+    shutdown: F,
+) -> io::Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let store = store.into_shared();
+    tracing::info!("Country Data API running on http://{}", listener.local_addr()?);
+    let app = build_app(&config, SharedStore::clone(&store));
+
+    let (draining_tx, draining_rx) = oneshot::channel();
+    let signal = async move {
+        shutdown.await;
+        tracing::info!("Shutting down; draining in-flight requests");
+        let _ = draining_tx.send(());
+    };
+    // Connection info gives the rate limiter each client's address
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(signal);
+    let drain_deadline = async move {
+        match draining_rx.await {
+            Ok(()) => tokio::time::sleep(config.drain_timeout).await,
+            // The server stopped without a shutdown signal
+            Err(_) => std::future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        result = server => result?,
+        () = drain_deadline => {
+            tracing::warn!(
+                "In-flight requests still running after {:?}; dropping them",
+                config.drain_timeout
+            );
+        }
+    }
+
+    save_on_shutdown(&store).await;
+    Ok(())
+}
+
+async fn save_on_shutdown(store: &SharedStore) {
+    // Saving a store that never finished loading would overwrite the file with nothing
+    let Some(path) = store.data_file().filter(|_| store.is_loaded()) else {
+        return;
+    };
+    match store.save_to_file(path).await {
+        Ok(()) => tracing::info!("Saved countries to {}", path.display()),
+        Err(err) => tracing::error!("Failed to save countries to {}: {}", path.display(), err),
+    }
+}
+
+/// Completes on Ctrl-C (SIGINT) or, on Unix, SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl-C: {}", err);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(err) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", err);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {}
+        () = terminate => {}
+    }
+}
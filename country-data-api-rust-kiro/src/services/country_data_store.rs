@@ -1,73 +1,716 @@
-use crate::models::Country;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use crate::models::{
+    distinct_names, BatchLookup, BulkInsertReport, Country, CountryStats, LanguageSummary,
+    LoadMode, LoadReport, Neighbors, PopulationRevision, RegionGroup, RegionSummary, SearchFields,
+    SnapshotId, SortField, SortOrder, UpdateCountryRequest, ValidationError,
+};
+use crate::models::validation::describe_errors;
+use crate::services::country_store::{CountryStore, SharedCountries};
+use crate::services::country_table::{taken_code, unknown_border, CountryTable};
+use crate::services::filters::{parse_region_list, search_countries};
+use crate::services::metrics::Metrics;
+use crate::services::summaries::{
+    country_stats, language_summaries, neighbors_of, region_groups, region_summaries,
+};
+use crate::utils::closest_match;
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::SeedableRng;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicBool, AtomicU64};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{RwLock, RwLockMappedWriteGuard, RwLockWriteGuard};
 
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoreError {
+    DuplicateName(String),
+    DuplicateCode(String),
+    NotFound(String),
+    Invalid(Vec<ValidationError>),
+    /// Never taken, or evicted to make room for newer snapshots
+    SnapshotNotFound(SnapshotId),
+    /// The backing storage failed, e.g. a database error
+    Storage(String),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::DuplicateName(name) => {
+                write!(f, "Country \"{}\" already exists", name)
+            }
+            StoreError::DuplicateCode(code) => {
+                write!(f, "Country code \"{}\" is already in use", code)
+            }
+            StoreError::NotFound(name) => write!(f, "Country \"{}\" not found", name),
+            StoreError::Invalid(errors) => write!(f, "{}", describe_errors(errors)),
+            StoreError::SnapshotNotFound(id) => write!(f, "Snapshot {} not found", id),
+            StoreError::Storage(message) => write!(f, "Storage error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+#[derive(Clone, Default)]
 pub struct CountryDataStore {
-    countries: Arc<RwLock<Vec<Country>>>,
+    /// Kept in insertion order; `get_all` returns them in that order.
+    /// Shared with snapshots and copied on the first write after one.
+    countries: Arc<RwLock<Arc<CountryTable>>>,
+    data_file: Option<Arc<PathBuf>>,
+    strict_borders: bool,
+    rng: SharedRng,
+    version: Arc<AtomicU64>,
+    loaded: Arc<AtomicBool>,
+    metrics: Metrics,
+    snapshots: Snapshots,
+}
+
+/// Random source shared by all clones of a store; seeded from the OS unless
+/// `CountryDataStore::with_rng_seed` is used
+#[derive(Clone)]
+pub(crate) struct SharedRng(Arc<Mutex<StdRng>>);
+
+impl SharedRng {
+    pub(crate) fn new(rng: StdRng) -> Self {
+        Self(Arc::new(Mutex::new(rng)))
+    }
+
+    /// Up to `count` distinct items of `candidates`, in random order
+    pub(crate) fn choose<T: Clone>(&self, candidates: &[T], count: usize) -> Vec<T> {
+        let mut rng = self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        candidates.choose_multiple(&mut *rng, count).cloned().collect()
+    }
+}
+
+impl Default for SharedRng {
+    fn default() -> Self {
+        Self::new(StdRng::from_os_rng())
+    }
+}
+
+/// Snapshots kept unless `CountryDataStore::with_max_snapshots` says otherwise
+pub const DEFAULT_MAX_SNAPSHOTS: usize = 10;
+
+/// A table as it was when a snapshot was taken
+type Snapshot = (SnapshotId, Arc<CountryTable>);
+
+/// Saved tables, oldest first, shared by all clones of a store. A snapshot
+/// holds the table the store had, so taking one copies nothing.
+#[derive(Clone)]
+struct Snapshots {
+    saved: Arc<Mutex<VecDeque<Snapshot>>>,
+    last_id: Arc<AtomicU64>,
+    max: usize,
+}
+
+impl Snapshots {
+    fn save(&self, table: Arc<CountryTable>) -> SnapshotId {
+        let id = SnapshotId(self.last_id.fetch_add(1, atomic::Ordering::SeqCst) + 1);
+        let mut saved = self.saved.lock().unwrap();
+        saved.push_back((id, table));
+        while saved.len() > self.max {
+            saved.pop_front();
+        }
+        id
+    }
+
+    fn get(&self, id: SnapshotId) -> Option<Arc<CountryTable>> {
+        let saved = self.saved.lock().unwrap();
+        saved
+            .iter()
+            .find(|(saved_id, _)| *saved_id == id)
+            .map(|(_, table)| Arc::clone(table))
+    }
+}
+
+impl Default for Snapshots {
+    fn default() -> Self {
+        Self {
+            saved: Arc::default(),
+            last_id: Arc::default(),
+            max: DEFAULT_MAX_SNAPSHOTS,
+        }
+    }
 }
 
 impl CountryDataStore {
     pub fn new() -> Self {
-        Self {
-            countries: Arc::new(RwLock::new(Vec::new())),
+        Self::default()
+    }
+
+    /// Persists the store to `path` after every mutation
+    pub fn with_data_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.data_file = Some(Arc::new(path.into()));
+        self
+    }
+
+    /// Rejects added or updated countries whose border codes don't belong to a
+    /// stored country. Data loaded in bulk at startup is not checked.
+    pub fn with_strict_borders(mut self) -> Self {
+        self.strict_borders = true;
+        self
+    }
+
+    /// File the store is persisted to, if any
+    pub fn data_file(&self) -> Option<&Path> {
+        self.data_file.as_deref().map(PathBuf::as_path)
+    }
+
+    /// Keeps up to `max` snapshots (at least one), evicting the oldest first
+    pub fn with_max_snapshots(mut self, max: usize) -> Self {
+        self.snapshots.max = max.max(1);
+        self
+    }
+
+    /// Seeds the random source used by `random_countries`, making its picks reproducible
+    pub fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.rng = SharedRng::new(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Writes all countries to `path` as JSON, via a temp file and rename
+    pub async fn save_to_file(&self, path: &Path) -> io::Result<()> {
+        let store = self.countries.read().await;
+        write_atomically(path, &store).await
+    }
+
+    /// Changes after every mutation; used as the ETag of responses built from the store.
+    /// Read it before the data so a concurrent write can only make it older than a
+    /// response, never newer.
+    pub fn version(&self) -> u64 {
+        self.version.load(atomic::Ordering::SeqCst)
+    }
+
+    /// Whether `load_countries` (or one of the file loaders) has completed
+    pub fn is_loaded(&self) -> bool {
+        self.loaded.load(atomic::Ordering::SeqCst)
+    }
+
+    /// Metrics updated by this store; the API adds its request metrics here too
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Records a mutation: bumps the version, updates the metrics and saves the
+    /// store to the data file, if one is configured. Called with the write lock
+    /// held so saves happen in mutation order.
+    async fn persist(&self, operation: &str, countries: &[Country]) {
+        self.record_mutation(operation, countries.len());
+        if let Some(path) = &self.data_file
+            && let Err(err) = write_atomically(path, countries).await
+        {
+            tracing::error!("Failed to save countries to {}: {}", path.display(), err);
         }
     }
 
-    pub async fn load_countries(&self, countries: Vec<Country>) {
-        let valid_countries: Vec<Country> = countries
-            .into_iter()
-            .filter(|c| {
-                if !c.validate() {
-                    tracing::error!("Invalid country data rejected: {}", c.name);
-                    false
-                } else {
-                    true
-                }
-            })
-            .collect();
+    /// Bumps the version and updates the metrics, without saving
+    fn record_mutation(&self, operation: &str, count: usize) {
+        self.version.fetch_add(1, atomic::Ordering::SeqCst);
+        self.metrics.record_mutation(operation, count);
+    }
+
+    /// Write access to the table, copying it first if a snapshot still shares it
+    async fn write_table(&self) -> RwLockMappedWriteGuard<'_, CountryTable> {
+        RwLockWriteGuard::map(self.countries.write().await, Arc::make_mut)
+    }
+
+    /// Saves the current countries for a later `rollback`. Only the newest
+    /// snapshots are kept; see `with_max_snapshots`.
+    pub async fn snapshot(&self) -> SnapshotId {
+        let table = self.countries.read().await;
+        self.snapshots.save(Arc::clone(&table))
+    }
+
+    /// Restores the countries saved by snapshot `id`. The snapshot is kept,
+    /// so the store can be rolled back to it again.
+    pub async fn rollback(&self, id: SnapshotId) -> Result<(), StoreError> {
+        let saved = self
+            .snapshots
+            .get(id)
+            .ok_or(StoreError::SnapshotNotFound(id))?;
+        let mut table = self.countries.write().await;
+        *table = saved;
+        self.persist("rollback", &table).await;
+        Ok(())
+    }
+
+    /// Replaces the stored countries with the valid entries of `countries`.
+    /// Invalid entries and repeated names are left out and listed in the report.
+    pub async fn load_countries(&self, countries: Vec<Country>) -> LoadReport {
+        self.load_countries_with_mode(countries, LoadMode::Replace).await
+    }
+
+    /// Loads countries into the store. Append keeps the existing data and
+    /// rejects names already present, replace discards it, and merge updates
+    /// countries with a matching name in place. Invalid entries are rejected,
+    /// and so is every repeat of a name within `countries` (ignoring case,
+    /// accents and extra whitespace): the first occurrence is the one kept.
+    pub async fn load_countries_with_mode(
+        &self,
+        countries: Vec<Country>,
+        mode: LoadMode,
+    ) -> LoadReport {
+        self.load(countries, mode, true).await
+    }
+
+    /// Replaces the stored countries like `load_countries`, but leaves the data
+    /// file alone. Used when the file couldn't be read, so the stand-in data
+    /// doesn't overwrite what's there.
+    pub async fn load_countries_unsaved(&self, countries: Vec<Country>) -> LoadReport {
+        self.load(countries, LoadMode::Replace, false).await
+    }
+
+    async fn load(&self, countries: Vec<Country>, mode: LoadMode, save: bool) -> LoadReport {
+        let mut store = self.write_table().await;
+        let report = store.load(countries, mode);
+        if save {
+            self.persist("load", &store).await;
+        } else {
+            self.record_mutation("load", store.len());
+        }
+        self.loaded.store(true, atomic::Ordering::SeqCst);
+        report
+    }
+
+    /// Adds a country, rejecting invalid countries, names already present (ignoring
+    /// case, accents and extra whitespace) and ISO codes already in use. Returns
+    /// the country as stored, with its flag emoji derived.
+    pub async fn add_country(&self, mut country: Country) -> Result<Country, StoreError> {
+        country.derive_flag_emoji();
+        country.validate().map_err(StoreError::Invalid)?;
+        let mut store = self.write_table().await;
+
+        if store.contains_name(&country.name) {
+            return Err(StoreError::DuplicateName(country.name));
+        }
+        if let Some(code) = taken_code(&store, &country, None) {
+            return Err(StoreError::DuplicateCode(code));
+        }
+        self.check_borders(&store, &country)?;
+
+        store.push(country.clone());
+        self.persist("add", &store).await;
+        Ok(country)
+    }
+
+    /// Inserts a batch of countries under a single write lock.
+    /// Invalid entries and duplicate names (within the batch, and against the
+    /// store in append mode) are skipped and reported; in merge mode a name
+    /// already in the store updates that country instead. In strict border
+    /// mode, borders may refer to other countries in the same batch.
+    pub async fn bulk_insert(&self, countries: Vec<Country>, mode: LoadMode) -> BulkInsertReport {
+        let mut store = self.write_table().await;
+        let report = store.bulk_insert(countries, mode, self.strict_borders);
+        self.persist("bulk", &store).await;
+        report
+    }
+
+    /// Replaces the country called `name` (case-insensitive) with `country`,
+    /// keeping its population history. The new name may differ, but must not
+    /// collide with another country.
+    pub async fn update(&self, name: &str, mut country: Country) -> Result<Country, StoreError> {
+        let mut store = self.write_table().await;
+        let index = Self::index_of(&store, name)?;
+        self.check_borders(&store, &country)?;
+        country.population_history = store[index].population_history.clone();
+        let updated = Self::replace_at(&mut store, index, country)?;
+        self.persist("update", &store).await;
+        Ok(updated)
+    }
+
+    /// Merges a partial update onto the country called `name` (case-insensitive)
+    pub async fn patch(
+        &self,
+        name: &str,
+        update: UpdateCountryRequest,
+    ) -> Result<Country, StoreError> {
+        let mut store = self.write_table().await;
+        let index = Self::index_of(&store, name)?;
+        let country = update.apply(&store[index]);
+        self.check_borders(&store, &country)?;
+        let updated = Self::replace_at(&mut store, index, country)?;
+        self.persist("patch", &store).await;
+        Ok(updated)
+    }
+
+    /// Sets the population of the country called `name` (case-insensitive) and
+    /// appends `revision` to its history
+    pub async fn revise_population(
+        &self,
+        name: &str,
+        revision: PopulationRevision,
+    ) -> Result<Country, StoreError> {
+        let mut store = self.write_table().await;
+        let index = Self::index_of(&store, name)?;
+        let mut country = store[index].clone();
+        country.revise_population(revision).map_err(StoreError::Invalid)?;
+        store.replace(index, country.clone());
+        self.persist("population", &store).await;
+        Ok(country)
+    }
+
+    /// Removes the country called `name` (case-insensitive), returning it if it existed
+    pub async fn remove(&self, name: &str) -> Option<Country> {
+        let mut store = self.write_table().await;
+        let index = Self::index_of(&store, name).ok()?;
+        let removed = store.remove(index);
+        self.persist("remove", &store).await;
+        Some(removed)
+    }
+
+    fn check_borders(&self, countries: &[Country], country: &Country) -> Result<(), StoreError> {
+        match unknown_border(countries, country) {
+            Some(code) if self.strict_borders => {
+                Err(StoreError::Invalid(vec![ValidationError::UnknownBorder(code.to_string())]))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn index_of(countries: &CountryTable, name: &str) -> Result<usize, StoreError> {
+        countries
+            .position(name)
+            .ok_or_else(|| StoreError::NotFound(name.to_string()))
+    }
+
+    fn replace_at(
+        countries: &mut CountryTable,
+        index: usize,
+        mut country: Country,
+    ) -> Result<Country, StoreError> {
+        country.derive_flag_emoji();
+        country.validate().map_err(StoreError::Invalid)?;
+
+        let collides = countries
+            .position(&country.name)
+            .is_some_and(|i| i != index);
+        if collides {
+            return Err(StoreError::DuplicateName(country.name));
+        }
+        if let Some(code) = taken_code(countries, &country, Some(index)) {
+            return Err(StoreError::DuplicateCode(code));
+        }
+
+        countries.replace(index, country.clone());
+        Ok(country)
+    }
 
-        let mut store = self.countries.write().await;
-        *store = valid_countries;
+    /// Number of stored countries
+    pub async fn count(&self) -> usize {
+        self.countries.read().await.len()
     }
 
     pub async fn get_all(&self) -> Vec<Country> {
-        self.countries.read().await.clone()
+        self.countries.read().await.to_vec()
     }
 
+    /// Looks a country up by name, ignoring case, accents and extra whitespace
     pub async fn get_by_name(&self, name: &str) -> Option<Country> {
-        let name_lower = name.to_lowercase();
+        self.countries.read().await.get(name).cloned()
+    }
+
+    /// Looks each of `names` up like `get_by_name` under one read lock, once
+    /// per distinct name; found countries keep the order of their names
+    pub async fn get_many(&self, names: &[String]) -> BatchLookup {
+        let countries = self.countries.read().await;
+        let mut lookup = BatchLookup::default();
+        for name in distinct_names(names) {
+            match countries.get(name) {
+                Some(country) => lookup.found.push(country.clone()),
+                None => lookup.missing.push(name.to_string()),
+            }
+        }
+        lookup
+    }
+
+    /// Looks a country up by its alpha-2 or alpha-3 code (case-insensitive)
+    pub async fn get_by_code(&self, code: &str) -> Option<Country> {
         self.countries
             .read()
             .await
             .iter()
-            .find(|c| c.name.to_lowercase() == name_lower)
+            .find(|c| c.has_code(code))
             .cloned()
     }
 
-    pub async fn filter_by_region(&self, region: &str) -> Vec<Country> {
-        let region_lower = region.to_lowercase();
+    /// The country called `name` with its borders resolved to stored countries.
+    /// Border codes that match no stored country are listed in `unresolved`.
+    pub async fn neighbors(&self, name: &str) -> Option<Neighbors> {
+        let countries = self.countries.read().await;
+        let country = countries.get(name)?;
+        Some(neighbors_of(&countries, country))
+    }
+
+    /// The stored country whose name is the closest likely typo of `name`
+    pub async fn closest_match(&self, name: &str) -> Option<Country> {
+        let countries = self.countries.read().await;
+        let best = closest_match(name, countries.iter().map(|c| c.name.as_str()))?;
+        countries.iter().find(|c| c.name == best).cloned()
+    }
+
+    /// Countries whose capital is `capital`, ignoring case, accents and extra
+    /// whitespace, in insertion order. Usually one, but capitals aren't unique.
+    pub async fn get_by_capital(&self, capital: &str) -> Vec<Country> {
         self.countries
             .read()
             .await
-            .iter()
-            .filter(|c| c.region.to_lowercase() == region_lower)
+            .with_capital(capital)
+            .into_iter()
             .cloned()
             .collect()
     }
 
-    pub async fn search_by_name(&self, query: &str) -> Vec<Country> {
-        if query.trim().is_empty() {
-            return self.get_all().await;
-        }
-
-        let query_lower = query.to_lowercase();
+    /// Countries in any of the comma-separated regions (case-insensitive)
+    pub async fn filter_by_region(&self, region: &str) -> Vec<Country> {
+        let regions = parse_region_list(region);
         self.countries
             .read()
             .await
-            .iter()
-            .filter(|c| c.name.to_lowercase().contains(&query_lower))
+            .in_regions(&regions)
+            .into_iter()
             .cloned()
             .collect()
     }
+
+    /// Up to `count` distinct countries chosen uniformly at random from those
+    /// matching `filter`, in random order. Fewer are returned if fewer match.
+    pub async fn random_countries<F>(&self, count: usize, filter: F) -> Vec<Country>
+    where
+        F: Fn(&Country) -> bool,
+    {
+        let countries = self.countries.read().await;
+        let candidates: Vec<&Country> = countries.iter().filter(|c| filter(c)).collect();
+        self.rng.choose(&candidates, count).into_iter().cloned().collect()
+    }
+
+    /// Distinct regions and their subregions, with country counts, sorted by name.
+    /// Grouping is case-insensitive and uses the first spelling seen; countries
+    /// without a subregion count towards their region only.
+    pub async fn regions(&self) -> Vec<RegionSummary> {
+        region_summaries(&self.countries.read().await)
+    }
+
+    /// Every country grouped by region, computed under one read lock. Regions are
+    /// grouped case-insensitively by their first spelling and sorted, like
+    /// `regions`; the countries in each are sorted by name.
+    pub async fn countries_by_region(&self) -> Vec<RegionGroup> {
+        region_groups(&self.countries.read().await)
+    }
+
+    /// Aggregate statistics, optionally limited to a comma-separated region list,
+    /// computed under one read lock. Regions are grouped case-insensitively by
+    /// their first spelling; population ties go to the country stored first.
+    pub async fn stats(&self, region: Option<&str>) -> CountryStats {
+        country_stats(&self.countries.read().await, region)
+    }
+
+    /// Every language spoken in the store, optionally limited to a comma-separated
+    /// region list, most countries first and ties by name. Languages are grouped
+    /// case-insensitively and shown with their most common spelling (the first
+    /// seen on a tie); a country listing a language twice counts once.
+    pub async fn language_stats(&self, region: Option<&str>) -> Vec<LanguageSummary> {
+        language_summaries(&self.countries.read().await, region)
+    }
+
+    pub async fn search_by_name(&self, query: &str, limit: Option<usize>) -> Vec<Country> {
+        self.search(query, SearchFields::Name, limit).await
+    }
+
+    /// Case-insensitive substring search over `fields`, name matches first,
+    /// keeping at most `limit` matches. Only the kept matches are cloned.
+    pub async fn search(
+        &self,
+        query: &str,
+        fields: SearchFields,
+        limit: Option<usize>,
+    ) -> Vec<Country> {
+        let table = self.countries.read().await;
+        let matches = search_countries(table.iter().collect(), query, fields, limit);
+        matches.into_iter().cloned().collect()
+    }
+}
+
+/// Forwards to the inherent methods, which use the table's indexes
+#[async_trait]
+impl CountryStore for CountryDataStore {
+    fn version(&self) -> u64 {
+        CountryDataStore::version(self)
+    }
+
+    fn is_loaded(&self) -> bool {
+        CountryDataStore::is_loaded(self)
+    }
+
+    fn metrics(&self) -> &Metrics {
+        CountryDataStore::metrics(self)
+    }
+
+    fn data_file(&self) -> Option<&Path> {
+        CountryDataStore::data_file(self)
+    }
+
+    async fn save_to_file(&self, path: &Path) -> io::Result<()> {
+        CountryDataStore::save_to_file(self, path).await
+    }
+
+    async fn count(&self) -> usize {
+        CountryDataStore::count(self).await
+    }
+
+    async fn get_all(&self) -> Vec<Country> {
+        CountryDataStore::get_all(self).await
+    }
+
+    /// The current table without copying it. Later writes copy the table
+    /// instead of changing it, so it can be read without holding the lock.
+    async fn shared_countries(&self) -> SharedCountries {
+        Arc::clone(&*self.countries.read().await) as SharedCountries
+    }
+
+    async fn get_by_name(&self, name: &str) -> Option<Country> {
+        CountryDataStore::get_by_name(self, name).await
+    }
+
+    async fn get_many(&self, names: &[String]) -> BatchLookup {
+        CountryDataStore::get_many(self, names).await
+    }
+
+    async fn get_by_code(&self, code: &str) -> Option<Country> {
+        CountryDataStore::get_by_code(self, code).await
+    }
+
+    async fn get_by_capital(&self, capital: &str) -> Vec<Country> {
+        CountryDataStore::get_by_capital(self, capital).await
+    }
+
+    async fn filter_by_region(&self, region: &str) -> Vec<Country> {
+        CountryDataStore::filter_by_region(self, region).await
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        fields: SearchFields,
+        limit: Option<usize>,
+    ) -> Vec<Country> {
+        CountryDataStore::search(self, query, fields, limit).await
+    }
+
+    async fn closest_match(&self, name: &str) -> Option<Country> {
+        CountryDataStore::closest_match(self, name).await
+    }
+
+    async fn neighbors(&self, name: &str) -> Option<Neighbors> {
+        CountryDataStore::neighbors(self, name).await
+    }
+
+    async fn random_countries(
+        &self,
+        count: usize,
+        filter: &(dyn for<'c> Fn(&'c Country) -> bool + Sync),
+    ) -> Vec<Country> {
+        CountryDataStore::random_countries(self, count, filter).await
+    }
+
+    async fn regions(&self) -> Vec<RegionSummary> {
+        CountryDataStore::regions(self).await
+    }
+
+    async fn countries_by_region(&self) -> Vec<RegionGroup> {
+        CountryDataStore::countries_by_region(self).await
+    }
+
+    async fn stats(&self, region: Option<&str>) -> CountryStats {
+        CountryDataStore::stats(self, region).await
+    }
+
+    async fn language_stats(&self, region: Option<&str>) -> Vec<LanguageSummary> {
+        CountryDataStore::language_stats(self, region).await
+    }
+
+    async fn load_countries_with_mode(
+        &self,
+        countries: Vec<Country>,
+        mode: LoadMode,
+    ) -> LoadReport {
+        CountryDataStore::load_countries_with_mode(self, countries, mode).await
+    }
+
+    async fn load_countries_unsaved(&self, countries: Vec<Country>) -> LoadReport {
+        CountryDataStore::load_countries_unsaved(self, countries).await
+    }
+
+    async fn add_country(&self, country: Country) -> Result<Country, StoreError> {
+        CountryDataStore::add_country(self, country).await
+    }
+
+    async fn bulk_insert(&self, countries: Vec<Country>, mode: LoadMode) -> BulkInsertReport {
+        CountryDataStore::bulk_insert(self, countries, mode).await
+    }
+
+    async fn update(&self, name: &str, country: Country) -> Result<Country, StoreError> {
+        CountryDataStore::update(self, name, country).await
+    }
+
+    async fn patch(
+        &self,
+        name: &str,
+        update: UpdateCountryRequest,
+    ) -> Result<Country, StoreError> {
+        CountryDataStore::patch(self, name, update).await
+    }
+
+    async fn revise_population(
+        &self,
+        name: &str,
+        revision: PopulationRevision,
+    ) -> Result<Country, StoreError> {
+        CountryDataStore::revise_population(self, name, revision).await
+    }
+
+    async fn remove(&self, name: &str) -> Option<Country> {
+        CountryDataStore::remove(self, name).await
+    }
+
+    async fn snapshot(&self) -> Result<SnapshotId, StoreError> {
+        Ok(CountryDataStore::snapshot(self).await)
+    }
+
+    async fn rollback(&self, id: SnapshotId) -> Result<(), StoreError> {
+        CountryDataStore::rollback(self, id).await
+    }
+}
+
+/// Sorts countries in place. The sort is stable, so countries that compare
+/// equal keep their relative order; names and regions compare case-insensitively.
+/// Countries without a population density go last whatever the order.
+pub fn sort_countries(countries: &mut [Country], field: SortField, order: SortOrder) {
+    let directed = |ordering: Ordering| match order {
+        SortOrder::Asc => ordering,
+        SortOrder::Desc => ordering.reverse(),
+    };
+
+    countries.sort_by(|a, b| match field {
+        SortField::Name => directed(a.name.to_lowercase().cmp(&b.name.to_lowercase())),
+        SortField::Population => directed(a.population.cmp(&b.population)),
+        SortField::Region => directed(a.region.to_lowercase().cmp(&b.region.to_lowercase())),
+        SortField::Density => match (a.population_density(), b.population_density()) {
+            (Some(x), Some(y)) => directed(x.total_cmp(&y)),
+            (x, y) => x.is_none().cmp(&y.is_none()),
+        },
+    });
+}
+
+pub(crate) async fn write_atomically(path: &Path, countries: &[Country]) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(countries)?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+
+    tokio::fs::write(&tmp, json).await?;
+    tokio::fs::rename(&tmp, path).await
 }
@@ -0,0 +1,82 @@
+use crate::models::{Country, RejectedCountry};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Countries read from a data file, plus the records that were skipped
+#[derive(Debug, Default)]
+pub struct LoadedCountries {
+    pub countries: Vec<Country>,
+    pub rejected: Vec<RejectedCountry>,
+}
+
+/// The data file as a whole could not be used
+#[derive(Debug)]
+pub enum CountryFileError {
+    Unreadable { path: PathBuf, source: io::Error },
+    InvalidJson { path: PathBuf, source: serde_json::Error },
+}
+
+impl fmt::Display for CountryFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CountryFileError::Unreadable { path, source } => {
+                write!(f, "Cannot read countries file {}: {}", path.display(), source)
+            }
+            CountryFileError::InvalidJson { path, source } => write!(
+                f,
+                "Countries file {} is not a valid JSON array: {}",
+                path.display(),
+                source
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CountryFileError {}
+
+/// Reads a JSON array of countries from `path`.
+/// The file must be readable and valid JSON; individual records that don't
+/// deserialize or fail validation are skipped and reported instead.
+pub fn read_countries_file(path: &Path) -> Result<LoadedCountries, CountryFileError> {
+    let contents = std::fs::read(path).map_err(|source| CountryFileError::Unreadable {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let records: Vec<serde_json::Value> =
+        serde_json::from_slice(&contents).map_err(|source| CountryFileError::InvalidJson {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+    let mut loaded = LoadedCountries::default();
+    for (index, record) in records.into_iter().enumerate() {
+        let name = record
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let rejected = match serde_json::from_value::<Country>(record) {
+            Ok(country) => match country.validate() {
+                Ok(()) => {
+                    loaded.countries.push(country);
+                    continue;
+                }
+                Err(errors) => RejectedCountry::invalid(index, name, errors),
+            },
+            Err(err) => RejectedCountry::new(index, name, err.to_string()),
+        };
+
+        tracing::warn!(
+            "Skipping country #{} ({:?}) in {}: {}",
+            index,
+            rejected.name,
+            path.display(),
+            rejected.reason
+        );
+        loaded.rejected.push(rejected);
+    }
+
+    Ok(loaded)
+}
@@ -0,0 +1,238 @@
+use async_trait::async_trait;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::models::{
+    distinct_names, BatchLookup, BulkInsertReport, Country, CountryStats, LanguageSummary,
+    LoadMode, LoadReport, Neighbors, PopulationRevision, RegionGroup, RegionSummary, SearchFields,
+    SnapshotId, UpdateCountryRequest,
+};
+use crate::services::country_data_store::{write_atomically, StoreError};
+use crate::services::filters::search_countries;
+use crate::services::metrics::Metrics;
+use crate::services::summaries::{
+    country_stats, language_summaries, neighbors_of, region_groups, region_summaries,
+};
+use crate::utils::closest_match;
+
+/// The store behind the API, shared by every handler
+pub type SharedStore = Arc<dyn CountryStore>;
+
+/// All countries as they were when taken; later writes don't change it
+pub type SharedCountries = Arc<dyn AsRef<[Country]> + Send + Sync>;
+
+/// Where the API keeps its countries: in memory with `CountryDataStore`, or
+/// in a database with `SqliteCountryStore` (the `sqlite` feature).
+///
+/// Names are matched ignoring case, accents and extra whitespace, so each
+/// spelling of a name refers to the same single country, which keeps the
+/// spelling of the write that last set it. Lists keep insertion order. The
+/// summaries default to working over `get_all`; stores with indexes override
+/// them.
+#[async_trait]
+pub trait CountryStore: Send + Sync {
+    /// Changes after every mutation; see `CountryDataStore::version`
+    fn version(&self) -> u64;
+
+    /// Whether the store holds its initial data
+    fn is_loaded(&self) -> bool;
+
+    /// Metrics updated by this store; the API adds its request metrics here too
+    fn metrics(&self) -> &Metrics;
+
+    /// File the store saves to on shutdown, if any
+    fn data_file(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Writes all countries to `path` as JSON
+    async fn save_to_file(&self, path: &Path) -> io::Result<()> {
+        write_atomically(path, &self.get_all().await).await
+    }
+
+    /// Replaces the stored countries with those in the JSON file at `path`
+    async fn load_from_file(&self, path: &Path) -> io::Result<LoadReport> {
+        let contents = tokio::fs::read(path).await?;
+        let countries: Vec<Country> = serde_json::from_slice(&contents)?;
+        Ok(self.load_countries(countries).await)
+    }
+
+    /// Loads the file at `path`, or `fallback` if it is missing or can't be parsed.
+    /// A file that can't be read or parsed is left as it is, not replaced by `fallback`.
+    async fn load_from_file_or(&self, path: &Path, fallback: Vec<Country>) -> LoadReport {
+        match self.load_from_file(path).await {
+            Ok(report) => {
+                tracing::info!("Loaded countries from {}", path.display());
+                report
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                tracing::info!("{} does not exist, using initial data", path.display());
+                self.load_countries(fallback).await
+            }
+            Err(err) => {
+                tracing::error!(
+                    "Failed to load {}: {}; using initial data",
+                    path.display(),
+                    err
+                );
+                self.load_countries_unsaved(fallback).await
+            }
+        }
+    }
+
+    async fn count(&self) -> usize;
+
+    async fn get_all(&self) -> Vec<Country>;
+
+    /// All countries, for reading lazily, e.g. while streaming a response
+    async fn shared_countries(&self) -> SharedCountries {
+        Arc::new(self.get_all().await)
+    }
+
+    async fn get_by_name(&self, name: &str) -> Option<Country>;
+
+    /// Looks each of `names` up like `get_by_name`, once per distinct name
+    async fn get_many(&self, names: &[String]) -> BatchLookup {
+        let mut lookup = BatchLookup::default();
+        for name in distinct_names(names) {
+            match self.get_by_name(name).await {
+                Some(country) => lookup.found.push(country),
+                None => lookup.missing.push(name.to_string()),
+            }
+        }
+        lookup
+    }
+
+    /// Looks a country up by its alpha-2 or alpha-3 code (case-insensitive)
+    async fn get_by_code(&self, code: &str) -> Option<Country>;
+
+    /// Countries whose capital is `capital`; capitals aren't unique
+    async fn get_by_capital(&self, capital: &str) -> Vec<Country>;
+
+    /// Countries in any of the comma-separated regions (case-insensitive)
+    async fn filter_by_region(&self, region: &str) -> Vec<Country>;
+
+    async fn search_by_name(&self, query: &str, limit: Option<usize>) -> Vec<Country> {
+        self.search(query, SearchFields::Name, limit).await
+    }
+
+    /// Case-insensitive substring search over `fields`, name matches first,
+    /// keeping at most `limit` matches
+    async fn search(
+        &self,
+        query: &str,
+        fields: SearchFields,
+        limit: Option<usize>,
+    ) -> Vec<Country> {
+        search_countries(self.get_all().await, query, fields, limit)
+    }
+
+    /// The stored country whose name is the closest likely typo of `name`
+    async fn closest_match(&self, name: &str) -> Option<Country> {
+        let countries = self.get_all().await;
+        let best = closest_match(name, countries.iter().map(|c| c.name.as_str()))?.to_string();
+        countries.into_iter().find(|c| c.name == best)
+    }
+
+    /// The country called `name` with its borders resolved to stored countries
+    async fn neighbors(&self, name: &str) -> Option<Neighbors> {
+        let country = self.get_by_name(name).await?;
+        Some(neighbors_of(&self.get_all().await, &country))
+    }
+
+    /// Up to `count` distinct countries matching `filter`, in random order
+    async fn random_countries(
+        &self,
+        count: usize,
+        filter: &(dyn for<'c> Fn(&'c Country) -> bool + Sync),
+    ) -> Vec<Country>;
+
+    /// Regions and their subregions with country counts, sorted by name
+    async fn regions(&self) -> Vec<RegionSummary> {
+        region_summaries(&self.get_all().await)
+    }
+
+    /// Every country grouped by region, regions and the countries in each sorted
+    /// by name
+    async fn countries_by_region(&self) -> Vec<RegionGroup> {
+        region_groups(&self.get_all().await)
+    }
+
+    /// Aggregate statistics, optionally limited to a comma-separated region list
+    async fn stats(&self, region: Option<&str>) -> CountryStats {
+        country_stats(&self.get_all().await, region)
+    }
+
+    /// Every language spoken, with the countries speaking it, most countries first
+    async fn language_stats(&self, region: Option<&str>) -> Vec<LanguageSummary> {
+        language_summaries(&self.get_all().await, region)
+    }
+
+    /// Replaces the stored countries with the valid entries of `countries`
+    async fn load_countries(&self, countries: Vec<Country>) -> LoadReport {
+        self.load_countries_with_mode(countries, LoadMode::Replace).await
+    }
+
+    /// See `CountryDataStore::load_countries_with_mode`
+    async fn load_countries_with_mode(
+        &self,
+        countries: Vec<Country>,
+        mode: LoadMode,
+    ) -> LoadReport;
+
+    /// See `CountryDataStore::load_countries_unsaved`; stores that don't save to
+    /// a data file load as usual
+    async fn load_countries_unsaved(&self, countries: Vec<Country>) -> LoadReport {
+        self.load_countries(countries).await
+    }
+
+    /// See `CountryDataStore::add_country`
+    async fn add_country(&self, country: Country) -> Result<Country, StoreError>;
+
+    /// See `CountryDataStore::bulk_insert`
+    async fn bulk_insert(&self, countries: Vec<Country>, mode: LoadMode) -> BulkInsertReport;
+
+    /// See `CountryDataStore::update`
+    async fn update(&self, name: &str, country: Country) -> Result<Country, StoreError>;
+
+    /// See `CountryDataStore::patch`
+    async fn patch(
+        &self,
+        name: &str,
+        update: UpdateCountryRequest,
+    ) -> Result<Country, StoreError>;
+
+    /// See `CountryDataStore::revise_population`
+    async fn revise_population(
+        &self,
+        name: &str,
+        revision: PopulationRevision,
+    ) -> Result<Country, StoreError>;
+
+    /// Removes the country called `name`, returning it if it existed
+    async fn remove(&self, name: &str) -> Option<Country>;
+
+    /// Saves the current countries for a later `rollback`
+    async fn snapshot(&self) -> Result<SnapshotId, StoreError>;
+
+    /// Restores the countries saved by snapshot `id`
+    async fn rollback(&self, id: SnapshotId) -> Result<(), StoreError>;
+}
+
+/// A store, or one that is already shared, ready to hand to the API
+pub trait IntoSharedStore {
+    fn into_shared(self) -> SharedStore;
+}
+
+impl<S: CountryStore + 'static> IntoSharedStore for S {
+    fn into_shared(self) -> SharedStore {
+        Arc::new(self)
+    }
+}
+
+impl IntoSharedStore for SharedStore {
+    fn into_shared(self) -> SharedStore {
+        self
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+
+use crate::models::{
+    BulkInsertReport, Country, LoadMode, LoadReport, RejectedCountry, ValidationError,
+};
+use crate::services::country_data_store::StoreError;
+use crate::utils::{name_key, normalize_for_match};
+
+/// The store's countries in insertion order, with an index from `name_key`
+/// to position for constant-time lookups by name, one from the folded region
+/// and one from the capital's `name_key` to positions. All changes go through
+/// the methods below so the indexes can't drift from the list; reads see the
+/// list as a slice.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CountryTable {
+    countries: Vec<Country>,
+    /// Keys are unique: loads and inserts reject names already taken
+    by_name: HashMap<String, usize>,
+    by_region: PositionIndex,
+    by_capital: PositionIndex,
+}
+
+pub(crate) fn region_key(country: &Country) -> String {
+    normalize_for_match(&country.region)
+}
+
+pub(crate) fn capital_key(country: &Country) -> String {
+    name_key(&country.capital)
+}
+
+impl CountryTable {
+    pub(crate) fn new(countries: Vec<Country>) -> Self {
+        let mut table = Self {
+            countries,
+            ..Self::default()
+        };
+        table.reindex();
+        table
+    }
+
+    /// Position of the country called `name`, ignoring case, accents and extra whitespace
+    pub(crate) fn position(&self, name: &str) -> Option<usize> {
+        self.by_name.get(&name_key(name)).copied()
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&Country> {
+        self.position(name).map(|index| &self.countries[index])
+    }
+
+    pub(crate) fn contains_name(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+
+    /// Countries whose lowercased region is one of `regions`, in insertion order
+    pub(crate) fn in_regions(&self, regions: &[String]) -> Vec<&Country> {
+        let mut positions: Vec<usize> = regions
+            .iter()
+            .flat_map(|region| self.by_region.get(region))
+            .copied()
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions.into_iter().map(|index| &self.countries[index]).collect()
+    }
+
+    /// Countries whose capital is `capital`, ignoring case, accents and extra
+    /// whitespace, in insertion order
+    pub(crate) fn with_capital(&self, capital: &str) -> Vec<&Country> {
+        self.by_capital
+            .get(&name_key(capital))
+            .iter()
+            .map(|index| &self.countries[*index])
+            .collect()
+    }
+
+    pub(crate) fn push(&mut self, country: Country) {
+        let index = self.countries.len();
+        self.by_name.entry(name_key(&country.name)).or_insert(index);
+        self.by_region.insert(region_key(&country), index);
+        self.by_capital.insert(capital_key(&country), index);
+        self.countries.push(country);
+    }
+
+    pub(crate) fn replace(&mut self, index: usize, country: Country) {
+        let old = &self.countries[index];
+        let renamed = name_key(&old.name) != name_key(&country.name);
+        self.by_region.rekey(region_key(old), region_key(&country), index);
+        self.by_capital.rekey(capital_key(old), capital_key(&country), index);
+        self.countries[index] = country;
+        if renamed {
+            self.reindex_names();
+        }
+    }
+
+    /// Removes the country at `index`; later positions shift down by one
+    pub(crate) fn remove(&mut self, index: usize) -> Country {
+        let removed = self.countries.remove(index);
+        self.by_region.remove(&region_key(&removed), index);
+        self.by_region.shift_down_after(index);
+        self.by_capital.remove(&capital_key(&removed), index);
+        self.by_capital.shift_down_after(index);
+        self.reindex_names();
+        removed
+    }
+
+    pub(crate) fn replace_all(&mut self, countries: Vec<Country>) {
+        *self = Self::new(countries);
+    }
+
+    pub(crate) fn to_vec(&self) -> Vec<Country> {
+        self.countries.clone()
+    }
+
+    /// Loads `countries` in `mode`, leaving out invalid ones; see
+    /// `CountryDataStore::load_countries_with_mode`
+    pub(crate) fn load(&mut self, countries: Vec<Country>, mode: LoadMode) -> LoadReport {
+        let mut report = LoadReport::default();
+        let mut valid_countries = Vec::with_capacity(countries.len());
+        let mut seen = HashSet::new();
+        for (index, mut country) in countries.into_iter().enumerate() {
+            if let Err(errors) = country.validate() {
+                report.rejected.push(RejectedCountry::invalid(index, country.name, errors));
+            } else if !seen.insert(name_key(&country.name)) {
+                let reason = StoreError::DuplicateName(country.name.clone()).to_string();
+                report.rejected.push(RejectedCountry::new(index, country.name, reason));
+            } else {
+                country.derive_flag_emoji();
+                valid_countries.push((index, country));
+            }
+        }
+
+        match mode {
+            LoadMode::Replace => {
+                report.loaded = valid_countries.len();
+                self.replace_all(valid_countries.into_iter().map(|(_, c)| c).collect());
+            }
+            LoadMode::Append => {
+                for (index, country) in valid_countries {
+                    if self.contains_name(&country.name) {
+                        let reason = StoreError::DuplicateName(country.name.clone()).to_string();
+                        report.rejected.push(RejectedCountry::new(index, country.name, reason));
+                    } else {
+                        self.push(country);
+                        report.loaded += 1;
+                    }
+                }
+            }
+            LoadMode::Merge => {
+                for (_, country) in valid_countries {
+                    match self.position(&country.name) {
+                        Some(index) => {
+                            self.replace(index, country);
+                            report.updated += 1;
+                        }
+                        None => {
+                            self.push(country);
+                            report.loaded += 1;
+                        }
+                    }
+                }
+            }
+        }
+        report.rejected.sort_by_key(|r| r.index);
+        report
+    }
+
+    /// Inserts a batch, leaving out entries that can't be stored; see
+    /// `CountryDataStore::bulk_insert`
+    pub(crate) fn bulk_insert(
+        &mut self,
+        countries: Vec<Country>,
+        mode: LoadMode,
+        strict_borders: bool,
+    ) -> BulkInsertReport {
+        let store_codes = || self.iter().flat_map(Country::codes).map(str::to_string).collect();
+        let (mut seen, mut seen_codes): (HashSet<String>, HashSet<String>) = match mode {
+            LoadMode::Append => (self.iter().map(|c| name_key(&c.name)).collect(), store_codes()),
+            LoadMode::Replace => (HashSet::new(), HashSet::new()),
+            LoadMode::Merge => (HashSet::new(), store_codes()),
+        };
+        // Entries with the position of the stored country they update, in merge mode
+        let mut accepted: Vec<(usize, Country, Option<usize>)> = Vec::new();
+        let mut report = BulkInsertReport::default();
+
+        for (index, country) in countries.into_iter().enumerate() {
+            let key = name_key(&country.name);
+            let target = match mode {
+                LoadMode::Merge => self.position(&country.name),
+                LoadMode::Append | LoadMode::Replace => None,
+            };
+            // Codes of the country being updated may be reused by its replacement
+            let freed: Vec<&str> = target.map_or_else(Vec::new, |i| self[i].codes().collect());
+
+            let error = if let Err(errors) = country.validate() {
+                Some(StoreError::Invalid(errors))
+            } else if seen.contains(&key) {
+                Some(StoreError::DuplicateName(country.name.clone()))
+            } else if let Some(code) = country
+                .codes()
+                .find(|code| seen_codes.contains(*code) && !freed.contains(code))
+            {
+                Some(StoreError::DuplicateCode(code.to_string()))
+            } else {
+                seen.insert(key);
+                for code in &freed {
+                    seen_codes.remove(*code);
+                }
+                seen_codes.extend(country.codes().map(str::to_string));
+                None
+            };
+
+            match error {
+                Some(StoreError::Invalid(errors)) => {
+                    report.rejected.push(RejectedCountry::invalid(index, country.name, errors))
+                }
+                Some(err) => {
+                    report.rejected.push(RejectedCountry::new(index, country.name, err.to_string()))
+                }
+                None => accepted.push((index, country, target)),
+            }
+        }
+
+        if strict_borders {
+            let replaced: HashSet<usize> = accepted.iter().filter_map(|(_, _, t)| *t).collect();
+            let base: Vec<&Country> = match mode {
+                LoadMode::Append => self.iter().collect(),
+                LoadMode::Replace => Vec::new(),
+                LoadMode::Merge => self
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| !replaced.contains(i))
+                    .map(|(_, c)| c)
+                    .collect(),
+            };
+            let known: Vec<Country> = base
+                .into_iter()
+                .chain(accepted.iter().map(|(_, c, _)| c))
+                .cloned()
+                .collect();
+
+            accepted.retain(|(index, country, _)| match unknown_border(&known, country) {
+                Some(code) => {
+                    let errors = vec![ValidationError::UnknownBorder(code.to_string())];
+                    report.rejected.push(RejectedCountry::invalid(
+                        *index,
+                        country.name.clone(),
+                        errors,
+                    ));
+                    false
+                }
+                None => true,
+            });
+            report.rejected.sort_by_key(|rejected| rejected.index);
+        }
+
+        let accepted: Vec<(Country, Option<usize>)> = accepted
+            .into_iter()
+            .map(|(_, mut c, target)| {
+                c.derive_flag_emoji();
+                (c, target)
+            })
+            .collect();
+        match mode {
+            LoadMode::Append | LoadMode::Merge => {
+                for (country, target) in accepted {
+                    match target {
+                        Some(index) => {
+                            self.replace(index, country);
+                            report.updated += 1;
+                        }
+                        None => {
+                            self.push(country);
+                            report.inserted += 1;
+                        }
+                    }
+                }
+            }
+            LoadMode::Replace => {
+                report.inserted = accepted.len();
+                self.replace_all(accepted.into_iter().map(|(c, _)| c).collect());
+            }
+        }
+        report
+    }
+
+    fn reindex(&mut self) {
+        self.reindex_names();
+        self.by_region = PositionIndex::default();
+        self.by_capital = PositionIndex::default();
+        for (index, country) in self.countries.iter().enumerate() {
+            self.by_region.insert(region_key(country), index);
+            self.by_capital.insert(capital_key(country), index);
+        }
+    }
+
+    fn reindex_names(&mut self) {
+        self.by_name.clear();
+        for (index, country) in self.countries.iter().enumerate() {
+            self.by_name.entry(name_key(&country.name)).or_insert(index);
+        }
+    }
+}
+
+/// The first of `country`'s border codes that matches none of `countries`
+pub(crate) fn unknown_border<'a>(countries: &[Country], country: &'a Country) -> Option<&'a str> {
+    country
+        .borders
+        .iter()
+        .find(|code| !countries.iter().any(|c| c.has_code(code)))
+        .map(String::as_str)
+}
+
+/// The first of `country`'s codes already used by a stored country other than `skip`
+pub(crate) fn taken_code(countries: &[Country], country: &Country, skip: Option<usize>) -> Option<String> {
+    country
+        .codes()
+        .find(|code| {
+            countries
+                .iter()
+                .enumerate()
+                .any(|(i, c)| Some(i) != skip && c.has_code(code))
+        })
+        .map(str::to_string)
+}
+
+impl AsRef<[Country]> for CountryTable {
+    fn as_ref(&self) -> &[Country] {
+        &self.countries
+    }
+}
+
+impl Deref for CountryTable {
+    type Target = [Country];
+
+    fn deref(&self) -> &[Country] {
+        &self.countries
+    }
+}
+
+/// Positions of the countries sharing a key, in ascending order so lookups
+/// keep insertion order. Keys without positions are dropped.
+#[derive(Debug, Clone, Default)]
+struct PositionIndex(HashMap<String, Vec<usize>>);
+
+impl PositionIndex {
+    fn get(&self, key: &str) -> &[usize] {
+        self.0.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    fn insert(&mut self, key: String, index: usize) {
+        let positions = self.0.entry(key).or_default();
+        if let Err(at) = positions.binary_search(&index) {
+            positions.insert(at, index);
+        }
+    }
+
+    fn remove(&mut self, key: &str, index: usize) {
+        if let Some(positions) = self.0.get_mut(key) {
+            positions.retain(|position| *position != index);
+            if positions.is_empty() {
+                self.0.remove(key);
+            }
+        }
+    }
+
+    /// Moves `index` from `old` to `new`, if the key changed
+    fn rekey(&mut self, old: String, new: String, index: usize) {
+        if old != new {
+            self.remove(&old, index);
+            self.insert(new, index);
+        }
+    }
+
+    /// Accounts for the country at `index` having been removed from the list
+    fn shift_down_after(&mut self, index: usize) {
+        for positions in self.0.values_mut() {
+            for position in positions.iter_mut().filter(|position| **position > index) {
+                *position -= 1;
+            }
+        }
+    }
+}
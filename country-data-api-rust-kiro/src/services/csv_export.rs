@@ -0,0 +1,64 @@
+use crate::models::Country;
+use csv::{QuoteStyle, WriterBuilder};
+
+/// Header row written by `countries_to_csv`
+pub const CSV_HEADERS: [&str; 14] = [
+    "name",
+    "capital",
+    "population",
+    "region",
+    "subregion",
+    "languages",
+    "alpha2",
+    "alpha3",
+    "currencies",
+    "area_km2",
+    "population_density",
+    "borders",
+    "flag_emoji",
+    "flag_url",
+];
+
+/// Joins the values of list columns (languages, currency codes, borders)
+pub const CSV_LIST_SEPARATOR: &str = ";";
+
+/// Renders countries as CSV with a header row. Every non-numeric field is
+/// quoted; list columns are joined with `CSV_LIST_SEPARATOR`, currencies are
+/// reduced to their codes and unset values are empty.
+pub fn countries_to_csv(countries: &[Country]) -> String {
+    let mut writer = WriterBuilder::new()
+        .quote_style(QuoteStyle::NonNumeric)
+        .from_writer(Vec::new());
+
+    writer
+        .write_record(CSV_HEADERS)
+        .expect("writing to a Vec can't fail");
+    for country in countries {
+        let currency_codes: Vec<&str> =
+            country.currencies.iter().map(|c| c.code.as_str()).collect();
+        writer
+            .write_record([
+                country.name.clone(),
+                country.capital.clone(),
+                country.population.to_string(),
+                country.region.clone(),
+                country.subregion.clone(),
+                country.languages.join(CSV_LIST_SEPARATOR),
+                country.alpha2.clone().unwrap_or_default(),
+                country.alpha3.clone().unwrap_or_default(),
+                currency_codes.join(CSV_LIST_SEPARATOR),
+                country.area_km2.to_string(),
+                country
+                    .population_density()
+                    .map(|density| density.to_string())
+                    .unwrap_or_default(),
+                country.borders.join(CSV_LIST_SEPARATOR),
+                country.flag_emoji.clone(),
+                country.flag_url.clone().unwrap_or_default(),
+            ])
+            .expect("writing to a Vec can't fail");
+    }
+
+    let bytes = writer.into_inner().expect("writing to a Vec can't fail");
+    String::from_utf8(bytes).expect("CSV of UTF-8 strings is UTF-8")
+}
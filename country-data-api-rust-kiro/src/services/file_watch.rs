@@ -0,0 +1,117 @@
+use crate::models::Country;
+use crate::services::{read_countries_file, CountryFileError, CountryStore, IntoSharedStore};
+use crate::utils::name_key;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Quiet period after a change before the file is reloaded, so an editor's
+/// burst of writes causes a single reload
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How a reload changed the store, comparing countries by name
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReloadSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+    /// Records in the file that failed to deserialize or validate
+    pub rejected: usize,
+}
+
+/// Re-reads `path` and swaps it in for the store's contents. If the file can't
+/// be read or isn't a JSON array, the store keeps its current data.
+pub async fn reload_from_file(
+    store: &dyn CountryStore,
+    path: &Path,
+) -> Result<ReloadSummary, CountryFileError> {
+    let loaded = read_countries_file(path)?;
+
+    let mut before: HashMap<String, Country> = store
+        .get_all()
+        .await
+        .into_iter()
+        .map(|c| (name_key(&c.name), c))
+        .collect();
+    let report = store.load_countries(loaded.countries).await;
+
+    let mut summary = ReloadSummary {
+        rejected: loaded.rejected.len() + report.rejected.len(),
+        ..ReloadSummary::default()
+    };
+    for country in store.get_all().await {
+        match before.remove(&name_key(&country.name)) {
+            Some(old) if old == country => summary.unchanged += 1,
+            Some(_) => summary.changed += 1,
+            None => summary.added += 1,
+        }
+    }
+    summary.removed = before.len();
+
+    tracing::info!(
+        "Reloaded {}: {} added, {} removed, {} changed, {} unchanged, {} rejected",
+        path.display(),
+        summary.added,
+        summary.removed,
+        summary.changed,
+        summary.unchanged,
+        summary.rejected
+    );
+    Ok(summary)
+}
+
+/// Reloads `path` into `store` whenever it changes, until the returned task is
+/// aborted. Failed reloads are logged and keep the current data.
+pub fn watch_file(
+    store: impl IntoSharedStore,
+    path: PathBuf,
+) -> notify::Result<JoinHandle<()>> {
+    let store = store.into_shared();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let file_name = path.file_name().map(|name| name.to_os_string());
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        match event {
+            Ok(event) => {
+                let ours = event
+                    .paths
+                    .iter()
+                    .any(|changed| changed.file_name() == file_name.as_deref());
+                if ours && !matches!(event.kind, EventKind::Access(_)) {
+                    let _ = tx.send(());
+                }
+            }
+            Err(err) => tracing::error!("Data file watcher failed: {}", err),
+        }
+    })?;
+
+    // Watch the directory: editors often replace the file instead of writing to it
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    tracing::info!("Watching {} for changes", path.display());
+
+    Ok(tokio::spawn(async move {
+        // Dropping the watcher stops the events, so it lives as long as the task
+        let _watcher = watcher;
+        // A store saving to the watched file rewrites it after each reload;
+        // skip the event that causes, or it would reload forever
+        let mut seen = None;
+        while rx.recv().await.is_some() {
+            while let Ok(Some(())) = tokio::time::timeout(DEBOUNCE, rx.recv()).await {}
+            let contents = std::fs::read(&path).ok();
+            if contents.is_some() && contents == seen {
+                continue;
+            }
+            if let Err(err) = reload_from_file(store.as_ref(), &path).await {
+                tracing::error!("{}; keeping the current data", err);
+            }
+            seen = std::fs::read(&path).ok();
+        }
+    }))
+}
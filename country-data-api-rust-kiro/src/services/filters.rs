@@ -0,0 +1,165 @@
+use std::borrow::Borrow;
+
+use crate::models::{Country, SearchFields};
+use crate::utils::normalize_for_match;
+
+/// Inclusive population bounds; either end may be left open
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PopulationRange {
+    min: Option<i64>,
+    max: Option<i64>,
+}
+
+impl PopulationRange {
+    /// Validates that both bounds are non-negative and `min <= max`
+    pub fn new(min: Option<i64>, max: Option<i64>) -> Result<Self, String> {
+        if let Some(min) = min
+            && min < 0
+        {
+            return Err(format!("min_population must be non-negative, got {}", min));
+        }
+        if let Some(max) = max
+            && max < 0
+        {
+            return Err(format!("max_population must be non-negative, got {}", max));
+        }
+        if let (Some(min), Some(max)) = (min, max)
+            && min > max
+        {
+            return Err(format!(
+                "min_population ({}) must not be greater than max_population ({})",
+                min, max
+            ));
+        }
+
+        Ok(Self { min, max })
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    pub fn contains(&self, population: i64) -> bool {
+        self.min.is_none_or(|min| population >= min) && self.max.is_none_or(|max| population <= max)
+    }
+}
+
+/// Inclusive population density bounds (people per km²); either end may be left open.
+/// Countries without a density never fall inside a bounded range.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DensityRange {
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl DensityRange {
+    /// Validates that both bounds are finite, non-negative and `min <= max`
+    pub fn new(min: Option<f64>, max: Option<f64>) -> Result<Self, String> {
+        if let Some(min) = min
+            && !(min.is_finite() && min >= 0.0)
+        {
+            return Err(format!("min_density must be non-negative, got {}", min));
+        }
+        if let Some(max) = max
+            && !(max.is_finite() && max >= 0.0)
+        {
+            return Err(format!("max_density must be non-negative, got {}", max));
+        }
+        if let (Some(min), Some(max)) = (min, max)
+            && min > max
+        {
+            return Err(format!(
+                "min_density ({}) must not be greater than max_density ({})",
+                min, max
+            ));
+        }
+
+        Ok(Self { min, max })
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    pub fn contains(&self, density: Option<f64>) -> bool {
+        match density {
+            Some(density) => {
+                self.min.is_none_or(|min| density >= min)
+                    && self.max.is_none_or(|max| density <= max)
+            }
+            None => self.is_unbounded(),
+        }
+    }
+}
+
+/// Substring match on the country name ignoring case and accents; a blank
+/// query matches everything
+pub fn name_matches(country: &Country, query: &str) -> bool {
+    query.trim().is_empty()
+        || normalize_for_match(&country.name).contains(&normalize_for_match(query))
+}
+
+/// Splits a comma-separated region list into trimmed names folded with
+/// `normalize_for_match`, ignoring empty segments
+/// (`"Europe,, Asia"` -> `["europe", "asia"]`)
+pub fn parse_region_list(regions: &str) -> Vec<String> {
+    regions
+        .split(',')
+        .map(|region| normalize_for_match(region.trim()))
+        .filter(|region| !region.is_empty())
+        .collect()
+}
+
+/// Substring search over the selected fields, ignoring case and accents.
+/// Name matches (including an exact ISO code) come first, then capital matches,
+/// then language matches; each country appears once and ties keep their original order.
+/// A blank query matches everything. With a `limit`, only that many of the best
+/// matches are kept; `countries` may be borrowed so the rest are never cloned.
+pub fn search_countries<C: Borrow<Country>>(
+    countries: Vec<C>,
+    query: &str,
+    fields: SearchFields,
+    limit: Option<usize>,
+) -> Vec<C> {
+    if query.trim().is_empty() {
+        let mut countries = countries;
+        countries.truncate(limit.unwrap_or(usize::MAX));
+        return countries;
+    }
+
+    let query_key = normalize_for_match(query);
+    let (name, capital, languages) = match fields {
+        SearchFields::Name => (true, false, false),
+        SearchFields::Capital => (false, true, false),
+        SearchFields::Languages => (false, false, true),
+        SearchFields::All => (true, true, true),
+    };
+
+    let rank = |country: &Country| -> Option<u8> {
+        if name && (name_matches(country, query) || country.has_code(query.trim())) {
+            Some(0)
+        } else if capital && normalize_for_match(&country.capital).contains(&query_key) {
+            Some(1)
+        } else if languages
+            && country
+                .languages
+                .iter()
+                .any(|lang| normalize_for_match(lang).contains(&query_key))
+        {
+            Some(2)
+        } else {
+            None
+        }
+    };
+
+    let mut ranked: Vec<(u8, C)> = countries
+        .into_iter()
+        .filter_map(|country| rank(country.borrow()).map(|r| (r, country)))
+        .collect();
+    ranked.sort_by_key(|(r, _)| *r);
+    ranked
+        .into_iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|(_, country)| country)
+        .collect()
+}
@@ -0,0 +1,103 @@
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+use std::time::Duration;
+
+/// Prometheus metrics for one store and the API serving it; each store owns
+/// its own registry, so separate stores (and tests) don't share counts
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    request_duration: HistogramVec,
+    countries: IntGauge,
+    mutations: IntCounterVec,
+    not_found: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled"),
+            &["method", "route", "status"],
+        )
+        .expect("valid metric");
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency"),
+            &["method", "route", "status"],
+        )
+        .expect("valid metric");
+        let countries =
+            IntGauge::new("countries_stored", "Countries in the store").expect("valid metric");
+        let mutations = IntCounterVec::new(
+            Opts::new("store_mutations_total", "Changes made to the store"),
+            &["operation"],
+        )
+        .expect("valid metric");
+        let not_found = IntCounterVec::new(
+            Opts::new("country_lookups_not_found_total", "Country lookups that found nothing"),
+            &["by"],
+        )
+        .expect("valid metric");
+
+        let registry = Registry::new();
+        for collector in [
+            Box::new(requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(request_duration.clone()),
+            Box::new(countries.clone()),
+            Box::new(mutations.clone()),
+            Box::new(not_found.clone()),
+        ] {
+            registry.register(collector).expect("metric names are unique");
+        }
+
+        Self {
+            registry,
+            requests,
+            request_duration,
+            countries,
+            mutations,
+            not_found,
+        }
+    }
+
+    /// Counts a handled request; `route` is the matched route pattern, not the raw path
+    pub fn record_request(&self, method: &str, route: &str, status: u16, latency: Duration) {
+        let status = status.to_string();
+        let labels = [method, route, status.as_str()];
+        self.requests.with_label_values(&labels).inc();
+        self.request_duration
+            .with_label_values(&labels)
+            .observe(latency.as_secs_f64());
+    }
+
+    /// Counts a store change such as "add" or "remove" and records the new size
+    pub fn record_mutation(&self, operation: &str, countries: usize) {
+        self.mutations.with_label_values(&[operation]).inc();
+        self.set_countries(countries);
+    }
+
+    pub fn set_countries(&self, countries: usize) {
+        self.countries.set(countries as i64);
+    }
+
+    /// Counts a lookup that ended in a 404; `by` is "name", "code" or "capital"
+    pub fn record_not_found(&self, by: &str) {
+        self.not_found.with_label_values(&[by]).inc();
+    }
+
+    /// Every metric in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("text encoding does not fail");
+        String::from_utf8(buffer).expect("text encoding is UTF-8")
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
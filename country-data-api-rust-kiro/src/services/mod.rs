@@ -1,3 +1,28 @@
 pub mod country_data_store;
-pub use country_data_store::CountryDataStore;
-
+pub mod country_loader;
+pub mod country_store;
+mod country_table;
+pub mod csv_export;
+pub mod file_watch;
+pub mod filters;
+pub mod metrics;
+pub mod projection;
+pub mod rest_countries;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_store;
+mod summaries;
+pub mod xml_export;
+pub use country_data_store::{sort_countries, CountryDataStore, StoreError};
+pub use country_store::{CountryStore, IntoSharedStore, SharedCountries, SharedStore};
+pub use filters::{
+    name_matches, parse_region_list, search_countries, DensityRange, PopulationRange,
+};
+pub use country_loader::{read_countries_file, CountryFileError, LoadedCountries};
+pub use csv_export::{countries_to_csv, CSV_HEADERS, CSV_LIST_SEPARATOR};
+pub use file_watch::{reload_from_file, watch_file, ReloadSummary};
+pub use metrics::Metrics;
+pub use projection::project_country;
+pub use rest_countries::{import_countries, CountrySource, FileCountrySource, SourceError};
+pub use xml_export::{countries_to_xml, country_to_xml, error_to_xml};
+#[cfg(feature = "sqlite")]
+pub use sqlite_store::SqliteCountryStore;
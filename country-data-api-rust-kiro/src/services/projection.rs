@@ -0,0 +1,15 @@
+use crate::models::{Country, CountryField};
+use serde_json::Value;
+
+/// The country's JSON with only the given fields. `name` is always kept so
+/// projected entries can still be told apart; optional fields that are unset
+/// (`alpha2`/`alpha3`) stay absent.
+pub fn project_country(country: &Country, fields: &[CountryField]) -> Value {
+    let mut json = serde_json::to_value(country).expect("countries always serialize");
+    if let Value::Object(map) = &mut json {
+        map.retain(|key, _| {
+            key == CountryField::Name.as_str() || fields.iter().any(|f| f.as_str() == key)
+        });
+    }
+    json
+}
@@ -0,0 +1,205 @@
+use crate::models::{Country, Currency, RejectedCountry};
+use crate::services::LoadedCountries;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::path::PathBuf;
+
+/// REST Countries v3.1 endpoint, limited to the fields we map (the API allows at most 10)
+pub const REST_COUNTRIES_URL: &str = concat!(
+    "https://restcountries.com/v3.1/all",
+    "?fields=name,cca2,cca3,capital,population,region,subregion,languages,currencies,area"
+);
+
+/// The subset of a REST Countries v3.1 record that maps onto `Country`
+#[derive(Debug, Deserialize)]
+pub struct RestCountry {
+    pub name: RestCountryName,
+    #[serde(default)]
+    pub cca2: Option<String>,
+    #[serde(default)]
+    pub cca3: Option<String>,
+    #[serde(default)]
+    pub capital: Vec<String>,
+    #[serde(default)]
+    pub population: i64,
+    #[serde(default)]
+    pub region: String,
+    #[serde(default)]
+    pub subregion: String,
+    /// Keyed by ISO 639-3 code, e.g. `{"fra": "French"}`
+    #[serde(default)]
+    pub languages: BTreeMap<String, String>,
+    /// Keyed by ISO 4217 code, e.g. `{"EUR": {"name": "Euro", "symbol": "€"}}`
+    #[serde(default)]
+    pub currencies: BTreeMap<String, RestCurrency>,
+    #[serde(default)]
+    pub area: f64,
+    /// Not in `REST_COUNTRIES_URL` (the field limit is reached), but present
+    /// in full records such as those read by `FileCountrySource`
+    #[serde(default)]
+    pub borders: Vec<String>,
+    /// Flag emoji; also left out of `REST_COUNTRIES_URL`, and derived from
+    /// `cca2` on load when missing
+    #[serde(default)]
+    pub flag: String,
+    #[serde(default)]
+    pub flags: Option<RestFlags>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RestCountryName {
+    pub common: String,
+}
+
+/// Flag image URLs
+#[derive(Debug, Deserialize)]
+pub struct RestFlags {
+    #[serde(default)]
+    pub svg: Option<String>,
+    #[serde(default)]
+    pub png: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RestCurrency {
+    pub name: String,
+    #[serde(default)]
+    pub symbol: String,
+}
+
+impl From<RestCountry> for Country {
+    fn from(rest: RestCountry) -> Self {
+        Country {
+            name: rest.name.common,
+            // Some territories (e.g. Antarctica) have no capital or official language
+            capital: rest.capital.into_iter().next().unwrap_or_default(),
+            population: rest.population,
+            region: rest.region,
+            subregion: rest.subregion,
+            languages: rest.languages.into_values().collect(),
+            alpha2: rest.cca2,
+            alpha3: rest.cca3,
+            currencies: rest
+                .currencies
+                .into_iter()
+                .map(|(code, currency)| Currency {
+                    code,
+                    name: currency.name,
+                    symbol: currency.symbol,
+                })
+                .collect(),
+            area_km2: rest.area,
+            borders: rest.borders,
+            flag_emoji: rest.flag,
+            flag_url: rest.flags.and_then(|flags| flags.svg.or(flags.png)),
+            population_history: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SourceError {
+    Unavailable(String),
+    InvalidResponse(serde_json::Error),
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Unavailable(message) => write!(f, "Country source unavailable: {}", message),
+            SourceError::InvalidResponse(err) => {
+                write!(f, "Country source returned invalid data: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+/// Somewhere to get a REST Countries v3.1 JSON document from
+pub trait CountrySource {
+    fn fetch(&self) -> impl Future<Output = Result<String, SourceError>> + Send;
+}
+
+/// Reads a saved REST Countries response from disk
+pub struct FileCountrySource {
+    path: PathBuf,
+}
+
+impl FileCountrySource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CountrySource for FileCountrySource {
+    async fn fetch(&self) -> Result<String, SourceError> {
+        tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|err| SourceError::Unavailable(format!("{}: {}", self.path.display(), err)))
+    }
+}
+
+/// Fetches live data over HTTP (requires the `fetch` feature)
+#[cfg(feature = "fetch")]
+pub struct HttpCountrySource {
+    url: String,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "fetch")]
+impl HttpCountrySource {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[cfg(feature = "fetch")]
+impl Default for HttpCountrySource {
+    fn default() -> Self {
+        Self::new(REST_COUNTRIES_URL)
+    }
+}
+
+#[cfg(feature = "fetch")]
+impl CountrySource for HttpCountrySource {
+    async fn fetch(&self) -> Result<String, SourceError> {
+        let unavailable = |err: reqwest::Error| SourceError::Unavailable(err.to_string());
+
+        self.client
+            .get(&self.url)
+            .timeout(std::time::Duration::from_secs(30))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(unavailable)?
+            .text()
+            .await
+            .map_err(unavailable)
+    }
+}
+
+/// Fetches from `source`, maps each record onto `Country` and validates it.
+/// Invalid records are skipped and reported rather than failing the import.
+pub async fn import_countries<S: CountrySource>(source: &S) -> Result<LoadedCountries, SourceError> {
+    let body = source.fetch().await?;
+    let records: Vec<RestCountry> =
+        serde_json::from_str(&body).map_err(SourceError::InvalidResponse)?;
+
+    let mut loaded = LoadedCountries::default();
+    for (index, country) in records.into_iter().map(Country::from).enumerate() {
+        match country.validate() {
+            Ok(()) => loaded.countries.push(country),
+            Err(errors) => {
+                loaded.rejected.push(RejectedCountry::invalid(index, country.name, errors))
+            }
+        }
+    }
+
+    Ok(loaded)
+}
@@ -0,0 +1,601 @@
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePool, SqlitePoolOptions,
+};
+use sqlx::{Executor, QueryBuilder, Sqlite, Transaction};
+use std::path::Path;
+use std::sync::atomic::{self, AtomicBool, AtomicU64};
+use tokio::sync::Mutex;
+
+use crate::models::{
+    BulkInsertReport, Country, LoadMode, LoadReport, PopulationRevision, RejectedCountry,
+    SnapshotId, UpdateCountryRequest, ValidationError,
+};
+use crate::services::country_data_store::{SharedRng, StoreError, DEFAULT_MAX_SNAPSHOTS};
+use crate::services::country_store::CountryStore;
+use crate::services::country_table::{capital_key, region_key, CountryTable};
+use crate::services::filters::parse_region_list;
+use crate::services::metrics::Metrics;
+use crate::utils::name_key;
+
+/// Countries kept in a SQLite database, so they survive restarts. Each row holds
+/// a country as JSON next to the keys it is looked up by; `migrations/` creates
+/// or upgrades the schema when the store is opened.
+///
+/// Writes are serialized and each runs in one transaction, with the same rules
+/// as `CountryDataStore`. Database errors are logged: reads then return nothing,
+/// writes that return a `Result` fail with `StoreError::Storage`, and loads
+/// reject the whole batch.
+pub struct SqliteCountryStore {
+    pool: SqlitePool,
+    /// Held for the whole of each write, so checks and changes don't interleave
+    writes: Mutex<()>,
+    strict_borders: bool,
+    max_snapshots: usize,
+    rng: SharedRng,
+    version: AtomicU64,
+    loaded: AtomicBool,
+    metrics: Metrics,
+}
+
+impl From<sqlx::Error> for StoreError {
+    fn from(err: sqlx::Error) -> Self {
+        StoreError::Storage(err.to_string())
+    }
+}
+
+impl SqliteCountryStore {
+    /// Opens the database at `path`, creating it if it doesn't exist
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, sqlx::Error> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal);
+        Self::connect(SqlitePoolOptions::new().connect_with(options).await?).await
+    }
+
+    /// A fresh database that lives as long as the store, e.g. for tests
+    pub async fn in_memory() -> Result<Self, sqlx::Error> {
+        // Each connection to an in-memory database gets its own, so keep exactly one
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(SqliteConnectOptions::new().in_memory(true))
+            .await?;
+        Self::connect(pool).await
+    }
+
+    async fn connect(pool: SqlitePool) -> Result<Self, sqlx::Error> {
+        sqlx::migrate!().run(&pool).await?;
+        rekey(&pool).await?;
+        let count = count_rows(&pool).await?;
+
+        let store = Self {
+            pool,
+            writes: Mutex::new(()),
+            strict_borders: false,
+            max_snapshots: DEFAULT_MAX_SNAPSHOTS,
+            rng: SharedRng::default(),
+            version: AtomicU64::new(0),
+            // Countries saved by an earlier run are the data to serve
+            loaded: AtomicBool::new(count > 0),
+            metrics: Metrics::new(),
+        };
+        store.metrics.set_countries(count);
+        Ok(store)
+    }
+
+    /// Rejects border codes that match no stored country; see
+    /// `CountryDataStore::with_strict_borders`
+    pub fn with_strict_borders(mut self) -> Self {
+        self.strict_borders = true;
+        self
+    }
+
+    /// Keeps at most `max` snapshots (at least one), evicting the oldest
+    pub fn with_max_snapshots(mut self, max: usize) -> Self {
+        self.max_snapshots = max.max(1);
+        self
+    }
+
+    /// Seeds the random source used by `random_countries`, making its picks reproducible
+    pub fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.rng = SharedRng::new(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Commits `tx`, then bumps the version and updates the metrics
+    async fn commit(&self, mut tx: Transaction<'_, Sqlite>, operation: &str) -> sqlx::Result<()> {
+        let count = count_rows(&mut *tx).await?;
+        tx.commit().await?;
+        self.version.fetch_add(1, atomic::Ordering::SeqCst);
+        self.metrics.record_mutation(operation, count);
+        Ok(())
+    }
+
+    async fn check_borders(
+        &self,
+        conn: &mut SqliteConnection,
+        country: &Country,
+    ) -> Result<(), StoreError> {
+        if !self.strict_borders {
+            return Ok(());
+        }
+        for code in &country.borders {
+            if find_by_code(&mut *conn, code).await?.is_none() {
+                let error = ValidationError::UnknownBorder(code.clone());
+                return Err(StoreError::Invalid(vec![error]));
+            }
+        }
+        Ok(())
+    }
+
+    async fn try_load(&self, countries: Vec<Country>, mode: LoadMode) -> sqlx::Result<LoadReport> {
+        let _writing = self.writes.lock().await;
+        let mut tx = self.pool.begin().await?;
+        let mut table = CountryTable::new(all_countries(&mut *tx).await?);
+        let report = table.load(countries, mode);
+        replace_all(&mut tx, &table).await?;
+        self.commit(tx, "load").await?;
+        Ok(report)
+    }
+
+    async fn try_bulk_insert(
+        &self,
+        countries: Vec<Country>,
+        mode: LoadMode,
+    ) -> sqlx::Result<BulkInsertReport> {
+        let _writing = self.writes.lock().await;
+        let mut tx = self.pool.begin().await?;
+        let mut table = CountryTable::new(all_countries(&mut *tx).await?);
+        let report = table.bulk_insert(countries, mode, self.strict_borders);
+        replace_all(&mut tx, &table).await?;
+        self.commit(tx, "bulk").await?;
+        Ok(report)
+    }
+
+    async fn try_remove(&self, name: &str) -> sqlx::Result<Option<Country>> {
+        let _writing = self.writes.lock().await;
+        let mut tx = self.pool.begin().await?;
+        let Some((id, country)) = find_by_name(&mut *tx, name).await? else {
+            return Ok(None);
+        };
+        sqlx::query("DELETE FROM countries WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        self.commit(tx, "remove").await?;
+        Ok(Some(country))
+    }
+
+    /// Validates `country` and stores it in place of the row `id`
+    async fn replace_at(
+        conn: &mut SqliteConnection,
+        id: i64,
+        mut country: Country,
+    ) -> Result<Country, StoreError> {
+        country.derive_flag_emoji();
+        country.validate().map_err(StoreError::Invalid)?;
+
+        let collides = find_by_name(&mut *conn, &country.name)
+            .await?
+            .is_some_and(|(other, _)| other != id);
+        if collides {
+            return Err(StoreError::DuplicateName(country.name));
+        }
+        if let Some(code) = taken_code(conn, &country, Some(id)).await? {
+            return Err(StoreError::DuplicateCode(code));
+        }
+
+        write_row(conn, id, &country).await?;
+        Ok(country)
+    }
+}
+
+#[async_trait]
+impl CountryStore for SqliteCountryStore {
+    fn version(&self) -> u64 {
+        self.version.load(atomic::Ordering::SeqCst)
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.loaded.load(atomic::Ordering::SeqCst)
+    }
+
+    fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    async fn count(&self) -> usize {
+        logged(count_rows(&self.pool).await)
+    }
+
+    async fn get_all(&self) -> Vec<Country> {
+        logged(all_countries(&self.pool).await)
+    }
+
+    async fn get_by_name(&self, name: &str) -> Option<Country> {
+        logged(find_by_name(&self.pool, name).await).map(|(_, country)| country)
+    }
+
+    async fn get_by_code(&self, code: &str) -> Option<Country> {
+        logged(find_by_code(&self.pool, code).await)
+    }
+
+    async fn get_by_capital(&self, capital: &str) -> Vec<Country> {
+        let query = "SELECT data FROM countries WHERE capital_key = ? ORDER BY id";
+        let rows = sqlx::query_scalar(query)
+            .bind(name_key(capital))
+            .fetch_all(&self.pool)
+            .await;
+        decode_all(logged(rows))
+    }
+
+    async fn filter_by_region(&self, region: &str) -> Vec<Country> {
+        let regions = parse_region_list(region);
+        if regions.is_empty() {
+            return Vec::new();
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT data FROM countries WHERE region_key IN (",
+        );
+        let mut list = query.separated(", ");
+        for region in regions {
+            list.push_bind(region);
+        }
+        list.push_unseparated(") ORDER BY id");
+        let rows = query
+            .build_query_scalar::<String>()
+            .fetch_all(&self.pool)
+            .await;
+        decode_all(logged(rows))
+    }
+
+    async fn random_countries(
+        &self,
+        count: usize,
+        filter: &(dyn for<'c> Fn(&'c Country) -> bool + Sync),
+    ) -> Vec<Country> {
+        let candidates: Vec<Country> = self
+            .get_all()
+            .await
+            .into_iter()
+            .filter(|c| filter(c))
+            .collect();
+        self.rng.choose(&candidates, count)
+    }
+
+    async fn load_countries_with_mode(
+        &self,
+        countries: Vec<Country>,
+        mode: LoadMode,
+    ) -> LoadReport {
+        let names = names_of(&countries);
+        match self.try_load(countries, mode).await {
+            Ok(report) => {
+                self.loaded.store(true, atomic::Ordering::SeqCst);
+                report
+            }
+            Err(err) => LoadReport {
+                rejected: reject_all(names, err),
+                ..LoadReport::default()
+            },
+        }
+    }
+
+    async fn add_country(&self, mut country: Country) -> Result<Country, StoreError> {
+        country.derive_flag_emoji();
+        country.validate().map_err(StoreError::Invalid)?;
+        let _writing = self.writes.lock().await;
+        let mut tx = self.pool.begin().await?;
+
+        if find_by_name(&mut *tx, &country.name).await?.is_some() {
+            return Err(StoreError::DuplicateName(country.name));
+        }
+        if let Some(code) = taken_code(&mut tx, &country, None).await? {
+            return Err(StoreError::DuplicateCode(code));
+        }
+        self.check_borders(&mut tx, &country).await?;
+
+        insert(&mut tx, &country).await?;
+        self.commit(tx, "add").await?;
+        Ok(country)
+    }
+
+    async fn bulk_insert(&self, countries: Vec<Country>, mode: LoadMode) -> BulkInsertReport {
+        let names = names_of(&countries);
+        self.try_bulk_insert(countries, mode)
+            .await
+            .unwrap_or_else(|err| BulkInsertReport {
+                rejected: reject_all(names, err),
+                ..BulkInsertReport::default()
+            })
+    }
+
+    async fn update(&self, name: &str, mut country: Country) -> Result<Country, StoreError> {
+        let _writing = self.writes.lock().await;
+        let mut tx = self.pool.begin().await?;
+        let (id, current) = find_by_name(&mut *tx, name)
+            .await?
+            .ok_or_else(|| StoreError::NotFound(name.to_string()))?;
+        self.check_borders(&mut tx, &country).await?;
+        country.population_history = current.population_history;
+        let updated = Self::replace_at(&mut tx, id, country).await?;
+        self.commit(tx, "update").await?;
+        Ok(updated)
+    }
+
+    async fn patch(
+        &self,
+        name: &str,
+        update: UpdateCountryRequest,
+    ) -> Result<Country, StoreError> {
+        let _writing = self.writes.lock().await;
+        let mut tx = self.pool.begin().await?;
+        let (id, current) = find_by_name(&mut *tx, name)
+            .await?
+            .ok_or_else(|| StoreError::NotFound(name.to_string()))?;
+        let country = update.apply(&current);
+        self.check_borders(&mut tx, &country).await?;
+        let updated = Self::replace_at(&mut tx, id, country).await?;
+        self.commit(tx, "patch").await?;
+        Ok(updated)
+    }
+
+    async fn revise_population(
+        &self,
+        name: &str,
+        revision: PopulationRevision,
+    ) -> Result<Country, StoreError> {
+        let _writing = self.writes.lock().await;
+        let mut tx = self.pool.begin().await?;
+        let (id, mut country) = find_by_name(&mut *tx, name)
+            .await?
+            .ok_or_else(|| StoreError::NotFound(name.to_string()))?;
+        country.revise_population(revision).map_err(StoreError::Invalid)?;
+        write_row(&mut tx, id, &country).await?;
+        self.commit(tx, "population").await?;
+        Ok(country)
+    }
+
+    async fn remove(&self, name: &str) -> Option<Country> {
+        logged(self.try_remove(name).await)
+    }
+
+    async fn snapshot(&self) -> Result<SnapshotId, StoreError> {
+        let _writing = self.writes.lock().await;
+        let mut tx = self.pool.begin().await?;
+        let id = sqlx::query("INSERT INTO snapshots DEFAULT VALUES")
+            .execute(&mut *tx)
+            .await?
+            .last_insert_rowid();
+        sqlx::query(
+            "INSERT INTO snapshot_countries (snapshot_id, position, data) \
+             SELECT ?, id, data FROM countries",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        // Evicting a snapshot deletes its countries too
+        sqlx::query(
+            "DELETE FROM snapshots WHERE id NOT IN \
+             (SELECT id FROM snapshots ORDER BY id DESC LIMIT ?)",
+        )
+        .bind(self.max_snapshots as i64)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(SnapshotId(id as u64))
+    }
+
+    async fn rollback(&self, id: SnapshotId) -> Result<(), StoreError> {
+        let key = i64::try_from(id.0).map_err(|_| StoreError::SnapshotNotFound(id))?;
+        let _writing = self.writes.lock().await;
+        let mut tx = self.pool.begin().await?;
+
+        let saved: Option<i64> = sqlx::query_scalar("SELECT id FROM snapshots WHERE id = ?")
+            .bind(key)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if saved.is_none() {
+            return Err(StoreError::SnapshotNotFound(id));
+        }
+        let rows = sqlx::query_scalar(
+            "SELECT data FROM snapshot_countries WHERE snapshot_id = ? ORDER BY position",
+        )
+        .bind(key)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        replace_all(&mut tx, &decode_all(rows)).await?;
+        self.commit(tx, "rollback").await?;
+        Ok(())
+    }
+}
+
+/// A country's row: its JSON and the columns it is looked up by
+struct CountryRow {
+    name_key: String,
+    alpha2: Option<String>,
+    alpha3: Option<String>,
+    region_key: String,
+    capital_key: String,
+    data: String,
+}
+
+impl CountryRow {
+    fn new(country: &Country) -> sqlx::Result<Self> {
+        Ok(Self {
+            name_key: name_key(&country.name),
+            alpha2: country.alpha2.as_deref().map(str::to_ascii_uppercase),
+            alpha3: country.alpha3.as_deref().map(str::to_ascii_uppercase),
+            region_key: region_key(country),
+            capital_key: capital_key(country),
+            data: serde_json::to_string(country).map_err(|err| sqlx::Error::Encode(err.into()))?,
+        })
+    }
+}
+
+/// The result of a read, or the empty value after logging the error
+fn logged<T: Default>(result: sqlx::Result<T>) -> T {
+    result.unwrap_or_else(|err| {
+        tracing::error!("SQLite query failed: {}", err);
+        T::default()
+    })
+}
+
+fn names_of(countries: &[Country]) -> Vec<String> {
+    countries.iter().map(|c| c.name.clone()).collect()
+}
+
+/// A batch that couldn't be stored: every entry is rejected with `err`
+fn reject_all(names: Vec<String>, err: sqlx::Error) -> Vec<RejectedCountry> {
+    tracing::error!("Failed to store countries: {}", err);
+    let reason = StoreError::from(err).to_string();
+    names
+        .into_iter()
+        .enumerate()
+        .map(|(index, name)| RejectedCountry::new(index, name, reason.clone()))
+        .collect()
+}
+
+/// A stored country; rows that no longer deserialize are logged and skipped
+fn decode(data: &str) -> Option<Country> {
+    serde_json::from_str(data)
+        .inspect_err(|err| tracing::error!("Skipping unreadable stored country: {}", err))
+        .ok()
+}
+
+fn decode_all(rows: Vec<String>) -> Vec<Country> {
+    rows.iter().filter_map(|data| decode(data)).collect()
+}
+
+async fn count_rows<'e>(executor: impl Executor<'e, Database = Sqlite>) -> sqlx::Result<usize> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM countries")
+        .fetch_one(executor)
+        .await?;
+    Ok(count as usize)
+}
+
+async fn all_countries<'e>(
+    executor: impl Executor<'e, Database = Sqlite>,
+) -> sqlx::Result<Vec<Country>> {
+    let rows = sqlx::query_scalar("SELECT data FROM countries ORDER BY id")
+        .fetch_all(executor)
+        .await?;
+    Ok(decode_all(rows))
+}
+
+/// The first country called `name`, ignoring case, accents and extra whitespace,
+/// with its row id
+async fn find_by_name<'e>(
+    executor: impl Executor<'e, Database = Sqlite>,
+    name: &str,
+) -> sqlx::Result<Option<(i64, Country)>> {
+    let row: Option<(i64, String)> =
+        sqlx::query_as("SELECT id, data FROM countries WHERE name_key = ? ORDER BY id LIMIT 1")
+            .bind(name_key(name))
+            .fetch_optional(executor)
+            .await?;
+    Ok(row.and_then(|(id, data)| Some((id, decode(&data)?))))
+}
+
+/// The first country whose alpha-2 or alpha-3 code is `code` (case-insensitive)
+async fn find_by_code<'e>(
+    executor: impl Executor<'e, Database = Sqlite>,
+    code: &str,
+) -> sqlx::Result<Option<Country>> {
+    let row: Option<String> = sqlx::query_scalar(
+        "SELECT data FROM countries WHERE alpha2 = ?1 OR alpha3 = ?1 ORDER BY id LIMIT 1",
+    )
+    .bind(code.to_ascii_uppercase())
+    .fetch_optional(executor)
+    .await?;
+    Ok(row.as_deref().and_then(decode))
+}
+
+/// The first of `country`'s codes already used by a stored country other than row `skip`
+async fn taken_code(
+    conn: &mut SqliteConnection,
+    country: &Country,
+    skip: Option<i64>,
+) -> sqlx::Result<Option<String>> {
+    for code in country.codes() {
+        let taken: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM countries WHERE (alpha2 = ?1 OR alpha3 = ?1) AND id IS NOT ?2 LIMIT 1",
+        )
+        .bind(code.to_ascii_uppercase())
+        .bind(skip)
+        .fetch_optional(&mut *conn)
+        .await?;
+        if taken.is_some() {
+            return Ok(Some(code.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+async fn insert(conn: &mut SqliteConnection, country: &Country) -> sqlx::Result<()> {
+    let row = CountryRow::new(country)?;
+    sqlx::query(
+        "INSERT INTO countries (name_key, alpha2, alpha3, region_key, capital_key, data) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(row.name_key)
+    .bind(row.alpha2)
+    .bind(row.alpha3)
+    .bind(row.region_key)
+    .bind(row.capital_key)
+    .bind(row.data)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Stores `country` in the row `id`
+async fn write_row(conn: &mut SqliteConnection, id: i64, country: &Country) -> sqlx::Result<()> {
+    let row = CountryRow::new(country)?;
+    sqlx::query(
+        "UPDATE countries SET name_key = ?, alpha2 = ?, alpha3 = ?, region_key = ?, \
+         capital_key = ?, data = ? WHERE id = ?",
+    )
+    .bind(row.name_key)
+    .bind(row.alpha2)
+    .bind(row.alpha3)
+    .bind(row.region_key)
+    .bind(row.capital_key)
+    .bind(row.data)
+    .bind(id)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Recomputes every row's lookup columns from its JSON, so a database
+/// written while names were normalized differently still matches
+async fn rekey(pool: &SqlitePool) -> sqlx::Result<()> {
+    let mut tx = pool.begin().await?;
+    let rows: Vec<(i64, String)> = sqlx::query_as("SELECT id, data FROM countries")
+        .fetch_all(&mut *tx)
+        .await?;
+    for (id, data) in rows {
+        if let Some(country) = decode(&data) {
+            write_row(&mut tx, id, &country).await?;
+        }
+    }
+    tx.commit().await
+}
+
+/// Replaces every stored country with `countries`, keeping their order
+async fn replace_all(conn: &mut SqliteConnection, countries: &[Country]) -> sqlx::Result<()> {
+    sqlx::query("DELETE FROM countries").execute(&mut *conn).await?;
+    for country in countries {
+        insert(conn, country).await?;
+    }
+    Ok(())
+}
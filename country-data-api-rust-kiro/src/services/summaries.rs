@@ -0,0 +1,220 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::models::{
+    Country, CountryStats, LanguageCount, LanguageSummary, Neighbors, RegionGroup, RegionStats,
+    RegionSummary, SortField, SortOrder, SubregionSummary,
+};
+use crate::services::country_data_store::sort_countries;
+use crate::services::filters::parse_region_list;
+use crate::utils::normalize_for_match;
+
+/// How many languages `country_stats` reports
+const TOP_LANGUAGES: usize = 10;
+
+/// See `CountryStore::regions`
+pub(crate) fn region_summaries(countries: &[Country]) -> Vec<RegionSummary> {
+    let mut regions: BTreeMap<String, (RegionSummary, BTreeMap<String, SubregionSummary>)> =
+        BTreeMap::new();
+
+    for country in countries.iter() {
+        let (region, subregions) = regions
+            .entry(normalize_for_match(&country.region))
+            .or_insert_with(|| {
+                let summary = RegionSummary {
+                    region: country.region.clone(),
+                    count: 0,
+                    subregions: Vec::new(),
+                };
+                (summary, BTreeMap::new())
+            });
+        region.count += 1;
+
+        if !country.subregion.trim().is_empty() {
+            subregions
+                .entry(normalize_for_match(&country.subregion))
+                .or_insert_with(|| SubregionSummary {
+                    subregion: country.subregion.clone(),
+                    count: 0,
+                })
+                .count += 1;
+        }
+    }
+
+    regions
+        .into_values()
+        .map(|(mut region, subregions)| {
+            region.subregions = subregions.into_values().collect();
+            region
+        })
+        .collect()
+}
+
+/// See `CountryStore::countries_by_region`
+pub(crate) fn region_groups(countries: &[Country]) -> Vec<RegionGroup> {
+    let mut groups: BTreeMap<String, RegionGroup> = BTreeMap::new();
+    for country in countries.iter() {
+        let group = groups
+            .entry(normalize_for_match(&country.region))
+            .or_insert_with(|| RegionGroup {
+                region: country.region.clone(),
+                count: 0,
+                total_population: 0,
+                countries: Vec::new(),
+            });
+        group.count += 1;
+        group.total_population += country.population;
+        group.countries.push(country.clone());
+    }
+
+    groups
+        .into_values()
+        .map(|mut group| {
+            sort_countries(&mut group.countries, SortField::Name, SortOrder::Asc);
+            group
+        })
+        .collect()
+}
+
+/// See `CountryStore::stats`
+pub(crate) fn country_stats(countries: &[Country], region: Option<&str>) -> CountryStats {
+    let regions = region.map(parse_region_list);
+    let selected: Vec<&Country> = countries
+        .iter()
+        .filter(|c| {
+            regions
+                .as_ref()
+                .is_none_or(|regions| regions.contains(&normalize_for_match(&c.region)))
+        })
+        .collect();
+
+    let mut by_region: BTreeMap<String, (String, RegionStats)> = BTreeMap::new();
+    let mut languages: HashMap<&str, usize> = HashMap::new();
+    for country in &selected {
+        let (_, region) = by_region
+            .entry(normalize_for_match(&country.region))
+            .or_insert_with(|| (country.region.clone(), RegionStats::default()));
+        region.count += 1;
+        region.total_population += country.population;
+
+        for language in &country.languages {
+            *languages.entry(language).or_default() += 1;
+        }
+    }
+
+    let mut top_languages: Vec<LanguageCount> = languages
+        .into_iter()
+        .map(|(language, count)| LanguageCount {
+            language: language.to_string(),
+            count,
+        })
+        .collect();
+    top_languages
+        .sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.language.cmp(&b.language)));
+    top_languages.truncate(TOP_LANGUAGES);
+
+    // max_by_key returns the last of several equal maxima; break ties on
+    // the reversed index so the first one wins, as with min_by_key
+    let most_populous = selected
+        .iter()
+        .enumerate()
+        .max_by_key(|(index, c)| (c.population, std::cmp::Reverse(*index)))
+        .map(|(_, c)| c.name.clone());
+    let least_populous = selected
+        .iter()
+        .min_by_key(|c| c.population)
+        .map(|c| c.name.clone());
+
+    CountryStats {
+        total_countries: selected.len(),
+        total_population: selected.iter().map(|c| c.population).sum(),
+        by_region: by_region
+            .into_values()
+            .map(|(name, mut region)| {
+                region.average_population =
+                    region.total_population as f64 / region.count as f64;
+                (name, region)
+            })
+            .collect(),
+        most_populous,
+        least_populous,
+        top_languages,
+    }
+}
+
+/// See `CountryStore::language_stats`
+pub(crate) fn language_summaries(
+    countries: &[Country],
+    region: Option<&str>,
+) -> Vec<LanguageSummary> {
+    struct Group<'a> {
+        summary: LanguageSummary,
+        spellings: Vec<(&'a str, usize)>,
+    }
+
+    let regions = region.map(parse_region_list);
+    let mut groups: HashMap<String, Group> = HashMap::new();
+    for country in countries.iter() {
+        if regions
+            .as_ref()
+            .is_some_and(|regions| !regions.contains(&normalize_for_match(&country.region)))
+        {
+            continue;
+        }
+
+        let mut seen = HashSet::new();
+        for language in &country.languages {
+            let spelling = language.trim();
+            let key = normalize_for_match(spelling);
+            if key.is_empty() || !seen.insert(key.clone()) {
+                continue;
+            }
+
+            let group = groups.entry(key).or_insert_with(|| Group {
+                summary: LanguageSummary {
+                    language: String::new(),
+                    count: 0,
+                    total_population: 0,
+                    countries: Vec::new(),
+                },
+                spellings: Vec::new(),
+            });
+            group.summary.count += 1;
+            group.summary.total_population += country.population;
+            group.summary.countries.push(country.name.clone());
+            match group.spellings.iter_mut().find(|(s, _)| *s == spelling) {
+                Some((_, uses)) => *uses += 1,
+                None => group.spellings.push((spelling, 1)),
+            }
+        }
+    }
+
+    let mut languages: Vec<LanguageSummary> = groups
+        .into_values()
+        .map(|mut group| {
+            // max_by_key keeps the last maximum; reverse so the first seen wins
+            let (spelling, _) = group
+                .spellings
+                .iter()
+                .rev()
+                .max_by_key(|(_, uses)| *uses)
+                .copied()
+                .unwrap_or_default();
+            group.summary.language = spelling.to_string();
+            group.summary
+        })
+        .collect();
+    languages.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.language.cmp(&b.language)));
+    languages
+}
+
+/// See `CountryStore::neighbors`
+pub(crate) fn neighbors_of(countries: &[Country], country: &Country) -> Neighbors {
+    let mut neighbors = Neighbors::default();
+    for code in &country.borders {
+        match countries.iter().find(|c| c.has_code(code)) {
+            Some(neighbor) => neighbors.neighbors.push(neighbor.clone()),
+            None => neighbors.unresolved.push(code.clone()),
+        }
+    }
+    neighbors
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Renders countries, or their projections, as
+/// `<countries><country><name>…</name>…</country></countries>`; see
+/// `country_to_xml` for the shape of each country.
+pub fn countries_to_xml<T: Serialize>(countries: &[T]) -> String {
+    let countries = serde_json::to_value(countries).expect("countries always serialize");
+    render(|writer| write_element(writer, "countries", &countries))
+}
+
+/// Renders a country, or its projection, as a `<country>` element with one
+/// child per JSON field, in the same order. Lists repeat an element named for
+/// one entry, e.g. `<languages><language>French</language></languages>`, and
+/// unset values are left out. Text is escaped.
+pub fn country_to_xml<T: Serialize>(country: &T) -> String {
+    let country = serde_json::to_value(country).expect("countries always serialize");
+    render(|writer| write_element(writer, "country", &country))
+}
+
+/// Renders an error body as `<error><message>…</message></error>`, with a
+/// `<suggestion>` and a `<fields>` list of `<field name="…">` elements holding
+/// a `<message>` each, when present
+pub fn error_to_xml(
+    message: &str,
+    suggestion: Option<&str>,
+    fields: Option<&BTreeMap<String, Vec<String>>>,
+) -> String {
+    render(|writer| {
+        write_start(writer, BytesStart::new("error"));
+        write_text(writer, "message", message);
+        if let Some(suggestion) = suggestion {
+            write_text(writer, "suggestion", suggestion);
+        }
+        if let Some(fields) = fields {
+            write_start(writer, BytesStart::new("fields"));
+            for (field, messages) in fields {
+                write_start(writer, BytesStart::new("field").with_attributes([("name", &**field)]));
+                for message in messages {
+                    write_text(writer, "message", message);
+                }
+                write_end(writer, "field");
+            }
+            write_end(writer, "fields");
+        }
+        write_end(writer, "error");
+    })
+}
+
+type XmlWriter = Writer<Vec<u8>>;
+
+/// A document with an XML declaration and the element written by `body`
+fn render(body: impl FnOnce(&mut XmlWriter)) -> String {
+    let mut writer = Writer::new(Vec::new());
+    write_event(&mut writer, Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)));
+    body(&mut writer);
+    String::from_utf8(writer.into_inner()).expect("XML is written from UTF-8 text")
+}
+
+fn write_element(writer: &mut XmlWriter, name: &str, value: &Value) {
+    match value {
+        Value::Null => {}
+        Value::Object(fields) => {
+            write_start(writer, BytesStart::new(name));
+            for (field, value) in fields {
+                write_element(writer, field, value);
+            }
+            write_end(writer, name);
+        }
+        Value::Array(items) => {
+            write_start(writer, BytesStart::new(name));
+            for item in items {
+                write_element(writer, entry_name(name), item);
+            }
+            write_end(writer, name);
+        }
+        Value::String(text) => write_text(writer, name, text),
+        Value::Number(number) => write_text(writer, name, &number.to_string()),
+        Value::Bool(flag) => write_text(writer, name, &flag.to_string()),
+    }
+}
+
+/// Name of one entry of the list `list`
+fn entry_name(list: &str) -> &str {
+    match list {
+        "countries" => "country",
+        "currencies" => "currency",
+        "population_history" => "revision",
+        _ => list.strip_suffix('s').unwrap_or("item"),
+    }
+}
+
+fn write_text(writer: &mut XmlWriter, name: &str, text: &str) {
+    write_start(writer, BytesStart::new(name));
+    write_event(writer, Event::Text(BytesText::new(text)));
+    write_end(writer, name);
+}
+
+fn write_start(writer: &mut XmlWriter, start: BytesStart) {
+    write_event(writer, Event::Start(start));
+}
+
+fn write_end(writer: &mut XmlWriter, name: &str) {
+    write_event(writer, Event::End(BytesEnd::new(name)));
+}
+
+fn write_event(writer: &mut XmlWriter, event: Event) {
+    writer.write_event(event).expect("writing to a Vec can't fail");
+}
@@ -0,0 +1,15 @@
+/// Offset from an ASCII uppercase letter to its regional indicator symbol
+const REGIONAL_INDICATOR_OFFSET: u32 = 0x1F1E6 - 'A' as u32;
+
+/// The flag emoji for an ISO 3166-1 alpha-2 code (case-insensitive), built
+/// from the pair of regional indicator symbols; `None` unless the code is
+/// exactly two ASCII letters
+pub fn flag_emoji_from_alpha2(code: &str) -> Option<String> {
+    if code.len() != 2 || !code.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    code.chars()
+        .map(|c| char::from_u32(c.to_ascii_uppercase() as u32 + REGIONAL_INDICATOR_OFFSET))
+        .collect()
+}
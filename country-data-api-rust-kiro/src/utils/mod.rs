@@ -0,0 +1,7 @@
+pub mod flags;
+pub mod text;
+pub use flags::flag_emoji_from_alpha2;
+pub use text::{
+    closest_match, levenshtein, name_key, normalize_for_match, normalized_distance,
+    SUGGESTION_MAX_DISTANCE,
+};
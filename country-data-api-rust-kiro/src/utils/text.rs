@@ -0,0 +1,76 @@
+use caseless::Caseless;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Largest normalized distance at which a name still counts as a likely typo
+pub const SUGGESTION_MAX_DISTANCE: f64 = 0.34;
+
+/// Levenshtein edit distance, counted in chars
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Case-insensitive edit distance scaled to 0.0 (equal) ..= 1.0 (nothing in common)
+pub fn normalized_distance(a: &str, b: &str) -> f64 {
+    let a = a.trim().to_lowercase();
+    let b = b.trim().to_lowercase();
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 0.0;
+    }
+
+    levenshtein(&a, &b) as f64 / longest as f64
+}
+
+/// The candidate closest to `query`, if it is within `SUGGESTION_MAX_DISTANCE`.
+/// Ties go to the earliest candidate.
+pub fn closest_match<'a>(
+    query: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    candidates
+        .into_iter()
+        .map(|candidate| (normalized_distance(query, candidate), candidate))
+        .filter(|(distance, _)| *distance <= SUGGESTION_MAX_DISTANCE)
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, candidate)| candidate)
+}
+
+/// Folds `text` for matching: Unicode case folding with compatibility forms
+/// (ligatures, full-width letters) decomposed and accents stripped, so
+/// `"Côte"`, `"COTE"` and `"Ｃｏｔｅ"` all become `"cote"`. Normalizing twice
+/// changes nothing. Used to compare names, regions, languages and search
+/// queries; the stored values keep their spelling.
+pub fn normalize_for_match(text: &str) -> String {
+    text.chars()
+        .nfd()
+        .default_case_fold()
+        .nfkd()
+        .default_case_fold()
+        .nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .collect()
+}
+
+/// Comparison key for country names: `normalize_for_match` with surrounding
+/// whitespace trimmed and inner runs collapsed to one space, so
+/// `" côte  D'Ivoire "` and `"Cote d'Ivoire"` share a key
+pub fn name_key(name: &str) -> String {
+    normalize_for_match(name)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
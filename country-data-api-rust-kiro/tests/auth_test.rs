@@ -0,0 +1,140 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use country_data_api_rust_kiro::{
+    api::{create_routes_with_config, ApiConfig, WriteAccess},
+    services::CountryDataStore,
+};
+use serde_json::Value;
+use tower::util::ServiceExt;
+
+fn app_with(config: ApiConfig) -> axum::Router {
+    create_routes_with_config(CountryDataStore::new(), &config)
+}
+
+fn keyed_app() -> axum::Router {
+    app_with(ApiConfig::default().with_api_keys(["first-key", " second-key "]))
+}
+
+fn spain() -> Value {
+    serde_json::json!({
+        "name": "Spain",
+        "capital": "Madrid",
+        "population": 47000000,
+        "region": "Europe",
+        "languages": ["Spanish"]
+    })
+}
+
+fn request(method: &str, uri: &str, api_key: Option<&str>) -> Request<Body> {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    if let Some(key) = api_key {
+        request = request.header("X-Api-Key", key);
+    }
+    let body = if method == "POST" {
+        Body::from(spain().to_string())
+    } else {
+        Body::empty()
+    };
+    request.body(body).unwrap()
+}
+
+async fn send(app: &axum::Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_get_without_key_succeeds() {
+    let app = keyed_app();
+    let (status, _) = send(&app, request("GET", "/api/countries", None)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let app = app_with(ApiConfig::default().with_api_keys([]));
+    let (status, _) = send(&app, request("GET", "/api/countries", None)).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_post_without_or_with_wrong_key_is_401() {
+    let app = keyed_app();
+
+    for key in [None, Some("wrong-key"), Some("first-ke"), Some("")] {
+        let (status, error) = send(&app, request("POST", "/api/countries", key)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{:?}", key);
+        assert!(error["error"].is_string());
+    }
+
+    let (status, _) = send(&app, request("DELETE", "/api/countries/Spain", None)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_post_with_valid_key_succeeds() {
+    let app = keyed_app();
+
+    let (status, _) = send(&app, request("POST", "/api/countries", Some("second-key"))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let delete = request("DELETE", "/api/countries/Spain", Some("first-key"));
+    let (status, _) = send(&app, delete).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_post_with_writes_disabled_is_403() {
+    let config = ApiConfig::default().with_api_keys([" ", ""]);
+    assert_eq!(*config.write_access(), WriteAccess::Disabled);
+    let app = app_with(config);
+
+    for key in [None, Some("first-key")] {
+        let (status, error) = send(&app, request("POST", "/api/countries", key)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(error["error"].as_str().unwrap().contains("disabled"));
+    }
+}
+
+#[tokio::test]
+async fn test_snapshot_endpoints_require_key() {
+    let app = keyed_app();
+
+    let (status, _) = send(&app, request("POST", "/api/admin/snapshots", None)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) =
+        send(&app, request("POST", "/api/admin/snapshots", Some("first-key"))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let rollback = format!("/api/admin/snapshots/{}/rollback", body["id"]);
+    let (status, _) = send(&app, request("POST", &rollback, None)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send(&app, request("POST", &rollback, Some("first-key"))).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_batch_lookup_needs_no_key() {
+    let batch = || {
+        Request::builder()
+            .method("POST")
+            .uri("/api/countries/batch")
+            .header("Content-Type", "application/json")
+            .body(Body::from(r#"{"names": ["Spain"]}"#))
+            .unwrap()
+    };
+
+    // Lookups only read, so they work even with writes disabled
+    for app in [keyed_app(), app_with(ApiConfig::default().with_api_keys([]))] {
+        let (status, body) = send(&app, batch()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["missing"], serde_json::json!(["Spain"]));
+    }
+}
@@ -0,0 +1,104 @@
+use clap::Parser;
+use country_data_api_rust_kiro::cli::{Cli, LaunchConfig};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+fn resolve(args: &[&str], env: &[(&str, &str)]) -> Result<LaunchConfig, String> {
+    let env: HashMap<String, String> = env
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let cli = Cli::try_parse_from(std::iter::once("server").chain(args.iter().copied())).unwrap();
+    cli.resolve(|key| env.get(key).cloned())
+}
+
+fn addr(addr: &str) -> SocketAddr {
+    addr.parse().unwrap()
+}
+
+#[test]
+fn test_defaults() {
+    let config = resolve(&[], &[]).unwrap();
+
+    assert_eq!(config.addr, addr("127.0.0.1:5000"));
+    assert_eq!(config.countries_file, None);
+}
+
+#[test]
+fn test_env_overrides_defaults() {
+    let config = resolve(
+        &[],
+        &[("HOST", "0.0.0.0"), ("PORT", "8080"), ("COUNTRIES_FILE", "env.json")],
+    )
+    .unwrap();
+
+    assert_eq!(config.addr, addr("0.0.0.0:8080"));
+    assert_eq!(config.countries_file, Some(PathBuf::from("env.json")));
+}
+
+#[test]
+fn test_cli_overrides_env() {
+    let config = resolve(
+        &["--host", "::1", "--port", "9000", "--data", "cli.json"],
+        &[("HOST", "0.0.0.0"), ("PORT", "8080"), ("COUNTRIES_FILE", "env.json")],
+    )
+    .unwrap();
+
+    assert_eq!(config.addr, addr("[::1]:9000"));
+    assert_eq!(config.countries_file, Some(PathBuf::from("cli.json")));
+}
+
+#[test]
+fn test_sources_mix_per_setting() {
+    let config = resolve(&["--port", "9000"], &[("HOST", "0.0.0.0")]).unwrap();
+
+    assert_eq!(config.addr, addr("0.0.0.0:9000"));
+}
+
+#[test]
+fn test_port_zero_is_allowed() {
+    assert_eq!(resolve(&[], &[("PORT", "0")]).unwrap().addr.port(), 0);
+    assert_eq!(resolve(&["--port", "0"], &[]).unwrap().addr.port(), 0);
+}
+
+#[test]
+fn test_blank_env_counts_as_unset() {
+    let config = resolve(&[], &[("HOST", " "), ("PORT", ""), ("COUNTRIES_FILE", "")]).unwrap();
+
+    assert_eq!(config.addr, addr("127.0.0.1:5000"));
+    assert_eq!(config.countries_file, None);
+}
+
+#[test]
+fn test_invalid_env_is_rejected() {
+    let err = resolve(&[], &[("PORT", "70000")]).unwrap_err();
+    assert!(err.contains("PORT"), "{}", err);
+
+    let err = resolve(&[], &[("HOST", "not an address")]).unwrap_err();
+    assert!(err.contains("HOST"), "{}", err);
+}
+
+#[test]
+fn test_invalid_env_is_ignored_when_cli_is_given() {
+    let config = resolve(&["--port", "9000"], &[("PORT", "nope")]).unwrap();
+
+    assert_eq!(config.addr.port(), 9000);
+}
+
+#[test]
+fn test_out_of_range_cli_port_is_rejected() {
+    assert!(Cli::try_parse_from(["server", "--port", "70000"]).is_err());
+    assert!(Cli::try_parse_from(["server", "--port", "-1"]).is_err());
+    assert!(Cli::try_parse_from(["server", "--host", "example"]).is_err());
+}
+
+#[test]
+fn test_watch_needs_a_data_file() {
+    let err = resolve(&["--watch"], &[]).unwrap_err();
+    assert!(err.contains("--watch"), "{}", err);
+
+    let config = resolve(&["--watch"], &[("COUNTRIES_FILE", "countries.json")]).unwrap();
+    assert!(config.watch);
+    assert!(!resolve(&["--data", "countries.json"], &[]).unwrap().watch);
+}
@@ -0,0 +1,116 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    response::Response,
+};
+use country_data_api_rust_kiro::{
+    api::{create_routes, create_routes_with_config, ApiConfig},
+    models::Country,
+    services::CountryDataStore,
+};
+use tower::util::ServiceExt;
+
+const ALLOWED: &str = "https://app.example.com";
+const DISALLOWED: &str = "https://evil.example.com";
+
+async fn restricted_app() -> axum::Router {
+    let store = CountryDataStore::new();
+    store
+        .load_countries(vec![Country {
+            name: "France".to_string(),
+            capital: "Paris".to_string(),
+            population: 67000000,
+            region: "Europe".to_string(),
+            languages: vec!["French".to_string()],
+            ..Default::default()
+        }])
+        .await;
+    let config = ApiConfig::default().with_cors_origins([ALLOWED, " ", "http://localhost:3000"]);
+    create_routes_with_config(store, &config)
+}
+
+async fn preflight(app: axum::Router, origin: &str, method: &str) -> Response {
+    app.oneshot(
+        Request::builder()
+            .method("OPTIONS")
+            .uri("/api/countries")
+            .header("Origin", origin)
+            .header("Access-Control-Request-Method", method)
+            .header("Access-Control-Request-Headers", "content-type")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+async fn cross_origin_get(app: axum::Router, origin: &str) -> Response {
+    app.oneshot(
+        Request::builder()
+            .uri("/api/countries/France")
+            .header("Origin", origin)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_preflight_for_allowed_origin() {
+    let response = preflight(restricted_app().await, ALLOWED, "DELETE").await;
+
+    // Answered by the layer, not the (GET/POST-only) route
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(headers["access-control-allow-origin"], ALLOWED);
+    let methods = headers["access-control-allow-methods"].to_str().unwrap();
+    for method in ["GET", "POST", "PUT", "PATCH", "DELETE"] {
+        assert!(methods.contains(method), "{}", methods);
+    }
+    assert!(headers["access-control-allow-headers"]
+        .to_str()
+        .unwrap()
+        .contains("content-type"));
+}
+
+#[tokio::test]
+async fn test_preflight_for_disallowed_origin() {
+    let response = preflight(restricted_app().await, DISALLOWED, "POST").await;
+    assert!(response.headers().get("access-control-allow-origin").is_none());
+}
+
+#[tokio::test]
+async fn test_cross_origin_get() {
+    let app = restricted_app().await;
+
+    let response = cross_origin_get(app.clone(), ALLOWED).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["access-control-allow-origin"], ALLOWED);
+    assert!(response.headers()["access-control-expose-headers"]
+        .to_str()
+        .unwrap()
+        .contains("etag"));
+
+    let response = cross_origin_get(app.clone(), "http://localhost:3000").await;
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "http://localhost:3000"
+    );
+
+    // The request is still served; the browser withholds it from the page
+    let response = cross_origin_get(app, DISALLOWED).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("access-control-allow-origin").is_none());
+}
+
+#[tokio::test]
+async fn test_default_config_allows_any_origin() {
+    let app = create_routes(CountryDataStore::new());
+
+    let response = cross_origin_get(app.clone(), DISALLOWED).await;
+    assert_eq!(response.headers()["access-control-allow-origin"], "*");
+
+    let response = preflight(app, DISALLOWED, "PUT").await;
+    assert_eq!(response.headers()["access-control-allow-origin"], "*");
+}
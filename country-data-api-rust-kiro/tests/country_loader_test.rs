@@ -0,0 +1,73 @@
+use country_data_api_rust_kiro::services::{
+    read_countries_file, CountryDataStore, CountryFileError,
+};
+use std::path::PathBuf;
+
+fn temp_file(label: &str, contents: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!(
+        "countries_file_{}_{}_{}.json",
+        label,
+        std::process::id(),
+        nanos
+    ));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[tokio::test]
+async fn test_load_file_with_valid_and_invalid_countries() {
+    let path = temp_file(
+        "mixed",
+        r#"[
+            {"name": "France", "capital": "Paris", "population": 67000000, "region": "Europe", "languages": ["French"]},
+            {"name": "Atlantis", "capital": "Poseidonia", "population": -1, "region": "Ocean", "languages": ["Atlantean"]},
+            {"name": "Japan", "capital": "Tokyo", "population": 125000000, "region": "Asia", "languages": ["Japanese"]},
+            {"name": "Nowhere", "capital": "None"},
+            {"name": "  ", "capital": "Blank", "population": 1, "region": "Europe", "languages": []}
+        ]"#,
+    );
+
+    let loaded = read_countries_file(&path).unwrap();
+    assert_eq!(loaded.countries.len(), 2);
+    assert_eq!(loaded.rejected.len(), 3);
+
+    let rejected: Vec<(usize, &str)> = loaded
+        .rejected
+        .iter()
+        .map(|r| (r.index, r.name.as_str()))
+        .collect();
+    assert_eq!(rejected, vec![(1, "Atlantis"), (3, "Nowhere"), (4, "  ")]);
+    assert!(loaded.rejected[0].reason.contains("population"));
+    assert!(loaded.rejected[1].reason.contains("missing field"));
+
+    let store = CountryDataStore::new();
+    store.load_countries(loaded.countries).await;
+
+    let names: Vec<String> = store.get_all().await.into_iter().map(|c| c.name).collect();
+    assert_eq!(names, vec!["France", "Japan"]);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_unreadable_file_is_an_error() {
+    let path = std::env::temp_dir().join("countries_file_that_does_not_exist.json");
+
+    let err = read_countries_file(&path).unwrap_err();
+    assert!(matches!(err, CountryFileError::Unreadable { .. }));
+    assert!(err.to_string().contains("countries_file_that_does_not_exist.json"));
+}
+
+#[test]
+fn test_invalid_json_file_is_an_error() {
+    let path = temp_file("invalid", r#"[{"name": "France", "#);
+
+    let err = read_countries_file(&path).unwrap_err();
+    assert!(matches!(err, CountryFileError::InvalidJson { .. }));
+
+    let _ = std::fs::remove_file(&path);
+}
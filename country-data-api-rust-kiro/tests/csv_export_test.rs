@@ -0,0 +1,95 @@
+use country_data_api_rust_kiro::models::Country;
+use country_data_api_rust_kiro::sample_data::get_sample_countries;
+use country_data_api_rust_kiro::services::{
+    countries_to_csv, CountryDataStore, CSV_HEADERS, CSV_LIST_SEPARATOR,
+};
+
+fn split_list(field: &str) -> Vec<String> {
+    if field.is_empty() {
+        Vec::new()
+    } else {
+        field.split(CSV_LIST_SEPARATOR).map(String::from).collect()
+    }
+}
+
+fn optional(field: &str) -> Option<String> {
+    (!field.is_empty()).then(|| field.to_string())
+}
+
+/// Rebuilds a country from a CSV row; currencies only keep their codes
+fn parse_row(row: &csv::StringRecord) -> (Country, Vec<String>) {
+    let country = Country {
+        name: row[0].to_string(),
+        capital: row[1].to_string(),
+        population: row[2].parse().unwrap(),
+        region: row[3].to_string(),
+        subregion: row[4].to_string(),
+        languages: split_list(&row[5]),
+        alpha2: optional(&row[6]),
+        alpha3: optional(&row[7]),
+        area_km2: row[9].parse().unwrap(),
+        borders: split_list(&row[11]),
+        flag_emoji: row[12].to_string(),
+        flag_url: optional(&row[13]),
+        ..Default::default()
+    };
+    (country, split_list(&row[8]))
+}
+
+#[tokio::test]
+async fn test_sample_data_csv_round_trips() {
+    let store = CountryDataStore::new();
+    store.load_countries(get_sample_countries()).await;
+    let countries = store.get_all().await;
+
+    let csv = countries_to_csv(&countries);
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    assert_eq!(reader.headers().unwrap(), CSV_HEADERS.as_slice());
+
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), countries.len());
+
+    for (row, original) in rows.iter().zip(&countries) {
+        let (parsed, currency_codes) = parse_row(row);
+        assert_eq!(
+            parsed,
+            Country {
+                currencies: Vec::new(),
+                ..original.clone()
+            }
+        );
+        let original_codes: Vec<&str> =
+            original.currencies.iter().map(|c| c.code.as_str()).collect();
+        assert_eq!(currency_codes, original_codes);
+
+        let density: Option<f64> = (!row[10].is_empty()).then(|| row[10].parse().unwrap());
+        assert_eq!(density, original.population_density());
+    }
+}
+
+#[test]
+fn test_csv_quotes_commas_and_quotes() {
+    let country = Country {
+        name: "Korea, \"North\"".to_string(),
+        capital: "Pyongyang".to_string(),
+        population: 25_000_000,
+        languages: vec!["Korean".to_string(), "English".to_string()],
+        ..Default::default()
+    };
+
+    let csv = countries_to_csv(std::slice::from_ref(&country));
+    let line = csv.lines().nth(1).unwrap();
+    assert!(line.starts_with(r#""Korea, ""North""","Pyongyang",25000000,"#));
+    assert!(line.contains(r#""Korean;English""#));
+
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let row = reader.records().next().unwrap().unwrap();
+    assert_eq!(parse_row(&row).0, country);
+}
+
+#[test]
+fn test_empty_list_is_header_only() {
+    let csv = countries_to_csv(&[]);
+    assert_eq!(csv.lines().count(), 1);
+    assert!(csv.starts_with("\"name\",\"capital\",\"population\""));
+}
@@ -1,6 +1,14 @@
-use country_data_api_rust_kiro::models::Country;
-use country_data_api_rust_kiro::services::CountryDataStore;
+use country_data_api_rust_kiro::models::{
+    Country, LoadMode, PopulationRevision, SearchFields, SortField, SortOrder,
+    UpdateCountryRequest,
+};
+use country_data_api_rust_kiro::services::{
+    sort_countries, CountryDataStore, CountryStore, StoreError,
+};
+use country_data_api_rust_kiro::utils::name_key;
 use proptest::prelude::*;
+use std::collections::HashSet;
+use std::path::PathBuf;
 
 fn country_strategy() -> impl Strategy<Value = Country> {
     (
@@ -16,14 +24,29 @@ fn country_strategy() -> impl Strategy<Value = Country> {
             population,
             region,
             languages,
+            ..Default::default()
         })
 }
 
+/// Countries with distinct names, as the store keeps only the first of each;
+/// names differing only in case or spacing count as the same
+fn unique_countries(
+    size: impl Into<prop::collection::SizeRange>,
+) -> impl Strategy<Value = Vec<Country>> {
+    prop::collection::vec(country_strategy(), size).prop_map(|countries| {
+        let mut seen = HashSet::new();
+        countries
+            .into_iter()
+            .filter(|country| seen.insert(name_key(&country.name)))
+            .collect()
+    })
+}
+
 // Feature: country-data-api-rust, Property 1: Complete country retrieval
 proptest! {
     #[test]
     fn test_complete_country_retrieval(
-        countries in prop::collection::vec(country_strategy(), 1..20)
+        countries in unique_countries(1..20)
     ) {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
@@ -40,34 +63,48 @@ proptest! {
     }
 }
 
+// Per-region statistics partition the overall totals
+proptest! {
+    #[test]
+    fn test_stats_regions_sum_to_total(
+        countries in unique_countries(0..30)
+    ) {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let stats = rt.block_on(async {
+            let store = CountryDataStore::new();
+            store.load_countries(countries.clone()).await;
+            store.stats(None).await
+        });
+
+        prop_assert_eq!(stats.total_countries, countries.len());
+        prop_assert_eq!(
+            stats.total_population,
+            countries.iter().map(|c| c.population).sum::<i64>()
+        );
+        prop_assert_eq!(
+            stats.by_region.values().map(|r| r.total_population).sum::<i64>(),
+            stats.total_population
+        );
+        prop_assert_eq!(
+            stats.by_region.values().map(|r| r.count).sum::<usize>(),
+            stats.total_countries
+        );
+        prop_assert!(stats.top_languages.len() <= 10);
+    }
+}
+
 // Feature: country-data-api-rust, Property 4: Country retrieval by name
 proptest! {
     #[test]
     fn test_country_retrieval_by_name(
-        countries in prop::collection::vec(country_strategy(), 1..10)
+        countries in unique_countries(1..10)
     ) {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
             let store = CountryDataStore::new();
+            store.load_countries(countries.clone()).await;
             
-            // Ensure unique names by deduplicating
-            let mut unique_countries = Vec::new();
-            let mut seen_names = std::collections::HashSet::new();
-            for country in countries {
-                let name_lower = country.name.to_lowercase();
-                if !seen_names.contains(&name_lower) {
-                    seen_names.insert(name_lower);
-                    unique_countries.push(country);
-                }
-            }
-            
-            if unique_countries.is_empty() {
-                return;
-            }
-            
-            store.load_countries(unique_countries.clone()).await;
-            
-            for country in &unique_countries {
+            for country in &countries {
                 let retrieved = store.get_by_name(&country.name).await;
                 assert_eq!(retrieved, Some(country.clone()));
                 
@@ -79,11 +116,67 @@ proptest! {
     }
 }
 
+/// `name` with the case of each letter picked by `upper`, cycling through it
+fn recase(name: &str, upper: &[bool]) -> String {
+    name.chars()
+        .zip(upper.iter().cycle())
+        .map(|(c, &upper)| if upper { c.to_ascii_uppercase() } else { c.to_ascii_lowercase() })
+        .collect()
+}
+
+// Feature: country-data-api-rust, Property 10: Any casing of a name addresses exactly one country
+proptest! {
+    #[test]
+    fn test_recased_names_address_a_single_country(
+        countries in prop::collection::vec(country_strategy(), 1..20),
+        pick in any::<prop::sample::Index>(),
+        upper in prop::collection::vec(any::<bool>(), 1..8),
+    ) {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let store = CountryDataStore::new();
+            store.load_countries(countries).await;
+
+            let before = store.get_all().await;
+            let keys: HashSet<String> = before.iter().map(|c| name_key(&c.name)).collect();
+            assert_eq!(keys.len(), before.len(), "names differing only by case were stored");
+
+            let target = pick.get(&before).clone();
+            let recased = recase(&target.name, &upper);
+            assert_eq!(store.get_by_name(&recased).await, Some(target.clone()));
+
+            let duplicate = Country { name: recased.to_lowercase(), ..target.clone() };
+            assert!(matches!(
+                store.add_country(duplicate).await,
+                Err(StoreError::DuplicateName(_))
+            ));
+
+            // The update takes the new spelling and changes no other country
+            let update = Country {
+                name: recased.clone(),
+                population: target.population + 1,
+                ..target.clone()
+            };
+            store.update(&recased.to_uppercase(), update.clone()).await.unwrap();
+            let after = store.get_all().await;
+            let changed: Vec<(&Country, &Country)> =
+                before.iter().zip(&after).filter(|(old, new)| old != new).collect();
+            assert_eq!(after.len(), before.len());
+            assert_eq!(changed, vec![(&target, &update)]);
+
+            let removed = store.remove(&recased.to_lowercase()).await;
+            assert_eq!(removed, Some(update));
+            assert_eq!(store.count().await, before.len() - 1);
+            assert!(store.get_by_name(&target.name).await.is_none());
+        });
+    }
+}
+
 // Feature: country-data-api-rust, Property 5: Region filtering is case-insensitive and accurate
 proptest! {
     #[test]
     fn test_region_filtering(
-        countries in prop::collection::vec(country_strategy(), 1..20)
+        countries in unique_countries(1..20)
     ) {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
@@ -113,7 +206,7 @@ proptest! {
 proptest! {
     #[test]
     fn test_name_search(
-        countries in prop::collection::vec(country_strategy(), 1..20)
+        countries in unique_countries(1..20)
     ) {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
@@ -123,7 +216,7 @@ proptest! {
             for country in &countries {
                 if country.name.len() >= 3 {
                     let query = &country.name[0..3];
-                    let results = store.search_by_name(query).await;
+                    let results = store.search_by_name(query, None).await;
                     
                     // All results should contain the query
                     for c in &results {
@@ -134,7 +227,7 @@ proptest! {
                     assert!(results.iter().any(|c| c == country));
                     
                     // Test case-insensitive
-                    let results_upper = store.search_by_name(&query.to_uppercase()).await;
+                    let results_upper = store.search_by_name(&query.to_uppercase(), None).await;
                     assert_eq!(results.len(), results_upper.len());
                 }
             }
@@ -142,6 +235,29 @@ proptest! {
     }
 }
 
+// Feature: country-data-api-rust, Property 9: Removing every loaded country empties the store
+proptest! {
+    #[test]
+    fn test_remove_all_countries_empties_store(
+        countries in unique_countries(1..20)
+    ) {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let store = CountryDataStore::new();
+            store.load_countries(countries.clone()).await;
+            
+            for country in &countries {
+                let removed = store.remove(&country.name.to_uppercase()).await;
+                
+                let removed = removed.expect("Loaded country should be removable");
+                assert_eq!(removed.name, country.name);
+            }
+            
+            assert!(store.get_all().await.is_empty());
+        });
+    }
+}
+
 #[tokio::test]
 async fn test_empty_store_returns_empty_array() {
     let store = CountryDataStore::new();
@@ -166,6 +282,7 @@ async fn test_empty_region_filter_returns_empty_array() {
             population: 67000000,
             region: "Europe".to_string(),
             languages: vec!["French".to_string()],
+            ..Default::default()
         }
     ]).await;
     
@@ -183,6 +300,7 @@ async fn test_empty_search_returns_all_countries() {
             population: 67000000,
             region: "Europe".to_string(),
             languages: vec!["French".to_string()],
+            ..Default::default()
         },
         Country {
             name: "Japan".to_string(),
@@ -190,11 +308,12 @@ async fn test_empty_search_returns_all_countries() {
             population: 125000000,
             region: "Asia".to_string(),
             languages: vec!["Japanese".to_string()],
+            ..Default::default()
         }
     ];
     store.load_countries(countries.clone()).await;
     
-    let results = store.search_by_name("").await;
+    let results = store.search_by_name("", None).await;
     assert_eq!(results.len(), 2);
 }
 
@@ -208,10 +327,786 @@ async fn test_whitespace_search_returns_all_countries() {
             population: 67000000,
             region: "Europe".to_string(),
             languages: vec!["French".to_string()],
+            ..Default::default()
         }
     ];
     store.load_countries(countries.clone()).await;
     
-    let results = store.search_by_name("   ").await;
+    let results = store.search_by_name("   ", None).await;
     assert_eq!(results.len(), 1);
 }
+
+#[tokio::test]
+async fn test_search_limit_keeps_best_ranked_matches() {
+    let store = CountryDataStore::new();
+    // Japan is stored first but only matches "e" by language; France matches by name
+    store.load_countries(sample_countries().into_iter().rev().collect()).await;
+
+    let all = store.search("e", SearchFields::All, None).await;
+    assert_eq!(all.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), ["France", "Japan"]);
+    let limited = store.search("e", SearchFields::All, Some(1)).await;
+    assert_eq!(limited, all[..1]);
+    assert!(store.search_by_name("", Some(0)).await.is_empty());
+}
+
+fn temp_data_file(label: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    std::env::temp_dir().join(format!(
+        "countries_{}_{}_{}.json",
+        label,
+        std::process::id(),
+        nanos
+    ))
+}
+
+fn sample_countries() -> Vec<Country> {
+    vec![
+        Country {
+            name: "France".to_string(),
+            capital: "Paris".to_string(),
+            population: 67000000,
+            region: "Europe".to_string(),
+            languages: vec!["French".to_string()],
+            ..Default::default()
+        },
+        Country {
+            name: "Japan".to_string(),
+            capital: "Tokyo".to_string(),
+            population: 125000000,
+            region: "Asia".to_string(),
+            languages: vec!["Japanese".to_string()],
+            ..Default::default()
+        },
+    ]
+}
+
+#[tokio::test]
+async fn test_save_and_load_round_trip() {
+    let path = temp_data_file("round_trip");
+    let store = CountryDataStore::new();
+    store.load_countries(sample_countries()).await;
+
+    store
+        .add_country(Country {
+            name: "Kenya".to_string(),
+            capital: "Nairobi".to_string(),
+            population: 54027000,
+            region: "Africa".to_string(),
+            languages: vec!["Swahili".to_string(), "English".to_string()],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    store
+        .patch(
+            "japan",
+            UpdateCountryRequest {
+                population: Some(124000000),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    store.remove("France").await.unwrap();
+
+    store.save_to_file(&path).await.unwrap();
+
+    let reloaded = CountryDataStore::new();
+    reloaded.load_from_file(&path).await.unwrap();
+    assert_eq!(reloaded.get_all().await, store.get_all().await);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_mutations_are_saved_to_data_file() {
+    let path = temp_data_file("auto_save");
+    let store = CountryDataStore::new().with_data_file(&path);
+    store.load_countries(sample_countries()).await;
+
+    store.remove("Japan").await.unwrap();
+
+    let reloaded = CountryDataStore::new();
+    reloaded.load_from_file(&path).await.unwrap();
+    let countries = reloaded.get_all().await;
+    assert_eq!(countries.len(), 1);
+    assert_eq!(countries[0].name, "France");
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_load_is_saved_to_data_file() {
+    let path = temp_data_file("load");
+    let store = CountryDataStore::new().with_data_file(&path);
+    store.load_countries(sample_countries()).await;
+
+    // Saved right away, not only on the next mutation
+    let reloaded = CountryDataStore::new();
+    reloaded.load_from_file(&path).await.unwrap();
+    assert_eq!(reloaded.get_all().await, sample_countries());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_population_history_is_saved_to_data_file() {
+    let path = temp_data_file("population");
+    let store = CountryDataStore::new().with_data_file(&path);
+    store.load_countries(sample_countries()).await;
+
+    let revision = PopulationRevision {
+        population: 68_000_000,
+        as_of: "2024-01-01".to_string(),
+    };
+    let france = store.revise_population("france", revision.clone()).await.unwrap();
+    assert_eq!(france.population, 68_000_000);
+    assert_eq!(france.population_history, vec![revision]);
+
+    let reloaded = CountryDataStore::new();
+    reloaded.load_from_file(&path).await.unwrap();
+    assert_eq!(reloaded.get_by_name("France").await.unwrap(), france);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_truncated_data_file_falls_back() {
+    let path = temp_data_file("truncated");
+    let json = serde_json::to_string(&sample_countries()).unwrap();
+    std::fs::write(&path, &json[..json.len() / 2]).unwrap();
+
+    let store = CountryDataStore::new();
+    assert!(store.load_from_file(&path).await.is_err());
+
+    let fallback = vec![Country {
+        name: "Fallback".to_string(),
+        capital: "Default".to_string(),
+        population: 1,
+        region: "Nowhere".to_string(),
+        languages: vec!["None".to_string()],
+        ..Default::default()
+    }];
+    store.load_from_file_or(&path, fallback.clone()).await;
+    assert_eq!(store.get_all().await, fallback);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_unreadable_data_file_is_not_overwritten() {
+    let path = temp_data_file("corrupt");
+    let json = serde_json::to_string(&sample_countries()).unwrap();
+    let truncated = &json[..json.len() / 2];
+    std::fs::write(&path, truncated).unwrap();
+
+    let store = CountryDataStore::new().with_data_file(&path);
+    store.load_from_file_or(&path, vec![country("Fallback", 1, "Nowhere")]).await;
+    assert_eq!(store.get_all().await.len(), 1);
+    assert!(store.is_loaded());
+
+    // The fallback stands in for the file without replacing it
+    assert_eq!(std::fs::read(&path).unwrap(), truncated.as_bytes());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_missing_data_file_falls_back() {
+    let path = temp_data_file("missing");
+
+    let store = CountryDataStore::new();
+    store.load_from_file_or(&path, sample_countries()).await;
+    assert_eq!(store.get_all().await, sample_countries());
+}
+
+fn country(name: &str, population: i64, region: &str) -> Country {
+    Country {
+        name: name.to_string(),
+        capital: "Capital".to_string(),
+        population,
+        region: region.to_string(),
+        languages: vec!["Language".to_string()],
+        ..Default::default()
+    }
+}
+
+fn names(countries: &[Country]) -> Vec<&str> {
+    countries.iter().map(|c| c.name.as_str()).collect()
+}
+
+#[test]
+fn test_sort_by_name_is_case_insensitive() {
+    let mut countries = vec![
+        country("chile", 1, "Americas"),
+        country("Brazil", 2, "Americas"),
+        country("argentina", 3, "Americas"),
+    ];
+
+    sort_countries(&mut countries, SortField::Name, SortOrder::Asc);
+    assert_eq!(names(&countries), vec!["argentina", "Brazil", "chile"]);
+
+    sort_countries(&mut countries, SortField::Name, SortOrder::Desc);
+    assert_eq!(names(&countries), vec!["chile", "Brazil", "argentina"]);
+}
+
+#[test]
+fn test_sort_by_population() {
+    let mut countries = vec![
+        country("Small", 10, "A"),
+        country("Large", 1000, "A"),
+        country("Medium", 100, "A"),
+    ];
+
+    sort_countries(&mut countries, SortField::Population, SortOrder::Desc);
+    assert_eq!(names(&countries), vec!["Large", "Medium", "Small"]);
+
+    sort_countries(&mut countries, SortField::Population, SortOrder::Asc);
+    assert_eq!(names(&countries), vec!["Small", "Medium", "Large"]);
+}
+
+#[test]
+fn test_sort_is_stable_for_equal_keys() {
+    let mut countries = vec![
+        country("First", 1, "europe"),
+        country("Other", 1, "Asia"),
+        country("Second", 1, "Europe"),
+        country("Third", 1, "EUROPE"),
+    ];
+
+    sort_countries(&mut countries, SortField::Region, SortOrder::Asc);
+    assert_eq!(names(&countries), vec!["Other", "First", "Second", "Third"]);
+
+    sort_countries(&mut countries, SortField::Region, SortOrder::Desc);
+    assert_eq!(names(&countries), vec!["First", "Second", "Third", "Other"]);
+}
+
+fn bordered(name: &str, alpha3: &str, borders: &[&str]) -> Country {
+    Country {
+        alpha3: Some(alpha3.to_string()),
+        borders: borders.iter().map(|b| b.to_string()).collect(),
+        ..country(name, 1, "Region")
+    }
+}
+
+#[tokio::test]
+async fn test_neighbors_lists_dangling_codes_as_unresolved() {
+    let store = CountryDataStore::new();
+    store
+        .load_countries(vec![
+            bordered("Spain", "ESP", &["FRA", "GIB"]),
+            bordered("France", "FRA", &["ESP"]),
+        ])
+        .await;
+
+    let neighbors = store.neighbors("spain").await.unwrap();
+    assert_eq!(names(&neighbors.neighbors), vec!["France"]);
+    assert_eq!(neighbors.unresolved, vec!["GIB"]);
+
+    assert!(store.neighbors("Atlantis").await.is_none());
+}
+
+#[tokio::test]
+async fn test_strict_borders_reject_unknown_codes() {
+    let store = CountryDataStore::new().with_strict_borders();
+    store.load_countries(vec![bordered("France", "FRA", &[])]).await;
+
+    let result = store.add_country(bordered("Spain", "ESP", &["FRA", "GIB"])).await;
+    assert!(matches!(result, Err(StoreError::Invalid(_))));
+
+    store
+        .add_country(bordered("Spain", "ESP", &["FRA"]))
+        .await
+        .unwrap();
+
+    let update = UpdateCountryRequest {
+        borders: Some(vec!["ESP".to_string(), "XXX".to_string()]),
+        ..Default::default()
+    };
+    assert!(matches!(
+        store.patch("France", update).await,
+        Err(StoreError::Invalid(_))
+    ));
+
+    // Without strict mode the same border is accepted
+    let lenient = CountryDataStore::new();
+    lenient
+        .add_country(bordered("Spain", "ESP", &["GIB"]))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_strict_bulk_insert_resolves_borders_within_batch() {
+    let store = CountryDataStore::new().with_strict_borders();
+
+    let report = store
+        .bulk_insert(
+            vec![
+                bordered("Spain", "ESP", &["FRA", "PRT"]),
+                bordered("Atlantis", "ATL", &["XXX"]),
+                bordered("France", "FRA", &["ESP"]),
+                bordered("Portugal", "PRT", &["ESP"]),
+            ],
+            LoadMode::Replace,
+        )
+        .await;
+
+    assert_eq!(report.inserted, 3);
+    assert_eq!(report.rejected.len(), 1);
+    assert_eq!(report.rejected[0].index, 1);
+    assert_eq!(report.rejected[0].name, "Atlantis");
+}
+
+fn capital_of(name: &str, capital: &str) -> Country {
+    Country {
+        capital: capital.to_string(),
+        ..country(name, 1, "Europe")
+    }
+}
+
+async fn store_with_france_and_spain() -> CountryDataStore {
+    let store = CountryDataStore::new();
+    store
+        .load_countries(vec![capital_of("France", "Paris"), capital_of("Spain", "Madrid")])
+        .await;
+    store
+}
+
+fn overlapping_batch() -> Vec<Country> {
+    vec![
+        capital_of("france", "Lyon"),
+        capital_of("Italy", "Rome"),
+        country("Invalid", -1, "Europe"),
+    ]
+}
+
+#[tokio::test]
+async fn test_load_append_keeps_existing_countries() {
+    let store = store_with_france_and_spain().await;
+
+    let report = store
+        .load_countries_with_mode(overlapping_batch(), LoadMode::Append)
+        .await;
+
+    assert_eq!(report.loaded, 1);
+    assert_eq!(report.updated, 0);
+    let rejected: Vec<&str> = report.rejected.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(rejected, vec!["france", "Invalid"]);
+    assert_eq!(store.get_all().await.len(), 3);
+    assert_eq!(store.get_by_name("France").await.unwrap().capital, "Paris");
+    assert_eq!(store.get_by_name("Italy").await.unwrap().capital, "Rome");
+}
+
+#[tokio::test]
+async fn test_load_merge_updates_matching_names() {
+    let store = store_with_france_and_spain().await;
+
+    let report = store
+        .load_countries_with_mode(overlapping_batch(), LoadMode::Merge)
+        .await;
+
+    assert_eq!(report.loaded, 1);
+    assert_eq!(report.updated, 1);
+    assert_eq!(report.rejected.len(), 1);
+    let names: Vec<String> = store.get_all().await.into_iter().map(|c| c.name).collect();
+    assert_eq!(names, vec!["france", "Spain", "Italy"]);
+    assert_eq!(store.get_by_name("France").await.unwrap().capital, "Lyon");
+}
+
+#[tokio::test]
+async fn test_load_replace_discards_existing_countries() {
+    let store = store_with_france_and_spain().await;
+
+    let report = store
+        .load_countries_with_mode(overlapping_batch(), LoadMode::Replace)
+        .await;
+
+    assert_eq!(report.loaded, 2);
+    assert_eq!(report.rejected.len(), 1);
+    assert!(store.get_by_name("Spain").await.is_none());
+    assert_eq!(store.get_by_name("France").await.unwrap().capital, "Lyon");
+}
+
+#[tokio::test]
+async fn test_load_reports_rejected_entries_with_reasons() {
+    let store = CountryDataStore::new();
+
+    let report = store
+        .load_countries(vec![
+            country("   ", 1, "Europe"),
+            country("France", 1, "Europe"),
+            country("Atlantis", -5, "Ocean"),
+            Country {
+                languages: vec!["Spanish".to_string(), String::new()],
+                ..country("Spain", 1, "Europe")
+            },
+        ])
+        .await;
+
+    assert_eq!(report.loaded, 1);
+    let rejected: Vec<(usize, &str)> = report
+        .rejected
+        .iter()
+        .map(|r| (r.index, r.name.as_str()))
+        .collect();
+    assert_eq!(rejected, vec![(0, "   "), (2, "Atlantis"), (3, "Spain")]);
+    assert!(report.rejected[0].reason.starts_with("name:"));
+    assert!(report.rejected[1].reason.starts_with("population:"));
+    assert!(report.rejected[2].reason.starts_with("languages:"));
+    assert_eq!(store.count().await, 1);
+}
+
+#[tokio::test]
+async fn test_load_keeps_first_of_duplicate_names() {
+    for mode in [LoadMode::Replace, LoadMode::Append, LoadMode::Merge] {
+        let store = CountryDataStore::new();
+
+        let report = store
+            .load_countries_with_mode(
+                vec![
+                    capital_of("France", "Paris"),
+                    capital_of("FRANCE", "Lyon"),
+                    capital_of("Spain", "Madrid"),
+                    capital_of(" france ", "Nice"),
+                ],
+                mode,
+            )
+            .await;
+
+        assert_eq!(report.loaded, 2, "{:?}", mode);
+        let rejected: Vec<(usize, &str)> = report
+            .rejected
+            .iter()
+            .map(|r| (r.index, r.name.as_str()))
+            .collect();
+        assert_eq!(rejected, vec![(1, "FRANCE"), (3, " france ")], "{:?}", mode);
+        assert_eq!(report.rejected[0].reason, "Country \"FRANCE\" already exists");
+        assert_eq!(store.count().await, 2);
+        assert_eq!(store.get_by_name("france").await.unwrap().capital, "Paris");
+    }
+}
+
+#[tokio::test]
+async fn test_bulk_merge_reuses_codes_of_updated_country() {
+    let store = CountryDataStore::new().with_strict_borders();
+    store
+        .bulk_insert(
+            vec![
+                bordered("France", "FRA", &["ESP"]),
+                bordered("Spain", "ESP", &["FRA"]),
+            ],
+            LoadMode::Append,
+        )
+        .await;
+
+    let report = store
+        .bulk_insert(
+            vec![
+                bordered("France", "FRA", &["ESP", "ITA"]),
+                bordered("Italy", "ITA", &["FRA"]),
+                bordered("Gaul", "FRA", &[]),
+            ],
+            LoadMode::Merge,
+        )
+        .await;
+
+    assert_eq!(report.inserted, 1);
+    assert_eq!(report.updated, 1);
+    assert_eq!(report.rejected.len(), 1);
+    assert_eq!(report.rejected[0].name, "Gaul");
+    assert_eq!(store.get_all().await.len(), 3);
+    assert_eq!(
+        store.get_by_name("France").await.unwrap().borders,
+        vec!["ESP", "ITA"]
+    );
+}
+
+#[tokio::test]
+async fn test_loaded_country_gets_flag_emoji_from_alpha2() {
+    let store = CountryDataStore::new();
+    store
+        .load_countries(vec![
+            Country {
+                name: "France".to_string(),
+                alpha2: Some("FR".to_string()),
+                ..Default::default()
+            },
+            Country {
+                name: "Custom".to_string(),
+                alpha2: Some("CU".to_string()),
+                flag_emoji: "🏳".to_string(),
+                ..Default::default()
+            },
+            Country {
+                name: "Nowhere".to_string(),
+                ..Default::default()
+            },
+        ])
+        .await;
+
+    assert_eq!(store.get_by_name("France").await.unwrap().flag_emoji, "🇫🇷");
+    // An existing emoji is kept, and there is nothing to derive without a code
+    assert_eq!(store.get_by_name("Custom").await.unwrap().flag_emoji, "🏳");
+    assert_eq!(store.get_by_name("Nowhere").await.unwrap().flag_emoji, "");
+}
+
+#[tokio::test]
+async fn test_name_lookups_on_large_store() {
+    let countries: Vec<Country> = (0..10_000)
+        .map(|i| country(&format!("Country {}", i), i, "Region"))
+        .collect();
+    let store = CountryDataStore::new();
+    store.load_countries(countries).await;
+
+    for i in [0, 1, 4_999, 9_999] {
+        let found = store.get_by_name(&format!("  COUNTRY   {} ", i)).await.unwrap();
+        assert_eq!(found.population, i);
+    }
+    assert!(store.get_by_name("Country 10000").await.is_none());
+
+    // Removing shifts every later position; lookups must follow
+    store.remove("Country 0").await.unwrap();
+    assert!(store.get_by_name("Country 0").await.is_none());
+    assert_eq!(store.get_by_name("Country 9999").await.unwrap().population, 9_999);
+    assert_eq!(store.get_all().await[0].name, "Country 1");
+}
+
+#[tokio::test]
+async fn test_add_country_validates_and_returns_stored_country() {
+    let store = CountryDataStore::new();
+
+    let err = store.add_country(country("Atlantis", -1, "Ocean")).await;
+    assert!(matches!(err, Err(StoreError::Invalid(_))));
+    assert!(store.get_by_name("Atlantis").await.is_none());
+
+    let added = store
+        .add_country(Country {
+            alpha2: Some("NZ".to_string()),
+            ..country("New Zealand", 5, "Oceania")
+        })
+        .await
+        .unwrap();
+    assert_eq!(added.flag_emoji, "🇳🇿");
+    assert_eq!(store.get_by_name("New Zealand").await.unwrap(), added);
+}
+
+#[tokio::test]
+async fn test_name_index_stays_consistent_across_mutations() {
+    let store = CountryDataStore::new();
+    store
+        .load_countries(vec![
+            country("France", 1, "Europe"),
+            country("Spain", 2, "Europe"),
+        ])
+        .await;
+
+    // Same name with different case, accents and spacing is a duplicate
+    let err = store.add_country(country(" FRANCE ", 3, "Europe")).await;
+    assert_eq!(err, Err(StoreError::DuplicateName(" FRANCE ".to_string())));
+    assert_eq!(store.get_by_name("france").await.unwrap().population, 1);
+
+    // Renaming frees the old name and takes the new one
+    store
+        .update("France", country("Gaul", 1, "Europe"))
+        .await
+        .unwrap();
+    assert!(store.get_by_name("France").await.is_none());
+    assert_eq!(store.get_by_name("gaul").await.unwrap().population, 1);
+    let err = store.update("Spain", country("Gaul", 2, "Europe")).await;
+    assert_eq!(err, Err(StoreError::DuplicateName("Gaul".to_string())));
+
+    store.add_country(country("France", 3, "Europe")).await.unwrap();
+    assert_eq!(store.get_by_name("France").await.unwrap().population, 3);
+
+    store.remove("Gaul").await.unwrap();
+    assert_eq!(store.get_by_name("Spain").await.unwrap().population, 2);
+    assert_eq!(store.get_by_name("France").await.unwrap().population, 3);
+    assert_eq!(names(&store.get_all().await), vec!["Spain", "France"]);
+}
+
+#[tokio::test]
+async fn test_region_index_follows_region_change_on_update() {
+    let store = CountryDataStore::new();
+    store
+        .load_countries(vec![
+            country("France", 1, "Europe"),
+            country("Japan", 2, "Asia"),
+            country("Spain", 3, "Europe"),
+        ])
+        .await;
+
+    store
+        .update("France", country("France", 1, "ASIA"))
+        .await
+        .unwrap();
+    assert_eq!(names(&store.filter_by_region("europe").await), vec!["Spain"]);
+    // Insertion order is kept across regions, not grouped by region
+    assert_eq!(
+        names(&store.filter_by_region("Asia").await),
+        vec!["France", "Japan"]
+    );
+
+    let update = UpdateCountryRequest {
+        region: Some("Europe".to_string()),
+        ..Default::default()
+    };
+    store.patch("Japan", update).await.unwrap();
+    assert_eq!(
+        names(&store.filter_by_region("Europe").await),
+        vec!["Japan", "Spain"]
+    );
+    assert_eq!(names(&store.filter_by_region("Asia").await), vec!["France"]);
+}
+
+#[tokio::test]
+async fn test_region_index_after_remove_and_add() {
+    let store = CountryDataStore::new();
+    store
+        .load_countries(vec![
+            country("France", 1, "Europe"),
+            country("Japan", 2, "Asia"),
+            country("Spain", 3, "Europe"),
+            country("Chile", 4, "Americas"),
+        ])
+        .await;
+
+    store.remove("France").await.unwrap();
+    assert_eq!(names(&store.filter_by_region("Europe").await), vec!["Spain"]);
+    assert_eq!(names(&store.filter_by_region("Americas").await), vec!["Chile"]);
+    assert_eq!(
+        names(&store.filter_by_region("americas, europe").await),
+        vec!["Spain", "Chile"]
+    );
+
+    store.remove("Japan").await.unwrap();
+    assert!(store.filter_by_region("Asia").await.is_empty());
+
+    store.add_country(country("Italy", 5, "europe")).await.unwrap();
+    assert_eq!(
+        names(&store.filter_by_region("EUROPE").await),
+        vec!["Spain", "Italy"]
+    );
+}
+
+#[tokio::test]
+async fn test_rollback_restores_exact_prior_contents() {
+    let store = CountryDataStore::new();
+    store.load_countries(sample_countries()).await;
+    let before = store.get_all().await;
+
+    let id = store.snapshot().await;
+    store.remove("France").await.unwrap();
+    store
+        .patch(
+            "Japan",
+            UpdateCountryRequest {
+                capital: Some("Kyoto".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    store.add_country(country("Atlantis", 1, "Ocean")).await.unwrap();
+    assert_ne!(store.get_all().await, before);
+
+    store.rollback(id).await.unwrap();
+    assert_eq!(store.get_all().await, before);
+    assert_eq!(store.get_by_name("Japan").await.unwrap().capital, "Tokyo");
+    assert!(store.get_by_name("Atlantis").await.is_none());
+
+    // The snapshot survives the rollback and later writes don't touch it
+    store.remove("Japan").await.unwrap();
+    store.rollback(id).await.unwrap();
+    assert_eq!(store.get_all().await, before);
+}
+
+#[tokio::test]
+async fn test_rollback_to_evicted_snapshot_is_not_found() {
+    let store = CountryDataStore::new().with_max_snapshots(2);
+    store.load_countries(sample_countries()).await;
+
+    let oldest = store.snapshot().await;
+    let middle = store.snapshot().await;
+    let newest = store.snapshot().await;
+    assert!(oldest != middle && middle != newest);
+
+    assert_eq!(
+        store.rollback(oldest).await,
+        Err(StoreError::SnapshotNotFound(oldest))
+    );
+    assert!(store.rollback(middle).await.is_ok());
+    assert!(store.rollback(newest).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_capital_index_follows_updates_and_removals() {
+    let store = CountryDataStore::new();
+    store
+        .load_countries(vec![
+            capital_of("France", "Paris"),
+            capital_of("Spain", "Madrid"),
+            capital_of("Italy", "Rome"),
+        ])
+        .await;
+
+    let names = |countries: Vec<Country>| -> Vec<String> {
+        countries.into_iter().map(|c| c.name).collect()
+    };
+    assert_eq!(names(store.get_by_capital("MADRID").await), vec!["Spain"]);
+
+    store
+        .patch(
+            "Italy",
+            UpdateCountryRequest {
+                capital: Some("Madrid".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(names(store.get_by_capital("madrid").await), vec!["Spain", "Italy"]);
+    assert!(store.get_by_capital("Rome").await.is_empty());
+
+    store.remove("France").await.unwrap();
+    assert_eq!(names(store.get_by_capital("Madrid").await), vec!["Spain", "Italy"]);
+    store.remove("Spain").await.unwrap();
+    assert_eq!(names(store.get_by_capital("Madrid").await), vec!["Italy"]);
+    assert!(store.get_by_capital("Paris").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_language_stats_merge_casing_variants() {
+    let speaking = |name: &str, population: i64, region: &str, languages: &[&str]| Country {
+        languages: languages.iter().map(|l| l.to_string()).collect(),
+        ..country(name, population, region)
+    };
+    let store = CountryDataStore::new();
+    store
+        .load_countries(vec![
+            speaking("Ireland", 5, "Europe", &["english", "Irish"]),
+            speaking("Malta", 1, "Europe", &["Maltese", "English"]),
+            speaking("Kenya", 50, "Africa", &["English ", "Swahili"]),
+            speaking("Tonga", 2, "Oceania", &["ENGLISH", "english", "Tongan"]),
+        ])
+        .await;
+
+    let languages = store.language_stats(None).await;
+    assert_eq!(languages.len(), 5);
+    let english = &languages[0];
+    // Shown with its most common spelling, after trimming
+    assert_eq!(english.language, "English");
+    assert_eq!(english.count, 4);
+    assert_eq!(english.total_population, 58);
+    assert_eq!(english.countries, vec!["Ireland", "Malta", "Kenya", "Tonga"]);
+    // Ties on count are sorted by name
+    assert_eq!(languages[1].language, "Irish");
+
+    let europe = store.language_stats(Some("europe")).await;
+    assert_eq!(europe.len(), 3);
+    assert_eq!(europe[0].countries, vec!["Ireland", "Malta"]);
+    assert!(store.language_stats(Some("Atlantis")).await.is_empty());
+}
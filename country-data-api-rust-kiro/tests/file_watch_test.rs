@@ -0,0 +1,123 @@
+use country_data_api_rust_kiro::services::{
+    reload_from_file, watch_file, CountryDataStore, CountryFileError, IntoSharedStore,
+    ReloadSummary,
+};
+use std::path::PathBuf;
+
+fn temp_file(label: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    std::env::temp_dir().join(format!(
+        "watched_countries_{}_{}_{}.json",
+        label,
+        std::process::id(),
+        nanos
+    ))
+}
+
+fn record(name: &str, capital: &str) -> String {
+    format!(
+        r#"{{"name": "{}", "capital": "{}", "population": 1, "region": "Europe", "languages": ["Language"]}}"#,
+        name, capital
+    )
+}
+
+fn write_records(path: &PathBuf, records: &[String]) {
+    std::fs::write(path, format!("[{}]", records.join(","))).unwrap();
+}
+
+#[tokio::test]
+async fn test_reload_swaps_in_changed_file() {
+    let path = temp_file("valid");
+    let store = CountryDataStore::new();
+
+    write_records(&path, &[record("France", "Paris"), record("Spain", "Madrid")]);
+    let first = reload_from_file(&store, &path).await.unwrap();
+    assert_eq!(first.added, 2);
+
+    write_records(
+        &path,
+        &[
+            record("France", "Lyon"),
+            record("Italy", "Rome"),
+            record("", "Nowhere"),
+        ],
+    );
+    let second = reload_from_file(&store, &path).await.unwrap();
+
+    assert_eq!(
+        second,
+        ReloadSummary {
+            added: 1,
+            removed: 1,
+            changed: 1,
+            unchanged: 0,
+            rejected: 1,
+        }
+    );
+    assert_eq!(store.count().await, 2);
+    assert_eq!(store.get_by_name("France").await.unwrap().capital, "Lyon");
+    assert!(store.get_by_name("Spain").await.is_none());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_reload_keeps_data_when_file_is_invalid() {
+    let path = temp_file("invalid");
+    let store = CountryDataStore::new();
+
+    write_records(&path, &[record("France", "Paris")]);
+    reload_from_file(&store, &path).await.unwrap();
+    let version = store.version();
+
+    std::fs::write(&path, r#"[{"name": "France", "capital": "#).unwrap();
+    let err = reload_from_file(&store, &path).await.unwrap_err();
+    assert!(matches!(err, CountryFileError::InvalidJson { .. }));
+
+    std::fs::remove_file(&path).unwrap();
+    let err = reload_from_file(&store, &path).await.unwrap_err();
+    assert!(matches!(err, CountryFileError::Unreadable { .. }));
+
+    assert_eq!(store.version(), version);
+    assert_eq!(store.get_by_name("France").await.unwrap().capital, "Paris");
+}
+
+#[tokio::test]
+async fn test_reload_of_same_file_reports_unchanged() {
+    let path = temp_file("same");
+    let store = CountryDataStore::new();
+
+    write_records(&path, &[record("France", "Paris"), record("Spain", "Madrid")]);
+    reload_from_file(&store, &path).await.unwrap();
+    let summary = reload_from_file(&store, &path).await.unwrap();
+
+    assert_eq!(summary.unchanged, 2);
+    assert_eq!(summary.added + summary.removed + summary.changed, 0);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_watching_the_data_file_settles_after_a_reload() {
+    let path = temp_file("data_file");
+    write_records(&path, &[record("France", "Paris")]);
+    let store = CountryDataStore::new().with_data_file(&path).into_shared();
+    store.load_from_file(&path).await.unwrap();
+    let watcher = watch_file(store.clone(), path.clone()).unwrap();
+
+    // Each reload saves the store back to the file it is watching
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    write_records(&path, &[record("France", "Lyon")]);
+    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+    assert_eq!(store.get_by_name("France").await.unwrap().capital, "Lyon");
+
+    let version = store.version();
+    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+    assert_eq!(store.version(), version, "saving a reload shouldn't trigger another");
+
+    watcher.abort();
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,121 @@
+use country_data_api_rust_kiro::models::{Country, SearchFields};
+use country_data_api_rust_kiro::services::{
+    name_matches, parse_region_list, search_countries, DensityRange, PopulationRange,
+};
+
+#[test]
+fn test_population_range_includes_boundaries() {
+    let range = PopulationRange::new(Some(100), Some(200)).unwrap();
+
+    assert!(!range.contains(99));
+    assert!(range.contains(100));
+    assert!(range.contains(150));
+    assert!(range.contains(200));
+    assert!(!range.contains(201));
+}
+
+#[test]
+fn test_population_range_open_ends() {
+    let at_least = PopulationRange::new(Some(100), None).unwrap();
+    assert!(!at_least.contains(99));
+    assert!(at_least.contains(100));
+    assert!(at_least.contains(i64::MAX));
+
+    let at_most = PopulationRange::new(None, Some(100)).unwrap();
+    assert!(at_most.contains(0));
+    assert!(at_most.contains(100));
+    assert!(!at_most.contains(101));
+
+    let unbounded = PopulationRange::new(None, None).unwrap();
+    assert!(unbounded.is_unbounded());
+    assert!(unbounded.contains(0));
+}
+
+#[test]
+fn test_population_range_single_value() {
+    let exact = PopulationRange::new(Some(42), Some(42)).unwrap();
+    assert!(exact.contains(42));
+    assert!(!exact.contains(41));
+    assert!(!exact.contains(43));
+}
+
+#[test]
+fn test_population_range_rejects_invalid_bounds() {
+    assert!(PopulationRange::new(Some(-1), None).is_err());
+    assert!(PopulationRange::new(None, Some(-1)).is_err());
+    assert!(PopulationRange::new(Some(200), Some(100)).is_err());
+}
+
+#[test]
+fn test_name_matches_is_case_insensitive_substring() {
+    let country = Country {
+        name: "United Kingdom".to_string(),
+        capital: "London".to_string(),
+        population: 67736000,
+        region: "Europe".to_string(),
+        languages: vec!["English".to_string()],
+        ..Default::default()
+    };
+
+    assert!(name_matches(&country, "KING"));
+    assert!(name_matches(&country, "  "));
+    assert!(!name_matches(&country, "london"));
+}
+
+#[test]
+fn test_search_ignores_accents_and_folds_case() {
+    let ivory_coast = Country {
+        name: "Côte d'Ivoire".to_string(),
+        capital: "Yamoussoukro".to_string(),
+        population: 28160000,
+        region: "Africa".to_string(),
+        languages: vec!["Français".to_string()],
+        ..Default::default()
+    };
+    let germany = Country {
+        name: "Germany".to_string(),
+        capital: "Berlin".to_string(),
+        population: 83240000,
+        region: "Europe".to_string(),
+        languages: vec!["Deutsch".to_string()],
+        ..Default::default()
+    };
+    let countries = vec![ivory_coast.clone(), germany.clone()];
+
+    assert!(name_matches(&ivory_coast, "cote"));
+    assert!(name_matches(&ivory_coast, "CÔTE D'IVOIRE"));
+    assert!(name_matches(&germany, "ＧＥＲ"));
+    let found = search_countries(countries.clone(), "francais", SearchFields::Languages, None);
+    assert_eq!(found, vec![ivory_coast.clone()]);
+    let found = search_countries(countries, "YAMOUSSOUKRÓ", SearchFields::All, None);
+    assert_eq!(found, vec![ivory_coast]);
+}
+
+#[test]
+fn test_parse_region_list() {
+    assert_eq!(parse_region_list("Europe"), vec!["europe"]);
+    assert_eq!(parse_region_list(" Europe , ASIA "), vec!["europe", "asia"]);
+    assert_eq!(parse_region_list("Europe,,Asia,"), vec!["europe", "asia"]);
+    assert_eq!(parse_region_list("ÉUROPE"), vec!["europe"]);
+    assert!(parse_region_list("").is_empty());
+    assert!(parse_region_list(" , ,").is_empty());
+}
+
+#[test]
+fn test_density_range_boundaries() {
+    let range = DensityRange::new(Some(10.0), Some(20.0)).unwrap();
+    assert!(range.contains(Some(10.0)));
+    assert!(range.contains(Some(20.0)));
+    assert!(!range.contains(Some(9.99)));
+    assert!(!range.contains(Some(20.01)));
+    // No area, no density: outside any bounded range
+    assert!(!range.contains(None));
+
+    let open = DensityRange::new(None, None).unwrap();
+    assert!(open.is_unbounded());
+    assert!(open.contains(None));
+
+    assert!(DensityRange::new(Some(-1.0), None).is_err());
+    assert!(DensityRange::new(None, Some(f64::NAN)).is_err());
+    assert!(DensityRange::new(Some(5.0), Some(1.0)).is_err());
+}
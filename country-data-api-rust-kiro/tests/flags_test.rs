@@ -0,0 +1,17 @@
+use country_data_api_rust_kiro::utils::flag_emoji_from_alpha2;
+
+#[test]
+fn test_flag_emoji_from_alpha2() {
+    assert_eq!(flag_emoji_from_alpha2("FR").as_deref(), Some("\u{1F1EB}\u{1F1F7}"));
+    assert_eq!(flag_emoji_from_alpha2("us").as_deref(), Some("\u{1F1FA}\u{1F1F8}"));
+    assert_eq!(flag_emoji_from_alpha2("jP").as_deref(), Some("\u{1F1EF}\u{1F1F5}"));
+}
+
+#[test]
+fn test_flag_emoji_rejects_garbage() {
+    assert_eq!(flag_emoji_from_alpha2(""), None);
+    assert_eq!(flag_emoji_from_alpha2("F"), None);
+    assert_eq!(flag_emoji_from_alpha2("FRA"), None);
+    assert_eq!(flag_emoji_from_alpha2("F1"), None);
+    assert_eq!(flag_emoji_from_alpha2("É!"), None);
+}
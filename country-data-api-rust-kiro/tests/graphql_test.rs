@@ -0,0 +1,3 @@
+include!("router/backends.rs");
+
+for_each_store!("router/graphql.rs");
@@ -0,0 +1,97 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use country_data_api_rust_kiro::{
+    api::{create_routes, create_routes_with_config, ApiConfig},
+    models::Country,
+    services::CountryDataStore,
+};
+use serde_json::Value;
+use tower::util::ServiceExt;
+
+async fn get_json(app: Router, uri: &str) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+fn country(name: &str) -> Country {
+    Country {
+        name: name.to_string(),
+        capital: "Capital".to_string(),
+        population: 1000,
+        region: "Europe".to_string(),
+        languages: vec!["English".to_string()],
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_health_reports_version_and_uptime() {
+    let (status, body) = get_json(create_routes(CountryDataStore::new()), "/health").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["uptime_seconds"].is_u64());
+}
+
+#[tokio::test]
+async fn test_ready_is_unavailable_before_load() {
+    let (status, body) = get_json(create_routes(CountryDataStore::new()), "/ready").await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "loading");
+    assert_eq!(body["countries"], 0);
+}
+
+#[tokio::test]
+async fn test_ready_after_load_reports_count() {
+    let store = CountryDataStore::new();
+    let app = create_routes(store.clone());
+
+    store
+        .load_countries(vec![country("France"), country("Germany")])
+        .await;
+    let (status, body) = get_json(app, "/ready").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ready");
+    assert_eq!(body["countries"], 2);
+}
+
+#[tokio::test]
+async fn test_ready_after_loading_nothing() {
+    let store = CountryDataStore::new();
+    store.load_countries(Vec::new()).await;
+    let (status, body) = get_json(create_routes(store), "/ready").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["countries"], 0);
+}
+
+#[tokio::test]
+async fn test_probes_skip_rate_limit() {
+    let config = ApiConfig::default().with_rate_limit(1, false);
+    let app = create_routes_with_config(CountryDataStore::new(), &config);
+
+    let (status, _) = get_json(app.clone(), "/api/regions").await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = get_json(app.clone(), "/api/regions").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+    for _ in 0..3 {
+        let (status, _) = get_json(app.clone(), "/health").await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = get_json(app.clone(), "/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,183 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use country_data_api_rust_kiro::{
+    api::create_routes,
+    models::Country,
+    services::{CountryDataStore, Metrics},
+};
+use tower::util::ServiceExt;
+
+async fn send(app: &Router, method: &str, uri: &str, body: Option<&str>) -> StatusCode {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .unwrap();
+    app.clone().oneshot(request).await.unwrap().status()
+}
+
+async fn scrape(app: &Router) -> String {
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+/// Value of the sample whose name and labels are exactly `series`
+fn sample(metrics: &str, series: &str) -> Option<f64> {
+    metrics
+        .lines()
+        .filter_map(|line| line.rsplit_once(' '))
+        .find(|(name, _)| *name == series)
+        .map(|(_, value)| value.parse().unwrap())
+}
+
+fn country(name: &str) -> Country {
+    Country {
+        name: name.to_string(),
+        capital: "Capital".to_string(),
+        population: 1000,
+        region: "Europe".to_string(),
+        languages: vec!["English".to_string()],
+        ..Default::default()
+    }
+}
+
+async fn loaded_app() -> Router {
+    let store = CountryDataStore::new();
+    store
+        .load_countries(vec![country("France"), country("Germany")])
+        .await;
+    create_routes(store)
+}
+
+#[tokio::test]
+async fn test_requests_are_counted_by_route_and_status() {
+    let app = loaded_app().await;
+
+    send(&app, "GET", "/api/countries/France", None).await;
+    send(&app, "GET", "/api/countries/Germany", None).await;
+    send(&app, "GET", "/api/countries/Atlantis", None).await;
+    send(&app, "GET", "/api/countries", None).await;
+    let metrics = scrape(&app).await;
+
+    assert_eq!(
+        sample(
+            &metrics,
+            r#"http_requests_total{method="GET",route="/api/countries/:name",status="200"}"#
+        ),
+        Some(2.0)
+    );
+    assert_eq!(
+        sample(
+            &metrics,
+            r#"http_requests_total{method="GET",route="/api/countries/:name",status="404"}"#
+        ),
+        Some(1.0)
+    );
+    assert_eq!(
+        sample(
+            &metrics,
+            r#"http_request_duration_seconds_count{method="GET",route="/api/countries",status="200"}"#
+        ),
+        Some(1.0)
+    );
+}
+
+#[tokio::test]
+async fn test_not_found_lookups_are_counted() {
+    let app = loaded_app().await;
+
+    send(&app, "GET", "/api/countries/Atlantis", None).await;
+    send(&app, "GET", "/api/countries/Atlantis/neighbors", None).await;
+    send(&app, "GET", "/api/countries/code/ZZ", None).await;
+    send(&app, "GET", "/api/countries/code/XYZ", None).await;
+    let metrics = scrape(&app).await;
+
+    assert_eq!(
+        sample(&metrics, r#"country_lookups_not_found_total{by="name"}"#),
+        Some(2.0)
+    );
+    assert_eq!(
+        sample(&metrics, r#"country_lookups_not_found_total{by="code"}"#),
+        Some(2.0)
+    );
+}
+
+#[tokio::test]
+async fn test_store_size_gauge_and_mutations() {
+    let app = loaded_app().await;
+    assert_eq!(sample(&scrape(&app).await, "countries_stored"), Some(2.0));
+
+    let body = serde_json::to_string(&country("Spain")).unwrap();
+    assert_eq!(
+        send(&app, "POST", "/api/countries", Some(&body)).await,
+        StatusCode::CREATED
+    );
+    // A rejected duplicate is not a mutation
+    assert_eq!(
+        send(&app, "POST", "/api/countries", Some(&body)).await,
+        StatusCode::CONFLICT
+    );
+    assert_eq!(
+        send(&app, "DELETE", "/api/countries/France", None).await,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        send(&app, "DELETE", "/api/countries/Germany", None).await,
+        StatusCode::NO_CONTENT
+    );
+    let metrics = scrape(&app).await;
+
+    assert_eq!(sample(&metrics, "countries_stored"), Some(1.0));
+    assert_eq!(
+        sample(&metrics, r#"store_mutations_total{operation="load"}"#),
+        Some(1.0)
+    );
+    assert_eq!(
+        sample(&metrics, r#"store_mutations_total{operation="add"}"#),
+        Some(1.0)
+    );
+    assert_eq!(
+        sample(&metrics, r#"store_mutations_total{operation="remove"}"#),
+        Some(2.0)
+    );
+}
+
+#[tokio::test]
+async fn test_unmatched_routes_share_one_label() {
+    let app = loaded_app().await;
+
+    send(&app, "GET", "/nope", None).await;
+    send(&app, "GET", "/also/nope", None).await;
+    let metrics = scrape(&app).await;
+
+    assert_eq!(
+        sample(
+            &metrics,
+            r#"http_requests_total{method="GET",route="unmatched",status="404"}"#
+        ),
+        Some(2.0)
+    );
+}
+
+#[test]
+fn test_separate_registries_do_not_share_counts() {
+    let first = Metrics::new();
+    let second = Metrics::new();
+
+    first.record_not_found("name");
+
+    assert!(first.render().contains(r#"country_lookups_not_found_total{by="name"} 1"#));
+    assert!(!second.render().contains("country_lookups_not_found_total{"));
+}
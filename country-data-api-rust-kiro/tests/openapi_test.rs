@@ -0,0 +1,95 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use country_data_api_rust_kiro::{api::create_routes, services::CountryDataStore};
+use serde_json::Value;
+use tower::util::ServiceExt;
+
+async fn get(uri: &str) -> (StatusCode, Vec<u8>) {
+    let response = create_routes(CountryDataStore::new())
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, body.to_vec())
+}
+
+async fn spec() -> Value {
+    let (status, body) = get("/api/openapi.json").await;
+    assert_eq!(status, StatusCode::OK);
+    serde_json::from_slice(&body).unwrap()
+}
+
+fn parameter_names(operation: &Value) -> Vec<&str> {
+    operation["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|param| param["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_list_documents_query_parameters() {
+    let spec = spec().await;
+    let names = parameter_names(&spec["paths"]["/api/countries"]["get"]);
+
+    for expected in ["region", "search", "limit", "offset", "sort", "order"] {
+        assert!(names.contains(&expected), "missing parameter {}", expected);
+    }
+}
+
+#[tokio::test]
+async fn test_by_name_documents_not_found_schema() {
+    let spec = spec().await;
+    let not_found = &spec["paths"]["/api/countries/{name}"]["get"]["responses"]["404"];
+
+    assert_eq!(
+        not_found["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/ErrorResponse"
+    );
+    assert_eq!(
+        spec["components"]["schemas"]["ErrorResponse"]["properties"]["error"]["type"],
+        "string"
+    );
+}
+
+#[tokio::test]
+async fn test_mutating_endpoints_require_api_key() {
+    let spec = spec().await;
+
+    assert_eq!(
+        spec["components"]["securitySchemes"]["api_key"]["name"],
+        "x-api-key"
+    );
+    let by_name = &spec["paths"]["/api/countries/{name}"];
+    for method in ["put", "patch", "delete"] {
+        assert!(
+            by_name[method]["security"][0].get("api_key").is_some(),
+            "{} should require the api_key scheme",
+            method
+        );
+    }
+    assert!(by_name["get"].get("security").is_none());
+}
+
+#[tokio::test]
+async fn test_country_schema_includes_computed_density() {
+    let spec = spec().await;
+    let country = &spec["components"]["schemas"]["Country"];
+
+    assert!(country["properties"]["population_density"].is_object());
+    assert!(country["properties"]["flag_emoji"].is_object());
+}
+
+#[tokio::test]
+async fn test_swagger_ui_is_served() {
+    let (status, body) = get("/docs/").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(String::from_utf8(body).unwrap().contains("swagger"));
+}
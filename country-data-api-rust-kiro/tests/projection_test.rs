@@ -0,0 +1,83 @@
+use country_data_api_rust_kiro::models::{Country, CountryField, Currency};
+use country_data_api_rust_kiro::services::project_country;
+use serde_json::Value;
+
+fn france() -> Country {
+    Country {
+        name: "France".to_string(),
+        capital: "Paris".to_string(),
+        population: 67391582,
+        region: "Europe".to_string(),
+        subregion: "Western Europe".to_string(),
+        languages: vec!["French".to_string()],
+        alpha2: Some("FR".to_string()),
+        alpha3: Some("FRA".to_string()),
+        currencies: vec![Currency::new("EUR", "Euro", "€")],
+        area_km2: 551695.0,
+        borders: vec!["BEL".to_string(), "ESP".to_string()],
+        flag_emoji: "🇫🇷".to_string(),
+        flag_url: Some("https://flagcdn.com/fr.svg".to_string()),
+        population_history: Vec::new(),
+    }
+}
+
+fn keys(json: &Value) -> Vec<&str> {
+    let mut keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+    keys.sort();
+    keys
+}
+
+#[test]
+fn test_every_field_projects_to_its_key() {
+    let country = france();
+    let full = serde_json::to_value(&country).unwrap();
+
+    for field in CountryField::ALL {
+        let projected = project_country(&country, &[field]);
+        let mut expected = vec!["name", field.as_str()];
+        expected.sort();
+        expected.dedup();
+
+        assert_eq!(keys(&projected), expected, "{:?}", field);
+        assert_eq!(projected[field.as_str()], full[field.as_str()], "{:?}", field);
+    }
+}
+
+#[test]
+fn test_projection_combinations() {
+    let country = france();
+
+    let projected = project_country(&country, &[CountryField::Population, CountryField::Capital]);
+    assert_eq!(keys(&projected), vec!["capital", "name", "population"]);
+    assert_eq!(projected["population"], 67391582);
+
+    // Every field selected is the full country
+    let all = project_country(&country, &CountryField::ALL);
+    assert_eq!(all, serde_json::to_value(&country).unwrap());
+
+    // Unset optional codes stay absent rather than becoming null
+    let codeless = Country {
+        alpha2: None,
+        ..france()
+    };
+    let projected = project_country(&codeless, &[CountryField::Alpha2, CountryField::Alpha3]);
+    assert_eq!(keys(&projected), vec!["alpha3", "name"]);
+}
+
+#[test]
+fn test_parse_field_list() {
+    assert_eq!(
+        CountryField::parse_list("name, Population,,name").unwrap(),
+        vec![CountryField::Name, CountryField::Population]
+    );
+    assert_eq!(
+        CountryField::parse_list("area_km2,population_density").unwrap(),
+        vec![CountryField::AreaKm2, CountryField::PopulationDensity]
+    );
+
+    let error = CountryField::parse_list("name,flag").unwrap_err();
+    assert!(error.contains("\"flag\""));
+    assert!(error.contains("flag_emoji") && error.contains("population_density"));
+
+    assert!(CountryField::parse_list(" , ").is_err());
+}
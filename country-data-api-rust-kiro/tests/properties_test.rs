@@ -1,4 +1,4 @@
-use country_data_api_rust_kiro::models::Country;
+use country_data_api_rust_kiro::models::{Country, Currency, ValidationError};
 use proptest::prelude::*;
 
 // Feature: country-data-api-rust, Property 2: Serialization includes all fields
@@ -17,6 +17,7 @@ proptest! {
             population,
             region,
             languages,
+            ..Default::default()
         };
 
         let json = serde_json::to_value(&country).unwrap();
@@ -45,6 +46,7 @@ proptest! {
             population,
             region,
             languages,
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&country).unwrap();
@@ -54,6 +56,105 @@ proptest! {
     }
 }
 
+// ISO codes survive a round-trip
+proptest! {
+    #[test]
+    fn test_serialization_roundtrip_with_codes(
+        name in "[a-zA-Z][a-zA-Z ]{0,49}",
+        alpha2 in prop::option::of("[A-Z]{2}"),
+        alpha3 in prop::option::of("[A-Z]{3}")
+    ) {
+        let country = Country {
+            name,
+            capital: "Capital".to_string(),
+            population: 1,
+            region: "Region".to_string(),
+            languages: vec!["Language".to_string()],
+            alpha2,
+            alpha3,
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&country).unwrap();
+        let deserialized: Country = serde_json::from_str(&json).unwrap();
+
+        prop_assert!(country.is_valid());
+        prop_assert_eq!(country, deserialized);
+    }
+
+    #[test]
+    fn test_validation_rejects_malformed_codes(
+        alpha2 in "[a-z]{2}|[A-Z]{1}|[A-Z]{3}|[A-Z][0-9]"
+    ) {
+        let country = Country {
+            name: "Name".to_string(),
+            alpha2: Some(alpha2.clone()),
+            ..Default::default()
+        };
+
+        prop_assert_eq!(country.validate(), Err(vec![ValidationError::InvalidAlpha2(alpha2)]));
+    }
+}
+
+#[test]
+fn test_country_without_optional_fields_deserializes() {
+    let json = r#"{"name": "France", "capital": "Paris", "population": 1, "region": "Europe", "languages": ["French"]}"#;
+    let country: Country = serde_json::from_str(json).unwrap();
+
+    assert_eq!(country.alpha2, None);
+    assert_eq!(country.alpha3, None);
+    assert!(country.currencies.is_empty());
+    assert!(!serde_json::to_string(&country).unwrap().contains("alpha"));
+}
+
+fn currency_strategy() -> impl Strategy<Value = Currency> {
+    ("[A-Z]{3}", "[a-zA-Z ]{1,30}", "\\PC{0,3}")
+        .prop_map(|(code, name, symbol)| Currency { code, name, symbol })
+}
+
+// Currencies survive a round-trip, on their own and nested in a country
+proptest! {
+    #[test]
+    fn test_currency_serialization_roundtrip(currency in currency_strategy()) {
+        let json = serde_json::to_string(&currency).unwrap();
+        let deserialized: Currency = serde_json::from_str(&json).unwrap();
+
+        prop_assert_eq!(currency, deserialized);
+    }
+
+    #[test]
+    fn test_country_with_currencies_roundtrip(
+        name in "[a-zA-Z][a-zA-Z ]{0,49}",
+        currencies in prop::collection::vec(currency_strategy(), 0..4)
+    ) {
+        let country = Country {
+            name,
+            currencies,
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&country).unwrap();
+        let deserialized: Country = serde_json::from_str(&json).unwrap();
+
+        prop_assert!(country.is_valid());
+        prop_assert_eq!(country, deserialized);
+    }
+
+    #[test]
+    fn test_validation_rejects_malformed_currency_code(code in "[a-z]{3}|[A-Z]{1,2}|[A-Z]{4}") {
+        let country = Country {
+            name: "Name".to_string(),
+            currencies: vec![Currency::new(&code, "Money", "$")],
+            ..Default::default()
+        };
+
+        prop_assert_eq!(
+            country.validate(),
+            Err(vec![ValidationError::InvalidCurrencyCode { index: 0, code }])
+        );
+    }
+}
+
 // Feature: country-data-api-rust, Property 8: Data validation rejects invalid countries
 proptest! {
     #[test]
@@ -69,9 +170,10 @@ proptest! {
             population,
             region,
             languages,
+            ..Default::default()
         };
         
-        assert!(!country.validate());
+        assert_eq!(country.validate(), Err(vec![ValidationError::EmptyName]));
     }
 
     #[test]
@@ -88,9 +190,13 @@ proptest! {
             population,
             region,
             languages,
+            ..Default::default()
         };
         
-        assert!(!country.validate());
+        assert_eq!(
+            country.validate(),
+            Err(vec![ValidationError::NegativePopulation(population)])
+        );
     }
 
     #[test]
@@ -107,9 +213,10 @@ proptest! {
             population,
             region,
             languages,
+            ..Default::default()
         };
         
-        assert!(country.validate());
+        assert_eq!(country.validate(), Ok(()));
     }
 }
 
@@ -125,3 +232,23 @@ fn test_error_response_structure() {
     assert!(error_json.get("error").is_some());
     assert!(error_json.get("error").unwrap().is_string());
 }
+
+#[test]
+fn test_validation_requires_http_flag_url() {
+    let with_url = |url: &str| Country {
+        name: "France".to_string(),
+        flag_url: Some(url.to_string()),
+        ..Default::default()
+    };
+
+    for url in ["ftp://flagcdn.com/fr.svg", "flagcdn.com/fr.svg", "https://", "javascript:x"] {
+        assert_eq!(
+            with_url(url).validate(),
+            Err(vec![ValidationError::InvalidFlagUrl(url.to_string())]),
+            "{} should be rejected",
+            url
+        );
+    }
+    assert!(with_url("https://flagcdn.com/fr.svg").is_valid());
+    assert!(with_url("HTTP://example.com/flag.png").is_valid());
+}
@@ -0,0 +1,165 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use country_data_api_rust_kiro::{
+    api::{create_routes, create_routes_with_config, ApiConfig},
+    models::Country,
+    services::CountryDataStore,
+};
+use serde_json::{json, Value};
+use tower::util::ServiceExt;
+
+async fn store() -> CountryDataStore {
+    let store = CountryDataStore::new();
+    store
+        .load_countries(vec![
+            Country {
+                name: "France".to_string(),
+                capital: "Paris".to_string(),
+                population: 67000000,
+                region: "Europe".to_string(),
+                languages: vec!["French".to_string()],
+                ..Default::default()
+            },
+            Country {
+                name: "Japan".to_string(),
+                capital: "Tokyo".to_string(),
+                population: 125000000,
+                region: "Asia".to_string(),
+                languages: vec!["Japanese".to_string()],
+                ..Default::default()
+            },
+        ])
+        .await;
+    store
+}
+
+async fn get_json(app: axum::Router, uri: &str) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+fn names(countries: &Value) -> Vec<&str> {
+    countries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_empty_values_return_400_for_their_key() {
+    let app = create_routes(store().await);
+
+    for (uri, key) in [
+        ("/api/countries?region=", "region"),
+        ("/api/countries?currency=%20", "currency"),
+        ("/api/countries?limit=", "limit"),
+        ("/api/countries/random?min_population=", "min_population"),
+        ("/api/countries/stats?region=", "region"),
+        ("/api/countries/France?fields=", "fields"),
+    ] {
+        let (status, error) = get_json(app.clone(), uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        assert_eq!(error["fields"], json!({ key: ["must not be empty"] }), "{}", uri);
+        assert_eq!(error["error"], format!("{}: must not be empty", key), "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_empty_search_still_matches_everything() {
+    let app = create_routes(store().await);
+
+    let (status, countries) = get_json(app, "/api/countries?search=").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&countries), vec!["France", "Japan"]);
+}
+
+#[tokio::test]
+async fn test_unparsable_values_are_reported_by_field() {
+    let app = create_routes(store().await);
+
+    for (uri, key) in [
+        ("/api/countries?limit=abc", "limit"),
+        ("/api/countries?region=europe&min_population=lots", "min_population"),
+        ("/api/countries?max_density=1e", "max_density"),
+        ("/api/countries?envelope=yes", "envelope"),
+        ("/api/countries?sort=size", "sort"),
+        ("/api/countries/random?count=-1", "count"),
+        ("/api/countries/France?fuzzy=maybe", "fuzzy"),
+    ] {
+        let (status, error) = get_json(app.clone(), uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        let fields = error["fields"].as_object().unwrap();
+        assert_eq!(fields.keys().collect::<Vec<_>>(), vec![key], "{}", uri);
+        let message = fields[key][0].as_str().unwrap();
+        assert!(message.starts_with("invalid value"), "{}: {}", uri, message);
+        assert!(error["error"].as_str().unwrap().starts_with(key), "{}", uri);
+    }
+
+    let (_, error) = get_json(app, "/api/countries?limit=abc").await;
+    assert!(error["error"].as_str().unwrap().contains("\"abc\""));
+}
+
+#[tokio::test]
+async fn test_unknown_keys_are_ignored_by_default() {
+    let app = create_routes(store().await);
+
+    let (status, countries) = get_json(app, "/api/countries?regoin=asia&region=europe").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&countries), vec!["France"]);
+}
+
+#[tokio::test]
+async fn test_strict_config_rejects_unknown_keys() {
+    let config = ApiConfig::default().with_strict_query();
+    let app = create_routes_with_config(store().await, &config);
+
+    let (status, error) = get_json(app.clone(), "/api/countries?regoin=asia").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let message = error["fields"]["regoin"][0].as_str().unwrap();
+    assert!(message.starts_with("unknown parameter"), "{}", message);
+    assert!(message.contains("region"), "{}", message);
+
+    // A key is only known to the endpoints that take it
+    let (status, error) = get_json(app.clone(), "/api/countries/France?sort=name").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error["fields"]["sort"].is_array());
+
+    let (status, _) = get_json(app, "/api/countries/stats?region=asia").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_well_formed_queries_are_unaffected() {
+    let config = ApiConfig::default().with_strict_query();
+    let strict = create_routes_with_config(store().await, &config);
+
+    for app in [create_routes(store().await), strict] {
+        let (status, countries) = get_json(
+            app.clone(),
+            "/api/countries?region=europe,asia&min_population=1&sort=population&order=desc",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(names(&countries), vec!["Japan", "France"]);
+
+        let (status, page) = get_json(app.clone(), "/api/countries?limit=1&offset=1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 2);
+
+        let (status, country) = get_json(app.clone(), "/api/countries/japan?fields=capital").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(country, json!({ "name": "Japan", "capital": "Tokyo" }));
+
+        let (status, _) = get_json(app, "/api/countries").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
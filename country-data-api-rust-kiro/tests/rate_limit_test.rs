@@ -0,0 +1,136 @@
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{Request, StatusCode},
+    response::Response,
+};
+use country_data_api_rust_kiro::{
+    api::{create_routes_with_config, ApiConfig, RateLimiter},
+    services::CountryDataStore,
+};
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+use tower::util::ServiceExt;
+
+fn ip(address: &str) -> IpAddr {
+    address.parse().unwrap()
+}
+
+#[test]
+fn test_bucket_allows_burst_then_refills() {
+    // 60 per minute: one token per second
+    let limiter = RateLimiter::new(60, false);
+    let client = ip("10.0.0.1");
+    let start = Instant::now();
+
+    for _ in 0..60 {
+        assert!(limiter.check(client, start).is_ok());
+    }
+    let retry_after = limiter.check(client, start).unwrap_err();
+    assert!((retry_after.as_secs_f64() - 1.0).abs() < 1e-9);
+
+    // Half a token is not enough; the wait shrinks accordingly
+    let half = start + Duration::from_millis(500);
+    let retry_after = limiter.check(client, half).unwrap_err();
+    assert!((retry_after.as_secs_f64() - 0.5).abs() < 1e-9);
+
+    let later = start + Duration::from_secs(3);
+    for _ in 0..3 {
+        assert!(limiter.check(client, later).is_ok());
+    }
+    assert!(limiter.check(client, later).is_err());
+}
+
+#[test]
+fn test_refill_is_capped_at_capacity() {
+    let limiter = RateLimiter::new(2, false);
+    let client = ip("10.0.0.1");
+    let start = Instant::now();
+
+    assert!(limiter.check(client, start).is_ok());
+    // Idle for an hour: still only two requests in a burst
+    let later = start + Duration::from_secs(3600);
+    assert!(limiter.check(client, later).is_ok());
+    assert!(limiter.check(client, later).is_ok());
+    let retry_after = limiter.check(client, later).unwrap_err();
+    assert!((retry_after.as_secs_f64() - 30.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_clients_have_separate_buckets_and_idle_ones_are_dropped() {
+    let limiter = RateLimiter::new(1, false);
+    let start = Instant::now();
+
+    assert!(limiter.check(ip("10.0.0.1"), start).is_ok());
+    assert!(limiter.check(ip("10.0.0.1"), start).is_err());
+    assert!(limiter.check(ip("10.0.0.2"), start).is_ok());
+    assert!(limiter.check(ip("::1"), start).is_ok());
+    assert_eq!(limiter.tracked_clients(), 3);
+
+    // After a minute every bucket has refilled; only the new client remains
+    let later = start + Duration::from_secs(61);
+    assert!(limiter.check(ip("10.0.0.3"), later).is_ok());
+    assert_eq!(limiter.tracked_clients(), 1);
+}
+
+fn request_from(peer: &str, forwarded_for: Option<&str>) -> Request<Body> {
+    let peer: SocketAddr = peer.parse().unwrap();
+    let mut request = Request::builder()
+        .uri("/api/countries")
+        .extension(ConnectInfo(peer));
+    if let Some(forwarded_for) = forwarded_for {
+        request = request.header("X-Forwarded-For", forwarded_for);
+    }
+    request.body(Body::empty()).unwrap()
+}
+
+async fn send(app: &axum::Router, request: Request<Body>) -> Response {
+    app.clone().oneshot(request).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_router_returns_429_with_retry_after() {
+    let config = ApiConfig::default().with_rate_limit(2, false);
+    let app = create_routes_with_config(CountryDataStore::new(), &config);
+
+    for _ in 0..2 {
+        let response = send(&app, request_from("10.0.0.1:5000", None)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = send(&app, request_from("10.0.0.1:5001", None)).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=30).contains(&retry_after), "{}", retry_after);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(error["error"].is_string());
+
+    // Another client is unaffected, and X-Forwarded-For is ignored without trust
+    let response = send(&app, request_from("10.0.0.2:5000", Some("10.0.0.1"))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = send(&app, request_from("10.0.0.1:5000", Some("10.9.9.9"))).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_router_keys_on_forwarded_for_when_trusted() {
+    let config = ApiConfig::default().with_rate_limit(1, true);
+    let app = create_routes_with_config(CountryDataStore::new(), &config);
+
+    // Same proxy address, different clients
+    let proxy = "192.168.0.1:8000";
+    let response = send(&app, request_from(proxy, Some("203.0.113.7, 192.168.0.1"))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = send(&app, request_from(proxy, Some("203.0.113.8"))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send(&app, request_from(proxy, Some("203.0.113.7"))).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
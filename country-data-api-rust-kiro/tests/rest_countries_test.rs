@@ -0,0 +1,99 @@
+use country_data_api_rust_kiro::models::{Country, Currency};
+use country_data_api_rust_kiro::services::{
+    import_countries, CountryDataStore, FileCountrySource, SourceError,
+};
+
+const FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/restcountries_v3.1_all.json"
+);
+
+#[tokio::test]
+async fn test_import_maps_rest_countries_fixture() {
+    let loaded = import_countries(&FileCountrySource::new(FIXTURE))
+        .await
+        .unwrap();
+
+    assert_eq!(loaded.countries.len(), 12);
+    assert!(loaded.rejected.is_empty());
+
+    let store = CountryDataStore::new();
+    store.load_countries(loaded.countries).await;
+
+    assert_eq!(
+        store.get_by_name("France").await.unwrap(),
+        Country {
+            name: "France".to_string(),
+            capital: "Paris".to_string(),
+            population: 67391582,
+            region: "Europe".to_string(),
+            subregion: "Western Europe".to_string(),
+            languages: vec!["French".to_string()],
+            alpha2: Some("FR".to_string()),
+            alpha3: Some("FRA".to_string()),
+            currencies: vec![Currency::new("EUR", "Euro", "€")],
+            area_km2: 551695.0,
+            borders: ["AND", "BEL", "DEU", "ITA", "LUX", "MCO", "ESP", "CHE"]
+                .map(String::from)
+                .to_vec(),
+            flag_emoji: "🇫🇷".to_string(),
+            flag_url: Some("https://flagcdn.com/fr.svg".to_string()),
+            population_history: Vec::new(),
+        }
+    );
+
+    // Records without a flag get the emoji from their alpha-2 code
+    let japan = store.get_by_name("Japan").await.unwrap();
+    assert_eq!(japan.flag_emoji, "🇯🇵");
+    assert_eq!(japan.flag_url, None);
+
+    // First listed capital wins; languages come out in language-code order
+    let south_africa = store.get_by_name("south africa").await.unwrap();
+    assert_eq!(south_africa.capital, "Pretoria");
+    assert_eq!(south_africa.languages.len(), 11);
+    assert_eq!(south_africa.languages[0], "Afrikaans");
+
+    let switzerland = store.get_by_name("Switzerland").await.unwrap();
+    assert_eq!(
+        switzerland.languages,
+        vec!["French", "Swiss German", "Italian", "Romansh"]
+    );
+
+    // Missing capital and languages default to empty
+    let antarctica = store.get_by_name("Antarctica").await.unwrap();
+    assert_eq!(antarctica.capital, "");
+    assert!(antarctica.languages.is_empty());
+    assert_eq!(store.get_by_name("Bouvet Island").await.unwrap().capital, "");
+
+    assert_eq!(store.filter_by_region("Antarctic").await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_import_reports_invalid_records() {
+    let path = std::env::temp_dir().join(format!(
+        "restcountries_invalid_{}.json",
+        std::process::id()
+    ));
+    std::fs::write(
+        &path,
+        r#"[
+            {"name": {"common": "Valid"}, "capital": ["Capital"], "region": "Europe", "languages": {"eng": "English"}, "population": 10},
+            {"name": {"common": "Negative"}, "region": "Europe", "population": -10}
+        ]"#,
+    )
+    .unwrap();
+
+    let loaded = import_countries(&FileCountrySource::new(&path)).await.unwrap();
+    assert_eq!(loaded.countries.len(), 1);
+    assert_eq!(loaded.rejected.len(), 1);
+    assert_eq!(loaded.rejected[0].index, 1);
+    assert_eq!(loaded.rejected[0].name, "Negative");
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_import_from_unavailable_source_is_an_error() {
+    let result = import_countries(&FileCountrySource::new("/nonexistent/restcountries.json")).await;
+    assert!(matches!(result, Err(SourceError::Unavailable(_))));
+}
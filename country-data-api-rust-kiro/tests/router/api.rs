@@ -0,0 +1,2231 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use country_data_api_rust_kiro::{
+    api::create_routes,
+    models::{Country, UpdateCountryRequest},
+    sample_data::get_sample_countries,
+    services::SharedStore,
+};
+use serde_json::Value;
+use tower::util::ServiceExt;
+
+async fn setup_test_store() -> SharedStore {
+    let store = new_store().await;
+    store
+        .load_countries(vec![
+            Country {
+                name: "France".to_string(),
+                capital: "Paris".to_string(),
+                population: 67000000,
+                region: "Europe".to_string(),
+                languages: vec!["French".to_string()],
+                ..Default::default()
+            },
+            Country {
+                name: "Japan".to_string(),
+                capital: "Tokyo".to_string(),
+                population: 125000000,
+                region: "Asia".to_string(),
+                languages: vec!["Japanese".to_string()],
+                ..Default::default()
+            },
+            Country {
+                name: "United States".to_string(),
+                capital: "Washington, D.C.".to_string(),
+                population: 331000000,
+                region: "Americas".to_string(),
+                languages: vec!["English".to_string()],
+                ..Default::default()
+            },
+        ])
+        .await;
+    store
+}
+
+#[tokio::test]
+async fn test_get_all_countries_returns_200() {
+    let store = setup_test_store().await;
+    let app = create_routes(store);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let countries: Vec<Country> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(countries.len(), 3);
+}
+
+#[tokio::test]
+async fn test_get_country_by_name_returns_200() {
+    let store = setup_test_store().await;
+    let app = create_routes(store);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries/France")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let country: Country = serde_json::from_slice(&body).unwrap();
+    assert_eq!(country.name, "France");
+}
+
+#[tokio::test]
+async fn test_get_country_by_name_returns_404() {
+    let store = setup_test_store().await;
+    let app = create_routes(store);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries/Atlantis")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert!(error.get("error").is_some());
+}
+
+#[tokio::test]
+async fn test_region_filter_returns_correct_subset() {
+    let store = setup_test_store().await;
+    let app = create_routes(store);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries?region=Europe")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let countries: Vec<Country> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(countries.len(), 1);
+    assert_eq!(countries[0].name, "France");
+}
+
+#[tokio::test]
+async fn test_name_search_returns_correct_subset() {
+    let store = setup_test_store().await;
+    let app = create_routes(store);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries?search=United")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let countries: Vec<Country> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(countries.len(), 1);
+    assert_eq!(countries[0].name, "United States");
+}
+
+#[tokio::test]
+async fn test_invalid_endpoint_returns_404() {
+    let store = setup_test_store().await;
+    let app = create_routes(store);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/invalid")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_error_response_contains_error_field() {
+    let store = setup_test_store().await;
+    let app = create_routes(store);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries/NonExistent")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    
+    assert!(error.get("error").is_some());
+    assert!(error.get("error").unwrap().is_string());
+}
+
+fn json_request(method: &str, uri: &str, body: serde_json::Value) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+fn post_country(country: serde_json::Value) -> Request<Body> {
+    json_request("POST", "/api/countries", country)
+}
+
+#[tokio::test]
+async fn test_create_country_then_fetch_by_name() {
+    let store = setup_test_store().await;
+    let app = create_routes(store);
+
+    let response = app
+        .clone()
+        .oneshot(post_country(serde_json::json!({
+            "name": "New Zealand",
+            "capital": "Wellington",
+            "population": 5124000,
+            "region": "Oceania",
+            "languages": ["English", "Māori"]
+        })))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(
+        response.headers().get("location").unwrap(),
+        "/api/countries/New%20Zealand"
+    );
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries/new%20zealand")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let country: Country = serde_json::from_slice(&body).unwrap();
+    assert_eq!(country.name, "New Zealand");
+    assert_eq!(country.capital, "Wellington");
+}
+
+#[tokio::test]
+async fn test_create_country_returns_stored_country() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let response = app
+        .oneshot(post_country(serde_json::json!({
+            "name": "New Zealand",
+            "capital": "Wellington",
+            "population": 5124000,
+            "region": "Oceania",
+            "languages": ["English", "Māori"],
+            "alpha2": "NZ"
+        })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // The flag emoji is derived by the store, so it's in the response too
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let created: Country = serde_json::from_slice(&body).unwrap();
+    assert_eq!(created.flag_emoji, "🇳🇿");
+    assert_eq!(store.get_by_name("New Zealand").await.unwrap(), created);
+}
+
+#[tokio::test]
+async fn test_create_duplicate_country_returns_409() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let response = app
+        .oneshot(post_country(serde_json::json!({
+            "name": "FRANCE",
+            "capital": "Paris",
+            "population": 1,
+            "region": "Europe",
+            "languages": ["French"]
+        })))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert!(error.get("error").unwrap().is_string());
+    assert_eq!(store.get_all().await.len(), 3);
+}
+
+#[tokio::test]
+async fn test_duplicate_names_in_load_are_rejected_and_post_conflicts() {
+    let store = new_store().await;
+    let france = |name: &str, capital: &str| Country {
+        name: name.to_string(),
+        capital: capital.to_string(),
+        population: 67000000,
+        region: "Europe".to_string(),
+        languages: vec!["French".to_string()],
+        ..Default::default()
+    };
+    let report = store
+        .load_countries(vec![
+            france("France", "Paris"),
+            france("FRANCE", "Lyon"),
+            france("france ", "Nice"),
+        ])
+        .await;
+
+    assert_eq!(report.loaded, 1);
+    let duplicates: Vec<&str> = report.rejected.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(duplicates, vec!["FRANCE", "france "]);
+    assert_eq!(store.count().await, 1);
+
+    let response = create_routes(store.clone())
+        .oneshot(post_country(serde_json::json!({
+            "name": "fRaNcE",
+            "capital": "Marseille",
+            "population": 1,
+            "region": "Europe",
+            "languages": ["French"]
+        })))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(store.count().await, 1);
+    assert_eq!(store.get_by_name("France").await.unwrap().capital, "Paris");
+}
+
+#[tokio::test]
+async fn test_create_country_with_invalid_population_returns_400() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let response = app
+        .oneshot(post_country(serde_json::json!({
+            "name": "Atlantis",
+            "capital": "Poseidonia",
+            "population": -5,
+            "region": "Ocean",
+            "languages": ["Atlantean"]
+        })))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert!(error["error"].as_str().unwrap().contains("population"));
+    assert!(store.get_by_name("Atlantis").await.is_none());
+}
+
+#[tokio::test]
+async fn test_invalid_put_reports_errors_by_field() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let response = app
+        .oneshot(json_request(
+            "PUT",
+            "/api/countries/France",
+            serde_json::json!({
+                "name": "",
+                "capital": "Paris",
+                "population": -1,
+                "region": "Europe",
+                "languages": ["French", "", ""]
+            }),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert!(error["error"].as_str().unwrap().starts_with("name: must not be empty; "));
+    let fields = error["fields"].as_object().unwrap();
+    let mut keys: Vec<&str> = fields.keys().map(String::as_str).collect();
+    keys.sort();
+    assert_eq!(keys, vec!["languages", "name", "population"]);
+    assert_eq!(fields["languages"].as_array().unwrap().len(), 2);
+    assert_eq!(store.get_by_name("France").await.unwrap().population, 67_000_000);
+}
+
+#[tokio::test]
+async fn test_put_replaces_country() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let response = app
+        .oneshot(json_request(
+            "PUT",
+            "/api/countries/france",
+            serde_json::json!({
+                "name": "France",
+                "capital": "Lyon",
+                "population": 68000000,
+                "region": "Western Europe",
+                "languages": ["French", "Occitan"]
+            }),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let france = store.get_by_name("France").await.unwrap();
+    assert_eq!(france.capital, "Lyon");
+    assert_eq!(france.population, 68000000);
+    assert_eq!(france.region, "Western Europe");
+    assert_eq!(france.languages, vec!["French", "Occitan"]);
+    assert_eq!(store.get_all().await.len(), 3);
+}
+
+#[tokio::test]
+async fn test_patch_updates_only_population() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let response = app
+        .oneshot(json_request(
+            "PATCH",
+            "/api/countries/Japan",
+            serde_json::json!({ "population": 124000000 }),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let country: Country = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        country,
+        Country {
+            name: "Japan".to_string(),
+            capital: "Tokyo".to_string(),
+            population: 124000000,
+            region: "Asia".to_string(),
+            languages: vec!["Japanese".to_string()],
+            ..Default::default()
+        }
+    );
+    assert_eq!(store.get_by_name("Japan").await.unwrap(), country);
+}
+
+#[tokio::test]
+async fn test_update_nonexistent_country_returns_404() {
+    let store = setup_test_store().await;
+    let app = create_routes(store);
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "PUT",
+            "/api/countries/Atlantis",
+            serde_json::json!({
+                "name": "Atlantis",
+                "capital": "Poseidonia",
+                "population": 1,
+                "region": "Ocean",
+                "languages": ["Atlantean"]
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .oneshot(json_request(
+            "PATCH",
+            "/api/countries/Atlantis",
+            serde_json::json!({ "population": 1 }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert!(error.get("error").unwrap().is_string());
+}
+
+#[tokio::test]
+async fn test_put_rename_collision_returns_409() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let response = app
+        .oneshot(json_request(
+            "PUT",
+            "/api/countries/France",
+            serde_json::json!({
+                "name": "japan",
+                "capital": "Paris",
+                "population": 67000000,
+                "region": "Europe",
+                "languages": ["French"]
+            }),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(store.get_by_name("France").await.unwrap().capital, "Paris");
+    assert_eq!(store.get_by_name("Japan").await.unwrap().capital, "Tokyo");
+}
+
+#[tokio::test]
+async fn test_delete_country_returns_204() {
+    let store = setup_test_store().await;
+    let app = create_routes(store);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri("/api/countries/france")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries/France")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_delete_nonexistent_country_returns_404() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri("/api/countries/Atlantis")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert!(error.get("error").unwrap().is_string());
+    assert_eq!(store.get_all().await.len(), 3);
+}
+
+fn revise_population(name: &str, population: i64, as_of: &str) -> Request<Body> {
+    json_request(
+        "PATCH",
+        &format!("/api/countries/{}/population", name),
+        serde_json::json!({ "population": population, "as_of": as_of }),
+    )
+}
+
+async fn population_history(app: axum::Router, name: &str) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/countries/{}/population/history", name))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_population_revisions_are_kept_in_order() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let (status, history) = population_history(app.clone(), "Japan").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(history, serde_json::json!([]));
+
+    for (population, as_of) in [(124500000, "2023-07-01"), (123900000, "2024-07-01")] {
+        let response = app
+            .clone()
+            .oneshot(revise_population("japan", population, as_of))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let (status, history) = population_history(app, "JAPAN").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        history,
+        serde_json::json!([
+            { "population": 124500000, "as_of": "2023-07-01" },
+            { "population": 123900000, "as_of": "2024-07-01" }
+        ])
+    );
+    let japan = store.get_by_name("Japan").await.unwrap();
+    assert_eq!(japan.population, 123900000);
+    assert_eq!(japan.capital, "Tokyo");
+}
+
+#[tokio::test]
+async fn test_invalid_population_revision_returns_400() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+    app.clone()
+        .oneshot(revise_population("Japan", 124500000, "2024-07-01"))
+        .await
+        .unwrap();
+    let before = store.get_by_name("Japan").await.unwrap();
+
+    for (population, as_of, field) in [
+        (-1, "2025-01-01", "population"),
+        (1, "2025-02-30", "as_of"),
+        (1, "2024-01-01", "as_of"),
+    ] {
+        let response = app
+            .clone()
+            .oneshot(revise_population("Japan", population, as_of))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: Value = serde_json::from_slice(&body).unwrap();
+        assert!(error["fields"].get(field).is_some(), "{}", error);
+    }
+
+    assert_eq!(store.get_by_name("Japan").await.unwrap(), before);
+}
+
+#[tokio::test]
+async fn test_population_revision_of_unknown_country_returns_404() {
+    let store = setup_test_store().await;
+    let app = create_routes(store);
+
+    let response = app
+        .clone()
+        .oneshot(revise_population("Atlantis", 1, "2024-07-01"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let (status, error) = population_history(app, "Atlantis").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(error.get("error").unwrap().is_string());
+}
+
+#[tokio::test]
+async fn test_put_keeps_population_history() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+    app.clone()
+        .oneshot(revise_population("France", 68000000, "2024-01-01"))
+        .await
+        .unwrap();
+
+    let response = app
+        .oneshot(json_request(
+            "PUT",
+            "/api/countries/France",
+            serde_json::json!({
+                "name": "France",
+                "capital": "Paris",
+                "population": 68100000,
+                "region": "Europe",
+                "languages": ["French"]
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let france = store.get_by_name("France").await.unwrap();
+    assert_eq!(france.population, 68100000);
+    assert_eq!(france.population_history.len(), 1);
+    assert_eq!(france.population_history[0].as_of, "2024-01-01");
+}
+
+fn bulk_batch() -> serde_json::Value {
+    serde_json::json!([
+        {
+            "name": "Germany",
+            "capital": "Berlin",
+            "population": 83294000,
+            "region": "Europe",
+            "languages": ["German"]
+        },
+        {
+            "name": "Atlantis",
+            "capital": "Poseidonia",
+            "population": -1,
+            "region": "Ocean",
+            "languages": ["Atlantean"]
+        },
+        {
+            "name": "japan",
+            "capital": "Kyoto",
+            "population": 1,
+            "region": "Asia",
+            "languages": ["Japanese"]
+        },
+        {
+            "name": "GERMANY",
+            "capital": "Bonn",
+            "population": 1,
+            "region": "Europe",
+            "languages": ["German"]
+        }
+    ])
+}
+
+async fn bulk_report(response: axum::response::Response) -> Value {
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_bulk_append_reports_rejected_entries() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let response = app
+        .oneshot(json_request("POST", "/api/countries/bulk", bulk_batch()))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let report = bulk_report(response).await;
+    assert_eq!(report["inserted"], 1);
+
+    let rejected = report["rejected"].as_array().unwrap();
+    let indexes: Vec<u64> = rejected.iter().map(|r| r["index"].as_u64().unwrap()).collect();
+    assert_eq!(indexes, vec![1, 2, 3]);
+    assert_eq!(rejected[0]["name"], "Atlantis");
+    assert!(rejected[0]["reason"].as_str().unwrap().contains("population"));
+    assert!(rejected[1]["reason"].as_str().unwrap().contains("already exists"));
+
+    assert_eq!(store.get_all().await.len(), 4);
+    assert_eq!(store.get_by_name("Germany").await.unwrap().capital, "Berlin");
+    assert_eq!(store.get_by_name("Japan").await.unwrap().capital, "Tokyo");
+}
+
+#[tokio::test]
+async fn test_bulk_replace_swaps_store() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let response = app
+        .oneshot(json_request(
+            "POST",
+            "/api/countries/bulk?mode=replace",
+            bulk_batch(),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let report = bulk_report(response).await;
+    assert_eq!(report["inserted"], 2);
+    assert_eq!(report["rejected"].as_array().unwrap().len(), 2);
+
+    let mut names: Vec<String> = store.get_all().await.into_iter().map(|c| c.name).collect();
+    names.sort();
+    assert_eq!(names, vec!["Germany", "japan"]);
+    assert_eq!(store.get_by_name("Japan").await.unwrap().capital, "Kyoto");
+}
+
+#[tokio::test]
+async fn test_bulk_merge_updates_existing_countries() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let response = app
+        .oneshot(json_request(
+            "POST",
+            "/api/countries/bulk?mode=merge",
+            bulk_batch(),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let report = bulk_report(response).await;
+    assert_eq!(report["inserted"], 1);
+    assert_eq!(report["updated"], 1);
+    let rejected = report["rejected"].as_array().unwrap();
+    let indexes: Vec<u64> = rejected.iter().map(|r| r["index"].as_u64().unwrap()).collect();
+    assert_eq!(indexes, vec![1, 3]);
+
+    assert_eq!(store.get_all().await.len(), 4);
+    assert_eq!(store.get_by_name("Japan").await.unwrap().capital, "Kyoto");
+    assert_eq!(store.get_by_name("Germany").await.unwrap().capital, "Berlin");
+    assert!(store.get_by_name("France").await.is_some());
+}
+
+#[tokio::test]
+async fn test_rollback_undoes_bulk_replace() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+    let before = store.get_all().await;
+
+    let response = app
+        .clone()
+        .oneshot(json_request("POST", "/api/admin/snapshots", Value::Null))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let snapshot = bulk_report(response).await;
+    assert_eq!(snapshot["countries"], 3);
+
+    app.clone()
+        .oneshot(json_request(
+            "POST",
+            "/api/countries/bulk?mode=replace",
+            bulk_batch(),
+        ))
+        .await
+        .unwrap();
+    assert_ne!(store.get_all().await, before);
+
+    let uri = format!("/api/admin/snapshots/{}/rollback", snapshot["id"]);
+    let response = app
+        .clone()
+        .oneshot(json_request("POST", &uri, Value::Null))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(bulk_report(response).await["countries"], 3);
+    assert_eq!(store.get_all().await, before);
+
+    for (uri, status) in [
+        ("/api/admin/snapshots/999/rollback", StatusCode::NOT_FOUND),
+        ("/api/admin/snapshots/latest/rollback", StatusCode::BAD_REQUEST),
+    ] {
+        let response = app
+            .clone()
+            .oneshot(json_request("POST", uri, Value::Null))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), status, "{}", uri);
+        assert!(bulk_report(response).await["error"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn test_bulk_rejects_oversized_batch() {
+    let store = setup_test_store().await;
+    let app = create_routes(store.clone());
+
+    let batch: Vec<Value> = (0..5001)
+        .map(|i| {
+            serde_json::json!({
+                "name": format!("Country {}", i),
+                "capital": "Capital",
+                "population": 1,
+                "region": "Region",
+                "languages": ["Language"]
+            })
+        })
+        .collect();
+
+    let response = app
+        .oneshot(json_request("POST", "/api/countries/bulk", Value::from(batch)))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(store.get_all().await.len(), 3);
+}
+
+async fn get_json(app: axum::Router, uri: &str) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+fn item_names(page: &Value) -> Vec<&str> {
+    page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect()
+}
+
+/// 10 countries found by capital, then 10 found by name, all matching "land"
+fn twenty_land_matches() -> Vec<Country> {
+    let by_capital = (0..10).map(|i| Country {
+        name: format!("Capital Match {}", i),
+        capital: format!("Landsby {}", i),
+        population: 1000,
+        region: "Europe".to_string(),
+        languages: vec!["Danish".to_string()],
+        ..Default::default()
+    });
+    let by_name = (0..10).map(|i| Country {
+        name: format!("Island {}", i),
+        capital: format!("Port {}", i),
+        population: 1000,
+        region: "Oceania".to_string(),
+        languages: vec!["English".to_string()],
+        ..Default::default()
+    });
+    by_capital.chain(by_name).collect()
+}
+
+#[tokio::test]
+async fn test_search_limit_keeps_top_ranked_matches() {
+    let store = new_store().await;
+    store.load_countries(twenty_land_matches()).await;
+    let app = create_routes(store);
+
+    let (status, all) =
+        get_json(app.clone(), "/api/countries?search=land&search_fields=all").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(all.as_array().unwrap().len(), 20);
+
+    let (status, top) = get_json(
+        app.clone(),
+        "/api/countries?search=land&search_fields=all&search_limit=5",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let names: Vec<&str> = top
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Island 0", "Island 1", "Island 2", "Island 3", "Island 4"]);
+
+    // Pagination works within the kept matches
+    let (_, page) = get_json(
+        app.clone(),
+        "/api/countries?search=land&search_fields=all&search_limit=12&limit=5&offset=10",
+    )
+    .await;
+    assert_eq!(page["total"], 12);
+    assert_eq!(item_names(&page), ["Capital Match 0", "Capital Match 1"]);
+
+    // Clamped to 50 like `limit` is to 500
+    let (status, all) = get_json(app, "/api/countries?search=land&search_limit=1000").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(all.as_array().unwrap().len(), 10);
+}
+
+#[tokio::test]
+async fn test_invalid_search_limit_returns_400() {
+    let app = create_routes(setup_test_store().await);
+
+    for query in ["search=a&search_limit=0", "search_limit=5"] {
+        let (status, error) = get_json(app.clone(), &format!("/api/countries?{}", query)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+        assert!(error["error"].as_str().unwrap().contains("search_limit"), "{}", error);
+    }
+
+    let (status, _) = get_json(app, "/api/countries?search=a&search_limit=five").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_pagination_page_boundaries() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, page) = get_json(app.clone(), "/api/countries?limit=2").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item_names(&page), vec!["France", "Japan"]);
+    assert_eq!(page["total"], 3);
+    assert_eq!(page["limit"], 2);
+    assert_eq!(page["offset"], 0);
+
+    let (_, page) = get_json(app.clone(), "/api/countries?limit=2&offset=2").await;
+    assert_eq!(item_names(&page), vec!["United States"]);
+    assert_eq!(page["total"], 3);
+
+    let (_, page) = get_json(app.clone(), "/api/countries?limit=2&offset=3").await;
+    assert!(item_names(&page).is_empty());
+
+    // Without a limit the response stays a bare array
+    let (_, countries) = get_json(app.clone(), "/api/countries?offset=1").await;
+    assert_eq!(countries.as_array().unwrap().len(), 2);
+
+    // Oversized limits are clamped
+    let (_, page) = get_json(app, "/api/countries?limit=10000").await;
+    assert_eq!(page["limit"], 500);
+    assert_eq!(item_names(&page).len(), 3);
+}
+
+#[tokio::test]
+async fn test_pagination_total_reflects_filter() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, page) = get_json(app, "/api/countries?region=europe&limit=10").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["total"], 1);
+    assert_eq!(item_names(&page), vec!["France"]);
+}
+
+#[tokio::test]
+async fn test_pagination_rejects_invalid_values() {
+    let app = create_routes(setup_test_store().await);
+
+    for uri in [
+        "/api/countries?limit=-1",
+        "/api/countries?limit=abc",
+        "/api/countries?offset=-5",
+        "/api/countries?limit=10&offset=1.5",
+    ] {
+        let (status, error) = get_json(app.clone(), uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        assert!(error.get("error").unwrap().is_string(), "{}", uri);
+    }
+}
+
+fn names(countries: &Value) -> Vec<&str> {
+    countries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_min_population_filter() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, countries) = get_json(app, "/api/countries?min_population=100000000").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&countries), vec!["Japan", "United States"]);
+}
+
+#[tokio::test]
+async fn test_population_range_is_inclusive_and_composable() {
+    let app = create_routes(setup_test_store().await);
+
+    let (_, countries) = get_json(
+        app.clone(),
+        "/api/countries?min_population=67000000&max_population=125000000",
+    )
+    .await;
+    assert_eq!(names(&countries), vec!["France", "Japan"]);
+
+    let (_, countries) = get_json(
+        app.clone(),
+        "/api/countries?search=an&max_population=100000000",
+    )
+    .await;
+    assert_eq!(names(&countries), vec!["France"]);
+
+    let (_, page) = get_json(
+        app,
+        "/api/countries?region=asia&min_population=1&limit=5",
+    )
+    .await;
+    assert_eq!(page["total"], 1);
+    assert_eq!(item_names(&page), vec!["Japan"]);
+}
+
+#[tokio::test]
+async fn test_population_range_rejects_invalid_values() {
+    let app = create_routes(setup_test_store().await);
+
+    for uri in [
+        "/api/countries?min_population=-1",
+        "/api/countries?max_population=-10",
+        "/api/countries?min_population=500&max_population=100",
+        "/api/countries?min_population=lots",
+    ] {
+        let (status, error) = get_json(app.clone(), uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        assert!(error.get("error").unwrap().is_string(), "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_close_typo_404_includes_suggestion() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, error) = get_json(app, "/api/countries/Franse").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(error.get("error").is_some());
+    assert_eq!(error["suggestion"], "France");
+}
+
+#[tokio::test]
+async fn test_nonsense_name_404_has_no_suggestion() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, error) = get_json(app, "/api/countries/Qwxzv").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(error.get("suggestion").is_none());
+}
+
+#[tokio::test]
+async fn test_fuzzy_lookup_returns_best_match() {
+    let store = setup_test_store().await;
+
+    let (status, country) =
+        get_json(create_routes(store.clone()), "/api/countries/Japn?fuzzy=true").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(country["name"], "Japan");
+
+    // Too far from anything stored: still a 404
+    let (status, _) = get_json(create_routes(store), "/api/countries/Atlantis?fuzzy=true").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_lookup_tolerates_whitespace_and_case() {
+    let store = setup_test_store().await;
+
+    for uri in [
+        "/api/countries/united%20states%20",
+        "/api/countries/%20UNITED%20%20States",
+    ] {
+        let (status, country) = get_json(create_routes(store.clone()), uri).await;
+        assert_eq!(status, StatusCode::OK, "{}", uri);
+        assert_eq!(country["name"], "United States", "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_lookup_matches_accented_name_without_accents() {
+    let store = setup_test_store().await;
+    store
+        .add_country(Country {
+            name: "Côte d'Ivoire".to_string(),
+            capital: "Yamoussoukro".to_string(),
+            population: 28_160_000,
+            region: "Africa".to_string(),
+            languages: vec!["French".to_string()],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let (status, country) =
+        get_json(create_routes(store.clone()), "/api/countries/cote%20d'ivoire").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(country["name"], "Côte d'Ivoire");
+
+    // The unaccented spelling is the same country, not a new one
+    let response = create_routes(store)
+        .oneshot(post_country(serde_json::json!({
+            "name": "Cote d'Ivoire",
+            "capital": "Yamoussoukro",
+            "population": 28160000,
+            "region": "Africa",
+            "languages": ["French"]
+        })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_create_country_with_taken_code_returns_409() {
+    let store = setup_test_store().await;
+    store
+        .patch(
+            "France",
+            UpdateCountryRequest {
+                alpha2: Some("FR".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let response = create_routes(store)
+        .oneshot(post_country(serde_json::json!({
+            "name": "Frankland",
+            "capital": "Frankfurt",
+            "population": 1,
+            "region": "Europe",
+            "languages": ["French"],
+            "alpha2": "FR"
+        })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+async fn setup_density_store() -> SharedStore {
+    let store = new_store().await;
+    store
+        .load_countries(vec![
+            Country {
+                name: "Sparse".to_string(),
+                population: 100,
+                area_km2: 10.0,
+                ..Default::default()
+            },
+            Country {
+                name: "Unmapped".to_string(),
+                population: 500,
+                ..Default::default()
+            },
+            Country {
+                name: "Dense".to_string(),
+                population: 200,
+                area_km2: 10.0,
+                ..Default::default()
+            },
+        ])
+        .await;
+    store
+}
+
+#[tokio::test]
+async fn test_zero_area_density_is_null_and_sorts_last() {
+    let store = setup_density_store().await;
+
+    let (_, country) = get_json(create_routes(store.clone()), "/api/countries/Unmapped").await;
+    assert!(country["population_density"].is_null());
+
+    let (_, countries) =
+        get_json(create_routes(store.clone()), "/api/countries?sort=density").await;
+    assert_eq!(names(&countries), vec!["Sparse", "Dense", "Unmapped"]);
+
+    let (_, countries) =
+        get_json(create_routes(store), "/api/countries?sort=density&order=desc").await;
+    assert_eq!(names(&countries), vec!["Dense", "Sparse", "Unmapped"]);
+}
+
+#[tokio::test]
+async fn test_density_filters_are_inclusive() {
+    let store = setup_density_store().await;
+
+    let (status, countries) = get_json(
+        create_routes(store.clone()),
+        "/api/countries?min_density=10&max_density=20",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&countries), vec!["Sparse", "Dense"]);
+
+    let (_, countries) =
+        get_json(create_routes(store.clone()), "/api/countries?min_density=10.5").await;
+    assert_eq!(names(&countries), vec!["Dense"]);
+
+    let (_, countries) =
+        get_json(create_routes(store.clone()), "/api/countries?max_density=10").await;
+    assert_eq!(names(&countries), vec!["Sparse"]);
+
+    let (status, _) = get_json(create_routes(store), "/api/countries?min_density=-1").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_countries_by_region_projects_nested_countries() {
+    let store = setup_test_store().await;
+    store
+        .add_country(Country {
+            name: "Belgium".to_string(),
+            capital: "Brussels".to_string(),
+            population: 11600000,
+            region: "europe".to_string(),
+            languages: vec!["Dutch".to_string(), "French".to_string()],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let app = create_routes(store);
+
+    let (status, groups) =
+        get_json(app.clone(), "/api/countries/by-region?fields=capital").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        groups,
+        serde_json::json!([
+            {
+                "region": "Americas",
+                "count": 1,
+                "total_population": 331000000,
+                "countries": [{ "name": "United States", "capital": "Washington, D.C." }]
+            },
+            {
+                "region": "Asia",
+                "count": 1,
+                "total_population": 125000000,
+                "countries": [{ "name": "Japan", "capital": "Tokyo" }]
+            },
+            {
+                "region": "Europe",
+                "count": 2,
+                "total_population": 78600000,
+                "countries": [
+                    { "name": "Belgium", "capital": "Brussels" },
+                    { "name": "France", "capital": "Paris" }
+                ]
+            }
+        ])
+    );
+
+    // Without fields, every key of each country is kept
+    let (_, groups) = get_json(app.clone(), "/api/countries/by-region").await;
+    assert_eq!(groups[2]["countries"][1]["languages"], serde_json::json!(["French"]));
+
+    let (status, error) = get_json(app, "/api/countries/by-region?fields=capital,motto").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error["error"].as_str().unwrap().contains("motto"));
+}
+
+#[tokio::test]
+async fn test_regions_update_after_create() {
+    let store = setup_test_store().await;
+
+    // The test countries have no subregion, so regions have no subregions yet
+    let (status, regions) = get_json(create_routes(store.clone()), "/api/regions").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        regions,
+        serde_json::json!([
+            { "region": "Americas", "count": 1, "subregions": [] },
+            { "region": "Asia", "count": 1, "subregions": [] },
+            { "region": "Europe", "count": 1, "subregions": [] }
+        ])
+    );
+
+    let response = create_routes(store.clone())
+        .oneshot(post_country(serde_json::json!({
+            "name": "Portugal",
+            "capital": "Lisbon",
+            "population": 10300000,
+            "region": "europe",
+            "subregion": "Southern Europe",
+            "languages": ["Portuguese"]
+        })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let (_, regions) = get_json(create_routes(store), "/api/regions").await;
+    assert_eq!(
+        regions[2],
+        serde_json::json!({
+            "region": "Europe",
+            "count": 2,
+            "subregions": [{ "subregion": "Southern Europe", "count": 1 }]
+        })
+    );
+}
+
+async fn seeded_sample_app(seed: u64) -> axum::Router {
+    let store = seeded_store(seed).await;
+    store.load_countries(get_sample_countries()).await;
+    create_routes(store)
+}
+
+#[tokio::test]
+async fn test_random_country_is_reproducible_with_seed() {
+    let mut picks = Vec::new();
+    for _ in 0..2 {
+        let app = seeded_sample_app(42).await;
+        let mut picked = Vec::new();
+        for _ in 0..5 {
+            let (status, country) = get_json(app.clone(), "/api/countries/random").await;
+            assert_eq!(status, StatusCode::OK);
+            picked.push(country["name"].as_str().unwrap().to_string());
+        }
+        let (_, several) = get_json(app, "/api/countries/random?count=3").await;
+        picked.extend(names(&several).into_iter().map(String::from));
+        picks.push(picked);
+    }
+
+    assert_eq!(picks[0], picks[1]);
+    // Five single picks out of 243 are very unlikely to all be the same country
+    assert!(picks[0][..5].iter().any(|name| *name != picks[0][0]));
+}
+
+#[tokio::test]
+async fn test_random_filters_constrain_pool() {
+    let app = seeded_sample_app(7).await;
+
+    let (status, countries) =
+        get_json(app.clone(), "/api/countries/random?region=asia&count=10").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(countries.as_array().unwrap().len(), 10);
+    assert!(countries
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|c| c["region"] == "Asia"));
+
+    let (_, countries) = get_json(
+        app,
+        "/api/countries/random?region=Europe&min_population=80000000&count=10",
+    )
+    .await;
+    let mut picked = names(&countries);
+    picked.sort();
+    assert_eq!(picked, vec!["Germany", "Russia"]);
+}
+
+#[tokio::test]
+async fn test_random_count_larger_than_pool_returns_all() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, countries) = get_json(app.clone(), "/api/countries/random?count=10").await;
+    assert_eq!(status, StatusCode::OK);
+    let mut picked = names(&countries);
+    picked.sort();
+    assert_eq!(picked, vec!["France", "Japan", "United States"]);
+
+    let (_, countries) =
+        get_json(app, "/api/countries/random?min_population=100000000&count=5").await;
+    let mut picked = names(&countries);
+    picked.sort();
+    assert_eq!(picked, vec!["Japan", "United States"]);
+}
+
+#[tokio::test]
+async fn test_random_rejects_bad_params_and_empty_pool() {
+    let app = create_routes(setup_test_store().await);
+
+    for uri in [
+        "/api/countries/random?count=0",
+        "/api/countries/random?count=11",
+        "/api/countries/random?min_population=-1",
+        "/api/countries/random?region=,",
+    ] {
+        let (status, error) = get_json(app.clone(), uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        assert!(error["error"].is_string(), "{}", uri);
+    }
+
+    let (status, error) = get_json(app, "/api/countries/random?region=Oceania").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(error["error"].is_string());
+
+    let (status, _) =
+        get_json(create_routes(new_store().await), "/api/countries/random").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+fn sorted_keys(country: &Value) -> Vec<&str> {
+    let mut keys: Vec<&str> = country.as_object().unwrap().keys().map(String::as_str).collect();
+    keys.sort();
+    keys
+}
+
+#[tokio::test]
+async fn test_fields_projection_on_list_and_single_country() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, countries) =
+        get_json(app.clone(), "/api/countries?fields=name,population").await;
+    assert_eq!(status, StatusCode::OK);
+    let countries = countries.as_array().unwrap();
+    assert_eq!(countries.len(), 3);
+    for country in countries {
+        assert_eq!(sorted_keys(country), vec!["name", "population"]);
+    }
+
+    let (_, page) = get_json(app.clone(), "/api/countries?fields=population&limit=1").await;
+    assert_eq!(page["total"], 3);
+    assert_eq!(sorted_keys(&page["items"][0]), vec!["name", "population"]);
+
+    let (status, france) = get_json(app.clone(), "/api/countries/France?fields=capital").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(sorted_keys(&france), vec!["capital", "name"]);
+    assert_eq!(france["capital"], "Paris");
+
+    // `name` is kept even when not requested
+    let (_, japan) = get_json(app, "/api/countries/japan?fields=region").await;
+    assert_eq!(sorted_keys(&japan), vec!["name", "region"]);
+}
+
+#[tokio::test]
+async fn test_unknown_field_returns_400_listing_valid_fields() {
+    let app = create_routes(setup_test_store().await);
+
+    for uri in [
+        "/api/countries?fields=name,gdp",
+        "/api/countries/France?fields=gdp",
+        "/api/countries?fields=,",
+    ] {
+        let (status, error) = get_json(app.clone(), uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        assert!(error["error"].is_string(), "{}", uri);
+    }
+
+    let (_, error) = get_json(app, "/api/countries?fields=gdp").await;
+    let message = error["error"].as_str().unwrap();
+    assert!(message.contains("capital") && message.contains("flag_url"));
+}
+
+#[tokio::test]
+async fn test_envelope_wraps_list_with_counts() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, body) = get_json(app.clone(), "/api/countries?envelope=true").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["count"], 3);
+    assert_eq!(body["total"], 3);
+    assert_eq!(names(&body["data"]), vec!["France", "Japan", "United States"]);
+
+    // Filters narrow both counts; pagination only narrows `count`
+    let (_, body) = get_json(app.clone(), "/api/countries?region=europe&envelope=true").await;
+    assert_eq!(body["count"], 1);
+    assert_eq!(body["total"], 1);
+    assert_eq!(names(&body["data"]), vec!["France"]);
+
+    let (_, body) =
+        get_json(app.clone(), "/api/countries?limit=2&offset=1&envelope=true").await;
+    assert_eq!(body["count"], 2);
+    assert_eq!(body["total"], 3);
+    assert_eq!(names(&body["data"]), vec!["Japan", "United States"]);
+
+    // Default and explicit false stay bare arrays
+    for uri in ["/api/countries", "/api/countries?envelope=false", "/api/countries?region=asia"] {
+        let (_, body) = get_json(app.clone(), uri).await;
+        assert!(body.is_array(), "{}", uri);
+    }
+
+    let (status, _) = get_json(app, "/api/countries?envelope=maybe").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_envelope_requested_by_header() {
+    let app = create_routes(setup_test_store().await);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries?min_population=100000000")
+                .header("X-Response-Envelope", "true")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body["count"], 2);
+    assert_eq!(body["total"], 2);
+    assert_eq!(names(&body["data"]), vec!["Japan", "United States"]);
+}
+
+async fn get_with_accept(app: axum::Router, uri: &str, accept: &str) -> axum::response::Response {
+    app.oneshot(
+        Request::builder()
+            .uri(uri)
+            .header("Accept", accept)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+async fn body_text(response: axum::response::Response) -> String {
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_csv_format_parameter() {
+    let app = create_routes(setup_test_store().await);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries?format=csv&min_population=100000000&sort=name")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/csv; charset=utf-8"
+    );
+    assert_eq!(
+        response.headers()["content-disposition"],
+        "attachment; filename=\"countries.csv\""
+    );
+
+    let csv = body_text(response).await;
+    let names: Vec<&str> = csv
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').next().unwrap())
+        .collect();
+    assert_eq!(names, vec!["\"Japan\"", "\"United States\""]);
+}
+
+#[tokio::test]
+async fn test_csv_accept_header() {
+    let app = create_routes(setup_test_store().await);
+
+    let response =
+        get_with_accept(app.clone(), "/api/countries", "text/html, text/csv;q=0.9").await;
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/csv; charset=utf-8"
+    );
+    assert_eq!(body_text(response).await.lines().count(), 4);
+
+    // An explicit format wins over the header, and JSON stays the default
+    let response = get_with_accept(app.clone(), "/api/countries?format=json", "text/csv").await;
+    assert_eq!(response.headers()["content-type"], "application/json");
+
+    let response = get_with_accept(app.clone(), "/api/countries", "application/json").await;
+    assert_eq!(response.headers()["content-type"], "application/json");
+
+    let (status, error) = get_json(app, "/api/countries?format=yaml").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error["error"].as_str().unwrap().contains("csv"));
+}
+
+fn ndjson_countries(body: &str) -> Vec<Country> {
+    assert!(body.ends_with('\n'));
+    body.lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_ndjson_matches_json_array() {
+    let store = new_store().await;
+    store.load_countries(get_sample_countries()).await;
+    let app = create_routes(store);
+
+    for query in [
+        "",
+        "?region=europe,asia&min_population=10000000",
+        "?currency=eur&limit=5&offset=3",
+        "?search=land&sort=population&order=desc",
+    ] {
+        let (_, array) = get_json(app.clone(), &format!("/api/countries{}", query)).await;
+        let array: Vec<Country> = match array.get("items") {
+            Some(items) => serde_json::from_value(items.clone()).unwrap(),
+            None => serde_json::from_value(array).unwrap(),
+        };
+
+        let separator = if query.is_empty() { '?' } else { '&' };
+        let uri = format!("/api/countries{}{}format=ndjson", query, separator);
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+
+        let lines = ndjson_countries(&body_text(response).await);
+        assert!(!lines.is_empty(), "no countries for {}", query);
+        assert_eq!(lines, array, "query {}", query);
+    }
+}
+
+#[tokio::test]
+async fn test_ndjson_accept_header_and_fields() {
+    let app = create_routes(setup_test_store().await);
+
+    let response =
+        get_with_accept(app.clone(), "/api/countries", "application/x-ndjson").await;
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+    let ndjson_etag = etag_of(&response);
+    assert_eq!(ndjson_countries(&body_text(response).await).len(), 3);
+
+    let json = get_with_accept(app.clone(), "/api/countries", "application/json").await;
+    assert_ne!(etag_of(&json), ndjson_etag);
+
+    let response = get_with_accept(
+        app,
+        "/api/countries?format=ndjson&fields=population",
+        "text/csv",
+    )
+    .await;
+    let body = body_text(response).await;
+    let first: Value = serde_json::from_str(body.lines().next().unwrap()).unwrap();
+    assert_eq!(first.as_object().unwrap().len(), 2);
+    assert!(first.get("population").is_some());
+}
+
+async fn conditional_get(
+    app: axum::Router,
+    uri: &str,
+    if_none_match: Option<&str>,
+) -> axum::response::Response {
+    let mut request = Request::builder().uri(uri);
+    if let Some(etag) = if_none_match {
+        request = request.header("If-None-Match", etag);
+    }
+    app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+}
+
+fn etag_of(response: &axum::response::Response) -> String {
+    response.headers()["etag"].to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_etag_is_stable_and_conditional_get_returns_304() {
+    let app = create_routes(setup_test_store().await);
+
+    let first = conditional_get(app.clone(), "/api/countries", None).await;
+    let second = conditional_get(app.clone(), "/api/countries", None).await;
+    let etag = etag_of(&first);
+    assert!(etag.starts_with('"') && etag.ends_with('"'), "strong ETag: {}", etag);
+    assert_eq!(etag, etag_of(&second));
+
+    let response = conditional_get(app.clone(), "/api/countries", Some(&etag)).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(etag_of(&response), etag);
+    assert!(body_text(response).await.is_empty());
+
+    // Lists of tags and weak comparison
+    let listed = format!("\"other\", W/{}", etag);
+    let response = conditional_get(app.clone(), "/api/countries", Some(&listed)).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    let response = conditional_get(app.clone(), "/api/countries", Some("\"other\"")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Single-country lookups are tagged too; misses are not
+    let france = conditional_get(app.clone(), "/api/countries/France", None).await;
+    let etag = etag_of(&france);
+    let response = conditional_get(app.clone(), "/api/countries/France", Some(&etag)).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    let response = conditional_get(app, "/api/countries/Atlantis", Some("*")).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().get("etag").is_none());
+}
+
+#[tokio::test]
+async fn test_representations_have_distinct_etags() {
+    let app = create_routes(setup_test_store().await);
+
+    let json = conditional_get(app.clone(), "/api/countries", None).await;
+    let csv = get_with_accept(app.clone(), "/api/countries", "text/csv").await;
+    assert_ne!(etag_of(&json), etag_of(&csv));
+
+    let response = get_with_accept(app, "/api/countries", "text/csv").await;
+    assert_eq!(etag_of(&response), etag_of(&csv));
+}
+
+#[tokio::test]
+async fn test_mutation_changes_etag() {
+    let app = create_routes(setup_test_store().await);
+
+    let response = conditional_get(app.clone(), "/api/countries", None).await;
+    let etag = etag_of(&response);
+
+    let response = app
+        .clone()
+        .oneshot(post_country(serde_json::json!({
+            "name": "Spain",
+            "capital": "Madrid",
+            "population": 47000000,
+            "region": "Europe",
+            "languages": ["Spanish"]
+        })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = conditional_get(app.clone(), "/api/countries", Some(&etag)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(etag_of(&response), etag);
+    let countries: Value = serde_json::from_str(&body_text(response).await).unwrap();
+    assert!(names(&countries).contains(&"Spain"));
+
+    // Deletes invalidate the tag as well
+    let response = conditional_get(app.clone(), "/api/countries", None).await;
+    let etag = etag_of(&response);
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri("/api/countries/Spain")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = conditional_get(app, "/api/countries", Some(&etag)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_capital_lookup_resolves_country() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, body) = get_json(app.clone(), "/api/capitals/paris").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), vec!["France"]);
+
+    let (status, body) = get_json(app, "/api/capitals/Atlantis").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body["error"].as_str().unwrap().contains("Atlantis"));
+}
+
+#[tokio::test]
+async fn test_shared_capital_returns_every_country() {
+    let store = setup_test_store().await;
+    store
+        .add_country(Country {
+            name: "Paris Twin".to_string(),
+            capital: " PARÍS ".to_string(),
+            population: 1,
+            region: "Europe".to_string(),
+            languages: vec!["French".to_string()],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let app = create_routes(store);
+
+    let (status, body) = get_json(app.clone(), "/api/capitals/Paris").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), vec!["France", "Paris Twin"]);
+
+    let (status, body) = get_json(app, "/api/countries?capital=paris&region=Europe").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), vec!["France", "Paris Twin"]);
+}
+
+#[tokio::test]
+async fn test_capital_filter_on_list() {
+    let app = create_routes(setup_test_store().await);
+
+    let (_, body) = get_json(app.clone(), "/api/countries?capital=tokyo").await;
+    assert_eq!(names(&body), vec!["Japan"]);
+
+    let (status, body) = get_json(app, "/api/countries?capital=Atlantis").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, serde_json::json!([]));
+}
+
+async fn request_with_method(
+    app: axum::Router,
+    method: &str,
+    uri: &str,
+) -> axum::response::Response {
+    app.oneshot(Request::builder().method(method).uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_head_matches_get_without_a_body() {
+    let app = create_routes(setup_test_store().await);
+
+    for uri in ["/api/countries", "/api/countries/France", "/api/regions"] {
+        let get = request_with_method(app.clone(), "GET", uri).await;
+        let head = request_with_method(app.clone(), "HEAD", uri).await;
+        assert_eq!(head.status(), StatusCode::OK, "{}", uri);
+        for name in ["content-type", "content-length", "etag"] {
+            assert_eq!(head.headers().get(name), get.headers().get(name), "{} {}", uri, name);
+        }
+        assert_ne!(head.headers()["content-length"], "0", "{}", uri);
+        assert!(body_text(head).await.is_empty(), "{}", uri);
+    }
+
+    let etag = etag_of(&request_with_method(app.clone(), "GET", "/api/countries").await);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("HEAD")
+                .uri("/api/countries")
+                .header("If-None-Match", &etag)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn test_unsupported_method_returns_405_with_allow() {
+    let app = create_routes(setup_test_store().await);
+
+    for (method, uri, allow) in [
+        ("DELETE", "/api/countries", "GET,HEAD,POST"),
+        ("POST", "/api/countries/France", "GET,HEAD,PUT,PATCH,DELETE"),
+        ("PUT", "/api/countries/stats", "GET,HEAD"),
+    ] {
+        let response = request_with_method(app.clone(), method, uri).await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED, "{} {}", method, uri);
+        assert_eq!(response.headers()["allow"], allow, "{} {}", method, uri);
+        assert_eq!(response.headers()["content-type"], "application/json");
+
+        let error: Value = serde_json::from_str(&body_text(response).await).unwrap();
+        let message = error["error"].as_str().unwrap();
+        assert!(message.starts_with(&format!("{} is not allowed on {}", method, uri)));
+        assert_eq!(error.as_object().unwrap().len(), 1, "{}", error);
+    }
+
+    // The collection itself is untouched
+    let (status, countries) = get_json(app, "/api/countries").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(countries.as_array().unwrap().len(), 3);
+}
+
+/// Fields whose XML element holds one child per entry
+const XML_LISTS: [&str; 5] =
+    ["countries", "languages", "currencies", "borders", "population_history"];
+
+/// An element being read by `xml_to_json`
+#[derive(Default)]
+struct OpenElement {
+    name: String,
+    children: Vec<(String, Value)>,
+    text: String,
+}
+
+/// Parses XML written by the API back into JSON: list elements become
+/// arrays, other elements with children objects, and text stays a string
+fn xml_to_json(xml: &str) -> Value {
+    use quick_xml::events::Event;
+
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut open = vec![OpenElement::default()];
+    loop {
+        match reader.read_event().unwrap() {
+            Event::Start(start) => open.push(OpenElement {
+                name: String::from_utf8(start.name().as_ref().to_vec()).unwrap(),
+                ..Default::default()
+            }),
+            Event::Text(text) => open.last_mut().unwrap().text.push_str(&text.unescape().unwrap()),
+            Event::End(_) => {
+                let element = open.pop().unwrap();
+                let value = if XML_LISTS.contains(&element.name.as_str()) {
+                    Value::Array(element.children.into_iter().map(|(_, child)| child).collect())
+                } else if element.children.is_empty() {
+                    Value::String(element.text)
+                } else {
+                    Value::Object(element.children.into_iter().collect())
+                };
+                open.last_mut().unwrap().children.push((element.name, value));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    open.pop().unwrap().children.pop().unwrap().1
+}
+
+/// `json` as it reads back from XML: numbers as text, without nulls
+fn as_xml_text(json: Value) -> Value {
+    match json {
+        Value::Number(number) => Value::String(number.to_string()),
+        Value::Bool(flag) => Value::String(flag.to_string()),
+        Value::Array(items) => Value::Array(items.into_iter().map(as_xml_text).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, as_xml_text(value)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Fractional numbers in XML text rounded to 9 places
+fn rounded(xml: Value) -> Value {
+    match xml {
+        Value::String(text) if text.contains('.') => match text.parse::<f64>() {
+            Ok(number) => Value::String(format!("{:.9}", number)),
+            Err(_) => Value::String(text),
+        },
+        Value::Array(items) => Value::Array(items.into_iter().map(rounded).collect()),
+        Value::Object(fields) => {
+            Value::Object(fields.into_iter().map(|(key, value)| (key, rounded(value))).collect())
+        }
+        other => other,
+    }
+}
+
+async fn get_xml(app: axum::Router, uri: &str, accept: &str) -> (StatusCode, String) {
+    let response = get_with_accept(app, uri, accept).await;
+    let status = response.status();
+    assert_eq!(response.headers()["content-type"], "application/xml", "{}", uri);
+    (status, body_text(response).await)
+}
+
+#[tokio::test]
+async fn test_xml_list_matches_json() {
+    let store = new_store().await;
+    store.load_countries(get_sample_countries()).await;
+    let app = create_routes(store);
+
+    for query in ["region=europe", "region=oceania&fields=capital,languages&limit=5"] {
+        let (_, json) = get_json(app.clone(), &format!("/api/countries?{}", query)).await;
+        let json = if json.is_array() { json } else { json["items"].clone() };
+
+        let (status, xml) =
+            get_xml(app.clone(), &format!("/api/countries?{}", query), "application/xml").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?><countries>"));
+        // Densities are compared to 9 places, as parsing the JSON may drop the last digit
+        assert_eq!(
+            rounded(xml_to_json(&xml)),
+            rounded(as_xml_text(json.clone())),
+            "{}",
+            query
+        );
+
+        // format wins over Accept, and text/xml is understood
+        let uri = format!("/api/countries?{}&format=xml", query);
+        let (_, by_param) = get_xml(app.clone(), &uri, "text/csv").await;
+        assert_eq!(by_param, xml);
+        let uri = format!("/api/countries?{}", query);
+        let (_, by_text_xml) = get_xml(app.clone(), &uri, "text/xml").await;
+        assert_eq!(by_text_xml, xml);
+    }
+
+    // JSON stays the default
+    let response = get_with_accept(app, "/api/countries", "*/*").await;
+    assert_eq!(response.headers()["content-type"], "application/json");
+}
+
+#[tokio::test]
+async fn test_xml_country_escapes_text() {
+    let store = setup_test_store().await;
+    store
+        .add_country(Country {
+            name: "Trinidad & <Tobago>".to_string(),
+            capital: "Port of Spain".to_string(),
+            population: 1500000,
+            region: "Americas".to_string(),
+            languages: vec!["English".to_string(), "Creole \"Trini\"".to_string()],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let app = create_routes(store);
+    let uri = "/api/countries/Trinidad%20%26%20%3CTobago%3E";
+
+    let (status, json) = get_json(app.clone(), uri).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, xml) = get_xml(app.clone(), uri, "application/xml").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(xml.contains("<name>Trinidad &amp; &lt;Tobago&gt;</name>"), "{}", xml);
+    assert!(xml.contains("<languages><language>English</language><language>"), "{}", xml);
+    assert_eq!(xml_to_json(&xml), as_xml_text(json));
+
+    let projected = format!("{}?fields=capital", uri);
+    let (_, xml) = get_xml(app.clone(), &projected, "application/xml").await;
+    assert_eq!(
+        xml_to_json(&xml),
+        serde_json::json!({ "name": "Trinidad & <Tobago>", "capital": "Port of Spain" })
+    );
+    let (_, by_param) = get_xml(app.clone(), &format!("{}&format=xml", projected), "").await;
+    assert_eq!(by_param, xml);
+
+    // The XML representation has its own tag
+    let json = conditional_get(app.clone(), uri, None).await;
+    let xml = get_with_accept(app, uri, "application/xml").await;
+    assert_ne!(etag_of(&json), etag_of(&xml));
+}
+
+#[tokio::test]
+async fn test_xml_errors_follow_the_negotiated_format() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, xml) = get_xml(app.clone(), "/api/countries/Frnace", "application/xml").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let error = xml_to_json(&xml);
+    assert_eq!(error["message"], "Country \"Frnace\" not found");
+    assert_eq!(error["suggestion"], "France");
+
+    let (status, xml) = get_xml(app.clone(), "/api/countries?format=xml&limit=abc", "").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(xml.contains("<field name=\"limit\"><message>invalid value &quot;abc&quot;"));
+
+    let (status, xml) = get_xml(app.clone(), "/api/countries?fields=motto", "text/xml").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(xml_to_json(&xml)["message"].as_str().unwrap().contains("motto"));
+
+    // CSV and NDJSON don't apply to one country; the error is JSON
+    let (status, error) = get_json(app, "/api/countries/France?format=csv").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["error"], "format must be json or xml for a single country");
+}
+
+async fn batch_lookup(app: axum::Router, names: Value) -> (StatusCode, Value) {
+    let body = serde_json::json!({ "names": names });
+    let request = json_request("POST", "/api/countries/batch", body);
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    (status, serde_json::from_str(&body_text(response).await).unwrap())
+}
+
+#[tokio::test]
+async fn test_batch_lookup_reports_found_and_missing() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, body) =
+        batch_lookup(app, serde_json::json!(["France", "japan", "Atlantis"])).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body["found"]), vec!["France", "Japan"]);
+    assert_eq!(body["found"][1]["capital"], "Tokyo");
+    assert_eq!(body["missing"], serde_json::json!(["Atlantis"]));
+}
+
+#[tokio::test]
+async fn test_batch_lookup_keeps_input_order_and_drops_repeats() {
+    let app = create_routes(setup_test_store().await);
+
+    let (status, body) = batch_lookup(
+        app.clone(),
+        serde_json::json!([
+            "UNITED STATES",
+            "Narnia",
+            "france",
+            " United  States ",
+            "Japan",
+            "narnia",
+            "France"
+        ]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body["found"]), vec!["United States", "France", "Japan"]);
+    // The first spelling of a missing name is kept
+    assert_eq!(body["missing"], serde_json::json!(["Narnia"]));
+
+    let (status, body) = batch_lookup(app, serde_json::json!([])).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, serde_json::json!({ "found": [], "missing": [] }));
+}
+
+#[tokio::test]
+async fn test_batch_lookup_is_capped_at_100_names() {
+    let app = create_routes(setup_test_store().await);
+
+    let mut names: Vec<String> = (0..99).map(|i| format!("Nowhere {}", i)).collect();
+    names.push("France".to_string());
+    let (status, body) = batch_lookup(app.clone(), serde_json::json!(names)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["missing"].as_array().unwrap().len(), 99);
+
+    names.push("Japan".to_string());
+    let (status, error) = batch_lookup(app, serde_json::json!(names)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["error"], "Batch lookups are limited to 100 names, got 101");
+}
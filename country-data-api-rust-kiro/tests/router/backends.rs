@@ -0,0 +1,43 @@
+// Store backends the router suites run against. Each `*_test.rs` entry point
+// includes this file and calls `for_each_store!` with its suite.
+
+/// Includes the suite at `$suite` (relative to `tests/`) once per backend:
+/// `memory`, and `sqlite` with the `sqlite` feature. Suites build their stores
+/// with `new_store()` and `seeded_store(seed)`.
+macro_rules! for_each_store {
+    ($suite:literal) => {
+        mod memory {
+            use country_data_api_rust_kiro::services::{CountryDataStore, IntoSharedStore};
+
+            #[allow(dead_code)]
+            async fn new_store() -> country_data_api_rust_kiro::services::SharedStore {
+                CountryDataStore::new().into_shared()
+            }
+
+            #[allow(dead_code)]
+            async fn seeded_store(seed: u64) -> country_data_api_rust_kiro::services::SharedStore {
+                CountryDataStore::new().with_rng_seed(seed).into_shared()
+            }
+
+            include!($suite);
+        }
+
+        #[cfg(feature = "sqlite")]
+        mod sqlite {
+            use country_data_api_rust_kiro::services::{IntoSharedStore, SqliteCountryStore};
+
+            #[allow(dead_code)]
+            async fn new_store() -> country_data_api_rust_kiro::services::SharedStore {
+                SqliteCountryStore::in_memory().await.unwrap().into_shared()
+            }
+
+            #[allow(dead_code)]
+            async fn seeded_store(seed: u64) -> country_data_api_rust_kiro::services::SharedStore {
+                let store = SqliteCountryStore::in_memory().await.unwrap();
+                store.with_rng_seed(seed).into_shared()
+            }
+
+            include!($suite);
+        }
+    };
+}
@@ -0,0 +1,128 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use country_data_api_rust_kiro::{
+    api::{create_routes, create_routes_with_config, ApiConfig},
+    sample_data,
+    services::SharedStore,
+};
+use serde_json::{json, Value};
+use tower::util::ServiceExt;
+
+async fn sample_store() -> SharedStore {
+    let store = new_store().await;
+    store.load_countries(sample_data::get_sample_countries()).await;
+    store
+}
+
+async fn query(app: axum::Router, query: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/graphql")
+        .header("Content-Type", "application/json")
+        .body(Body::from(json!({ "query": query }).to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_countries_query_returns_only_selected_fields() {
+    let store = sample_store().await;
+    let expected = store.filter_by_region("Europe").await;
+    let app = create_routes(store);
+
+    let (status, body) = query(app, r#"{ countries(region: "Europe") { name capital } }"#).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.get("errors").is_none(), "{}", body);
+    let countries = body["data"]["countries"].as_array().unwrap();
+    assert_eq!(countries.len(), expected.len());
+    for (country, expected) in countries.iter().zip(&expected) {
+        assert_eq!(
+            country,
+            &json!({ "name": expected.name, "capital": expected.capital })
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_country_stats_and_pagination() {
+    let app = create_routes(sample_store().await);
+
+    let (_, body) = query(
+        app,
+        r#"{
+            country(name: "france") { name populationDensity currencies { code } }
+            missing: country(name: "Atlantis") { name }
+            stats { totalCountries }
+            page: countries(limit: 2, offset: 1) { name }
+        }"#,
+    )
+    .await;
+
+    assert!(body.get("errors").is_none(), "{}", body);
+    let data = &body["data"];
+    assert_eq!(data["country"]["name"], "France");
+    assert!(data["country"]["populationDensity"].is_f64());
+    assert_eq!(data["country"]["currencies"][0]["code"], "EUR");
+    assert!(data["missing"].is_null());
+    assert!(data["stats"]["totalCountries"].as_u64().unwrap() >= 15);
+    assert_eq!(data["page"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_introspection_query_succeeds() {
+    let app = create_routes(sample_store().await);
+
+    let (status, body) = query(
+        app,
+        "{ __schema { queryType { name fields { name } } } }",
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.get("errors").is_none(), "{}", body);
+    let query_type = &body["data"]["__schema"]["queryType"];
+    assert_eq!(query_type["name"], "QueryRoot");
+    let mut fields: Vec<&str> = query_type["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["name"].as_str().unwrap())
+        .collect();
+    fields.sort();
+    assert_eq!(fields, vec!["countries", "country", "stats"]);
+}
+
+#[tokio::test]
+async fn test_queries_do_not_need_an_api_key() {
+    let config = ApiConfig::default().with_api_keys(["secret"]);
+    let app = create_routes_with_config(sample_store().await, &config);
+
+    let (status, body) = query(app, r#"{ country(name: "Japan") { capital } }"#).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["country"]["capital"], "Tokyo");
+}
+
+#[tokio::test]
+async fn test_get_serves_graphiql() {
+    let app = create_routes(new_store().await);
+
+    let response = app
+        .oneshot(Request::builder().uri("/graphql").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(String::from_utf8_lossy(&body).contains("graphiql"));
+}
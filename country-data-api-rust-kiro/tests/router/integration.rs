@@ -0,0 +1,562 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use country_data_api_rust_kiro::{api::create_routes, sample_data};
+use tower::util::ServiceExt;
+
+#[tokio::test]
+async fn test_full_application_startup_with_sample_data() {
+    let store = new_store().await;
+    store.load_countries(sample_data::get_sample_countries()).await;
+
+    let countries = store.get_all().await;
+    assert!(countries.len() >= 15, "Should have at least 15 countries");
+}
+
+#[tokio::test]
+async fn test_all_endpoints_work_together() {
+    let store = new_store().await;
+    store.load_countries(sample_data::get_sample_countries()).await;
+    let app = create_routes(store);
+
+    // Test get all countries
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Test get specific country
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries/France")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Test region filter
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries?region=Europe")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Test search
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries?search=United")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_error_handling_in_complete_request_flow() {
+    let store = new_store().await;
+    store.load_countries(sample_data::get_sample_countries()).await;
+    let app = create_routes(store);
+
+    // Test 404 for non-existent country
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries/Atlantis")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(error.get("error").is_some());
+}
+
+async fn get_sample_json(uri: &str) -> (StatusCode, serde_json::Value) {
+    let store = new_store().await;
+    store.load_countries(sample_data::get_sample_countries()).await;
+    let app = create_routes(store);
+
+    let response = app
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_sort_by_population_descending() {
+    let (status, countries) = get_sample_json("/api/countries?sort=population&order=desc").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let countries = countries.as_array().unwrap();
+    assert_eq!(countries.len(), sample_data::get_sample_countries().len());
+    assert_eq!(countries.first().unwrap()["name"], "India");
+    assert_eq!(countries.last().unwrap()["name"], "Pitcairn Islands");
+}
+
+#[tokio::test]
+async fn test_sort_is_applied_before_pagination() {
+    let (status, page) =
+        get_sample_json("/api/countries?region=Europe&sort=population&order=desc&limit=2").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let names: Vec<&str> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Russia", "Germany"]);
+}
+
+#[tokio::test]
+async fn test_invalid_sort_lists_valid_options() {
+    let (status, error) = get_sample_json("/api/countries?sort=capital").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let message = error["error"].as_str().unwrap();
+    assert!(message.contains("name") && message.contains("population") && message.contains("region"));
+
+    let (status, error) = get_sample_json("/api/countries?sort=name&order=up").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let message = error["error"].as_str().unwrap();
+    assert!(message.contains("asc") && message.contains("desc"));
+}
+
+fn sorted_names(countries: &serde_json::Value) -> Vec<String> {
+    let mut names: Vec<String> = countries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_multi_region_filter() {
+    let (status, countries) = get_sample_json("/api/countries?region=Europe,Asia").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(countries.as_array().unwrap().len(), 103);
+
+    // Whitespace, casing and empty segments don't matter
+    let (_, same) = get_sample_json("/api/countries?region=%20europe%20,,ASIA,").await;
+    assert_eq!(sorted_names(&same), sorted_names(&countries));
+
+    // A single region behaves as before
+    let (_, europe) = get_sample_json("/api/countries?region=Europe").await;
+    assert_eq!(europe.as_array().unwrap().len(), 52);
+}
+
+#[tokio::test]
+async fn test_multi_region_filter_with_search() {
+    let (status, countries) = get_sample_json("/api/countries?region=Europe,Asia&search=land").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        sorted_names(&countries),
+        vec![
+            "Faroe Islands",
+            "Finland",
+            "Iceland",
+            "Ireland",
+            "Netherlands",
+            "Poland",
+            "Switzerland",
+            "Thailand",
+            "Åland Islands"
+        ]
+    );
+
+    let (_, countries) = get_sample_json("/api/countries?region=africa,oceania&search=GUI").await;
+    assert_eq!(
+        sorted_names(&countries),
+        vec![
+            "Equatorial Guinea",
+            "Guinea",
+            "Guinea-Bissau",
+            "Papua New Guinea"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_empty_region_filter_returns_400() {
+    for uri in ["/api/countries?region=", "/api/countries?region=,%20,"] {
+        let (status, error) = get_sample_json(uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        assert!(error.get("error").is_some(), "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_search_across_capital_and_languages() {
+    let (status, countries) = get_sample_json("/api/countries?search=paris&search_fields=all").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(sorted_names(&countries), vec!["France"]);
+
+    let (_, countries) =
+        get_sample_json("/api/countries?search=japanese&search_fields=languages").await;
+    assert_eq!(sorted_names(&countries), vec!["Japan"]);
+
+    // The default still only looks at names
+    let (_, countries) = get_sample_json("/api/countries?search=paris").await;
+    assert!(countries.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_search_all_fields_lists_name_matches_first() {
+    let (_, countries) = get_sample_json("/api/countries?search=city&search_fields=all").await;
+    let names: Vec<&str> = countries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+
+    // Vatican City matches by name and capital but is listed once, ahead of capital-only matches
+    assert_eq!(names[0], "Vatican City");
+    assert_eq!(names.iter().filter(|n| **n == "Vatican City").count(), 1);
+    assert!(names.contains(&"Mexico"));
+    assert!(names.contains(&"Kuwait"));
+}
+
+#[tokio::test]
+async fn test_invalid_search_fields_returns_400() {
+    let (status, error) = get_sample_json("/api/countries?search=paris&search_fields=flag").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error.get("error").is_some());
+}
+
+#[tokio::test]
+async fn test_lookup_by_iso_code() {
+    for uri in ["/api/countries/code/fr", "/api/countries/code/FRA"] {
+        let (status, country) = get_sample_json(uri).await;
+        assert_eq!(status, StatusCode::OK, "{}", uri);
+        assert_eq!(country["name"], "France", "{}", uri);
+        assert_eq!(country["alpha2"], "FR");
+        assert_eq!(country["alpha3"], "FRA");
+    }
+
+    let (_, country) = get_sample_json("/api/countries/code/fr?fields=alpha3").await;
+    assert_eq!(country, serde_json::json!({"name": "France", "alpha3": "FRA"}));
+
+    let (status, error) = get_sample_json("/api/countries/code/FRAN").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error.get("error").is_some());
+
+    let (status, error) = get_sample_json("/api/countries/code/QQ").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(error.get("error").is_some());
+}
+
+#[tokio::test]
+async fn test_search_matches_iso_code_exactly() {
+    let (_, countries) = get_sample_json("/api/countries?search=deu").await;
+    assert_eq!(sorted_names(&countries), vec!["Germany"]);
+
+    // Partial codes don't match
+    let (_, countries) = get_sample_json("/api/countries?search=eu").await;
+    assert!(!sorted_names(&countries).contains(&"Germany".to_string()));
+}
+
+#[tokio::test]
+async fn test_currency_filter_returns_euro_countries() {
+    let (status, countries) = get_sample_json("/api/countries?currency=EUR").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        sorted_names(&countries),
+        vec![
+            "Andorra", "Austria", "Belgium", "Croatia", "Cyprus", "Estonia", "Finland", "France",
+            "French Guiana", "Germany", "Greece", "Guadeloupe", "Ireland", "Italy", "Kosovo",
+            "Latvia", "Lithuania", "Luxembourg", "Malta", "Martinique", "Mayotte", "Monaco",
+            "Montenegro", "Netherlands", "Portugal", "Réunion", "Saint Barthélemy", "Saint Martin",
+            "Saint Pierre and Miquelon", "San Marino", "Slovakia", "Slovenia", "Spain",
+            "Vatican City", "Åland Islands"
+        ]
+    );
+
+    // Case-insensitive, and composes with the other filters
+    let (_, countries) = get_sample_json("/api/countries?currency=eur&region=asia").await;
+    assert_eq!(sorted_names(&countries), vec!["Cyprus"]);
+}
+
+#[tokio::test]
+async fn test_population_density_is_computed() {
+    let (status, france) = get_sample_json("/api/countries/France").await;
+    assert_eq!(status, StatusCode::OK);
+
+    // 64,756,000 people / 551,695 km²
+    let density = france["population_density"].as_f64().unwrap();
+    assert_eq!(format!("{:.2}", density), "117.38");
+    assert_eq!(france["area_km2"], 551695.0);
+}
+
+#[tokio::test]
+async fn test_subregion_filter() {
+    let (status, countries) =
+        get_sample_json("/api/countries?subregion=western%20europe").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        sorted_names(&countries),
+        vec![
+            "Austria",
+            "Belgium",
+            "France",
+            "Germany",
+            "Liechtenstein",
+            "Luxembourg",
+            "Monaco",
+            "Netherlands",
+            "Switzerland"
+        ]
+    );
+
+    // Composes with region: a subregion outside the region matches nothing
+    let (_, countries) =
+        get_sample_json("/api/countries?region=Europe&subregion=Western%20Asia").await;
+    assert!(countries.as_array().unwrap().is_empty());
+
+    let (_, countries) =
+        get_sample_json("/api/countries?region=Asia&subregion=Western%20Asia&search=saudi").await;
+    assert_eq!(sorted_names(&countries), vec!["Saudi Arabia"]);
+
+    let (status, _) = get_sample_json("/api/countries?subregion=,").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_regions_hierarchy_groups_sample_data() {
+    let (status, regions) = get_sample_json("/api/regions").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let regions = regions.as_array().unwrap();
+    let region_names: Vec<&str> = regions.iter().map(|r| r["region"].as_str().unwrap()).collect();
+    assert_eq!(region_names, vec!["Africa", "Americas", "Asia", "Europe", "Oceania"]);
+
+    let total: u64 = regions.iter().map(|r| r["count"].as_u64().unwrap()).sum();
+    assert_eq!(total, 243);
+
+    let europe = &regions[3];
+    assert_eq!(europe["count"], 52);
+    let subregions: Vec<(&str, u64)> = europe["subregions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| (s["subregion"].as_str().unwrap(), s["count"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(
+        subregions,
+        vec![
+            ("Eastern Europe", 10),
+            ("Northern Europe", 16),
+            ("Southern Europe", 17),
+            ("Western Europe", 9)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_countries_by_region_groups_sample_data() {
+    let (status, groups) = get_sample_json("/api/countries/by-region").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let groups = groups.as_array().unwrap();
+    let summary: Vec<(&str, u64)> = groups
+        .iter()
+        .map(|g| (g["region"].as_str().unwrap(), g["count"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![("Africa", 58), ("Americas", 55), ("Asia", 51), ("Europe", 52), ("Oceania", 27)]
+    );
+
+    for group in groups {
+        let countries = group["countries"].as_array().unwrap();
+        assert_eq!(countries.len() as u64, group["count"].as_u64().unwrap());
+        assert!(countries.iter().all(|c| c["region"] == group["region"]));
+
+        let names: Vec<String> =
+            countries.iter().map(|c| c["name"].as_str().unwrap().to_string()).collect();
+        let mut sorted = names.clone();
+        sorted.sort_by_key(|name| name.to_lowercase());
+        assert_eq!(names, sorted, "{}", group["region"]);
+    }
+
+    let europe = &groups[3];
+    assert_eq!(europe["total_population"], 734393825);
+    let europe = sorted_names(&europe["countries"]);
+    assert!(europe.contains(&"France".to_string()));
+    assert!(!europe.contains(&"Japan".to_string()));
+    assert!(sorted_names(&groups[2]["countries"]).contains(&"Japan".to_string()));
+    assert_eq!(
+        &sorted_names(&groups[1]["countries"])[..3],
+        ["Anguilla", "Antigua and Barbuda", "Argentina"]
+    );
+}
+
+#[tokio::test]
+async fn test_neighbors_endpoint() {
+    let (status, france) = get_sample_json("/api/countries/France/neighbors").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(sorted_names(&france["neighbors"]).contains(&"Spain".to_string()));
+    assert!(france["unresolved"].as_array().unwrap().is_empty());
+
+    // Landlocked between two landlocked countries; every border resolves
+    let (_, liechtenstein) = get_sample_json("/api/countries/liechtenstein/neighbors").await;
+    assert_eq!(
+        sorted_names(&liechtenstein["neighbors"]),
+        vec!["Austria", "Switzerland"]
+    );
+    assert!(liechtenstein["unresolved"].as_array().unwrap().is_empty());
+
+    // Island nation
+    let (status, japan) = get_sample_json("/api/countries/Japan/neighbors").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(japan["neighbors"].as_array().unwrap().is_empty());
+
+    let (status, error) = get_sample_json("/api/countries/Atlantis/neighbors").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(error.get("error").is_some());
+}
+
+#[tokio::test]
+async fn test_stats_match_sample_data() {
+    let (status, stats) = get_sample_json("/api/countries/stats").await;
+    assert_eq!(status, StatusCode::OK);
+
+    assert_eq!(stats["total_countries"], 243);
+    assert_eq!(stats["total_population"], 7991958475i64);
+    assert_eq!(stats["most_populous"], "India");
+    assert_eq!(stats["least_populous"], "Pitcairn Islands");
+
+    let regions = stats["by_region"].as_object().unwrap();
+    assert_eq!(
+        regions.keys().collect::<Vec<_>>(),
+        vec!["Africa", "Americas", "Asia", "Europe", "Oceania"]
+    );
+    assert_eq!(regions["Europe"]["count"], 52);
+    assert_eq!(regions["Europe"]["total_population"], 734393825);
+    let average = regions["Oceania"]["average_population"].as_f64().unwrap();
+    assert!((average - 45349650.0 / 27.0).abs() < 1e-6);
+
+    let languages: Vec<(&str, u64)> = stats["top_languages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| (l["language"].as_str().unwrap(), l["count"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(languages.len(), 10);
+    assert_eq!(
+        languages[..4],
+        [("English", 86), ("French", 43), ("Arabic", 26), ("Spanish", 22)]
+    );
+    // Ties are broken by name
+    assert_eq!(languages[8..], [("Malay", 4), ("Russian", 4)]);
+}
+
+#[tokio::test]
+async fn test_stats_region_filter() {
+    let (status, stats) = get_sample_json("/api/countries/stats?region=europe").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(stats["total_countries"], 52);
+    assert_eq!(stats["total_population"], 734393825);
+    assert_eq!(stats["most_populous"], "Russia");
+    assert_eq!(stats["by_region"].as_object().unwrap().len(), 1);
+
+    let (_, stats) = get_sample_json("/api/countries/stats?region=Atlantis").await;
+    assert_eq!(stats["total_countries"], 0);
+    assert_eq!(stats["most_populous"], serde_json::Value::Null);
+
+    let (status, error) = get_sample_json("/api/countries/stats?region=,").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error.get("error").is_some());
+}
+
+#[tokio::test]
+async fn test_language_stats_match_sample_data() {
+    let (status, languages) = get_sample_json("/api/languages").await;
+    assert_eq!(status, StatusCode::OK);
+    let languages = languages.as_array().unwrap();
+
+    let english: Vec<_> = sample_data::get_sample_countries()
+        .into_iter()
+        .filter(|c| c.languages.iter().any(|l| l == "English"))
+        .collect();
+    assert_eq!(languages[0]["language"], "English");
+    assert_eq!(languages[0]["count"], 86);
+    assert_eq!(languages[0]["count"], english.len());
+    assert_eq!(
+        languages[0]["total_population"],
+        english.iter().map(|c| c.population).sum::<i64>()
+    );
+    let names: Vec<&str> = languages[0]["countries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|name| name.as_str().unwrap())
+        .collect();
+    assert_eq!(names, english.iter().map(|c| c.name.as_str()).collect::<Vec<_>>());
+
+    let counts: Vec<u64> = languages.iter().map(|l| l["count"].as_u64().unwrap()).collect();
+    assert!(counts.windows(2).all(|pair| pair[0] >= pair[1]));
+}
+
+#[tokio::test]
+async fn test_language_stats_region_scope() {
+    let (status, languages) = get_sample_json("/api/languages?region=Europe").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let europe: Vec<String> = sample_data::get_sample_countries()
+        .into_iter()
+        .filter(|c| c.region == "Europe")
+        .map(|c| c.name)
+        .collect();
+    for language in languages.as_array().unwrap() {
+        for name in language["countries"].as_array().unwrap() {
+            assert!(europe.iter().any(|c| c == name.as_str().unwrap()));
+        }
+    }
+    let french = languages
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|l| l["language"] == "French")
+        .unwrap();
+    assert!(french["countries"].as_array().unwrap().contains(&"France".into()));
+    assert!(!french["countries"].as_array().unwrap().contains(&"Senegal".into()));
+
+    let (status, _) = get_sample_json("/api/languages?region=,").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
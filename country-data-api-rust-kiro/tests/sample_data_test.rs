@@ -0,0 +1,43 @@
+use country_data_api_rust_kiro::models::Country;
+use country_data_api_rust_kiro::sample_data::{get_sample_countries, SAMPLE_COUNTRIES_JSON};
+use country_data_api_rust_kiro::services::CountryDataStore;
+use country_data_api_rust_kiro::utils::name_key;
+use std::collections::HashSet;
+
+#[test]
+fn test_embedded_json_parses_into_valid_countries() {
+    let countries: Vec<Country> = serde_json::from_str(SAMPLE_COUNTRIES_JSON).unwrap();
+    assert_eq!(countries, get_sample_countries());
+    assert!(countries.len() >= 196, "only {} countries", countries.len());
+
+    for country in &countries {
+        assert_eq!(country.validate(), Ok(()), "{}", country.name);
+        assert!(country.alpha2.is_some() && country.alpha3.is_some(), "{}", country.name);
+        assert!(country.flag_url.is_some(), "{}", country.name);
+    }
+}
+
+#[test]
+fn test_embedded_names_and_codes_are_unique() {
+    let countries = get_sample_countries();
+
+    let mut names = HashSet::new();
+    let mut codes = HashSet::new();
+    for country in &countries {
+        assert!(names.insert(name_key(&country.name)), "duplicate name {}", country.name);
+        for code in country.codes() {
+            assert!(codes.insert(code.to_string()), "duplicate code {}", code);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_embedded_countries_all_load_with_strict_borders() {
+    let store = CountryDataStore::new().with_strict_borders();
+    let report = store.load_countries(get_sample_countries()).await;
+
+    assert!(report.rejected.is_empty(), "{:?}", report.rejected);
+    assert_eq!(store.count().await, get_sample_countries().len());
+    // Flags come from the alpha-2 codes as the countries are stored
+    assert_eq!(store.get_by_code("GL").await.unwrap().flag_emoji, "🇬🇱");
+}
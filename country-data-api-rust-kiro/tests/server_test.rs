@@ -0,0 +1,191 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use country_data_api_rust_kiro::{
+    models::Country,
+    server::{build_app, run, serve, ServerConfig},
+    services::CountryDataStore,
+};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+use tower::util::ServiceExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+fn country(name: &str) -> Country {
+    Country {
+        name: name.to_string(),
+        capital: "Capital".to_string(),
+        population: 1000,
+        region: "Europe".to_string(),
+        languages: vec!["English".to_string()],
+        ..Default::default()
+    }
+}
+
+fn temp_data_file(label: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    std::env::temp_dir().join(format!(
+        "server_{}_{}_{}.json",
+        label,
+        std::process::id(),
+        nanos
+    ))
+}
+
+/// Serves `store` on an ephemeral port until the returned sender fires
+async fn start(
+    store: CountryDataStore,
+    drain_timeout: Duration,
+) -> (SocketAddr, oneshot::Sender<()>, JoinHandle<std::io::Result<()>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let config = ServerConfig {
+        drain_timeout,
+        ..ServerConfig::default()
+    };
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let server = tokio::spawn(serve(listener, config, store, async {
+        let _ = shutdown_rx.await;
+    }));
+    (addr, shutdown_tx, server)
+}
+
+/// Sends the request line, headers and the first half of a POST body
+async fn start_slow_post(addr: SocketAddr, body: &str) -> TcpStream {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let head = format!(
+        "POST /api/countries HTTP/1.1\r\nHost: localhost\r\n\
+         Content-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await.unwrap();
+    stream
+        .write_all(&body.as_bytes()[..body.len() / 2])
+        .await
+        .unwrap();
+    stream
+}
+
+#[tokio::test]
+async fn test_in_flight_request_completes_after_shutdown_signal() {
+    let (addr, shutdown, server) = start(CountryDataStore::new(), Duration::from_secs(10)).await;
+
+    let body = serde_json::to_string(&country("Atlantis")).unwrap();
+    let mut stream = start_slow_post(addr, &body).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    shutdown.send(()).unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!server.is_finished(), "server should wait for the request");
+
+    stream
+        .write_all(&body.as_bytes()[body.len() / 2..])
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(
+        response.starts_with("HTTP/1.1 201"),
+        "unexpected response: {}",
+        response
+    );
+
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("server should stop once drained")
+        .unwrap()
+        .unwrap();
+    assert!(TcpStream::connect(addr).await.is_err());
+}
+
+#[tokio::test]
+async fn test_drain_timeout_drops_stuck_requests() {
+    let (addr, shutdown, server) =
+        start(CountryDataStore::new(), Duration::from_millis(200)).await;
+
+    // Never finished
+    let _stream = start_slow_post(addr, &serde_json::to_string(&country("Mu")).unwrap()).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    shutdown.send(()).unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("server should give up after the drain timeout")
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_shutdown_saves_store_to_data_file() {
+    let path = temp_data_file("shutdown_save");
+    let store = CountryDataStore::new().with_data_file(&path);
+    store.load_countries(vec![country("France")]).await;
+    // Loading may already save the file; remove it to see that shutdown saves it
+    let _ = std::fs::remove_file(&path);
+
+    let (_, shutdown, server) = start(store, Duration::from_secs(1)).await;
+    shutdown.send(()).unwrap();
+    server.await.unwrap().unwrap();
+
+    let saved: Vec<Country> = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    assert_eq!(saved.len(), 1);
+    assert_eq!(saved[0].name, "France");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_shutdown_before_load_keeps_data_file() {
+    let path = temp_data_file("shutdown_unloaded");
+    std::fs::write(&path, "[]").unwrap();
+    let store = CountryDataStore::new().with_data_file(&path);
+
+    let (_, shutdown, server) = start(store, Duration::from_secs(1)).await;
+    shutdown.send(()).unwrap();
+    server.await.unwrap().unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "[]");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_address_in_use_is_reported() {
+    let taken = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = ServerConfig {
+        addr: taken.local_addr().unwrap(),
+        ..ServerConfig::default()
+    };
+
+    let err = run(config, CountryDataStore::new(), std::future::pending())
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+    assert!(err.to_string().contains("already in use"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_build_app_serves_the_api() {
+    let store = CountryDataStore::new();
+    store.load_countries(vec![country("France")]).await;
+    let app = build_app(&ServerConfig::default(), store);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/countries/France")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "sqlite")]
+
+use country_data_api_rust_kiro::models::Country;
+use country_data_api_rust_kiro::sample_data::get_sample_countries;
+use country_data_api_rust_kiro::services::{CountryStore, SqliteCountryStore, StoreError};
+use std::path::{Path, PathBuf};
+
+fn temp_database(label: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    std::env::temp_dir().join(format!(
+        "countries_{}_{}_{}.db",
+        label,
+        std::process::id(),
+        nanos
+    ))
+}
+
+fn remove_database(path: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let mut file = path.as_os_str().to_owned();
+        file.push(suffix);
+        let _ = std::fs::remove_file(file);
+    }
+}
+
+#[tokio::test]
+async fn test_countries_survive_reopening() {
+    let path = temp_database("reopen");
+    {
+        let store = SqliteCountryStore::open(&path).await.unwrap();
+        assert!(!store.is_loaded());
+        store.load_countries(get_sample_countries()).await;
+        store.remove("Japan").await.unwrap();
+    }
+
+    let reopened = SqliteCountryStore::open(&path).await.unwrap();
+    assert!(reopened.is_loaded());
+    assert_eq!(reopened.count().await, get_sample_countries().len() - 1);
+    assert!(reopened.get_by_name("Japan").await.is_none());
+    assert_eq!(reopened.get_by_code("fra").await.unwrap().name, "France");
+
+    remove_database(&path);
+}
+
+#[tokio::test]
+async fn test_rejected_writes_leave_the_database_unchanged() {
+    let store = SqliteCountryStore::in_memory().await.unwrap();
+    store.load_countries(get_sample_countries()).await;
+    let before = store.get_all().await;
+
+    let duplicate = Country {
+        name: " FRANCE ".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        store.add_country(duplicate).await,
+        Err(StoreError::DuplicateName(" FRANCE ".to_string()))
+    );
+
+    let invalid = Country {
+        population: -1,
+        ..before[0].clone()
+    };
+    let name = before[0].name.clone();
+    assert!(matches!(store.update(&name, invalid).await, Err(StoreError::Invalid(_))));
+
+    let invalid = Country {
+        name: "Atlantis".to_string(),
+        population: -1,
+        ..Default::default()
+    };
+    assert!(matches!(store.add_country(invalid).await, Err(StoreError::Invalid(_))));
+
+    assert_eq!(store.get_all().await, before);
+}
+
+#[tokio::test]
+async fn test_snapshots_beyond_the_limit_are_evicted() {
+    let store = SqliteCountryStore::in_memory()
+        .await
+        .unwrap()
+        .with_max_snapshots(2);
+    store.load_countries(get_sample_countries()).await;
+    let saved = store.get_all().await;
+
+    let first = store.snapshot().await.unwrap();
+    store.snapshot().await.unwrap();
+    let last = store.snapshot().await.unwrap();
+
+    store.remove("France").await.unwrap();
+    assert_eq!(
+        store.rollback(first).await,
+        Err(StoreError::SnapshotNotFound(first))
+    );
+    store.rollback(last).await.unwrap();
+    assert_eq!(store.get_all().await, saved);
+}
+
+#[tokio::test]
+async fn test_lookup_keys_are_recomputed_on_open() {
+    let path = temp_database("rekey");
+    {
+        let store = SqliteCountryStore::open(&path).await.unwrap();
+        store.load_countries(get_sample_countries()).await;
+    }
+    // As if written while names were normalized differently
+    {
+        let pool = sqlx::SqlitePool::connect(&format!("sqlite://{}", path.display()))
+            .await
+            .unwrap();
+        sqlx::query("UPDATE countries SET name_key = 'stale', region_key = 'stale'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+    }
+
+    let reopened = SqliteCountryStore::open(&path).await.unwrap();
+    assert_eq!(reopened.get_by_name("FRANCE").await.unwrap().name, "France");
+    assert!(!reopened.filter_by_region("europe").await.is_empty());
+
+    remove_database(&path);
+}
@@ -0,0 +1,75 @@
+use country_data_api_rust_kiro::utils::{
+    closest_match, levenshtein, name_key, normalize_for_match, normalized_distance,
+};
+use proptest::prelude::*;
+
+#[test]
+fn test_levenshtein_distance() {
+    assert_eq!(levenshtein("", ""), 0);
+    assert_eq!(levenshtein("abc", ""), 3);
+    assert_eq!(levenshtein("", "abc"), 3);
+    assert_eq!(levenshtein("kitten", "sitting"), 3);
+    assert_eq!(levenshtein("france", "franse"), 1);
+    // Counted in chars, not bytes
+    assert_eq!(levenshtein("côte", "cote"), 1);
+}
+
+#[test]
+fn test_normalized_distance_ignores_case_and_scales() {
+    assert_eq!(normalized_distance("France", "fRANCE"), 0.0);
+    assert_eq!(normalized_distance("", ""), 0.0);
+    assert_eq!(normalized_distance("abc", "xyz"), 1.0);
+    assert!((normalized_distance("Franse", "France") - 1.0 / 6.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_closest_match_respects_threshold() {
+    let names = ["France", "Finland", "Japan"];
+
+    assert_eq!(closest_match("Franse", names), Some("France"));
+    assert_eq!(closest_match("finlnd", names), Some("Finland"));
+    assert_eq!(closest_match("Qwxzv", names), None);
+    assert_eq!(closest_match("France", std::iter::empty()), None);
+}
+
+#[test]
+fn test_name_key_normalizes_whitespace_case_and_accents() {
+    assert_eq!(name_key("  United   States "), "united states");
+    assert_eq!(name_key("Côte d'Ivoire"), name_key("cote D'IVOIRE"));
+    assert_eq!(name_key("São Tomé and Príncipe"), "sao tome and principe");
+    assert_ne!(name_key("Niger"), name_key("Nigeria"));
+}
+
+#[test]
+fn test_normalize_for_match_strips_accents() {
+    assert_eq!(normalize_for_match("Côte d'Ivoire"), "cote d'ivoire");
+    assert_eq!(normalize_for_match("ÅLAND"), "aland");
+    assert_eq!(normalize_for_match("Curaçao"), normalize_for_match("CURACAO"));
+    assert_eq!(normalize_for_match("Việt Nam"), "viet nam");
+}
+
+#[test]
+fn test_normalize_for_match_folds_beyond_lowercase() {
+    // Full case folding, not just lowercasing
+    assert_eq!(normalize_for_match("Straße"), "strasse");
+    assert_eq!(normalize_for_match("STRASSE"), normalize_for_match("straße"));
+    // Dotted capital I folds to a plain i once its dot is stripped
+    assert_eq!(normalize_for_match("İstanbul"), "istanbul");
+    // Final and medial sigma fold alike
+    assert_eq!(normalize_for_match("ΕΛΛΆΣ"), normalize_for_match("Ελλάς"));
+    // Ligatures and full-width letters decompose to plain letters
+    assert_eq!(normalize_for_match("ﬁnland"), "finland");
+    assert_eq!(normalize_for_match("Ｊａｐａｎ"), "japan");
+    // Whitespace is left alone; `name_key` collapses it
+    assert_eq!(normalize_for_match(" a  b "), " a  b ");
+    assert_eq!(name_key("Ｊａｐａｎ\u{3000}Ｓｅａ"), "japan sea");
+}
+
+proptest! {
+    #[test]
+    fn test_normalize_for_match_is_idempotent(text in "\\PC{0,20}") {
+        let once = normalize_for_match(&text);
+        prop_assert_eq!(normalize_for_match(&once), once.clone());
+        prop_assert_eq!(name_key(&once), name_key(&text));
+    }
+}
@@ -0,0 +1,169 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    middleware,
+    routing::get,
+    Router,
+};
+use country_data_api_rust_kiro::{
+    api::{create_routes, trace::trace_request},
+    services::CountryDataStore,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tower::util::ServiceExt;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{registry, Layer};
+
+type Fields = HashMap<String, String>;
+
+/// Records the fields of every span and event
+#[derive(Clone, Default)]
+struct Capture {
+    spans: Arc<Mutex<HashMap<u64, (String, Fields)>>>,
+    events: Arc<Mutex<Vec<(tracing::Level, Fields)>>>,
+}
+
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Capture {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _: Context<'_, S>) {
+        let mut fields = Fields::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        let name = attrs.metadata().name().to_string();
+        self.spans.lock().unwrap().insert(id.into_u64(), (name, fields));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _: Context<'_, S>) {
+        if let Some((_, fields)) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+            values.record(&mut FieldVisitor(fields));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let mut fields = Fields::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        self.events
+            .lock()
+            .unwrap()
+            .push((*event.metadata().level(), fields));
+    }
+}
+
+impl Capture {
+    fn request_spans(&self) -> Vec<Fields> {
+        self.spans
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|(name, _)| name == "request")
+            .map(|(_, fields)| fields.clone())
+            .collect()
+    }
+}
+
+async fn send(app: Router, request: Request<Body>) -> axum::response::Response {
+    app.oneshot(request).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_request_span_records_fields_and_header() {
+    let capture = Capture::default();
+    let _guard = tracing::subscriber::set_default(registry().with(capture.clone()));
+
+    let response = send(
+        create_routes(CountryDataStore::new()),
+        Request::builder()
+            .uri("/api/countries/Atlantis")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let request_id = response.headers()["x-request-id"].to_str().unwrap();
+    assert_eq!(request_id.len(), 36, "expected a UUID, got {}", request_id);
+
+    let spans = capture.request_spans();
+    assert_eq!(spans.len(), 1);
+    let span = &spans[0];
+    assert_eq!(span["method"], "GET");
+    assert_eq!(span["path"], "/api/countries/Atlantis");
+    assert_eq!(span["request_id"], request_id);
+    assert_eq!(span["status"], "404");
+    assert!(span["latency_ms"].parse::<f64>().unwrap() >= 0.0);
+}
+
+#[tokio::test]
+async fn test_incoming_request_id_is_reused() {
+    let response = send(
+        create_routes(CountryDataStore::new()),
+        Request::builder()
+            .uri("/api/regions")
+            .header("X-Request-Id", "abc-123")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(response.headers()["x-request-id"], "abc-123");
+}
+
+#[tokio::test]
+async fn test_each_request_gets_its_own_id() {
+    let app = create_routes(CountryDataStore::new());
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        let request = Request::builder().uri("/health").body(Body::empty()).unwrap();
+        let response = send(app.clone(), request).await;
+        ids.push(response.headers()["x-request-id"].clone());
+    }
+
+    assert_ne!(ids[0], ids[1]);
+}
+
+#[tokio::test]
+async fn test_server_errors_are_logged_with_body() {
+    let capture = Capture::default();
+    let _guard = tracing::subscriber::set_default(registry().with(capture.clone()));
+    let app = Router::new()
+        .route(
+            "/boom",
+            get(|| async {
+                let body = serde_json::json!({"error": "disk on fire"});
+                (StatusCode::INTERNAL_SERVER_ERROR, axum::Json(body))
+            }),
+        )
+        .layer(middleware::from_fn(trace_request));
+
+    let response = send(app, Request::builder().uri("/boom").body(Body::empty()).unwrap()).await;
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, r#"{"error":"disk on fire"}"#);
+
+    let events = capture.events.lock().unwrap();
+    let (level, fields) = events
+        .iter()
+        .find(|(_, fields)| fields.get("message").is_some_and(|m| m == "request failed"))
+        .expect("a request failed event");
+    assert_eq!(*level, tracing::Level::ERROR);
+    assert_eq!(fields["body"], r#"{"error":"disk on fire"}"#);
+}
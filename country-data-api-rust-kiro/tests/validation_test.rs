@@ -0,0 +1,183 @@
+use country_data_api_rust_kiro::models::validation::{
+    describe_errors, errors_by_field, MAX_NAME_LEN,
+};
+use country_data_api_rust_kiro::models::{Country, Currency, ValidationError};
+
+fn valid_country() -> Country {
+    Country {
+        name: "France".to_string(),
+        capital: "Paris".to_string(),
+        population: 67_000_000,
+        region: "Europe".to_string(),
+        languages: vec!["French".to_string()],
+        alpha2: Some("FR".to_string()),
+        alpha3: Some("FRA".to_string()),
+        currencies: vec![Currency::new("EUR", "Euro", "€")],
+        area_km2: 551_695.0,
+        borders: vec!["ESP".to_string()],
+        flag_url: Some("https://flagcdn.com/fr.svg".to_string()),
+        ..Default::default()
+    }
+}
+
+fn errors_of(country: Country) -> Vec<ValidationError> {
+    country.validate().unwrap_err()
+}
+
+#[test]
+fn test_valid_country_has_no_errors() {
+    assert_eq!(valid_country().validate(), Ok(()));
+    assert!(valid_country().is_valid());
+}
+
+#[test]
+fn test_empty_name() {
+    let errors = errors_of(Country {
+        name: " \t".to_string(),
+        ..valid_country()
+    });
+    assert_eq!(errors, vec![ValidationError::EmptyName]);
+    assert_eq!(errors[0].to_string(), "name: must not be empty");
+}
+
+#[test]
+fn test_name_too_long() {
+    let long = Country {
+        name: "é".repeat(MAX_NAME_LEN + 1),
+        ..valid_country()
+    };
+    assert_eq!(
+        errors_of(long),
+        vec![ValidationError::NameTooLong { len: MAX_NAME_LEN + 1 }]
+    );
+
+    // The limit counts characters, not bytes
+    let at_limit = Country {
+        name: "é".repeat(MAX_NAME_LEN),
+        ..valid_country()
+    };
+    assert!(at_limit.is_valid());
+}
+
+#[test]
+fn test_negative_population() {
+    let errors = errors_of(Country {
+        population: -3,
+        ..valid_country()
+    });
+    assert_eq!(errors, vec![ValidationError::NegativePopulation(-3)]);
+    assert_eq!(errors[0].field(), "population");
+}
+
+#[test]
+fn test_empty_language_reports_its_index() {
+    let errors = errors_of(Country {
+        languages: vec!["French".to_string(), String::new()],
+        ..valid_country()
+    });
+    assert_eq!(errors, vec![ValidationError::EmptyLanguage { index: 1 }]);
+}
+
+#[test]
+fn test_malformed_codes() {
+    let errors = errors_of(Country {
+        alpha2: Some("fr".to_string()),
+        alpha3: Some("FRAN".to_string()),
+        ..valid_country()
+    });
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError::InvalidAlpha2("fr".to_string()),
+            ValidationError::InvalidAlpha3("FRAN".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_area() {
+    for area in [-1.0, f64::NAN, f64::INFINITY] {
+        let errors = errors_of(Country {
+            area_km2: area,
+            ..valid_country()
+        });
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], ValidationError::InvalidArea(_)));
+    }
+}
+
+#[test]
+fn test_invalid_currency_code() {
+    let errors = errors_of(Country {
+        currencies: vec![Currency::new("EUR", "Euro", "€"), Currency::new("eu", "Euro", "€")],
+        ..valid_country()
+    });
+    assert_eq!(
+        errors,
+        vec![ValidationError::InvalidCurrencyCode {
+            index: 1,
+            code: "eu".to_string()
+        }]
+    );
+}
+
+#[test]
+fn test_invalid_flag_url() {
+    let errors = errors_of(Country {
+        flag_url: Some("ftp://flagcdn.com/fr.svg".to_string()),
+        ..valid_country()
+    });
+    assert_eq!(
+        errors,
+        vec![ValidationError::InvalidFlagUrl("ftp://flagcdn.com/fr.svg".to_string())]
+    );
+}
+
+#[test]
+fn test_invalid_border() {
+    let errors = errors_of(Country {
+        borders: vec!["ESP".to_string(), "es".to_string()],
+        ..valid_country()
+    });
+    assert_eq!(
+        errors,
+        vec![ValidationError::InvalidBorder {
+            index: 1,
+            code: "es".to_string()
+        }]
+    );
+}
+
+#[test]
+fn test_multiple_failures_are_reported_together() {
+    let errors = errors_of(Country {
+        name: String::new(),
+        population: -1,
+        languages: vec![String::new(), "French".to_string(), String::new()],
+        alpha3: Some("fr".to_string()),
+        ..valid_country()
+    });
+
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError::EmptyName,
+            ValidationError::NegativePopulation(-1),
+            ValidationError::EmptyLanguage { index: 0 },
+            ValidationError::EmptyLanguage { index: 2 },
+            ValidationError::InvalidAlpha3("fr".to_string()),
+        ]
+    );
+
+    assert_eq!(
+        describe_errors(&errors[..2]),
+        "name: must not be empty; population: must be non-negative, got -1"
+    );
+
+    let fields = errors_by_field(&errors);
+    assert_eq!(
+        fields.keys().collect::<Vec<_>>(),
+        vec!["alpha3", "languages", "name", "population"]
+    );
+    assert_eq!(fields["languages"].len(), 2);
+}
@@ -0,0 +1,80 @@
+use country_data_api_rust_kiro::models::{Country, Currency, PopulationRevision};
+use country_data_api_rust_kiro::services::{countries_to_xml, country_to_xml, error_to_xml};
+use std::collections::BTreeMap;
+
+const DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>";
+
+#[test]
+fn test_country_lists_repeat_one_element_per_entry() {
+    let country = Country {
+        name: "Belgium".to_string(),
+        capital: "Brussels".to_string(),
+        population: 11600000,
+        region: "Europe".to_string(),
+        languages: vec!["Dutch".to_string(), "French".to_string()],
+        currencies: vec![Currency::new("EUR", "Euro", "€")],
+        borders: vec!["FRA".to_string(), "NLD".to_string()],
+        population_history: vec![PopulationRevision {
+            population: 11600000,
+            as_of: "2023-01-01".to_string(),
+        }],
+        ..Default::default()
+    };
+    let xml = country_to_xml(&country);
+
+    assert!(xml.starts_with(&format!("{}<country><name>Belgium</name>", DECLARATION)));
+    for expected in [
+        "<languages><language>Dutch</language><language>French</language></languages>",
+        "<currencies><currency><code>EUR</code><name>Euro</name><symbol>€</symbol></currency>",
+        "<borders><border>FRA</border><border>NLD</border></borders>",
+        "<population_history><revision><population>11600000</population>",
+        "<subregion></subregion>",
+    ] {
+        assert!(xml.contains(expected), "missing {} in {}", expected, xml);
+    }
+    // Unset values are left out rather than written empty
+    assert!(!xml.contains("alpha2") && !xml.contains("population_density"), "{}", xml);
+}
+
+#[test]
+fn test_countries_are_wrapped_and_escaped() {
+    let countries = vec![
+        Country {
+            name: "Bosnia & Herzegovina".to_string(),
+            capital: "<Sarajevo>".to_string(),
+            ..Default::default()
+        },
+        Country::default(),
+    ];
+    let xml = countries_to_xml(&countries);
+
+    assert!(xml.starts_with(&format!("{}<countries><country>", DECLARATION)));
+    assert!(xml.ends_with("</country></countries>"));
+    assert_eq!(xml.matches("<country>").count(), 2);
+    assert!(xml.contains("<name>Bosnia &amp; Herzegovina</name>"));
+    assert!(xml.contains("<capital>&lt;Sarajevo&gt;</capital>"));
+
+    assert_eq!(countries_to_xml::<Country>(&[]), format!("{}<countries></countries>", DECLARATION));
+}
+
+#[test]
+fn test_error_lists_messages_by_field() {
+    assert_eq!(
+        error_to_xml("Country \"Frnace\" not found", Some("France"), None),
+        format!(
+            "{}<error><message>Country &quot;Frnace&quot; not found</message>\
+             <suggestion>France</suggestion></error>",
+            DECLARATION
+        )
+    );
+
+    let fields = BTreeMap::from([(
+        "a\"b".to_string(),
+        vec!["must not be empty".to_string(), "x < y".to_string()],
+    )]);
+    let xml = error_to_xml("invalid", None, Some(&fields));
+    assert!(xml.contains(
+        "<fields><field name=\"a&quot;b\"><message>must not be empty</message>\
+         <message>x &lt; y</message></field></fields>"
+    ));
+}
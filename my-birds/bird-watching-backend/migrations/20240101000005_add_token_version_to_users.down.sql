@@ -0,0 +1,4 @@
+-- Rollback migration: Remove token versioning from users
+
+ALTER TABLE users
+DROP COLUMN IF EXISTS token_version;
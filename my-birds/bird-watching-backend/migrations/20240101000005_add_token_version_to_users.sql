@@ -0,0 +1,9 @@
+-- Add token_version column to users table
+-- Migration: Invalidate previously issued JWTs on password change / logout everywhere
+
+-- Every issued token carries the user's token_version at the time of issue.
+-- Bumping this value makes all older tokens stale.
+ALTER TABLE users
+ADD COLUMN token_version INTEGER NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN users.token_version IS 'Incremented on password change and logout everywhere; JWTs with an older version are rejected';
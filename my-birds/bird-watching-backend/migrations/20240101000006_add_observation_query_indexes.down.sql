@@ -0,0 +1,10 @@
+-- Rollback migration: Restore the original single-column observation indexes
+
+DROP INDEX IF EXISTS idx_observations_shared_date;
+CREATE INDEX idx_observations_is_shared ON observations(is_shared);
+
+DROP INDEX IF EXISTS idx_observations_trip_id_date;
+CREATE INDEX idx_observations_trip_id ON observations(trip_id);
+
+DROP INDEX IF EXISTS idx_observations_user_id_date;
+CREATE INDEX idx_observations_user_id ON observations(user_id);
@@ -0,0 +1,27 @@
+-- Add composite and partial indexes for the hot observation query paths
+-- Migration: Index the filters and orderings used by the observation repository
+
+-- User's observations: WHERE user_id = $1 ORDER BY observation_date DESC
+-- Replaces the single-column user_id index, which is a prefix of this one
+CREATE INDEX idx_observations_user_id_date
+ON observations(user_id, observation_date DESC);
+
+DROP INDEX IF EXISTS idx_observations_user_id;
+
+-- Trip observations: WHERE trip_id = $1 ORDER BY observation_date DESC
+-- Replaces the single-column trip_id index, which is a prefix of this one
+CREATE INDEX idx_observations_trip_id_date
+ON observations(trip_id, observation_date DESC);
+
+DROP INDEX IF EXISTS idx_observations_trip_id;
+
+-- Shared feed: WHERE is_shared = true ORDER BY observation_date DESC
+-- Partial index: only shared rows are indexed, replacing the low-selectivity boolean index
+CREATE INDEX idx_observations_shared_date
+ON observations(observation_date DESC)
+WHERE is_shared = true;
+
+DROP INDEX IF EXISTS idx_observations_is_shared;
+
+-- Proximity search: WHERE latitude IS NOT NULL AND longitude IS NOT NULL
+-- Already covered by the partial idx_observations_coordinates index (see 20240101000004)
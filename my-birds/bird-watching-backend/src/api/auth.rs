@@ -1,78 +1,85 @@
-use crate::middleware::auth::extract_claims;
-use crate::models::user::{LoginRequest, RegisterRequest};
+use crate::middleware::auth::{extract_authenticated_user_id, AuthMiddleware};
+use crate::models::user::{ChangePasswordRequest, LoginRequest, RegisterRequest};
 use crate::services::auth_service::AuthService;
-use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use crate::utils::errors::ServiceError;
+use actix_web::{web, HttpRequest, HttpResponse};
 use sqlx::PgPool;
 
 /// POST /api/auth/register - Register a new user
 pub async fn register(
     pool: web::Data<PgPool>,
     req: web::Json<RegisterRequest>,
-) -> impl Responder {
+) -> Result<HttpResponse, ServiceError> {
     let auth_service = AuthService::new(pool.get_ref().clone());
 
-    match auth_service.register(req.into_inner()).await {
-        Ok(user) => HttpResponse::Created().json(user),
-        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
-            "error": e
-        })),
-    }
+    let user = auth_service.register(req.into_inner()).await?;
+    Ok(HttpResponse::Created().json(user))
 }
 
 /// POST /api/auth/login - Authenticate a user
 pub async fn login(
     pool: web::Data<PgPool>,
     req: web::Json<LoginRequest>,
-) -> impl Responder {
+) -> Result<HttpResponse, ServiceError> {
     let auth_service = AuthService::new(pool.get_ref().clone());
 
-    match auth_service.login(req.into_inner()).await {
-        Ok(response) => HttpResponse::Ok().json(response),
-        Err(e) => HttpResponse::Unauthorized().json(serde_json::json!({
-            "error": e
-        })),
-    }
+    let response = auth_service.login(req.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(response))
 }
 
 /// GET /api/users/me - Get current user profile
 pub async fn get_me(
     pool: web::Data<PgPool>,
     req: HttpRequest,
-) -> impl Responder {
-    let claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => return HttpResponse::Unauthorized().json(serde_json::json!({
-            "error": e.to_string()
-        })),
-    };
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
+    let auth_service = AuthService::new(pool.get_ref().clone());
 
-    let user_id = match uuid::Uuid::parse_str(&claims.sub) {
-        Ok(id) => id,
-        Err(e) => return HttpResponse::BadRequest().json(serde_json::json!({
-            "error": e.to_string()
-        })),
-    };
+    let user = auth_service.get_user_profile(user_id).await?;
+    Ok(HttpResponse::Ok().json(user))
+}
 
+/// PUT /api/users/me/password - Change the current user's password
+pub async fn change_password(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    body: web::Json<ChangePasswordRequest>,
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
     let auth_service = AuthService::new(pool.get_ref().clone());
 
-    match auth_service.get_user_profile(user_id).await {
-        Ok(user) => HttpResponse::Ok().json(user),
-        Err(e) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": e
-        })),
-    }
+    let response = auth_service.change_password(user_id, body.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// POST /api/auth/logout-all - Revoke every token issued to the current user
+pub async fn logout_all(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
+    let auth_service = AuthService::new(pool.get_ref().clone());
+
+    auth_service.logout_all(user_id).await?;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 /// Configure authentication routes
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/auth")
-            .route("/register", web::post().to(register))
-            .route("/login", web::post().to(login))
+            .service(web::resource("/register").route(web::post().to(register)))
+            .service(web::resource("/login").route(web::post().to(login)))
+            .service(
+                web::resource("/logout-all")
+                    .wrap(AuthMiddleware)
+                    .route(web::post().to(logout_all)),
+            )
     )
     .service(
         web::scope("/api/users")
+            .wrap(AuthMiddleware)
             .route("/me", web::get().to(get_me))
+            .route("/me/password", web::put().to(change_password))
     );
 }
-
@@ -3,3 +3,18 @@ pub mod auth;
 pub mod observations;
 pub mod trips;
 pub mod photos;
+
+use crate::utils::errors;
+use actix_web::web;
+
+/// Configure all API routes, extractor error handlers and the unknown-route fallback
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(errors::json_config())
+        .app_data(errors::query_config())
+        .app_data(errors::path_config())
+        .configure(auth::configure)
+        .configure(observations::configure)
+        .configure(photos::configure)
+        .configure(trips::configure)
+        .default_service(web::route().to(errors::route_not_found));
+}
@@ -1,9 +1,14 @@
-use crate::middleware::auth::extract_claims;
-use crate::models::observation::{CreateObservationRequest, UpdateObservationRequest};
+use crate::middleware::auth::{extract_authenticated_user_id, extract_claims, AuthMiddleware};
+use crate::models::observation::{
+    CreateObservationRequest, Observation, UpdateObservationRequest,
+};
 use crate::services::observation_service::ObservationService;
-use crate::utils::jwt::extract_user_id;
-use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use crate::services::photo_service::PhotoService;
+use crate::utils::errors::{ErrorCode, ServiceError};
+use actix_multipart::Multipart;
+use actix_web::{web, HttpRequest, HttpResponse};
 use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
 use serde::Deserialize;
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -13,63 +18,122 @@ pub async fn create_observation(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     body: web::Json<CreateObservationRequest>,
-) -> impl Responder {
-    let claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
-    let user_id = match extract_user_id(&claims) {
-        Ok(id) => id,
-        Err(e) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
     let observation_service = ObservationService::new(pool.get_ref().clone());
 
-    match observation_service.create(user_id, body.into_inner()).await {
-        Ok(observation) => HttpResponse::Created().json(observation),
-        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
-            "error": e
-        })),
-    }
+    let observation = observation_service.create(user_id, body.into_inner()).await?;
+    Ok(HttpResponse::Created().json(observation))
 }
 
-/// GET /api/observations - Get user's observations
-pub async fn get_observations(pool: web::Data<PgPool>, req: HttpRequest) -> impl Responder {
-    let claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
+/// Largest accepted `metadata` part, in bytes
+const MAX_METADATA_SIZE: usize = 64 * 1024;
+
+/// POST /api/observations/with-photo - Create an observation and upload its photo in one request
+///
+/// Expects multipart/form-data with a `metadata` part (CreateObservationRequest as JSON)
+/// followed by a `photo` file part. The metadata is validated before the photo is stored,
+/// and the stored photo is removed again if a later part or the insert fails.
+pub async fn create_observation_with_photo(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    mut payload: Multipart,
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
+    let observation_service = ObservationService::new(pool.get_ref().clone());
+    let photo_service = PhotoService::new("./uploads");
+
+    let mut photo_url: Option<String> = None;
+    let result = create_from_parts(
+        &observation_service,
+        &photo_service,
+        user_id,
+        &mut payload,
+        &mut photo_url,
+    )
+    .await;
+
+    if result.is_err() {
+        // Whichever step failed, don't leave an orphaned file behind
+        if let Some(photo_url) = photo_url {
+            if let Err(delete_err) = photo_service.delete_photo(&photo_url) {
+                log::error!("Failed to remove photo {}: {}", photo_url, delete_err);
+            }
         }
-    };
-
-    let user_id = match extract_user_id(&claims) {
-        Ok(id) => id,
-        Err(e) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": e.to_string()
-            }))
+    }
+
+    Ok(HttpResponse::Created().json(result?))
+}
+
+/// Read the metadata and photo parts and create the observation.
+/// `photo_url` is set as soon as the photo is stored, so the caller can remove it
+/// if anything after that fails.
+async fn create_from_parts(
+    observation_service: &ObservationService,
+    photo_service: &PhotoService,
+    user_id: Uuid,
+    payload: &mut Multipart,
+    photo_url: &mut Option<String>,
+) -> Result<Observation, ServiceError> {
+    let mut metadata: Option<CreateObservationRequest> = None;
+
+    while let Some(item) = payload.next().await {
+        let mut field = item.map_err(|e| invalid_upload(e.to_string()))?;
+        let name = field.content_disposition().get_name().unwrap_or_default().to_string();
+
+        match name.as_str() {
+            "metadata" => {
+                let mut bytes = Vec::new();
+                while let Some(chunk) = field.next().await {
+                    let data = chunk.map_err(|e| invalid_upload(e.to_string()))?;
+                    if bytes.len() + data.len() > MAX_METADATA_SIZE {
+                        return Err(invalid_upload("Metadata part is too large"));
+                    }
+                    bytes.extend_from_slice(&data);
+                }
+
+                let request: CreateObservationRequest = serde_json::from_slice(&bytes)
+                    .map_err(|e| ServiceError::new(ErrorCode::MalformedJson, e.to_string()))?;
+                ObservationService::validate_create(&request)?;
+                metadata = Some(request);
+            }
+            "photo" => {
+                if metadata.is_none() {
+                    return Err(invalid_upload("The metadata part must come before the photo part"));
+                }
+                if photo_url.is_some() {
+                    return Err(invalid_upload("Only one photo part is allowed"));
+                }
+                *photo_url = Some(photo_service.save_photo(field).await?);
+            }
+            other => {
+                return Err(invalid_upload(format!("Unexpected multipart field: {:?}", other)));
+            }
         }
-    };
+    }
+
+    let mut request = metadata.ok_or_else(|| invalid_upload("Missing metadata part"))?;
+    request.photo_url = Some(
+        photo_url.clone().ok_or_else(|| invalid_upload("Missing photo part"))?,
+    );
+
+    observation_service.create(user_id, request).await
+}
 
+fn invalid_upload(message: impl Into<String>) -> ServiceError {
+    ServiceError::new(ErrorCode::InvalidUpload, message)
+}
+
+/// GET /api/observations - Get user's observations
+pub async fn get_observations(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
     let observation_service = ObservationService::new(pool.get_ref().clone());
 
-    match observation_service.get_user_observations(user_id).await {
-        Ok(observations) => HttpResponse::Ok().json(observations),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e
-        })),
-    }
+    let observations = observation_service.get_user_observations(user_id).await?;
+    Ok(HttpResponse::Ok().json(observations))
 }
 
 /// GET /api/observations/:id - Get a specific observation
@@ -77,25 +141,14 @@ pub async fn get_observation(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     path: web::Path<Uuid>,
-) -> impl Responder {
-    let _claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
+) -> Result<HttpResponse, ServiceError> {
+    extract_claims(&req)?;
 
     let observation_id = path.into_inner();
     let observation_service = ObservationService::new(pool.get_ref().clone());
 
-    match observation_service.get_by_id(observation_id).await {
-        Ok(observation) => HttpResponse::Ok().json(observation),
-        Err(e) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": e
-        })),
-    }
+    let observation = observation_service.get_by_id(observation_id).await?;
+    Ok(HttpResponse::Ok().json(observation))
 }
 
 /// PUT /api/observations/:id - Update an observation
@@ -104,45 +157,16 @@ pub async fn update_observation(
     req: HttpRequest,
     path: web::Path<Uuid>,
     body: web::Json<UpdateObservationRequest>,
-) -> impl Responder {
-    let claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
-    let user_id = match extract_user_id(&claims) {
-        Ok(id) => id,
-        Err(e) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
 
     let observation_id = path.into_inner();
     let observation_service = ObservationService::new(pool.get_ref().clone());
 
-    match observation_service
+    let observation = observation_service
         .update(observation_id, user_id, body.into_inner())
-        .await
-    {
-        Ok(observation) => HttpResponse::Ok().json(observation),
-        Err(e) => {
-            if e.contains("Unauthorized") {
-                HttpResponse::Forbidden().json(serde_json::json!({
-                    "error": e
-                }))
-            } else {
-                HttpResponse::BadRequest().json(serde_json::json!({
-                    "error": e
-                }))
-            }
-        }
-    }
+        .await?;
+    Ok(HttpResponse::Ok().json(observation))
 }
 
 /// DELETE /api/observations/:id - Delete an observation
@@ -150,63 +174,27 @@ pub async fn delete_observation(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     path: web::Path<Uuid>,
-) -> impl Responder {
-    let claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
-    let user_id = match extract_user_id(&claims) {
-        Ok(id) => id,
-        Err(e) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
 
     let observation_id = path.into_inner();
     let observation_service = ObservationService::new(pool.get_ref().clone());
 
-    match observation_service.delete(observation_id, user_id).await {
-        Ok(_) => HttpResponse::NoContent().finish(),
-        Err(e) => {
-            if e.contains("Unauthorized") {
-                HttpResponse::Forbidden().json(serde_json::json!({
-                    "error": e
-                }))
-            } else {
-                HttpResponse::NotFound().json(serde_json::json!({
-                    "error": e
-                }))
-            }
-        }
-    }
+    observation_service.delete(observation_id, user_id).await?;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 /// GET /api/observations/shared - Get all shared observations
-pub async fn get_shared_observations(pool: web::Data<PgPool>, req: HttpRequest) -> impl Responder {
-    let _claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
+pub async fn get_shared_observations(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ServiceError> {
+    extract_claims(&req)?;
 
     let observation_service = ObservationService::new(pool.get_ref().clone());
 
-    match observation_service.get_shared_observations().await {
-        Ok(observations) => HttpResponse::Ok().json(observations),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e
-        })),
-    }
+    let observations = observation_service.get_shared_observations().await?;
+    Ok(HttpResponse::Ok().json(observations))
 }
 
 #[derive(Deserialize)]
@@ -222,28 +210,11 @@ pub async fn search_observations(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     query: web::Query<SearchQuery>,
-) -> impl Responder {
-    let claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
-    let user_id = match extract_user_id(&claims) {
-        Ok(id) => id,
-        Err(e) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
     let observation_service = ObservationService::new(pool.get_ref().clone());
 
-    match observation_service
+    let observations = observation_service
         .search(
             user_id,
             query.species.clone(),
@@ -251,13 +222,8 @@ pub async fn search_observations(
             query.start_date,
             query.end_date,
         )
-        .await
-    {
-        Ok(observations) => HttpResponse::Ok().json(observations),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e
-        })),
-    }
+        .await?;
+    Ok(HttpResponse::Ok().json(observations))
 }
 
 #[derive(Deserialize)]
@@ -274,19 +240,12 @@ pub async fn get_nearby_observations(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     query: web::Query<NearbyQuery>,
-) -> impl Responder {
-    let _claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
+) -> Result<HttpResponse, ServiceError> {
+    extract_claims(&req)?;
 
     let observation_service = ObservationService::new(pool.get_ref().clone());
 
-    match observation_service
+    let observations = observation_service
         .find_nearby(
             query.lat,
             query.lng,
@@ -294,21 +253,18 @@ pub async fn get_nearby_observations(
             query.user_id,
             query.species.clone(),
         )
-        .await
-    {
-        Ok(observations) => HttpResponse::Ok().json(observations),
-        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
-            "error": e
-        })),
-    }
+        .await?;
+    Ok(HttpResponse::Ok().json(observations))
 }
 
 /// Configure observation routes
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/observations")
+            .wrap(AuthMiddleware)
             .route("", web::post().to(create_observation))
             .route("", web::get().to(get_observations))
+            .route("/with-photo", web::post().to(create_observation_with_photo))
             .route("/shared", web::get().to(get_shared_observations))
             .route("/search", web::get().to(search_observations))
             .route("/nearby", web::get().to(get_nearby_observations))
@@ -1,8 +1,8 @@
-use crate::middleware::auth::extract_claims;
+use crate::middleware::auth::{extract_authenticated_user_id, extract_claims, AuthMiddleware};
 use crate::models::trip::{CreateTripRequest, UpdateTripRequest};
 use crate::services::trip_service::TripService;
-use crate::utils::jwt::extract_user_id;
-use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use crate::utils::errors::ServiceError;
+use actix_web::{web, HttpRequest, HttpResponse};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -11,63 +11,24 @@ pub async fn create_trip(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     body: web::Json<CreateTripRequest>,
-) -> impl Responder {
-    let claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
-    let user_id = match extract_user_id(&claims) {
-        Ok(id) => id,
-        Err(e) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
     let trip_service = TripService::new(pool.get_ref().clone());
 
-    match trip_service.create(user_id, body.into_inner()).await {
-        Ok(trip) => HttpResponse::Created().json(trip),
-        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
-            "error": e
-        })),
-    }
+    let trip = trip_service.create(user_id, body.into_inner()).await?;
+    Ok(HttpResponse::Created().json(trip))
 }
 
 /// GET /api/trips - Get user's trips
-pub async fn get_trips(pool: web::Data<PgPool>, req: HttpRequest) -> impl Responder {
-    let claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
-    let user_id = match extract_user_id(&claims) {
-        Ok(id) => id,
-        Err(e) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
+pub async fn get_trips(
+    pool: web::Data<PgPool>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
     let trip_service = TripService::new(pool.get_ref().clone());
 
-    match trip_service.get_user_trips(user_id).await {
-        Ok(trips) => HttpResponse::Ok().json(trips),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e
-        })),
-    }
+    let trips = trip_service.get_user_trips(user_id).await?;
+    Ok(HttpResponse::Ok().json(trips))
 }
 
 /// GET /api/trips/:id - Get a specific trip with observations
@@ -75,28 +36,17 @@ pub async fn get_trip(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     path: web::Path<Uuid>,
-) -> impl Responder {
-    let _claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
+) -> Result<HttpResponse, ServiceError> {
+    extract_claims(&req)?;
 
     let trip_id = path.into_inner();
     let trip_service = TripService::new(pool.get_ref().clone());
 
-    match trip_service.get_trip_with_observations(trip_id).await {
-        Ok((trip, observations)) => HttpResponse::Ok().json(serde_json::json!({
-            "trip": trip,
-            "observations": observations
-        })),
-        Err(e) => HttpResponse::NotFound().json(serde_json::json!({
-            "error": e
-        })),
-    }
+    let (trip, observations) = trip_service.get_trip_with_observations(trip_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "trip": trip,
+        "observations": observations
+    })))
 }
 
 /// PUT /api/trips/:id - Update a trip
@@ -105,45 +55,16 @@ pub async fn update_trip(
     req: HttpRequest,
     path: web::Path<Uuid>,
     body: web::Json<UpdateTripRequest>,
-) -> impl Responder {
-    let claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
-    let user_id = match extract_user_id(&claims) {
-        Ok(id) => id,
-        Err(e) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
 
     let trip_id = path.into_inner();
     let trip_service = TripService::new(pool.get_ref().clone());
 
-    match trip_service
+    let trip = trip_service
         .update(trip_id, user_id, body.into_inner())
-        .await
-    {
-        Ok(trip) => HttpResponse::Ok().json(trip),
-        Err(e) => {
-            if e.contains("Unauthorized") {
-                HttpResponse::Forbidden().json(serde_json::json!({
-                    "error": e
-                }))
-            } else {
-                HttpResponse::BadRequest().json(serde_json::json!({
-                    "error": e
-                }))
-            }
-        }
-    }
+        .await?;
+    Ok(HttpResponse::Ok().json(trip))
 }
 
 /// DELETE /api/trips/:id - Delete a trip
@@ -151,48 +72,21 @@ pub async fn delete_trip(
     pool: web::Data<PgPool>,
     req: HttpRequest,
     path: web::Path<Uuid>,
-) -> impl Responder {
-    let claims = match extract_claims(&req) {
-        Ok(claims) => claims,
-        Err(e) => {
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
-
-    let user_id = match extract_user_id(&claims) {
-        Ok(id) => id,
-        Err(e) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": e.to_string()
-            }))
-        }
-    };
+) -> Result<HttpResponse, ServiceError> {
+    let user_id = extract_authenticated_user_id(&req)?;
 
     let trip_id = path.into_inner();
     let trip_service = TripService::new(pool.get_ref().clone());
 
-    match trip_service.delete(trip_id, user_id).await {
-        Ok(_) => HttpResponse::NoContent().finish(),
-        Err(e) => {
-            if e.contains("Unauthorized") {
-                HttpResponse::Forbidden().json(serde_json::json!({
-                    "error": e
-                }))
-            } else {
-                HttpResponse::NotFound().json(serde_json::json!({
-                    "error": e
-                }))
-            }
-        }
-    }
+    trip_service.delete(trip_id, user_id).await?;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 /// Configure trip routes
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/trips")
+            .wrap(AuthMiddleware)
             .route("", web::post().to(create_trip))
             .route("", web::get().to(get_trips))
             .route("/{id}", web::get().to(get_trip))
@@ -1,10 +1,5 @@
-mod models;
-mod api;
-mod services;
-mod repositories;
-mod middleware;
-mod utils;
-
+use bird_watching_backend::api;
+use bird_watching_backend::utils::errors;
 use actix_web::{web, App, HttpServer};
 use dotenv::dotenv;
 use sqlx::postgres::PgPoolOptions;
@@ -39,10 +34,8 @@ async fn main() -> std::io::Result<()> {
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(pool.clone()))
-            .configure(api::auth::configure)
-            .configure(api::observations::configure)
-            .configure(api::photos::configure)
-            .configure(api::trips::configure)
+            .wrap(errors::error_handlers())
+            .configure(api::configure)
     })
     .bind(("127.0.0.1", 8080))?
     .run()
@@ -1,18 +1,22 @@
-use crate::utils::jwt::{validate_token, Claims};
+use crate::services::auth_service::AuthService;
+use crate::utils::errors::ServiceError;
+use crate::utils::jwt::{extract_user_id, validate_token, Claims};
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage,
+    web, Error, HttpMessage,
 };
+use sqlx::PgPool;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::rc::Rc;
 use std::future::{ready, Ready};
+use uuid::Uuid;
 
 /// Middleware for JWT authentication
 pub struct AuthMiddleware;
 
 impl<S, B> Transform<S, ServiceRequest> for AuthMiddleware
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -23,17 +27,19 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(AuthMiddlewareService { service }))
+        ready(Ok(AuthMiddlewareService {
+            service: Rc::new(service),
+        }))
     }
 }
 
 pub struct AuthMiddlewareService<S> {
-    service: S,
+    service: Rc<S>,
 }
 
 impl<S, B> Service<ServiceRequest> for AuthMiddlewareService<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -52,22 +58,23 @@ where
                     Ok(s) => s,
                     Err(_) => {
                         return Box::pin(async {
-                            Err(actix_web::error::ErrorUnauthorized("Invalid authorization header"))
+                            Err(ServiceError::unauthorized("Invalid authorization header").into())
                         });
                     }
                 };
 
-                if auth_str.starts_with("Bearer ") {
-                    &auth_str[7..]
-                } else {
-                    return Box::pin(async {
-                        Err(actix_web::error::ErrorUnauthorized("Invalid authorization format"))
-                    });
+                match auth_str.strip_prefix("Bearer ") {
+                    Some(token) => token,
+                    None => {
+                        return Box::pin(async {
+                            Err(ServiceError::unauthorized("Invalid authorization format").into())
+                        });
+                    }
                 }
             }
             None => {
                 return Box::pin(async {
-                    Err(actix_web::error::ErrorUnauthorized("Missing authorization header"))
+                    Err(ServiceError::unauthorized("Missing authorization header").into())
                 });
             }
         };
@@ -76,27 +83,52 @@ where
             Ok(claims) => claims,
             Err(_) => {
                 return Box::pin(async {
-                    Err(actix_web::error::ErrorUnauthorized("Invalid or expired token"))
+                    Err(ServiceError::unauthorized("Invalid or expired token").into())
                 });
             }
         };
 
-        // Insert claims into request extensions for later use
-        req.extensions_mut().insert(claims);
+        let pool = match req.app_data::<web::Data<PgPool>>() {
+            Some(pool) => pool.get_ref().clone(),
+            None => {
+                return Box::pin(async {
+                    Err(ServiceError::internal("Database pool not configured").into())
+                });
+            }
+        };
 
-        let fut = self.service.call(req);
+        let service = Rc::clone(&self.service);
         Box::pin(async move {
-            let res = fut.await?;
+            // Reject tokens issued before the user's last password change / logout everywhere
+            let user_id = extract_user_id(&claims)
+                .map_err(|_| ServiceError::unauthorized("Invalid or expired token"))?;
+            let is_current = AuthService::new(pool)
+                .is_token_version_current(user_id, claims.ver)
+                .await?;
+
+            if !is_current {
+                return Err(ServiceError::unauthorized("Token has been revoked").into());
+            }
+
+            // Insert claims into request extensions for later use
+            req.extensions_mut().insert(claims);
+
+            let res = service.call(req).await?;
             Ok(res)
         })
     }
 }
 
 /// Extract claims from request extensions
-pub fn extract_claims(req: &actix_web::HttpRequest) -> Result<Claims, actix_web::Error> {
+pub fn extract_claims(req: &actix_web::HttpRequest) -> Result<Claims, ServiceError> {
     req.extensions()
         .get::<Claims>()
         .cloned()
-        .ok_or_else(|| actix_web::error::ErrorUnauthorized("No authentication claims found"))
+        .ok_or_else(|| ServiceError::unauthorized("No authentication claims found"))
 }
 
+/// Extract the authenticated user's ID from request extensions
+pub fn extract_authenticated_user_id(req: &actix_web::HttpRequest) -> Result<Uuid, ServiceError> {
+    let claims = extract_claims(req)?;
+    extract_user_id(&claims).map_err(|_| ServiceError::unauthorized("Invalid token subject"))
+}
@@ -11,6 +11,8 @@ pub struct User {
     pub email: String,
     #[serde(skip_serializing)]
     pub password_hash: String,
+    #[serde(skip_serializing)]
+    pub token_version: i32,
     pub created_at: DateTime<Utc>,
 }
 
@@ -49,6 +51,13 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+/// Request payload for changing the current user's password
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
 /// Response payload for successful login
 #[derive(Debug, Serialize)]
 pub struct LoginResponse {
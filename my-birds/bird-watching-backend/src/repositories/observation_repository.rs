@@ -1,6 +1,6 @@
 use crate::models::observation::{Observation, ObservationWithUser};
 use chrono::{DateTime, Utc};
-use sqlx::{PgPool, Result};
+use sqlx::{PgPool, Postgres, QueryBuilder, Result};
 use uuid::Uuid;
 
 /// Repository for observation database operations
@@ -19,6 +19,7 @@ impl ObservationRepository {
     }
 
     /// Create a new observation
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         &self,
         user_id: Uuid,
@@ -32,23 +33,24 @@ impl ObservationRepository {
         trip_id: Option<Uuid>,
         is_shared: bool,
     ) -> Result<Observation> {
-        let observation = sqlx::query_as::<_, Observation>(
+        let observation = sqlx::query_as!(
+            Observation,
             r#"
             INSERT INTO observations (user_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, trip_id, is_shared)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            RETURNING id, user_id, trip_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, is_shared, created_at, updated_at
+            RETURNING id, user_id, trip_id, species_name, observation_date, location, latitude as "latitude?", longitude as "longitude?", notes, photo_url, is_shared as "is_shared!", created_at as "created_at!", updated_at as "updated_at!"
             "#,
+            user_id,
+            species_name,
+            observation_date,
+            location,
+            latitude,
+            longitude,
+            notes,
+            photo_url,
+            trip_id,
+            is_shared,
         )
-        .bind(user_id)
-        .bind(species_name)
-        .bind(observation_date)
-        .bind(location)
-        .bind(latitude)
-        .bind(longitude)
-        .bind(notes)
-        .bind(photo_url)
-        .bind(trip_id)
-        .bind(is_shared)
         .fetch_one(&self.pool)
         .await?;
 
@@ -57,14 +59,15 @@ impl ObservationRepository {
 
     /// Find an observation by ID
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Observation>> {
-        let observation = sqlx::query_as::<_, Observation>(
+        let observation = sqlx::query_as!(
+            Observation,
             r#"
-            SELECT id, user_id, trip_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, is_shared, created_at, updated_at
+            SELECT id, user_id, trip_id, species_name, observation_date, location, latitude as "latitude?", longitude as "longitude?", notes, photo_url, is_shared as "is_shared!", created_at as "created_at!", updated_at as "updated_at!"
             FROM observations
             WHERE id = $1
             "#,
+            id,
         )
-        .bind(id)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -73,15 +76,34 @@ impl ObservationRepository {
 
     /// Find all observations for a user
     pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<Observation>> {
-        let observations = sqlx::query_as::<_, Observation>(
+        let observations = sqlx::query_as!(
+            Observation,
             r#"
-            SELECT id, user_id, trip_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, is_shared, created_at, updated_at
+            SELECT id, user_id, trip_id, species_name, observation_date, location, latitude as "latitude?", longitude as "longitude?", notes, photo_url, is_shared as "is_shared!", created_at as "created_at!", updated_at as "updated_at!"
             FROM observations
             WHERE user_id = $1
             ORDER BY observation_date DESC
             "#,
+            user_id,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(observations)
+    }
+
+    /// Find all observations belonging to a trip
+    pub async fn find_by_trip(&self, trip_id: Uuid) -> Result<Vec<Observation>> {
+        let observations = sqlx::query_as!(
+            Observation,
+            r#"
+            SELECT id, user_id, trip_id, species_name, observation_date, location, latitude as "latitude?", longitude as "longitude?", notes, photo_url, is_shared as "is_shared!", created_at as "created_at!", updated_at as "updated_at!"
+            FROM observations
+            WHERE trip_id = $1
+            ORDER BY observation_date DESC
+            "#,
+            trip_id,
         )
-        .bind(user_id)
         .fetch_all(&self.pool)
         .await?;
 
@@ -90,12 +112,13 @@ impl ObservationRepository {
 
     /// Find all shared observations with username
     pub async fn find_shared(&self) -> Result<Vec<ObservationWithUser>> {
-        let observations = sqlx::query_as::<_, ObservationWithUser>(
+        let observations = sqlx::query_as!(
+            ObservationWithUser,
             r#"
             SELECT 
                 o.id, o.user_id, u.username, o.trip_id, o.species_name, 
-                o.observation_date, o.location, o.latitude, o.longitude, o.notes, o.photo_url, 
-                o.is_shared, o.created_at, o.updated_at
+                o.observation_date, o.location, o.latitude as "latitude?", o.longitude as "longitude?", o.notes, o.photo_url, 
+                o.is_shared as "is_shared!", o.created_at as "created_at!", o.updated_at as "updated_at!"
             FROM observations o
             JOIN users u ON o.user_id = u.id
             WHERE o.is_shared = true
@@ -109,6 +132,7 @@ impl ObservationRepository {
     }
 
     /// Update an observation
+    #[allow(clippy::too_many_arguments)]
     pub async fn update(
         &self,
         id: Uuid,
@@ -123,87 +147,50 @@ impl ObservationRepository {
         is_shared: Option<bool>,
     ) -> Result<Observation> {
         // Build dynamic update query
-        let mut query = String::from("UPDATE observations SET updated_at = NOW()");
-        let mut param_count = 1;
-
-        if species_name.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", species_name = ${}", param_count));
-        }
-        if observation_date.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", observation_date = ${}", param_count));
-        }
-        if location.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", location = ${}", param_count));
-        }
-        if latitude.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", latitude = ${}", param_count));
-        }
-        if longitude.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", longitude = ${}", param_count));
-        }
-        if notes.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", notes = ${}", param_count));
-        }
-        if photo_url.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", photo_url = ${}", param_count));
-        }
-        if trip_id.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", trip_id = ${}", param_count));
-        }
-        if is_shared.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", is_shared = ${}", param_count));
-        }
-
-        query.push_str(" WHERE id = $1 RETURNING id, user_id, trip_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, is_shared, created_at, updated_at");
-
-        let mut query_builder = sqlx::query_as::<_, Observation>(&query).bind(id);
+        let mut query = QueryBuilder::<Postgres>::new("UPDATE observations SET updated_at = NOW()");
 
         if let Some(val) = species_name {
-            query_builder = query_builder.bind(val);
+            query.push(", species_name = ").push_bind(val);
         }
         if let Some(val) = observation_date {
-            query_builder = query_builder.bind(val);
+            query.push(", observation_date = ").push_bind(val);
         }
         if let Some(val) = location {
-            query_builder = query_builder.bind(val);
+            query.push(", location = ").push_bind(val);
         }
         if let Some(val) = latitude {
-            query_builder = query_builder.bind(val);
+            query.push(", latitude = ").push_bind(val);
         }
         if let Some(val) = longitude {
-            query_builder = query_builder.bind(val);
+            query.push(", longitude = ").push_bind(val);
         }
         if let Some(val) = notes {
-            query_builder = query_builder.bind(val);
+            query.push(", notes = ").push_bind(val);
         }
         if let Some(val) = photo_url {
-            query_builder = query_builder.bind(val);
+            query.push(", photo_url = ").push_bind(val);
         }
         if let Some(val) = trip_id {
-            query_builder = query_builder.bind(val);
+            query.push(", trip_id = ").push_bind(val);
         }
         if let Some(val) = is_shared {
-            query_builder = query_builder.bind(val);
+            query.push(", is_shared = ").push_bind(val);
         }
 
-        let observation = query_builder.fetch_one(&self.pool).await?;
+        query.push(" WHERE id = ").push_bind(id);
+        query.push(" RETURNING id, user_id, trip_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, is_shared, created_at, updated_at");
+
+        let observation = query
+            .build_query_as::<Observation>()
+            .fetch_one(&self.pool)
+            .await?;
 
         Ok(observation)
     }
 
     /// Delete an observation
     pub async fn delete(&self, id: Uuid) -> Result<bool> {
-        let result = sqlx::query("DELETE FROM observations WHERE id = $1")
-            .bind(id)
+        let result = sqlx::query!("DELETE FROM observations WHERE id = $1", id)
             .execute(&self.pool)
             .await?;
 
@@ -219,46 +206,36 @@ impl ObservationRepository {
         start_date: Option<DateTime<Utc>>,
         end_date: Option<DateTime<Utc>>,
     ) -> Result<Vec<Observation>> {
-        let mut query = String::from(
-            "SELECT id, user_id, trip_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, is_shared, created_at, updated_at FROM observations WHERE user_id = $1"
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, user_id, trip_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, is_shared, created_at, updated_at FROM observations WHERE user_id = "
         );
-        let mut param_count = 1;
-
-        if species_name.is_some() {
-            param_count += 1;
-            query.push_str(&format!(" AND LOWER(species_name) LIKE LOWER(${})", param_count));
-        }
-        if location.is_some() {
-            param_count += 1;
-            query.push_str(&format!(" AND LOWER(location) LIKE LOWER(${})", param_count));
-        }
-        if start_date.is_some() {
-            param_count += 1;
-            query.push_str(&format!(" AND observation_date >= ${}", param_count));
-        }
-        if end_date.is_some() {
-            param_count += 1;
-            query.push_str(&format!(" AND observation_date <= ${}", param_count));
-        }
-
-        query.push_str(" ORDER BY observation_date DESC");
-
-        let mut query_builder = sqlx::query_as::<_, Observation>(&query).bind(user_id);
+        query.push_bind(user_id);
 
         if let Some(val) = species_name {
-            query_builder = query_builder.bind(format!("%{}%", val));
+            query
+                .push(" AND LOWER(species_name) LIKE LOWER(")
+                .push_bind(format!("%{}%", val))
+                .push(")");
         }
         if let Some(val) = location {
-            query_builder = query_builder.bind(format!("%{}%", val));
+            query
+                .push(" AND LOWER(location) LIKE LOWER(")
+                .push_bind(format!("%{}%", val))
+                .push(")");
         }
         if let Some(val) = start_date {
-            query_builder = query_builder.bind(val);
+            query.push(" AND observation_date >= ").push_bind(val);
         }
         if let Some(val) = end_date {
-            query_builder = query_builder.bind(val);
+            query.push(" AND observation_date <= ").push_bind(val);
         }
 
-        let observations = query_builder.fetch_all(&self.pool).await?;
+        query.push(" ORDER BY observation_date DESC");
+
+        let observations = query
+            .build_query_as::<Observation>()
+            .fetch_all(&self.pool)
+            .await?;
 
         Ok(observations)
     }
@@ -274,48 +251,62 @@ impl ObservationRepository {
     ) -> Result<Vec<Observation>> {
         use crate::services::geo_service::GeoService;
 
-        // Build query based on filters
-        let query = match (user_id, species_name) {
-            (Some(_), Some(_)) => {
-                "SELECT id, user_id, trip_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, is_shared, created_at, updated_at 
-                 FROM observations 
-                 WHERE latitude IS NOT NULL AND longitude IS NOT NULL AND user_id = $1 AND LOWER(species_name) LIKE LOWER($2)"
-            }
-            (Some(_), None) => {
-                "SELECT id, user_id, trip_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, is_shared, created_at, updated_at 
-                 FROM observations 
-                 WHERE latitude IS NOT NULL AND longitude IS NOT NULL AND user_id = $1"
-            }
-            (None, Some(_)) => {
-                "SELECT id, user_id, trip_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, is_shared, created_at, updated_at 
-                 FROM observations 
-                 WHERE latitude IS NOT NULL AND longitude IS NOT NULL AND LOWER(species_name) LIKE LOWER($1)"
-            }
-            (None, None) => {
-                "SELECT id, user_id, trip_id, species_name, observation_date, location, latitude, longitude, notes, photo_url, is_shared, created_at, updated_at 
-                 FROM observations 
-                 WHERE latitude IS NOT NULL AND longitude IS NOT NULL"
-            }
-        };
-
-        let mut query_builder = sqlx::query_as::<_, Observation>(query);
-
-        // Bind parameters based on what's provided
-        query_builder = match (user_id, species_name) {
+        // Query based on filters
+        let all_observations = match (user_id, species_name) {
             (Some(uid), Some(species)) => {
-                query_builder.bind(uid).bind(format!("%{}%", species))
+                sqlx::query_as!(
+                    Observation,
+                    r#"
+                    SELECT id, user_id, trip_id, species_name, observation_date, location, latitude as "latitude?", longitude as "longitude?", notes, photo_url, is_shared as "is_shared!", created_at as "created_at!", updated_at as "updated_at!"
+                    FROM observations
+                    WHERE latitude IS NOT NULL AND longitude IS NOT NULL AND user_id = $1 AND LOWER(species_name) LIKE LOWER($2)
+                    "#,
+                    uid,
+                    format!("%{}%", species),
+                )
+                .fetch_all(&self.pool)
+                .await?
             }
             (Some(uid), None) => {
-                query_builder.bind(uid)
+                sqlx::query_as!(
+                    Observation,
+                    r#"
+                    SELECT id, user_id, trip_id, species_name, observation_date, location, latitude as "latitude?", longitude as "longitude?", notes, photo_url, is_shared as "is_shared!", created_at as "created_at!", updated_at as "updated_at!"
+                    FROM observations
+                    WHERE latitude IS NOT NULL AND longitude IS NOT NULL AND user_id = $1
+                    "#,
+                    uid,
+                )
+                .fetch_all(&self.pool)
+                .await?
             }
             (None, Some(species)) => {
-                query_builder.bind(format!("%{}%", species))
+                sqlx::query_as!(
+                    Observation,
+                    r#"
+                    SELECT id, user_id, trip_id, species_name, observation_date, location, latitude as "latitude?", longitude as "longitude?", notes, photo_url, is_shared as "is_shared!", created_at as "created_at!", updated_at as "updated_at!"
+                    FROM observations
+                    WHERE latitude IS NOT NULL AND longitude IS NOT NULL AND LOWER(species_name) LIKE LOWER($1)
+                    "#,
+                    format!("%{}%", species),
+                )
+                .fetch_all(&self.pool)
+                .await?
+            }
+            (None, None) => {
+                sqlx::query_as!(
+                    Observation,
+                    r#"
+                    SELECT id, user_id, trip_id, species_name, observation_date, location, latitude as "latitude?", longitude as "longitude?", notes, photo_url, is_shared as "is_shared!", created_at as "created_at!", updated_at as "updated_at!"
+                    FROM observations
+                    WHERE latitude IS NOT NULL AND longitude IS NOT NULL
+                    "#,
+                )
+                .fetch_all(&self.pool)
+                .await?
             }
-            (None, None) => query_builder,
         };
 
-        let all_observations = query_builder.fetch_all(&self.pool).await?;
-
         // Filter by distance using Haversine formula
         let nearby_observations: Vec<Observation> = all_observations
             .into_iter()
@@ -1,6 +1,6 @@
 use crate::models::trip::Trip;
 use chrono::{DateTime, Utc};
-use sqlx::{PgPool, Result};
+use sqlx::{PgPool, Postgres, QueryBuilder, Result};
 use uuid::Uuid;
 
 /// Repository for trip database operations
@@ -22,18 +22,19 @@ impl TripRepository {
         location: &str,
         description: Option<&str>,
     ) -> Result<Trip> {
-        let trip = sqlx::query_as::<_, Trip>(
+        let trip = sqlx::query_as!(
+            Trip,
             r#"
             INSERT INTO trips (user_id, name, trip_date, location, description)
             VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, user_id, name, trip_date, location, description, created_at, updated_at
+            RETURNING id, user_id, name, trip_date, location, description, created_at as "created_at!", updated_at as "updated_at!"
             "#,
+            user_id,
+            name,
+            trip_date,
+            location,
+            description,
         )
-        .bind(user_id)
-        .bind(name)
-        .bind(trip_date)
-        .bind(location)
-        .bind(description)
         .fetch_one(&self.pool)
         .await?;
 
@@ -42,14 +43,15 @@ impl TripRepository {
 
     /// Find a trip by ID
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Trip>> {
-        let trip = sqlx::query_as::<_, Trip>(
+        let trip = sqlx::query_as!(
+            Trip,
             r#"
-            SELECT id, user_id, name, trip_date, location, description, created_at, updated_at
+            SELECT id, user_id, name, trip_date, location, description, created_at as "created_at!", updated_at as "updated_at!"
             FROM trips
             WHERE id = $1
             "#,
+            id,
         )
-        .bind(id)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -58,15 +60,16 @@ impl TripRepository {
 
     /// Find all trips for a user
     pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<Trip>> {
-        let trips = sqlx::query_as::<_, Trip>(
+        let trips = sqlx::query_as!(
+            Trip,
             r#"
-            SELECT id, user_id, name, trip_date, location, description, created_at, updated_at
+            SELECT id, user_id, name, trip_date, location, description, created_at as "created_at!", updated_at as "updated_at!"
             FROM trips
             WHERE user_id = $1
             ORDER BY trip_date DESC
             "#,
+            user_id,
         )
-        .bind(user_id)
         .fetch_all(&self.pool)
         .await?;
 
@@ -83,52 +86,32 @@ impl TripRepository {
         description: Option<&str>,
     ) -> Result<Trip> {
         // Build dynamic update query
-        let mut query = String::from("UPDATE trips SET updated_at = NOW()");
-        let mut param_count = 1;
-
-        if name.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", name = ${}", param_count));
-        }
-        if trip_date.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", trip_date = ${}", param_count));
-        }
-        if location.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", location = ${}", param_count));
-        }
-        if description.is_some() {
-            param_count += 1;
-            query.push_str(&format!(", description = ${}", param_count));
-        }
-
-        query.push_str(" WHERE id = $1 RETURNING id, user_id, name, trip_date, location, description, created_at, updated_at");
-
-        let mut query_builder = sqlx::query_as::<_, Trip>(&query).bind(id);
+        let mut query = QueryBuilder::<Postgres>::new("UPDATE trips SET updated_at = NOW()");
 
         if let Some(val) = name {
-            query_builder = query_builder.bind(val);
+            query.push(", name = ").push_bind(val);
         }
         if let Some(val) = trip_date {
-            query_builder = query_builder.bind(val);
+            query.push(", trip_date = ").push_bind(val);
         }
         if let Some(val) = location {
-            query_builder = query_builder.bind(val);
+            query.push(", location = ").push_bind(val);
         }
         if let Some(val) = description {
-            query_builder = query_builder.bind(val);
+            query.push(", description = ").push_bind(val);
         }
 
-        let trip = query_builder.fetch_one(&self.pool).await?;
+        query.push(" WHERE id = ").push_bind(id);
+        query.push(" RETURNING id, user_id, name, trip_date, location, description, created_at, updated_at");
+
+        let trip = query.build_query_as::<Trip>().fetch_one(&self.pool).await?;
 
         Ok(trip)
     }
 
     /// Delete a trip (observations will have trip_id set to NULL via ON DELETE SET NULL)
     pub async fn delete(&self, id: Uuid) -> Result<bool> {
-        let result = sqlx::query("DELETE FROM trips WHERE id = $1")
-            .bind(id)
+        let result = sqlx::query!("DELETE FROM trips WHERE id = $1", id)
             .execute(&self.pool)
             .await?;
 
@@ -14,16 +14,17 @@ impl UserRepository {
 
     /// Create a new user in the database
     pub async fn create(&self, username: &str, email: &str, password_hash: &str) -> Result<User> {
-        let user = sqlx::query_as::<_, User>(
+        let user = sqlx::query_as!(
+            User,
             r#"
             INSERT INTO users (username, email, password_hash)
             VALUES ($1, $2, $3)
-            RETURNING id, username, email, password_hash, created_at
+            RETURNING id, username, email, password_hash, token_version, created_at as "created_at!"
             "#,
+            username,
+            email,
+            password_hash,
         )
-        .bind(username)
-        .bind(email)
-        .bind(password_hash)
         .fetch_one(&self.pool)
         .await?;
 
@@ -32,14 +33,15 @@ impl UserRepository {
 
     /// Find a user by username
     pub async fn find_by_username(&self, username: &str) -> Result<Option<User>> {
-        let user = sqlx::query_as::<_, User>(
+        let user = sqlx::query_as!(
+            User,
             r#"
-            SELECT id, username, email, password_hash, created_at
+            SELECT id, username, email, password_hash, token_version, created_at as "created_at!"
             FROM users
             WHERE username = $1
             "#,
+            username,
         )
-        .bind(username)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -48,14 +50,15 @@ impl UserRepository {
 
     /// Find a user by email
     pub async fn find_by_email(&self, email: &str) -> Result<Option<User>> {
-        let user = sqlx::query_as::<_, User>(
+        let user = sqlx::query_as!(
+            User,
             r#"
-            SELECT id, username, email, password_hash, created_at
+            SELECT id, username, email, password_hash, token_version, created_at as "created_at!"
             FROM users
             WHERE email = $1
             "#,
+            email,
         )
-        .bind(email)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -64,14 +67,15 @@ impl UserRepository {
 
     /// Find a user by ID
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<User>> {
-        let user = sqlx::query_as::<_, User>(
+        let user = sqlx::query_as!(
+            User,
             r#"
-            SELECT id, username, email, password_hash, created_at
+            SELECT id, username, email, password_hash, token_version, created_at as "created_at!"
             FROM users
             WHERE id = $1
             "#,
+            id,
         )
-        .bind(id)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -80,29 +84,77 @@ impl UserRepository {
 
     /// Check if a username already exists
     pub async fn username_exists(&self, username: &str) -> Result<bool> {
-        let exists: (bool,) = sqlx::query_as(
+        let exists = sqlx::query_scalar!(
             r#"
-            SELECT EXISTS(SELECT 1 FROM users WHERE username = $1)
+            SELECT EXISTS(SELECT 1 FROM users WHERE username = $1) as "exists!"
             "#,
+            username,
         )
-        .bind(username)
         .fetch_one(&self.pool)
         .await?;
 
-        Ok(exists.0)
+        Ok(exists)
     }
 
     /// Check if an email already exists
     pub async fn email_exists(&self, email: &str) -> Result<bool> {
-        let exists: (bool,) = sqlx::query_as(
+        let exists = sqlx::query_scalar!(
             r#"
-            SELECT EXISTS(SELECT 1 FROM users WHERE email = $1)
+            SELECT EXISTS(SELECT 1 FROM users WHERE email = $1) as "exists!"
             "#,
+            email,
         )
-        .bind(email)
         .fetch_one(&self.pool)
         .await?;
 
-        Ok(exists.0)
+        Ok(exists)
+    }
+
+    /// Get the current token version for a user
+    pub async fn find_token_version(&self, id: Uuid) -> Result<Option<i32>> {
+        let version = sqlx::query_scalar!(
+            r#"
+            SELECT token_version FROM users WHERE id = $1
+            "#,
+            id,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(version)
+    }
+
+    /// Replace a user's password hash and invalidate all previously issued tokens
+    pub async fn update_password(&self, id: Uuid, password_hash: &str) -> Result<User> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET password_hash = $2, token_version = token_version + 1
+            WHERE id = $1
+            RETURNING id, username, email, password_hash, token_version, created_at as "created_at!"
+            "#,
+            id,
+            password_hash,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Bump a user's token version, invalidating all previously issued tokens
+    pub async fn increment_token_version(&self, id: Uuid) -> Result<i32> {
+        let version = sqlx::query_scalar!(
+            r#"
+            UPDATE users SET token_version = token_version + 1 WHERE id = $1
+            RETURNING token_version
+            "#,
+            id,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(version)
     }
 }
@@ -1,8 +1,16 @@
-use crate::models::user::{LoginRequest, LoginResponse, RegisterRequest, User, UserProfile};
+use crate::models::user::{
+    ChangePasswordRequest, LoginRequest, LoginResponse, RegisterRequest, UserProfile,
+};
 use crate::repositories::user_repository::UserRepository;
+use crate::utils::errors::{ErrorCode, ServiceError};
 use crate::utils::jwt::generate_token;
 use crate::utils::password::{hash_password, verify_password};
+use crate::utils::token_version_cache::TokenVersionCache;
 use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Shortest password accepted at registration or on a password change
+const MIN_PASSWORD_LENGTH: usize = 8;
 
 pub struct AuthService {
     user_repo: UserRepository,
@@ -16,50 +24,49 @@ impl AuthService {
     }
 
     /// Register a new user
-    pub async fn register(&self, req: RegisterRequest) -> Result<UserProfile, String> {
+    pub async fn register(&self, req: RegisterRequest) -> Result<UserProfile, ServiceError> {
+        validate_password(&req.password, "password")?;
+
         // Check if username already exists
-        if self.user_repo.username_exists(&req.username).await.map_err(|e| e.to_string())? {
-            return Err("Username already exists".to_string());
+        if self.user_repo.username_exists(&req.username).await? {
+            return Err(ServiceError::new(ErrorCode::UsernameTaken, "Username already exists"));
         }
 
         // Check if email already exists
-        if self.user_repo.email_exists(&req.email).await.map_err(|e| e.to_string())? {
-            return Err("Email already exists".to_string());
+        if self.user_repo.email_exists(&req.email).await? {
+            return Err(ServiceError::new(ErrorCode::EmailTaken, "Email already exists"));
         }
 
         // Hash the password
-        let password_hash = hash_password(&req.password).map_err(|e| e.to_string())?;
+        let password_hash = hash_password(&req.password)?;
 
         // Create the user
         let user = self
             .user_repo
             .create(&req.username, &req.email, &password_hash)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         Ok(user.into())
     }
 
     /// Authenticate a user and return a token
-    pub async fn login(&self, req: LoginRequest) -> Result<LoginResponse, String> {
+    pub async fn login(&self, req: LoginRequest) -> Result<LoginResponse, ServiceError> {
         // Find user by username
         let user = self
             .user_repo
             .find_by_username(&req.username)
-            .await
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "Invalid credentials".to_string())?;
+            .await?
+            .ok_or_else(invalid_credentials)?;
 
         // Verify password
-        let is_valid = verify_password(&req.password, &user.password_hash)
-            .map_err(|e| e.to_string())?;
+        let is_valid = verify_password(&req.password, &user.password_hash)?;
 
         if !is_valid {
-            return Err("Invalid credentials".to_string());
+            return Err(invalid_credentials());
         }
 
         // Generate JWT token
-        let token = generate_token(user.id, &user.username).map_err(|e| e.to_string())?;
+        let token = generate_token(user.id, &user.username, user.token_version)?;
 
         Ok(LoginResponse {
             token,
@@ -68,15 +75,105 @@ impl AuthService {
     }
 
     /// Get user profile by ID
-    pub async fn get_user_profile(&self, user_id: uuid::Uuid) -> Result<UserProfile, String> {
+    pub async fn get_user_profile(&self, user_id: uuid::Uuid) -> Result<UserProfile, ServiceError> {
         let user = self
             .user_repo
             .find_by_id(user_id)
-            .await
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "User not found".to_string())?;
+            .await?
+            .ok_or_else(|| ServiceError::new(ErrorCode::UserNotFound, "User not found"))?;
 
         Ok(user.into())
     }
+
+    /// Change a user's password and return a fresh token.
+    /// All tokens issued before the change stop working.
+    pub async fn change_password(
+        &self,
+        user_id: Uuid,
+        req: ChangePasswordRequest,
+    ) -> Result<LoginResponse, ServiceError> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| ServiceError::new(ErrorCode::UserNotFound, "User not found"))?;
+
+        let is_valid = verify_password(&req.current_password, &user.password_hash)?;
+
+        if !is_valid {
+            return Err(invalid_credentials());
+        }
+
+        validate_password(&req.new_password, "new_password")?;
+        let password_hash = hash_password(&req.new_password)?;
+
+        let user = self
+            .user_repo
+            .update_password(user_id, &password_hash)
+            .await?;
+        TokenVersionCache::global().insert(user_id, user.token_version);
+
+        let token = generate_token(user.id, &user.username, user.token_version)?;
+
+        Ok(LoginResponse {
+            token,
+            user: user.into(),
+        })
+    }
+
+    /// Invalidate every token issued to a user so far ("logout everywhere")
+    pub async fn logout_all(&self, user_id: Uuid) -> Result<(), ServiceError> {
+        let version = self
+            .user_repo
+            .increment_token_version(user_id)
+            .await?;
+        TokenVersionCache::global().insert(user_id, version);
+
+        Ok(())
+    }
+
+    /// Check whether a token carrying `token_version` is still current for a user.
+    /// Uses the in-process cache and only reads the database on a miss.
+    pub async fn is_token_version_current(
+        &self,
+        user_id: Uuid,
+        token_version: i32,
+    ) -> Result<bool, ServiceError> {
+        let cache = TokenVersionCache::global();
+
+        let current = match cache.get(user_id) {
+            Some(version) => version,
+            None => {
+                let version = match self
+                    .user_repo
+                    .find_token_version(user_id)
+                    .await?
+                {
+                    Some(version) => version,
+                    None => return Ok(false),
+                };
+                cache.insert(user_id, version);
+                version
+            }
+        };
+
+        Ok(token_version == current)
+    }
 }
 
+fn invalid_credentials() -> ServiceError {
+    ServiceError::new(ErrorCode::InvalidCredentials, "Invalid credentials")
+}
+
+/// Check a new password against the rules shared by registration and password changes.
+/// `field` names the request field in the error details.
+fn validate_password(password: &str, field: &str) -> Result<(), ServiceError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(ServiceError::validation(format!(
+            "Password must be at least {} characters",
+            MIN_PASSWORD_LENGTH
+        ))
+        .with_details(serde_json::json!({ "field": field })));
+    }
+    Ok(())
+}
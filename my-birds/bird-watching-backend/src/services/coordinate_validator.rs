@@ -1,8 +1,9 @@
+use crate::utils::errors::{ErrorCode, ServiceError};
 use actix_web::{HttpResponse, ResponseError};
 use std::fmt;
 
 /// Custom error type for coordinate validation
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum CoordinateValidationError {
     InvalidLatitude(f64),
     InvalidLongitude(f64),
@@ -25,11 +26,27 @@ impl fmt::Display for CoordinateValidationError {
     }
 }
 
+impl From<CoordinateValidationError> for ServiceError {
+    fn from(err: CoordinateValidationError) -> Self {
+        let details = match err {
+            CoordinateValidationError::InvalidLatitude(lat) => {
+                serde_json::json!({ "field": "latitude", "value": lat })
+            }
+            CoordinateValidationError::InvalidLongitude(lng) => {
+                serde_json::json!({ "field": "longitude", "value": lng })
+            }
+            CoordinateValidationError::IncompleteCoordinates => {
+                serde_json::json!({ "fields": ["latitude", "longitude"] })
+            }
+        };
+
+        ServiceError::new(ErrorCode::InvalidCoordinates, err.to_string()).with_details(details)
+    }
+}
+
 impl ResponseError for CoordinateValidationError {
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::BadRequest().json(serde_json::json!({
-            "error": self.to_string()
-        }))
+        ServiceError::from(*self).error_response()
     }
 }
 
@@ -43,7 +60,7 @@ impl CoordinateValidator {
             return Err(CoordinateValidationError::InvalidLatitude(lat));
         }
         
-        if lat < -90.0 || lat > 90.0 {
+        if !(-90.0..=90.0).contains(&lat) {
             return Err(CoordinateValidationError::InvalidLatitude(lat));
         }
         
@@ -56,7 +73,7 @@ impl CoordinateValidator {
             return Err(CoordinateValidationError::InvalidLongitude(lng));
         }
         
-        if lng < -180.0 || lng > 180.0 {
+        if !(-180.0..=180.0).contains(&lng) {
             return Err(CoordinateValidationError::InvalidLongitude(lng));
         }
         
@@ -3,6 +3,7 @@ use crate::models::observation::{
 };
 use crate::repositories::observation_repository::ObservationRepository;
 use crate::services::coordinate_validator::CoordinateValidator;
+use crate::utils::errors::{ErrorCode, ServiceError};
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -23,15 +24,8 @@ impl ObservationService {
         &self,
         user_id: Uuid,
         req: CreateObservationRequest,
-    ) -> Result<Observation, String> {
-        // Validate observation date is not in the future
-        if req.observation_date > Utc::now() {
-            return Err("Observation date cannot be in the future".to_string());
-        }
-
-        // Validate coordinates if provided
-        CoordinateValidator::validate_coordinate_pair(req.latitude, req.longitude)
-            .map_err(|e| e.to_string())?;
+    ) -> Result<Observation, ServiceError> {
+        Self::validate_create(&req)?;
 
         let observation = self
             .observation_repo
@@ -47,35 +41,46 @@ impl ObservationService {
                 req.trip_id,
                 req.is_shared,
             )
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         Ok(observation)
     }
 
+    /// Validate a create request without touching the database
+    pub fn validate_create(req: &CreateObservationRequest) -> Result<(), ServiceError> {
+        // Validate observation date is not in the future
+        if req.observation_date > Utc::now() {
+            return Err(future_date_error());
+        }
+
+        // Validate coordinates if provided
+        CoordinateValidator::validate_coordinate_pair(req.latitude, req.longitude)?;
+
+        Ok(())
+    }
+
     /// Get an observation by ID
-    pub async fn get_by_id(&self, id: Uuid) -> Result<Observation, String> {
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Observation, ServiceError> {
         self.observation_repo
             .find_by_id(id)
-            .await
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "Observation not found".to_string())
+            .await?
+            .ok_or_else(|| ServiceError::new(ErrorCode::ObservationNotFound, "Observation not found"))
     }
 
     /// Get all observations for a user
-    pub async fn get_user_observations(&self, user_id: Uuid) -> Result<Vec<Observation>, String> {
+    pub async fn get_user_observations(&self, user_id: Uuid) -> Result<Vec<Observation>, ServiceError> {
         self.observation_repo
             .find_by_user(user_id)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(ServiceError::from)
     }
 
     /// Get all shared observations
-    pub async fn get_shared_observations(&self) -> Result<Vec<ObservationWithUser>, String> {
+    pub async fn get_shared_observations(&self) -> Result<Vec<ObservationWithUser>, ServiceError> {
         self.observation_repo
             .find_shared()
             .await
-            .map_err(|e| e.to_string())
+            .map_err(ServiceError::from)
     }
 
     /// Update an observation
@@ -84,23 +89,24 @@ impl ObservationService {
         id: Uuid,
         user_id: Uuid,
         req: UpdateObservationRequest,
-    ) -> Result<Observation, String> {
+    ) -> Result<Observation, ServiceError> {
         // Check if observation exists and belongs to user
         let existing = self.get_by_id(id).await?;
         if existing.user_id != user_id {
-            return Err("Unauthorized: You can only update your own observations".to_string());
+            return Err(ServiceError::forbidden(
+                "Unauthorized: You can only update your own observations",
+            ));
         }
 
         // Validate observation date is not in the future if provided
         if let Some(date) = req.observation_date {
             if date > Utc::now() {
-                return Err("Observation date cannot be in the future".to_string());
+                return Err(future_date_error());
             }
         }
 
         // Validate coordinates if provided
-        CoordinateValidator::validate_coordinate_pair(req.latitude, req.longitude)
-            .map_err(|e| e.to_string())?;
+        CoordinateValidator::validate_coordinate_pair(req.latitude, req.longitude)?;
 
         let observation = self
             .observation_repo
@@ -116,18 +122,19 @@ impl ObservationService {
                 req.trip_id,
                 req.is_shared,
             )
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         Ok(observation)
     }
 
     /// Delete an observation
-    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<(), String> {
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<(), ServiceError> {
         // Check if observation exists and belongs to user
         let existing = self.get_by_id(id).await?;
         if existing.user_id != user_id {
-            return Err("Unauthorized: You can only delete your own observations".to_string());
+            return Err(ServiceError::forbidden(
+                "Unauthorized: You can only delete your own observations",
+            ));
         }
 
         // Delete associated photo if exists
@@ -138,8 +145,7 @@ impl ObservationService {
 
         self.observation_repo
             .delete(id)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         Ok(())
     }
@@ -152,7 +158,7 @@ impl ObservationService {
         location: Option<String>,
         start_date: Option<DateTime<Utc>>,
         end_date: Option<DateTime<Utc>>,
-    ) -> Result<Vec<Observation>, String> {
+    ) -> Result<Vec<Observation>, ServiceError> {
         self.observation_repo
             .search(
                 user_id,
@@ -162,7 +168,7 @@ impl ObservationService {
                 end_date,
             )
             .await
-            .map_err(|e| e.to_string())
+            .map_err(ServiceError::from)
     }
 
     /// Find observations near a location
@@ -173,23 +179,23 @@ impl ObservationService {
         radius_km: f64,
         user_id: Option<Uuid>,
         species_name: Option<String>,
-    ) -> Result<Vec<crate::models::observation::ObservationWithDistance>, String> {
+    ) -> Result<Vec<crate::models::observation::ObservationWithDistance>, ServiceError> {
         use crate::services::geo_service::GeoService;
 
         // Validate center coordinates
-        CoordinateValidator::validate_latitude(center_lat).map_err(|e| e.to_string())?;
-        CoordinateValidator::validate_longitude(center_lng).map_err(|e| e.to_string())?;
+        CoordinateValidator::validate_latitude(center_lat)?;
+        CoordinateValidator::validate_longitude(center_lng)?;
 
         // Validate radius
         if radius_km <= 0.0 || !radius_km.is_finite() {
-            return Err("Radius must be a positive number".to_string());
+            return Err(ServiceError::validation("Radius must be a positive number")
+                .with_details(serde_json::json!({ "field": "radius" })));
         }
 
         let observations = self
             .observation_repo
             .find_nearby(center_lat, center_lng, radius_km, user_id, species_name.as_deref())
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         // Calculate distances and create ObservationWithDistance objects
         let observations_with_distance: Vec<crate::models::observation::ObservationWithDistance> =
@@ -211,3 +217,8 @@ impl ObservationService {
         Ok(observations_with_distance)
     }
 }
+
+fn future_date_error() -> ServiceError {
+    ServiceError::validation("Observation date cannot be in the future")
+        .with_details(serde_json::json!({ "field": "observation_date" }))
+}
@@ -1,4 +1,5 @@
-use actix_multipart::Multipart;
+use crate::utils::errors::{ErrorCode, ServiceError};
+use actix_multipart::{Field, Multipart};
 use actix_web::web;
 use futures_util::StreamExt;
 use std::fs;
@@ -6,6 +7,9 @@ use std::io::Write;
 use std::path::PathBuf;
 use uuid::Uuid;
 
+/// Largest accepted photo, in bytes
+pub const MAX_PHOTO_SIZE: usize = 10 * 1024 * 1024;
+
 pub struct PhotoService {
     upload_dir: PathBuf,
 }
@@ -21,69 +25,93 @@ impl PhotoService {
     }
 
     /// Upload a photo and return the URL
-    pub async fn upload_photo(&self, mut payload: Multipart) -> Result<String, String> {
-        while let Some(item) = payload.next().await {
-            let mut field = item.map_err(|e| e.to_string())?;
-            
-            let content_disposition = field.content_disposition();
-            let filename = content_disposition
-                .get_filename()
-                .ok_or_else(|| "No filename provided".to_string())?;
-            
-            // Validate file type
-            let content_type = field.content_type();
-            if !self.is_valid_image_type(content_type) {
-                return Err(format!("Invalid file type: {:?}", content_type));
-            }
-            
-            // Generate unique filename
-            let extension = PathBuf::from(filename)
-                .extension()
-                .and_then(|s| s.to_str())
-                .unwrap_or("jpg")
-                .to_string();
-            
-            let unique_filename = format!("{}_{}.{}", Uuid::new_v4(), filename, extension);
-            let filepath = self.upload_dir.join(&unique_filename);
-            
-            // Save file
-            let mut file = web::block(move || std::fs::File::create(filepath))
-                .await
-                .map_err(|e| e.to_string())?
-                .map_err(|e| e.to_string())?;
-            
-            // Write chunks to file
-            while let Some(chunk) = field.next().await {
-                let data = chunk.map_err(|e| e.to_string())?;
-                file = web::block(move || {
-                    let mut f = file;
-                    f.write_all(&data)?;
-                    Ok::<_, std::io::Error>(f)
-                })
-                .await
-                .map_err(|e| e.to_string())?
-                .map_err(|e| e.to_string())?;
-            }
+    pub async fn upload_photo(&self, mut payload: Multipart) -> Result<String, ServiceError> {
+        if let Some(item) = payload.next().await {
+            let field = item.map_err(|e| invalid_upload(e.to_string()))?;
+            return self.save_photo(field).await;
+        }
+        
+        Err(invalid_upload("No file uploaded"))
+    }
+
+    /// Validate and store a single multipart file field, returning its URL.
+    /// A partially written file is removed if the upload turns out to be invalid.
+    pub async fn save_photo(&self, mut field: Field) -> Result<String, ServiceError> {
+        let content_disposition = field.content_disposition();
+        let filename = content_disposition
+            .get_filename()
+            .ok_or_else(|| invalid_upload("No filename provided"))?;
+        
+        // Validate file type
+        let content_type = field.content_type();
+        if !self.is_valid_image_type(content_type) {
+            return Err(invalid_upload(format!("Invalid file type: {:?}", content_type)));
+        }
+        
+        // Generate unique filename
+        let extension = PathBuf::from(filename)
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or("jpg")
+            .to_string();
+        
+        let unique_filename = format!("{}_{}.{}", Uuid::new_v4(), filename, extension);
+        let filepath = self.upload_dir.join(&unique_filename);
+        let photo_url = format!("/uploads/{}", unique_filename);
+        
+        // Save file
+        let mut file = web::block(move || std::fs::File::create(filepath))
+            .await
+            .map_err(|e| ServiceError::internal(e.to_string()))?
+            .map_err(|e| ServiceError::internal(e.to_string()))?;
+        
+        // Write chunks to file
+        let mut size = 0;
+        while let Some(chunk) = field.next().await {
+            let result = match chunk {
+                Ok(data) if size + data.len() > MAX_PHOTO_SIZE => Err(invalid_upload(format!(
+                    "File exceeds the maximum size of {} bytes",
+                    MAX_PHOTO_SIZE
+                ))),
+                Ok(data) => {
+                    size += data.len();
+                    web::block(move || {
+                        let mut f = file;
+                        f.write_all(&data)?;
+                        Ok::<_, std::io::Error>(f)
+                    })
+                    .await
+                    .map_err(|e| ServiceError::internal(e.to_string()))
+                    .and_then(|r| r.map_err(|e| ServiceError::internal(e.to_string())))
+                }
+                Err(e) => Err(invalid_upload(e.to_string())),
+            };
             
-            // Return URL (in production, this would be a full URL)
-            return Ok(format!("/uploads/{}", unique_filename));
+            file = match result {
+                Ok(f) => f,
+                Err(e) => {
+                    let _ = self.delete_photo(&photo_url);
+                    return Err(e);
+                }
+            };
         }
         
-        Err("No file uploaded".to_string())
+        // Return URL (in production, this would be a full URL)
+        Ok(photo_url)
     }
 
     /// Delete a photo by URL
-    pub fn delete_photo(&self, photo_url: &str) -> Result<(), String> {
+    pub fn delete_photo(&self, photo_url: &str) -> Result<(), ServiceError> {
         // Extract filename from URL
         let filename = photo_url
             .split('/')
-            .last()
-            .ok_or_else(|| "Invalid photo URL".to_string())?;
+            .next_back()
+            .ok_or_else(|| ServiceError::validation("Invalid photo URL"))?;
         
         let filepath = self.upload_dir.join(filename);
         
         if filepath.exists() {
-            fs::remove_file(filepath).map_err(|e| e.to_string())?;
+            fs::remove_file(filepath).map_err(|e| ServiceError::internal(e.to_string()))?;
         }
         
         Ok(())
@@ -102,6 +130,10 @@ impl PhotoService {
     }
 }
 
+fn invalid_upload(message: impl Into<String>) -> ServiceError {
+    ServiceError::new(ErrorCode::InvalidUpload, message)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
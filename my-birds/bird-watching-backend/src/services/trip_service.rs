@@ -2,6 +2,7 @@ use crate::models::observation::Observation;
 use crate::models::trip::{CreateTripRequest, Trip, UpdateTripRequest};
 use crate::repositories::observation_repository::ObservationRepository;
 use crate::repositories::trip_repository::TripRepository;
+use crate::utils::errors::{ErrorCode, ServiceError};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -19,7 +20,7 @@ impl TripService {
     }
 
     /// Create a new trip
-    pub async fn create(&self, user_id: Uuid, req: CreateTripRequest) -> Result<Trip, String> {
+    pub async fn create(&self, user_id: Uuid, req: CreateTripRequest) -> Result<Trip, ServiceError> {
         let trip = self
             .trip_repo
             .create(
@@ -29,51 +30,41 @@ impl TripService {
                 &req.location,
                 req.description.as_deref(),
             )
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         Ok(trip)
     }
 
     /// Get a trip by ID
-    pub async fn get_by_id(&self, id: Uuid) -> Result<Trip, String> {
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Trip, ServiceError> {
         self.trip_repo
             .find_by_id(id)
-            .await
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "Trip not found".to_string())
+            .await?
+            .ok_or_else(|| ServiceError::new(ErrorCode::TripNotFound, "Trip not found"))
     }
 
     /// Get trip with observations
     pub async fn get_trip_with_observations(
         &self,
         id: Uuid,
-    ) -> Result<(Trip, Vec<Observation>), String> {
+    ) -> Result<(Trip, Vec<Observation>), ServiceError> {
         let trip = self.get_by_id(id).await?;
 
         // Get observations for this trip
-        let observations = sqlx::query_as::<_, Observation>(
-            r#"
-            SELECT id, user_id, trip_id, species_name, observation_date, location, notes, photo_url, is_shared, created_at, updated_at
-            FROM observations
-            WHERE trip_id = $1
-            ORDER BY observation_date DESC
-            "#,
-        )
-        .bind(id)
-        .fetch_all(self.observation_repo.pool())
-        .await
-        .map_err(|e| e.to_string())?;
+        let observations = self
+            .observation_repo
+            .find_by_trip(id)
+            .await?;
 
         Ok((trip, observations))
     }
 
     /// Get all trips for a user
-    pub async fn get_user_trips(&self, user_id: Uuid) -> Result<Vec<Trip>, String> {
+    pub async fn get_user_trips(&self, user_id: Uuid) -> Result<Vec<Trip>, ServiceError> {
         self.trip_repo
             .find_by_user(user_id)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(ServiceError::from)
     }
 
     /// Update a trip
@@ -82,11 +73,13 @@ impl TripService {
         id: Uuid,
         user_id: Uuid,
         req: UpdateTripRequest,
-    ) -> Result<Trip, String> {
+    ) -> Result<Trip, ServiceError> {
         // Check if trip exists and belongs to user
         let existing = self.get_by_id(id).await?;
         if existing.user_id != user_id {
-            return Err("Unauthorized: You can only update your own trips".to_string());
+            return Err(ServiceError::forbidden(
+                "Unauthorized: You can only update your own trips",
+            ));
         }
 
         let trip = self
@@ -98,24 +91,24 @@ impl TripService {
                 req.location.as_deref(),
                 req.description.as_deref(),
             )
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         Ok(trip)
     }
 
     /// Delete a trip
-    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<(), String> {
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<(), ServiceError> {
         // Check if trip exists and belongs to user
         let existing = self.get_by_id(id).await?;
         if existing.user_id != user_id {
-            return Err("Unauthorized: You can only delete your own trips".to_string());
+            return Err(ServiceError::forbidden(
+                "Unauthorized: You can only delete your own trips",
+            ));
         }
 
         self.trip_repo
             .delete(id)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         Ok(())
     }
@@ -1 +1,255 @@
-// Error types - to be implemented
+use actix_web::body::EitherBody;
+use actix_web::dev::ServiceResponse;
+use actix_web::http::StatusCode;
+use actix_web::middleware::{ErrorHandlerResponse, ErrorHandlers};
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// Catalog of machine-readable error codes returned in `error.code`.
+/// Each code maps to exactly one HTTP status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// Request body failed a business validation rule (400)
+    ValidationFailed,
+    /// Latitude/longitude out of range or only one of them provided (400)
+    InvalidCoordinates,
+    /// Request body is not valid JSON or doesn't match the expected shape (400)
+    MalformedJson,
+    /// Query string could not be parsed (400)
+    InvalidQuery,
+    /// Path parameter could not be parsed (400)
+    InvalidPathParameter,
+    /// Uploaded file is missing or not an accepted image (400)
+    InvalidUpload,
+    /// Username is already registered (409)
+    UsernameTaken,
+    /// Email is already registered (409)
+    EmailTaken,
+    /// Missing, malformed, expired or revoked token (401)
+    Unauthorized,
+    /// Username/password combination is wrong (401)
+    InvalidCredentials,
+    /// Authenticated user doesn't own the resource (403)
+    Forbidden,
+    /// No user with the given ID (404)
+    UserNotFound,
+    /// No observation with the given ID (404)
+    ObservationNotFound,
+    /// No trip with the given ID (404)
+    TripNotFound,
+    /// No route matches the request path (404)
+    RouteNotFound,
+    /// Route exists but doesn't accept the request method (405)
+    MethodNotAllowed,
+    /// Unexpected failure, e.g. a database error (500)
+    InternalError,
+}
+
+impl ErrorCode {
+    /// HTTP status code for this error code
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ErrorCode::ValidationFailed
+            | ErrorCode::InvalidCoordinates
+            | ErrorCode::MalformedJson
+            | ErrorCode::InvalidQuery
+            | ErrorCode::InvalidPathParameter
+            | ErrorCode::InvalidUpload => StatusCode::BAD_REQUEST,
+            ErrorCode::UsernameTaken | ErrorCode::EmailTaken => StatusCode::CONFLICT,
+            ErrorCode::Unauthorized | ErrorCode::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::UserNotFound
+            | ErrorCode::ObservationNotFound
+            | ErrorCode::TripNotFound
+            | ErrorCode::RouteNotFound => StatusCode::NOT_FOUND,
+            ErrorCode::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Error returned by services and handlers.
+/// Serialized as `{"error": {"code": "...", "message": "...", "details": {...}}}`.
+#[derive(Debug)]
+pub struct ServiceError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub details: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: ErrorPayload<'a>,
+}
+
+#[derive(Serialize)]
+struct ErrorPayload<'a> {
+    code: ErrorCode,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<&'a Value>,
+}
+
+impl ServiceError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// Attach structured details (e.g. the offending field) to the error
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ValidationFailed, message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Unauthorized, message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Forbidden, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InternalError, message)
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ServiceError {}
+
+impl ResponseError for ServiceError {
+    fn status_code(&self) -> StatusCode {
+        self.code.status_code()
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(ErrorBody {
+            error: ErrorPayload {
+                code: self.code,
+                message: &self.message,
+                details: self.details.as_ref(),
+            },
+        })
+    }
+}
+
+impl From<sqlx::Error> for ServiceError {
+    fn from(err: sqlx::Error) -> Self {
+        log::error!("Database error: {}", err);
+        ServiceError::internal("An internal database error occurred")
+    }
+}
+
+impl From<bcrypt::BcryptError> for ServiceError {
+    fn from(err: bcrypt::BcryptError) -> Self {
+        log::error!("Password hashing error: {}", err);
+        ServiceError::internal("Failed to process password")
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for ServiceError {
+    fn from(err: jsonwebtoken::errors::Error) -> Self {
+        log::error!("Token generation error: {}", err);
+        ServiceError::internal("Failed to generate token")
+    }
+}
+
+/// JSON body extractor config: malformed bodies produce a MALFORMED_JSON error
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(|err, _req: &HttpRequest| {
+        ServiceError::new(ErrorCode::MalformedJson, err.to_string()).into()
+    })
+}
+
+/// Query string extractor config: unparsable queries produce an INVALID_QUERY error
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(|err, _req: &HttpRequest| {
+        ServiceError::new(ErrorCode::InvalidQuery, err.to_string()).into()
+    })
+}
+
+/// Path extractor config: unparsable path parameters produce an INVALID_PATH_PARAMETER error
+pub fn path_config() -> web::PathConfig {
+    web::PathConfig::default().error_handler(|err, _req: &HttpRequest| {
+        ServiceError::new(ErrorCode::InvalidPathParameter, err.to_string()).into()
+    })
+}
+
+/// Default service for requests that match no route
+pub async fn route_not_found(req: HttpRequest) -> HttpResponse {
+    ServiceError::new(
+        ErrorCode::RouteNotFound,
+        format!("No route for {} {}", req.method(), req.path()),
+    )
+    .error_response()
+}
+
+/// Rewrites actix's empty 405 responses (unsupported method on a known route)
+/// into the standard error shape
+pub fn error_handlers<B: 'static>() -> ErrorHandlers<B> {
+    ErrorHandlers::new().handler(StatusCode::METHOD_NOT_ALLOWED, method_not_allowed)
+}
+
+fn method_not_allowed<B>(res: ServiceResponse<B>) -> actix_web::Result<ErrorHandlerResponse<B>> {
+    let error = ServiceError::new(
+        ErrorCode::MethodNotAllowed,
+        format!(
+            "Method {} is not allowed for {}",
+            res.request().method(),
+            res.request().path()
+        ),
+    );
+    let (req, _) = res.into_parts();
+    let res: ServiceResponse<EitherBody<B>> =
+        ServiceResponse::new(req, error.error_response()).map_into_right_body();
+
+    Ok(ErrorHandlerResponse::Response(res))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+
+    #[actix_web::test]
+    async fn test_error_response_shape() {
+        let error = ServiceError::validation("Species name is required")
+            .with_details(serde_json::json!({ "field": "species_name" }));
+
+        let response = error.error_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = to_bytes(response.into_body()).await.unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"]["code"], "VALIDATION_FAILED");
+        assert_eq!(json["error"]["message"], "Species name is required");
+        assert_eq!(json["error"]["details"]["field"], "species_name");
+    }
+
+    #[test]
+    fn test_error_codes_map_to_status() {
+        assert_eq!(ErrorCode::MalformedJson.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(ErrorCode::UsernameTaken.status_code(), StatusCode::CONFLICT);
+        assert_eq!(ErrorCode::Unauthorized.status_code(), StatusCode::UNAUTHORIZED);
+        assert_eq!(ErrorCode::Forbidden.status_code(), StatusCode::FORBIDDEN);
+        assert_eq!(ErrorCode::ObservationNotFound.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(ErrorCode::MethodNotAllowed.status_code(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(ErrorCode::InternalError.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
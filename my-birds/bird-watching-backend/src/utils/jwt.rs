@@ -11,10 +11,16 @@ pub struct Claims {
     pub username: String,
     pub exp: i64,     // Expiration time
     pub iat: i64,     // Issued at
+    #[serde(default)]
+    pub ver: i32,     // User's token version at issue time
 }
 
 /// Generate a JWT token for a user
-pub fn generate_token(user_id: Uuid, username: &str) -> Result<String, jsonwebtoken::errors::Error> {
+pub fn generate_token(
+    user_id: Uuid,
+    username: &str,
+    token_version: i32,
+) -> Result<String, jsonwebtoken::errors::Error> {
     let secret = env::var("JWT_SECRET").unwrap_or_else(|_| "default-secret-key".to_string());
     
     let expiration = Utc::now()
@@ -27,6 +33,7 @@ pub fn generate_token(user_id: Uuid, username: &str) -> Result<String, jsonwebto
         username: username.to_string(),
         exp: expiration,
         iat: Utc::now().timestamp(),
+        ver: token_version,
     };
     
     encode(
@@ -63,7 +70,7 @@ mod tests {
         let user_id = Uuid::new_v4();
         let username = "testuser";
         
-        let token = generate_token(user_id, username).expect("Failed to generate token");
+        let token = generate_token(user_id, username, 0).expect("Failed to generate token");
         let claims = validate_token(&token).expect("Failed to validate token");
         
         assert_eq!(claims.username, username);
@@ -75,7 +82,7 @@ mod tests {
         let user_id = Uuid::new_v4();
         let username = "testuser";
         
-        let token = generate_token(user_id, username).expect("Failed to generate token");
+        let token = generate_token(user_id, username, 0).expect("Failed to generate token");
         let claims = validate_token(&token).expect("Failed to validate token");
         let extracted_id = extract_user_id(&claims).expect("Failed to extract user ID");
         
@@ -93,12 +100,22 @@ mod tests {
         let user_id = Uuid::new_v4();
         let username = "testuser";
         
-        let token = generate_token(user_id, username).expect("Failed to generate token");
+        let token = generate_token(user_id, username, 0).expect("Failed to generate token");
         let claims = validate_token(&token).expect("Failed to validate token");
         
         let now = Utc::now().timestamp();
         assert!(claims.exp > now, "Token should expire in the future");
         assert!(claims.iat <= now, "Token should be issued at or before now");
     }
+
+    #[test]
+    fn test_token_version_is_embedded() {
+        let user_id = Uuid::new_v4();
+
+        let token = generate_token(user_id, "testuser", 3).expect("Failed to generate token");
+        let claims = validate_token(&token).expect("Failed to validate token");
+
+        assert_eq!(claims.ver, 3);
+    }
 }
 
@@ -2,3 +2,4 @@
 pub mod errors;
 pub mod jwt;
 pub mod password;
+pub mod token_version_cache;
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// How long a cached token version is trusted before re-reading the database
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// Small in-process cache of user ID -> token version, so the auth middleware
+/// doesn't have to hit the database on every request
+pub struct TokenVersionCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<Uuid, (i32, Instant)>>,
+}
+
+impl TokenVersionCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Process-wide cache shared by the auth middleware and the auth service
+    pub fn global() -> &'static TokenVersionCache {
+        static CACHE: OnceLock<TokenVersionCache> = OnceLock::new();
+        CACHE.get_or_init(|| TokenVersionCache::new(DEFAULT_TTL))
+    }
+
+    /// Get the cached version for a user, if present and not expired
+    pub fn get(&self, user_id: Uuid) -> Option<i32> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(&user_id) {
+            Some((version, cached_at)) if cached_at.elapsed() < self.ttl => Some(*version),
+            Some(_) => {
+                entries.remove(&user_id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Cache the current version for a user.
+    /// Versions only ever go up, so an older version never replaces a newer one:
+    /// a database read that started before a bump can't bring back the old version.
+    pub fn insert(&self, user_id: Uuid, version: i32) {
+        let mut entries = self.entries.lock().unwrap();
+        let version = match entries.get(&user_id) {
+            Some((cached, _)) => version.max(*cached),
+            None => version,
+        };
+        entries.insert(user_id, (version, Instant::now()));
+    }
+
+    /// Drop the cached version for a user, so the next check re-reads the database.
+    /// After bumping a version, `insert` the new one instead: a read already in
+    /// flight could otherwise cache the old version again.
+    pub fn invalidate(&self, user_id: Uuid) {
+        self.entries.lock().unwrap().remove(&user_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_get() {
+        let cache = TokenVersionCache::new(Duration::from_secs(60));
+        let user_id = Uuid::new_v4();
+
+        assert_eq!(cache.get(user_id), None);
+        cache.insert(user_id, 2);
+        assert_eq!(cache.get(user_id), Some(2));
+    }
+
+    #[test]
+    fn test_invalidate_removes_entry() {
+        let cache = TokenVersionCache::new(Duration::from_secs(60));
+        let user_id = Uuid::new_v4();
+
+        cache.insert(user_id, 1);
+        cache.invalidate(user_id);
+        assert_eq!(cache.get(user_id), None);
+    }
+
+    #[test]
+    fn test_stale_insert_does_not_replace_newer_version() {
+        let cache = TokenVersionCache::new(Duration::from_secs(60));
+        let user_id = Uuid::new_v4();
+
+        // A check misses the cache and reads version 1 from the database...
+        assert_eq!(cache.get(user_id), None);
+        let read_before_bump = 1;
+        // ...then the password is changed and version 2 is cached...
+        cache.insert(user_id, 2);
+        // ...and only then does the first check cache what it read
+        cache.insert(user_id, read_before_bump);
+
+        assert_eq!(cache.get(user_id), Some(2));
+    }
+
+    #[test]
+    fn test_expired_entry_is_not_returned() {
+        let cache = TokenVersionCache::new(Duration::from_millis(0));
+        let user_id = Uuid::new_v4();
+
+        cache.insert(user_id, 1);
+        assert_eq!(cache.get(user_id), None);
+    }
+}
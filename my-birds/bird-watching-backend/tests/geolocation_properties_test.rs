@@ -371,7 +371,7 @@ proptest! {
 
             // Creation should fail with validation error
             prop_assert!(result.is_err(), "Creation with invalid latitude should fail");
-            let error_msg = result.unwrap_err();
+            let error_msg = result.unwrap_err().to_string();
             prop_assert!(error_msg.contains("Latitude") || error_msg.contains("latitude"),
                         "Error should mention latitude: {}", error_msg);
 
@@ -433,7 +433,7 @@ proptest! {
 
             // Creation should fail with validation error
             prop_assert!(result.is_err(), "Creation with invalid longitude should fail");
-            let error_msg = result.unwrap_err();
+            let error_msg = result.unwrap_err().to_string();
             prop_assert!(error_msg.contains("Longitude") || error_msg.contains("longitude"),
                         "Error should mention longitude: {}", error_msg);
 
@@ -499,7 +499,7 @@ proptest! {
 
             // Creation should fail with validation error
             prop_assert!(result.is_err(), "Creation with incomplete coordinates should fail");
-            let error_msg = result.unwrap_err();
+            let error_msg = result.unwrap_err().to_string();
             prop_assert!(error_msg.contains("Both") || error_msg.contains("both") || 
                         error_msg.contains("together") || error_msg.contains("neither"),
                         "Error should mention coordinate pair requirement: {}", error_msg);
@@ -389,7 +389,7 @@ proptest! {
 
             // Update should fail
             prop_assert!(update_result.is_err(), "Update by non-owner should fail");
-            prop_assert!(update_result.unwrap_err().contains("Unauthorized"),
+            prop_assert!(update_result.unwrap_err().to_string().contains("Unauthorized"),
                         "Error should indicate unauthorized access");
 
             // Verify observation was not modified
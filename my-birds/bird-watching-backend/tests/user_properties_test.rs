@@ -130,7 +130,7 @@ proptest! {
             
             let result2 = auth_service.register(register_req2).await;
             prop_assert!(result2.is_err(), "Duplicate username registration should fail");
-            prop_assert!(result2.unwrap_err().contains("Username already exists"), 
+            prop_assert!(result2.unwrap_err().to_string().contains("Username already exists"), 
                         "Error should indicate username exists");
             
             // Try to register with same email
@@ -142,7 +142,7 @@ proptest! {
             
             let result3 = auth_service.register(register_req3).await;
             prop_assert!(result3.is_err(), "Duplicate email registration should fail");
-            prop_assert!(result3.unwrap_err().contains("Email already exists"), 
+            prop_assert!(result3.unwrap_err().to_string().contains("Email already exists"), 
                         "Error should indicate email exists");
             
             // Clean up
@@ -249,7 +249,7 @@ proptest! {
             // Only assert failure if the wrong password is actually different
             if wrong_password != password {
                 prop_assert!(login_result.is_err(), "Login should fail with wrong password");
-                prop_assert!(login_result.unwrap_err().contains("Invalid credentials"), 
+                prop_assert!(login_result.unwrap_err().to_string().contains("Invalid credentials"), 
                             "Error should indicate invalid credentials");
             }
             
@@ -261,7 +261,7 @@ proptest! {
             
             let login_result2 = auth_service.login(login_req2).await;
             prop_assert!(login_result2.is_err(), "Login should fail with non-existent username");
-            prop_assert!(login_result2.unwrap_err().contains("Invalid credentials"), 
+            prop_assert!(login_result2.unwrap_err().to_string().contains("Invalid credentials"), 
                         "Error should indicate invalid credentials");
             
             // Clean up
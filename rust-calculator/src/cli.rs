@@ -4,25 +4,48 @@
 // This module provides a command-line interface for the calculator, allowing
 // users to interactively enter expressions and see results.
 
-use crate::{Lexer, Parser};
+use rust_calculator::{
+    format_program, simplify, CalcError, CalcResult, Evaluator, Expr, Lexer, Parser, ANS,
+};
 use rustyline::error::ReadlineError;
 use rustyline::DefaultEditor;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::BufRead;
+
+/// Significant digits shown for results until changed with `precision N`
+pub const DEFAULT_PRECISION: usize = 10;
+
+/// The most significant digits an f64 can hold
+const MAX_PRECISION: usize = 17;
+
+/// What a line of input produced
+pub enum Outcome {
+    Value(f64),       // The value of the last statement
+    Defined(String),  // The signature of a function the line ended by defining
+}
 
 /// Interactive CLI calculator
-/// Maintains state between expressions (variables persist)
+/// Maintains state between expressions (variables and functions persist)
 pub struct CalculatorCLI {
     editor: DefaultEditor,
-    variables: HashMap<String, f64>,
+    evaluator: Evaluator,
+    precision: usize, // Significant digits shown; values are stored unrounded
+    json: bool,       // Print each result as a line of JSON instead
+    lines: LineJoiner, // Holds a statement that's continued on the next line
 }
 
 impl CalculatorCLI {
-    /// Create a new CLI calculator instance
-    pub fn new() -> rustyline::Result<Self> {
+    /// Create a new CLI calculator instance, printing JSON from the start if `json` is set
+    /// and starting random numbers from `seed` if given
+    pub fn new(json: bool, seed: Option<u64>) -> rustyline::Result<Self> {
         let editor = DefaultEditor::new()?;
         Ok(CalculatorCLI {
             editor,
-            variables: HashMap::new(),
+            evaluator: seeded_evaluator(seed),
+            precision: DEFAULT_PRECISION,
+            json,
+            lines: LineJoiner::default(),
         })
     }
 
@@ -30,18 +53,28 @@ impl CalculatorCLI {
     pub fn run(&mut self) -> rustyline::Result<()> {
         println!("🧮 Rust Calculator - Interactive Mode");
         println!("Type expressions like: 2 + 3, sin(pi()/2), x = 5; y = x + 2");
-        println!("Type 'help' for help, 'vars' to see variables, 'quit' to exit");
+        println!("Type 'help' for help, 'vars' to see variables and functions, 'quit' to exit");
         println!();
 
         loop {
-            let readline = self.editor.readline("calc> ");
+            // An unfinished statement gets a different prompt for its next line
+            let prompt = if self.lines.is_pending() { "  ... " } else { "calc> " };
+            let readline = self.editor.readline(prompt);
             match readline {
                 Ok(line) => {
                     let line = line.trim();
+
+                    // The rest of a statement begun on an earlier line: no commands here
+                    if self.lines.is_pending() {
+                        if let Some((_, input)) = self.lines.push(line) {
+                            self.evaluate_input(&input)?;
+                        }
+                        continue;
+                    }
                     
                     // Handle special commands
                     match line {
-                        "" => continue, // Empty line
+                        _ if Lexer::is_blank(line) => continue, // Empty line or just a comment
                         "quit" | "exit" | "q" => {
                             println!("Goodbye! 👋");
                             break;
@@ -55,26 +88,66 @@ impl CalculatorCLI {
                             continue;
                         }
                         "clear" => {
-                            self.variables.clear();
-                            println!("Variables cleared.");
+                            self.evaluator.clear(); // Settings like strict stay
+                            println!("Variables (including ans) and functions cleared.");
                             continue;
                         }
                         _ => {}
                     }
 
-                    // Add to history
-                    self.editor.add_history_entry(line)?;
+                    // "precision" or "precision N"
+                    if let Some(digits) = precision_command(line) {
+                        self.set_precision(digits);
+                        continue;
+                    }
+
+                    // "strict", "strict on" or "strict off"
+                    if let Some(setting) = line.strip_prefix("strict")
+                        && matches!(setting.trim(), "" | "on" | "off")
+                    {
+                        self.set_strict(setting.trim());
+                        continue;
+                    }
+
+                    // "json", "json on" or "json off"
+                    if let Some(setting) = line.strip_prefix("json")
+                        && matches!(setting.trim(), "" | "on" | "off")
+                    {
+                        self.set_json(setting.trim());
+                        continue;
+                    }
 
-                    // Evaluate expression
-                    match self.evaluate_expression(line) {
-                        Ok(result) => {
-                            println!("= {}", result);
+                    // "fmt EXPR": show EXPR with uniform spacing, without evaluating it
+                    if let Some(input) = line.strip_prefix("fmt ") {
+                        match Parser::new(Lexer::new(input)).and_then(|mut p| p.parse_ast()) {
+                            Ok(program) => println!("{}", format_program(&program)),
+                            Err(error) => println!("{}", error.report(input)),
                         }
-                        Err(error) => {
-                            println!("Error: {}", error);
+                        continue;
+                    }
+
+                    // "simplify EXPR": show a simpler form of EXPR, without evaluating it
+                    if let Some(input) = line.strip_prefix("simplify ") {
+                        match Parser::new(Lexer::new(input)).and_then(|mut p| p.parse_ast()) {
+                            Ok(program) => {
+                                let program: Vec<Expr> = program.iter().map(simplify).collect();
+                                println!("{}", format_program(&program));
+                            }
+                            Err(error) => println!("{}", error.report(input)),
                         }
+                        continue;
+                    }
+
+                    // Evaluate it, unless it goes on to the next line like "x = 2 +"
+                    if let Some((_, input)) = self.lines.push(line) {
+                        self.evaluate_input(&input)?;
                     }
                 }
+                Err(ReadlineError::Interrupted) if self.lines.is_pending() => {
+                    // Drop the unfinished statement rather than leaving the REPL
+                    self.lines = LineJoiner::default();
+                    println!("CTRL-C (unfinished input discarded)");
+                }
                 Err(ReadlineError::Interrupted) => {
                     println!("CTRL-C");
                     break;
@@ -93,27 +166,74 @@ impl CalculatorCLI {
         Ok(())
     }
 
-    /// Evaluate a single expression and update variables
-    fn evaluate_expression(&mut self, input: &str) -> std::result::Result<f64, String> {
-        // Create lexer and parser
-        let lexer = Lexer::new(input);
-        let mut parser = Parser::new(lexer);
-        
-        // Transfer existing variables to parser
-        parser.set_variables(self.variables.clone());
-
-        // Parse and evaluate
-        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            parser.parse()
-        }));
-
-        match result {
-            Ok(value) => {
-                // Update our variables with any new assignments
-                self.variables = parser.get_variables();
-                Ok(value)
+    /// Evaluate a complete input, which may span several lines, and print the result
+    fn evaluate_input(&mut self, input: &str) -> rustyline::Result<()> {
+        // Add to history
+        self.editor.add_history_entry(input)?;
+
+        // Evaluate expression
+        let outcome = evaluate_line(&mut self.evaluator, input);
+        if self.json {
+            print_json(input, &outcome, self.evaluator.variables());
+            return Ok(());
+        }
+        match outcome {
+            Ok(Outcome::Value(result)) => {
+                println!("= {}", format_number(result, self.precision));
+            }
+            Ok(Outcome::Defined(signature)) => {
+                println!("Defined {}", signature);
+            }
+            Err(error) => {
+                println!("{}", error.report(input));
+            }
+        }
+        Ok(())
+    }
+
+    /// Show or change how many significant digits results are shown with
+    /// Example: "precision 4" shows 2/3 as 0.6667, "" shows the current setting
+    fn set_precision(&mut self, digits: &str) {
+        if digits.is_empty() {
+            println!("Precision: {} significant digits", self.precision);
+            return;
+        }
+        match parse_precision(digits) {
+            Ok(precision) => {
+                self.precision = precision;
+                println!("Precision set to {} significant digits.", precision);
             }
-            Err(_) => Err("Invalid expression".to_string()),
+            Err(message) => println!("{}", message),
+        }
+    }
+
+    /// Show or change whether division by zero and domain errors are reported
+    /// Example: "off" lets 1/0 give inf, "" shows the current setting
+    fn set_strict(&mut self, setting: &str) {
+        match setting {
+            "on" => self.evaluator.set_strict(true),
+            "off" => self.evaluator.set_strict(false),
+            _ => {}
+        }
+        if self.evaluator.is_strict() {
+            println!("Strict mode is on: division by zero and domain errors are reported.");
+        } else {
+            println!("Strict mode is off: 1/0 gives inf and sqrt(-1) gives NaN.");
+        }
+    }
+
+    /// Show or change whether results are printed as JSON
+    /// Example: "on" prints {"input":"1 + 1","result":2.0,"variables":{...}} for "1 + 1"
+    fn set_json(&mut self, setting: &str) {
+        match setting {
+            "on" => self.json = true,
+            "off" => self.json = false,
+            _ => {}
+        }
+        if self.json {
+            println!("JSON output is on: each result is printed as a line of JSON.");
+        } else {
+            println!("JSON output is off.");
         }
     }
 
@@ -125,39 +245,458 @@ impl CalculatorCLI {
         println!("  2 + 3 * 4        Basic arithmetic with precedence");
         println!("  2 ^ 3            Exponentiation (right associative)");
         println!("  10 % 3           Modulo (remainder)");
-        println!("  -5               Unary minus");
+        println!("  -5, +5, --5      Unary minus and plus (-2^2 is -(2^2) = -4)");
+        println!("  1.5e3, 0xFF      Scientific notation; hex 0x, octal 0o, binary 0b");
+        println!("  1_000_000        Underscores between digits are ignored");
+        println!("  5!               Factorial (whole numbers only)");
+        println!("  2(3 + 4), 3x     Implicit multiplication after a number or ')'");
+        println!("  0xFF & 0x0F      Bitwise: & | ~ << >> (whole numbers only)");
+        println!("  3 > 2, x == 5    Comparisons: == != < <= > >= (1 = true, 0 = false)");
+        println!("  x > 0 and not y  Logic: and, or, not (0 is false, anything else true)");
+        println!("  x > 0 ? x : -x   Conditional: picks a branch by the condition");
         println!();
         println!("Variables:");
         println!("  x = 5            Assign value to variable");
         println!("  y = x + 2        Use variables in expressions");
         println!("  x = 5; y = x * 2 Multiple statements");
+        println!("  x = 2 +          A line ending partway through carries on to the next");
+        println!("  x = 5 # note     Comments run from # or // to the end of the line");
+        println!("  ans * 2          ans holds the previous result");
+        println!();
+        println!("User functions:");
+        println!("  f(x) = x^2 + 1   Define a function (built-in names can't be reused)");
+        println!("  f(3)             Call it: = 10");
         println!();
         println!("Functions:");
         println!("  sin(pi()/2)      Trigonometric: sin, cos, tan, asin, acos, atan");
         println!("  sqrt(16)         Mathematical: sqrt, abs, floor, ceil, round");
+        println!("  trunc(-2.7)      Rounding toward zero, and parts: trunc, fract, sign");
+        println!("  clamp(x, 0, 10)  Keep x between two bounds");
+        println!("  root(-8, 3)      Roots: cbrt, root (odd roots of negatives), hypot");
         println!("  ln(e()), exp(1)  Logarithmic/exponential: ln, log10, log2, exp");
         println!("  pi(), e()        Constants");
         println!("  min(5, 3)        Multi-argument: min, max, pow, atan2");
+        println!("  sum(1, 2, 3)     Any number of arguments: sum, avg, product");
+        println!("  gcd(12, 18)      Whole numbers: gcd, lcm");
+        println!("  randint(1, 6)    Random: random() in [0, 1), randint(lo, hi); seed(N) repeats");
         println!();
         println!("Commands:");
         println!("  help             Show this help");
-        println!("  vars             Show current variables");
-        println!("  clear            Clear all variables");
+        println!("  vars             Show current variables and functions");
+        println!("  clear            Clear all variables (and ans) and functions");
+        println!("  precision N      Show results to N significant digits (default {})",
+            DEFAULT_PRECISION);
+        println!("  strict on|off    Report 1/0 and sqrt(-1) as errors, or give inf/NaN");
+        println!("  json on|off      Print each result as a line of JSON");
+        println!("  fmt 2+3  *4      Show an expression with uniform spacing: 2 + 3 * 4");
+        println!("  simplify 2*3+x   Show a simpler form without evaluating it: 6 + x");
         println!("  quit             Exit calculator");
         println!();
     }
 
-    /// Show current variables
+    /// Show current variables and user-defined functions
     fn show_variables(&self) {
-        if self.variables.is_empty() {
+        let variables = self.evaluator.variables();
+        if variables.is_empty() {
             println!("No variables defined.");
         } else {
             println!("Current variables:");
-            let mut vars: Vec<_> = self.variables.iter().collect();
+            let mut vars: Vec<_> = variables.iter().collect();
             vars.sort_by_key(|(name, _)| *name);
             for (name, value) in vars {
-                println!("  {} = {}", name, value);
+                println!("  {} = {}", name, format_number(*value, self.precision));
+            }
+        }
+
+        let functions = self.evaluator.functions();
+        if !functions.is_empty() {
+            println!("Functions:");
+            let mut funcs: Vec<_> = functions.iter().collect();
+            funcs.sort_by_key(|(name, _)| *name);
+            for (name, function) in funcs {
+                println!("  {}({})", name, function.params.join(", "));
+            }
+        }
+    }
+}
+
+/// Evaluate a line of input and update the evaluator's variables and functions
+/// On error, assignments and definitions made earlier in the same line are discarded
+/// A value is also stored in `ans` for the next line to use
+fn evaluate_line(evaluator: &mut Evaluator, input: &str) -> CalcResult<Outcome> {
+    // Create lexer and parser
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer)?;
+    let program = parser.parse_ast()?;
+
+    // Evaluate on a copy, so a failing line leaves everything as it was
+    let mut updated = evaluator.clone();
+    let value = updated.evaluate_program(&program)?;
+    *evaluator = updated;
+
+    let outcome = outcome_of(&program, value);
+    if let Outcome::Value(value) = outcome {
+        evaluator.set_variable(ANS, value);
+    }
+    Ok(outcome)
+}
+
+/// The digits of a "precision N" line, or "" for a bare "precision"
+/// Other lines give None, including ones using a variable named precision,
+/// like "precision = 5" or "precision + 1"
+fn precision_command(line: &str) -> Option<&str> {
+    let digits = line.strip_prefix("precision")?.trim();
+    digits.chars().all(|ch| ch.is_ascii_digit()).then_some(digits)
+}
+
+/// Read the N of "precision N", which must be from 1 to MAX_PRECISION
+fn parse_precision(digits: &str) -> Result<usize, String> {
+    match digits.parse() {
+        Ok(precision @ 1..=MAX_PRECISION) => Ok(precision),
+        _ => Err(format!("Precision must be from 1 to {} digits.", MAX_PRECISION)),
+    }
+}
+
+/// A new evaluator whose random numbers start from `seed`, if one is given
+fn seeded_evaluator(seed: Option<u64>) -> Evaluator {
+    let mut evaluator = Evaluator::new();
+    if let Some(seed) = seed {
+        evaluator.set_seed(seed);
+    }
+    evaluator
+}
+
+/// What a program produced, given the value of its last statement
+/// A program that ends by defining a function produced that function, not a value
+fn outcome_of(program: &[Expr], value: f64) -> Outcome {
+    match program.last() {
+        Some(Expr::FunctionDef { name, params, .. }) => {
+            Outcome::Defined(format!("{}({})", name, params.join(", ")))
+        }
+        _ => Outcome::Value(value),
+    }
+}
+
+/// Evaluate piped input line by line, sharing variables and functions between lines
+/// Prints one result per line to stdout and each error to stderr, then carries on.
+/// Blank lines and lines holding only a comment are skipped, and a line that stops
+/// partway through a statement, like "x = 2 +", goes on to the next one. With `json`
+/// set, every line's result or error is printed to stdout as one line of JSON instead.
+/// With `seed` set, random numbers start from it. Results are rounded as in the
+/// REPL, and a "precision N" line changes the digits shown for the lines after it.
+/// 
+/// Returns false if any line failed, so the caller can exit with an error status.
+pub fn run_batch(input: impl BufRead, json: bool, seed: Option<u64>) -> bool {
+    let mut evaluator = seeded_evaluator(seed);
+    let mut precision = DEFAULT_PRECISION;
+    let mut lines = LineJoiner::default();
+    let mut succeeded = true;
+
+    for line in input.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                eprintln!("Error reading input: {}", error);
+                return false;
+            }
+        };
+        if let Some((number, input)) = lines.push(line.trim()) {
+            succeeded &= run_batch_input(&mut evaluator, &mut precision, number, &input, json);
+        }
+    }
+    // Input that ends partway through a statement still gets its error
+    if let Some((number, input)) = lines.finish() {
+        succeeded &= run_batch_input(&mut evaluator, &mut precision, number, &input, json);
+    }
+
+    succeeded
+}
+
+/// Evaluate one input for run_batch, starting on line `number`, and print its result
+/// A "precision N" input changes `precision` instead. Returns false if it failed
+fn run_batch_input(
+    evaluator: &mut Evaluator,
+    precision: &mut usize,
+    number: usize,
+    input: &str,
+    json: bool,
+) -> bool {
+    if let Some(digits) = precision_command(input).filter(|digits| !digits.is_empty()) {
+        return match parse_precision(digits) {
+            Ok(digits) => {
+                *precision = digits;
+                true
+            }
+            Err(message) => {
+                eprintln!("In line {}:\nError: {}", number, message);
+                false
+            }
+        };
+    }
+
+    let outcome = evaluate_line(evaluator, input);
+    let succeeded = outcome.is_ok();
+    if json {
+        print_json(input, &outcome, evaluator.variables());
+        return succeeded;
+    }
+    match outcome {
+        Ok(Outcome::Value(result)) => println!("{}", format_number(result, *precision)),
+        Ok(Outcome::Defined(signature)) => println!("Defined {}", signature),
+        Err(error) => eprintln!("In line {}:\n{}", number, error.report(input)),
+    }
+    succeeded
+}
+
+/// Run a script: evaluate its lines top to bottom, sharing variables and functions,
+/// and print the value of every statement that isn't an assignment or a definition
+/// Blank lines and lines holding only a comment are skipped, and a line that stops
+/// partway through a statement goes on to the next one. The first error stops
+/// the script and is reported with the file name and line number. With `json` set, each line's
+/// result or error is printed to stdout as one line of JSON instead. With `seed` set,
+/// random numbers start from it. Values are rounded as in the REPL, and a
+/// "precision N" line changes the digits shown for the lines after it.
+/// 
+/// Returns false if the script failed, so the caller can exit with an error status.
+pub fn run_script(file_name: &str, source: &str, json: bool, seed: Option<u64>) -> bool {
+    let mut evaluator = seeded_evaluator(seed);
+    let mut precision = DEFAULT_PRECISION;
+    let mut lines = LineJoiner::default();
+    let mut inputs: Vec<_> = source.lines().filter_map(|line| lines.push(line.trim())).collect();
+    inputs.extend(lines.finish());
+
+    for (number, input) in inputs {
+        if let Some(digits) = precision_command(&input).filter(|digits| !digits.is_empty()) {
+            match parse_precision(digits) {
+                Ok(digits) => precision = digits,
+                Err(message) => {
+                    eprintln!("In {}, line {}:\nError: {}", file_name, number, message);
+                    return false;
+                }
+            }
+            continue;
+        }
+
+        let result = Parser::new(Lexer::new(&input))
+            .and_then(|mut parser| parser.parse_ast())
+            .and_then(|program| {
+                let mut value = 0.0;
+                for statement in &program {
+                    value = evaluator.evaluate(statement)?;
+                    let is_calculation =
+                        !matches!(statement, Expr::Assignment { .. } | Expr::FunctionDef { .. });
+                    if is_calculation && !json {
+                        println!("{}", format_number(value, precision));
+                    }
+                }
+                Ok(outcome_of(&program, value))
+            });
+        if json {
+            print_json(&input, &result, evaluator.variables());
+        }
+        if let Err(error) = result {
+            if !json {
+                eprintln!("In {}, line {}:\n{}", file_name, number, error.report(&input));
             }
+            return false;
         }
     }
-}
\ No newline at end of file
+
+    true
+}
+
+/// Joins lines of input into complete inputs to evaluate
+/// A line that stops partway through a statement, like "x = 2 +" or "max(1,",
+/// is held until the lines after it finish the statement. Blank lines between
+/// statements are dropped.
+#[derive(Default)]
+struct LineJoiner {
+    pending: String,   // Lines of an unfinished statement, joined by '\n'
+    first_line: usize, // Number of the pending input's first line
+    lines_read: usize, // Lines pushed so far
+}
+
+impl LineJoiner {
+    /// Add the next line; returns the input it completes, with the number
+    /// of the line it started on (counting from 1)
+    fn push(&mut self, line: &str) -> Option<(usize, String)> {
+        self.lines_read += 1;
+        if self.pending.is_empty() {
+            if Lexer::is_blank(line) {
+                return None;
+            }
+            self.first_line = self.lines_read;
+        } else {
+            self.pending.push('\n');
+        }
+        self.pending.push_str(line);
+
+        if is_unfinished(&self.pending) {
+            return None;
+        }
+        Some((self.first_line, std::mem::take(&mut self.pending)))
+    }
+
+    /// Whether an unfinished statement is waiting for more lines
+    fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Take what's left once the input runs out: an unfinished statement, if any
+    fn finish(self) -> Option<(usize, String)> {
+        self.is_pending().then_some((self.first_line, self.pending))
+    }
+}
+
+/// Check whether input stops partway through a statement, so more should be read
+/// Examples: "x = 2 +", "max(1,", "f(x) =" → true; "x = 2", "2 +* 3" → false
+fn is_unfinished(input: &str) -> bool {
+    matches!(
+        Parser::new(Lexer::new(input)).and_then(|mut parser| parser.parse_ast()),
+        Err(CalcError::UnexpectedEof { .. })
+    )
+}
+
+/// One evaluation as printed in JSON mode; fields that don't apply are left out
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    input: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    defined: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variables: Option<BTreeMap<&'a str, f64>>, // Sorted by name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a CalcError>,
+}
+
+/// Print one evaluation as a line of JSON, for tools reading the output
+/// 
+/// Examples:
+///   {"input":"x = 2","result":2.0,"variables":{"x":2.0}}
+///   {"input":"f(x) = x^2","defined":"f(x)","variables":{}}
+///   {"input":"y","error":{"kind":"UndefinedVariable","message":"Undefined variable: y",
+///    "position":null}}
+pub fn print_json(input: &str, outcome: &CalcResult<Outcome>, variables: &HashMap<String, f64>) {
+    let mut record = JsonRecord {
+        input,
+        result: None,
+        defined: None,
+        variables: None,
+        error: None,
+    };
+    match outcome {
+        Ok(outcome) => {
+            match outcome {
+                Outcome::Value(value) => record.result = Some(*value),
+                Outcome::Defined(signature) => record.defined = Some(signature),
+            }
+            let variables = variables.iter().map(|(name, value)| (name.as_str(), *value));
+            record.variables = Some(variables.collect());
+        }
+        Err(error) => record.error = Some(error),
+    }
+    println!("{}", serde_json::to_string(&record).expect("a JSON record always serializes"));
+}
+
+/// Format a result to `precision` significant digits for display
+/// Examples (precision 10): 0.1 + 0.2 → "0.3", 2/3 → "0.6666666667", 1e20 → "1e20"
+/// 
+/// Values within 10^-precision of a whole number are shown as that number, so
+/// rounding noise like sin(pi()) = 0.00000000000000012246467991473532 shows as 0.
+/// Numbers too large or small to write out in full switch to scientific notation.
+pub fn format_number(value: f64, precision: usize) -> String {
+    if !value.is_finite() {
+        return value.to_string(); // inf, -inf and NaN
+    }
+
+    // Collapse rounding noise around whole numbers
+    let value = if (value - value.round()).abs() < 10f64.powi(-(precision as i32)) {
+        value.round()
+    } else {
+        value
+    };
+    if value == 0.0 {
+        return "0".to_string(); // Also shows -0 as 0
+    }
+
+    // Round to the requested significant digits, e.g. 2/3 → "6.666666667e-1"
+    let scientific = format!("{:.*e}", precision - 1, value);
+    let (mantissa, exponent) = scientific.split_once('e').expect("{:e} output has an exponent");
+    let exponent: i32 = exponent.parse().expect("{:e} output has an integer exponent");
+
+    if exponent < -6 || exponent >= precision as i32 {
+        format!("{}e{}", trim_zeros(mantissa), exponent)
+    } else {
+        let decimals = (precision as i32 - 1 - exponent).max(0) as usize;
+        trim_zeros(&format!("{:.*}", decimals, value)).to_string()
+    }
+}
+
+/// Drop trailing zeros after a decimal point: "1.500" → "1.5", "2.000" → "2"
+fn trim_zeros(number: &str) -> &str {
+    if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ---- Result formatting ----
+
+    #[test]
+    fn results_show_the_default_significant_digits() {
+        let format = |value: f64| format_number(value, DEFAULT_PRECISION);
+        assert_eq!(format(0.1 + 0.2), "0.3");
+        assert_eq!(format(2.0 / 3.0), "0.6666666667");
+        assert_eq!(format(-2.0 / 3.0), "-0.6666666667");
+        assert_eq!(format(1.5), "1.5");
+        assert_eq!(format(42.0), "42");
+        assert_eq!(format(123456.789), "123456.789");
+    }
+
+    #[test]
+    fn rounding_noise_collapses_to_a_whole_number() {
+        assert_eq!(format_number(std::f64::consts::PI.sin(), DEFAULT_PRECISION), "0");
+        assert_eq!(format_number(2.0000000000001, DEFAULT_PRECISION), "2");
+        assert_eq!(format_number(-0.0, DEFAULT_PRECISION), "0");
+        // Only noise below the precision collapses
+        assert_eq!(format_number(2.0001, DEFAULT_PRECISION), "2.0001");
+    }
+
+    #[test]
+    fn very_large_and_small_values_use_scientific_notation() {
+        assert_eq!(format_number(1e20, DEFAULT_PRECISION), "1e20");
+        assert_eq!(format_number(12345678901.0, DEFAULT_PRECISION), "1.23456789e10");
+        assert_eq!(format_number(1.5e-7, DEFAULT_PRECISION), "1.5e-7");
+        assert_eq!(format_number(0.000001, DEFAULT_PRECISION), "0.000001");
+    }
+
+    #[test]
+    fn precision_sets_the_significant_digits() {
+        assert_eq!(format_number(2.0 / 3.0, 4), "0.6667");
+        assert_eq!(format_number(std::f64::consts::PI, 3), "3.14");
+        assert_eq!(format_number(12345.0, 3), "1.23e4");
+        assert_eq!(format_number(0.1 + 0.2, MAX_PRECISION), "0.30000000000000004");
+    }
+
+    #[test]
+    fn non_finite_values_are_shown_as_is() {
+        assert_eq!(format_number(f64::INFINITY, DEFAULT_PRECISION), "inf");
+        assert_eq!(format_number(f64::NEG_INFINITY, DEFAULT_PRECISION), "-inf");
+        assert_eq!(format_number(f64::NAN, DEFAULT_PRECISION), "NaN");
+    }
+
+    #[test]
+    fn variables_keep_the_unrounded_value() {
+        let mut evaluator = seeded_evaluator(None);
+        assert!(evaluate_line(&mut evaluator, "x = 2/3").is_ok());
+        assert_eq!(evaluator.variables().get("x"), Some(&(2.0 / 3.0)));
+        assert_eq!(format_number(evaluator.variables()["x"], 4), "0.6667");
+    }
+}
@@ -0,0 +1,4188 @@
+// ============================================================================
+// RUST CALCULATOR - A LEARNING PROJECT FOR LEXERS AND PARSERS
+// ============================================================================
+//
+// This is a complete implementation of a calculator that demonstrates how to
+// build a lexer (tokenizer) and parser for a simple programming language.
+//
+// FEATURES:
+// - Arithmetic operators: + - * / % ^ ! (with correct precedence)
+// - Variables: x = 5; y = x + 2
+// - Parentheses for grouping: (2 + 3) * 4
+// - Implicit multiplication: 2(3 + 4), 2pi(), 3x
+// - Number literals: 1.5e3, 0xFF, 0o17, 0b1010, 1_000_000
+// - Multiple statements: x = 5; y = x + 2; x * y, or one per line
+// - Comments: x = 5 # initial value
+// - Bitwise operators on whole numbers: 0xFF & 0x0F, 1 << 4, ~0
+// - Comparisons: 3 > 2 gives 1, 2 == 3 gives 0
+// - Logic: x > 0 and not (x > 10)
+// - Conditionals: x > 0 ? x : -x
+// - User-defined functions: f(x) = x^2 + 1; f(3)
+// - Previous result in the REPL: ans * 2
+//
+// This file is the library: the calculator can be embedded in another program
+// with evaluate() or Calculator. main.rs builds the command line tool on top.
+//
+// ARCHITECTURE:
+// 1. LEXER: Converts text "2 + 3" into tokens [Number(2), Plus, Number(3)]
+// 2. PARSER: Uses recursive descent to build a syntax tree (AST) from tokens
+// 3. EVALUATOR: Walks the tree to compute the result
+//
+// PRECEDENCE (highest to lowest):
+// - Parentheses: ()
+// - Factorial: ! (postfix)
+// - Power: ^ (right associative)
+// - Unary: - + ~ (prefix)
+// - Multiply/Divide/Modulo: * / %
+// - Add/Subtract: + -
+// - Shifts: << >>
+// - Bitwise and: &
+// - Bitwise or: |
+// - Comparison: == != < <= > >=
+// - Logical: not, and, or
+// - Conditional: ? : (right associative)
+//
+// This is an excellent starting point for learning compiler/interpreter design!
+//
+// ============================================================================
+// TOKEN DEFINITION
+// ============================================================================
+// Tokens are the "words" of our programming language. The lexer breaks down
+// source code into these atomic units that the parser can understand.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    // Literals and identifiers
+    Number(f64),         // Numbers like 3.14, 42, 1.5e3, 0xFF
+    Identifier(String),  // Variable names like "x", "foo", "my_var"
+    
+    // Arithmetic operators (in order of precedence, lowest to highest)
+    Plus,                // + addition
+    Minus,               // - subtraction  
+    Multiply,            // * multiplication
+    Divide,              // / division
+    Modulo,              // % remainder (e.g., 10 % 3 = 1)
+    Power,               // ^ exponentiation (e.g., 2^3 = 8)
+    Bang,                // ! factorial, written after its operand (e.g., 5! = 120)
+    
+    // Bitwise operators (work on whole numbers)
+    Ampersand,           // & bitwise and
+    Pipe,                // | bitwise or
+    Tilde,               // ~ bitwise not
+    ShiftLeft,           // << shift bits left
+    ShiftRight,          // >> shift bits right
+    
+    // Comparison operators (give 1 for true, 0 for false)
+    EqEq,                // == equal
+    NotEq,               // != not equal
+    Less,                // < less than
+    LessEq,              // <= less than or equal
+    Greater,             // > greater than
+    GreaterEq,           // >= greater than or equal
+    
+    // Logical keywords (0 is false, anything else is true)
+    And,                 // and
+    Or,                  // or
+    Not,                 // not
+    
+    // Grouping and structure
+    LeftParen,           // ( for grouping expressions
+    RightParen,          // ) for grouping expressions
+    Comma,               // , to separate function arguments
+    Assign,              // = for variable assignment
+    Semicolon,           // ; to separate statements
+    Newline,             // A line break, which also separates statements
+    Question,            // ? in a conditional: cond ? a : b
+    Colon,               // : in a conditional: cond ? a : b
+    
+    // Functions
+    Function(String),    // Function names like "sin", "cos", "tan"
+    
+    // Special
+    EOF,                 // End of file/input marker
+}
+
+/// How a token is described in error messages
+/// Example: Token::Plus → "'+'", Token::Number(3.0) → "number 3"
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "number {}", value),
+            Token::Identifier(name) => write!(f, "identifier '{}'", name),
+            Token::Function(name) => write!(f, "function '{}'", name),
+            _ => write!(f, "{}", self.kind()),
+        }
+    }
+}
+
+/// What sort of token something is, without the value it carries
+/// The parser states what it expects as a kind, so checking for an identifier
+/// doesn't need a made-up name to compare against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Number,
+    Identifier,
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    Modulo,
+    Power,
+    Bang,
+    Ampersand,
+    Pipe,
+    Tilde,
+    ShiftLeft,
+    ShiftRight,
+    EqEq,
+    NotEq,
+    Less,
+    LessEq,
+    Greater,
+    GreaterEq,
+    And,
+    Or,
+    Not,
+    LeftParen,
+    RightParen,
+    Comma,
+    Assign,
+    Semicolon,
+    Newline,
+    Question,
+    Colon,
+    Function,
+    EOF,
+}
+
+impl Token {
+    /// The kind of this token
+    /// Example: Token::Identifier("x") → TokenKind::Identifier
+    pub fn kind(&self) -> TokenKind {
+        match self {
+            Token::Number(_) => TokenKind::Number,
+            Token::Identifier(_) => TokenKind::Identifier,
+            Token::Plus => TokenKind::Plus,
+            Token::Minus => TokenKind::Minus,
+            Token::Multiply => TokenKind::Multiply,
+            Token::Divide => TokenKind::Divide,
+            Token::Modulo => TokenKind::Modulo,
+            Token::Power => TokenKind::Power,
+            Token::Bang => TokenKind::Bang,
+            Token::Ampersand => TokenKind::Ampersand,
+            Token::Pipe => TokenKind::Pipe,
+            Token::Tilde => TokenKind::Tilde,
+            Token::ShiftLeft => TokenKind::ShiftLeft,
+            Token::ShiftRight => TokenKind::ShiftRight,
+            Token::EqEq => TokenKind::EqEq,
+            Token::NotEq => TokenKind::NotEq,
+            Token::Less => TokenKind::Less,
+            Token::LessEq => TokenKind::LessEq,
+            Token::Greater => TokenKind::Greater,
+            Token::GreaterEq => TokenKind::GreaterEq,
+            Token::And => TokenKind::And,
+            Token::Or => TokenKind::Or,
+            Token::Not => TokenKind::Not,
+            Token::LeftParen => TokenKind::LeftParen,
+            Token::RightParen => TokenKind::RightParen,
+            Token::Comma => TokenKind::Comma,
+            Token::Assign => TokenKind::Assign,
+            Token::Semicolon => TokenKind::Semicolon,
+            Token::Newline => TokenKind::Newline,
+            Token::Question => TokenKind::Question,
+            Token::Colon => TokenKind::Colon,
+            Token::Function(_) => TokenKind::Function,
+            Token::EOF => TokenKind::EOF,
+        }
+    }
+}
+
+/// How an expected kind is described in error messages
+/// Example: TokenKind::Plus → "'+'", TokenKind::Number → "a number"
+impl std::fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TokenKind::Number => write!(f, "a number"),
+            TokenKind::Identifier => write!(f, "a name"),
+            TokenKind::Function => write!(f, "a function"),
+            TokenKind::Plus => write!(f, "'+'"),
+            TokenKind::Minus => write!(f, "'-'"),
+            TokenKind::Multiply => write!(f, "'*'"),
+            TokenKind::Divide => write!(f, "'/'"),
+            TokenKind::Modulo => write!(f, "'%'"),
+            TokenKind::Power => write!(f, "'^'"),
+            TokenKind::Bang => write!(f, "'!'"),
+            TokenKind::Ampersand => write!(f, "'&'"),
+            TokenKind::Pipe => write!(f, "'|'"),
+            TokenKind::Tilde => write!(f, "'~'"),
+            TokenKind::ShiftLeft => write!(f, "'<<'"),
+            TokenKind::ShiftRight => write!(f, "'>>'"),
+            TokenKind::EqEq => write!(f, "'=='"),
+            TokenKind::NotEq => write!(f, "'!='"),
+            TokenKind::Less => write!(f, "'<'"),
+            TokenKind::LessEq => write!(f, "'<='"),
+            TokenKind::Greater => write!(f, "'>'"),
+            TokenKind::GreaterEq => write!(f, "'>='"),
+            TokenKind::And => write!(f, "'and'"),
+            TokenKind::Or => write!(f, "'or'"),
+            TokenKind::Not => write!(f, "'not'"),
+            TokenKind::LeftParen => write!(f, "'('"),
+            TokenKind::RightParen => write!(f, "')'"),
+            TokenKind::Comma => write!(f, "','"),
+            TokenKind::Assign => write!(f, "'='"),
+            TokenKind::Semicolon => write!(f, "';'"),
+            TokenKind::Newline => write!(f, "end of line"),
+            TokenKind::Question => write!(f, "'?'"),
+            TokenKind::Colon => write!(f, "':'"),
+            TokenKind::EOF => write!(f, "end of input"),
+        }
+    }
+}
+
+// ============================================================================
+// SOURCE POSITIONS
+// ============================================================================
+// Every token remembers where it came from, so errors can point at the exact
+// spot in the input. Lines and columns count from 1, and columns count
+// characters rather than bytes.
+
+use serde::Serialize;
+
+/// A place in the input, like line 1, column 5
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// The stretch of input a token covers: `start` is its first character and
+/// `end` is just past its last one
+/// Example: in "x + 3.14", the number covers columns 5 to 9 (end is exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// A token together with where it was found
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+// Instead of panicking, the lexer and parser return a CalcError describing
+// what went wrong. The `?` operator passes an error up through the recursive
+// descent, so the first problem found stops the whole parse.
+//
+// Syntax errors carry the span of the offending token; errors about names
+// are reported by name alone.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcError {
+    UnexpectedCharacter { ch: char, span: Span },                    // "2 $ 3"
+    MalformedNumber { text: String, span: Span },                    // "2e+"
+    UnexpectedToken { expected: String, found: Token, span: Span }, // "2 3", "(2 + 3))"
+    UnexpectedEof { expected: String, span: Span },                  // "2 +", "sin("
+    UndefinedVariable(String),                                       // "y + 1", y unassigned
+    UnknownFunction(String),                                         // "foo(1)"
+    WrongArgumentCount { name: String, expected: Arity, found: usize }, // "sqrt(1, 2)"
+    DivisionByZero { dividend: f64, operator: char },               // "1 / 0", "5 % 0"
+    DomainError { name: String, arg: f64, requirement: &'static str },  // "ln(0)"
+    InvalidOperand { operator: String, value: f64, requirement: &'static str }, // "1.5 & 2"
+    ReservedName(String),                                            // "sin(x) = x"
+    RecursionLimit(String),                                          // "f(x) = f(x); f(1)"
+}
+
+impl CalcError {
+    /// The name of the kind of error, for tools that handle some kinds specially
+    /// Example: "UndefinedVariable" for CalcError::UndefinedVariable("y")
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CalcError::UnexpectedCharacter { .. } => "UnexpectedCharacter",
+            CalcError::MalformedNumber { .. } => "MalformedNumber",
+            CalcError::UnexpectedToken { .. } => "UnexpectedToken",
+            CalcError::UnexpectedEof { .. } => "UnexpectedEof",
+            CalcError::UndefinedVariable(_) => "UndefinedVariable",
+            CalcError::UnknownFunction(_) => "UnknownFunction",
+            CalcError::WrongArgumentCount { .. } => "WrongArgumentCount",
+            CalcError::DivisionByZero { .. } => "DivisionByZero",
+            CalcError::DomainError { .. } => "DomainError",
+            CalcError::InvalidOperand { .. } => "InvalidOperand",
+            CalcError::ReservedName(_) => "ReservedName",
+            CalcError::RecursionLimit(_) => "RecursionLimit",
+        }
+    }
+
+    /// Where in the input the error was found, if it's tied to one spot
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            CalcError::UnexpectedCharacter { span, .. }
+            | CalcError::MalformedNumber { span, .. }
+            | CalcError::UnexpectedToken { span, .. }
+            | CalcError::UnexpectedEof { span, .. } => Some(*span),
+            CalcError::UndefinedVariable(_)
+            | CalcError::UnknownFunction(_)
+            | CalcError::WrongArgumentCount { .. }
+            | CalcError::DivisionByZero { .. }
+            | CalcError::DomainError { .. }
+            | CalcError::InvalidOperand { .. }
+            | CalcError::ReservedName(_)
+            | CalcError::RecursionLimit(_) => None,
+        }
+    }
+
+    /// Describe the error for a user, quoting the line of `input` it was found
+    /// on with carets under the offending token:
+    ///
+    ///   2 + $ 3
+    ///       ^
+    ///   Error: Unexpected character '$' at line 1, column 5
+    ///
+    /// ```
+    /// use rust_calculator::evaluate;
+    ///
+    /// let error = evaluate("2 + $ 3").unwrap_err();
+    /// assert_eq!(
+    ///     error.report("2 + $ 3"),
+    ///     "2 + $ 3\n    ^\nError: Unexpected character '$' at line 1, column 5"
+    /// );
+    /// ```
+    pub fn report(&self, input: &str) -> String {
+        let Some(span) = self.span() else {
+            return format!("Error: {}", self);
+        };
+        let line = input.lines().nth(span.start.line - 1).unwrap_or("");
+
+        // Pad with the line's own tabs so the carets line up under it
+        let padding: String = line
+            .chars()
+            .take(span.start.column - 1)
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+        let width = if span.end.line == span.start.line {
+            span.end.column.saturating_sub(span.start.column).max(1)
+        } else {
+            1
+        };
+
+        format!("{}\n{}{}\nError: {}", line, padding, "^".repeat(width), self)
+    }
+}
+
+impl std::fmt::Display for CalcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CalcError::UnexpectedCharacter { ch, span } => {
+                write!(f, "Unexpected character '{}' at {}", ch, span.start)
+            }
+            CalcError::MalformedNumber { text, span } => {
+                write!(f, "Malformed number '{}' at {}", text, span.start)
+            }
+            CalcError::UnexpectedToken { expected, found, span } => {
+                write!(f, "Expected {}, found {} at {}", expected, found, span.start)
+            }
+            CalcError::UnexpectedEof { expected, span } => {
+                write!(f, "Expected {}, but the input ended at {}", expected, span.start)
+            }
+            CalcError::UndefinedVariable(name) => write!(f, "Undefined variable: {}", name),
+            CalcError::UnknownFunction(name) => write!(f, "Unknown function: {}", name),
+            CalcError::WrongArgumentCount { name, expected, found } => {
+                write!(f, "{}() takes {}, got {}", name, expected, found)
+            }
+            CalcError::DivisionByZero { dividend, operator } => {
+                let operation = if *operator == '%' { "Modulo" } else { "Division" };
+                write!(f, "{} by zero: {} {} 0", operation, dividend, operator)
+            }
+            CalcError::DomainError { name, arg, requirement } => {
+                write!(f, "{}() needs {}, got {}", name, requirement, arg)
+            }
+            CalcError::InvalidOperand { operator, value, requirement } => {
+                write!(f, "'{}' needs {}, got {}", operator, requirement, value)
+            }
+            CalcError::ReservedName(name) => {
+                write!(f, "'{}' is a built-in name and can't be redefined", name)
+            }
+            CalcError::RecursionLimit(name) => {
+                write!(f, "{}() calls itself more than {} levels deep", name, MAX_CALL_DEPTH)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+/// An error is serialized by kind, message and the position it starts at:
+/// {"kind": "UnexpectedCharacter", "message": "Unexpected character '$' at line 1,
+/// column 5", "position": {"line": 1, "column": 5}}, with a null position for
+/// errors that aren't tied to one spot
+impl Serialize for CalcError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut error = serializer.serialize_struct("CalcError", 3)?;
+        error.serialize_field("kind", self.kind())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.serialize_field("position", &self.span().map(|span| span.start))?;
+        error.end()
+    }
+}
+
+/// How many arguments a function takes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arity {
+    Exactly(usize), // "sqrt" takes Exactly(1)
+    AtLeast(usize), // "sum" takes AtLeast(1): any number of arguments, but not none
+}
+
+impl Arity {
+    /// Check whether a call with `count` arguments is allowed
+    pub fn accepts(self, count: usize) -> bool {
+        match self {
+            Arity::Exactly(expected) => count == expected,
+            Arity::AtLeast(minimum) => count >= minimum,
+        }
+    }
+}
+
+/// How an arity is described in error messages
+/// Example: Exactly(1) → "1 argument", AtLeast(2) → "at least 2 arguments"
+impl std::fmt::Display for Arity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (prefix, count) = match self {
+            Arity::Exactly(count) => ("", count),
+            Arity::AtLeast(count) => ("at least ", count),
+        };
+        let plural = if *count == 1 { "" } else { "s" };
+        write!(f, "{}{} argument{}", prefix, count, plural)
+    }
+}
+
+/// Result type used throughout the lexer and parser
+pub type CalcResult<T> = Result<T, CalcError>;
+
+// ============================================================================
+// LEXER (TOKENIZER)
+// ============================================================================
+// The lexer's job is to take raw text like "x = 2 + 3" and break it into
+// tokens like [Identifier("x"), Assign, Number(2.0), Plus, Number(3.0)]
+//
+// Think of it like reading a sentence and identifying: noun, verb, adjective, etc.
+
+#[derive(Clone)]
+pub struct Lexer {
+    input: Vec<char>,           // The source code as individual characters
+    position: usize,            // Current position in the input
+    current_char: Option<char>, // The character we're currently looking at
+    line: usize,                // Line of current_char, counting from 1
+    column: usize,              // Column of current_char, counting from 1
+    open_parens: usize,         // '(' read so far without a matching ')'
+    line_continues: bool,       // Whether the last token can't end a statement
+}
+
+impl Lexer {
+    /// Create a new lexer from input string
+    /// Example: Lexer::new("2 + 3") sets up lexer to tokenize "2 + 3"
+    pub fn new(input: &str) -> Self {
+        let chars: Vec<char> = input.chars().collect();
+        let current_char = chars.first().copied(); // Start at first character
+        
+        Lexer {
+            input: chars,
+            position: 0,
+            current_char,
+            line: 1,
+            column: 1,
+            open_parens: 0,
+            line_continues: true, // Line breaks before the first token separate nothing
+        }
+    }
+
+    /// Check whether the input holds nothing to evaluate: only whitespace and comments
+    /// Examples: "", "  ", "# just a note" → true; "1 # one" → false
+    pub fn is_blank(input: &str) -> bool {
+        matches!(Lexer::new(input).next_token(), Ok(SpannedToken { token: Token::EOF, .. }))
+    }
+
+    /// Move to the next character in the input
+    /// Like moving a cursor forward when reading text
+    /// Stepping past a newline moves to the start of the next line
+    fn advance(&mut self) {
+        if self.current_char == Some('\n') {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        self.position += 1;
+        self.current_char = self.input.get(self.position).copied();
+    }
+
+    /// Look at the character after the current one without moving
+    /// Used to tell two-character operators like "<=" from "<"
+    fn peek(&self) -> Option<char> {
+        self.input.get(self.position + 1).copied()
+    }
+
+    /// Read an operator that may be followed by '=' to form a longer one
+    /// Example: at "<= 3", returns `with_eq` (LessEq); at "< 3", returns `alone` (Less)
+    fn read_operator(&mut self, alone: Token, with_eq: Token) -> Token {
+        let token = if self.peek() == Some('=') {
+            self.advance();
+            with_eq
+        } else {
+            alone
+        };
+        self.advance();
+        token
+    }
+
+    /// Skip over whitespace characters (spaces, tabs, newlines) and comments
+    /// We ignore both since they don't affect meaning in our language,
+    /// except for a newline that ends a statement (see note_token)
+    /// 
+    /// A comment runs from '#' or '//' to the end of the line.
+    /// Example: "x = 5 # initial value" reads the same as "x = 5"
+    fn skip_whitespace(&mut self) {
+        while let Some(ch) = self.current_char {
+            if ch == '\n' && self.open_parens == 0 && !self.line_continues {
+                break; // Read as a Newline token
+            } else if ch.is_whitespace() {
+                self.advance();
+            } else if ch == '#' || (ch == '/' && self.peek() == Some('/')) {
+                self.skip_comment();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Skip a comment, stopping at the newline (if any) that ends it
+    fn skip_comment(&mut self) {
+        while let Some(ch) = self.current_char
+            && ch != '\n'
+        {
+            self.advance();
+        }
+    }
+
+    /// Read a complete number (including decimals and an exponent)
+    /// Examples: "42" -> 42.0, "3.14" -> 3.14, "1.5e3" -> 1500.0, "2E-4" -> 0.0002
+    /// 
+    /// An 'e' followed by a letter or '(' starts a name instead, so "2e()" and
+    /// "2exp(1)" still multiply 2 by a function. Any other 'e' must be followed
+    /// by digits: "2e" and "2e+" are malformed numbers.
+    /// 
+    /// A leading "0x", "0o" or "0b" switches to hexadecimal, octal or binary.
+    /// 
+    /// A decimal point may come first or last ("5." -> 5.0, ".5" -> 0.5), but
+    /// only once: "1.2.3" and a lone "." are malformed numbers.
+    /// 
+    /// Underscores can separate digits for readability: "1_000_000" -> 1000000.0.
+    /// Each one must sit between two digits, so "100_", "1__0" and "1_.5" are
+    /// malformed numbers ("_100" never gets here - it's a variable name).
+    fn read_number(&mut self) -> CalcResult<f64> {
+        let start = self.location();
+        if self.current_char == Some('0') {
+            let radix = match self.peek() {
+                Some('x' | 'X') => Some(16),
+                Some('o' | 'O') => Some(8),
+                Some('b' | 'B') => Some(2),
+                _ => None,
+            };
+            if let Some(radix) = radix {
+                return self.read_radix_number(radix, start);
+            }
+        }
+
+        let mut number_str = String::new();
+        
+        // Keep reading digits, decimal points and separators
+        while let Some(ch) = self.current_char {
+            if ch.is_ascii_digit() || ch == '.' || ch == '_' {
+                number_str.push(ch);
+                self.advance();
+            } else {
+                break; // Stop when we hit a non-digit, non-decimal character
+            }
+        }
+
+        // Optional exponent: 'e' or 'E', an optional sign, then digits
+        if let Some(marker @ ('e' | 'E')) = self.current_char {
+            let starts_name = matches!(
+                self.peek(),
+                Some(ch) if ch.is_ascii_alphabetic() || ch == '_' || ch == '('
+            );
+            if !starts_name {
+                number_str.push(marker);
+                self.advance();
+                if let Some(sign @ ('+' | '-')) = self.current_char {
+                    number_str.push(sign);
+                    self.advance();
+                }
+
+                let mut has_digits = false;
+                let is_exponent_char = |ch: &char| ch.is_ascii_digit() || *ch == '_';
+                while let Some(ch) = self.current_char.filter(is_exponent_char) {
+                    number_str.push(ch);
+                    self.advance();
+                    has_digits |= ch != '_';
+                }
+                if !has_digits {
+                    return Err(CalcError::MalformedNumber {
+                        text: number_str,
+                        span: Span {
+                            start,
+                            end: self.location(),
+                        },
+                    });
+                }
+            }
+        }
+
+        // Convert string to number (without separators). Text that doesn't parse,
+        // like "1.2.3" or a lone ".", is reported instead of becoming some value.
+        let value = if Self::separators_are_valid(&number_str, 10) {
+            number_str.replace('_', "").parse().ok()
+        } else {
+            None
+        };
+        value.ok_or_else(|| CalcError::MalformedNumber {
+            text: number_str,
+            span: Span {
+                start,
+                end: self.location(),
+            },
+        })
+    }
+
+    /// Check that every '_' in a number sits between two digits of the given base
+    /// Examples: "1_000" and "FF_FF" (base 16) pass; "100_", "1__0" and "1_.5" don't
+    fn separators_are_valid(digits: &str, radix: u32) -> bool {
+        let chars: Vec<char> = digits.chars().collect();
+        chars.iter().enumerate().all(|(i, &ch)| {
+            let is_digit = |index: Option<usize>| {
+                index.and_then(|index| chars.get(index)).is_some_and(|c| c.is_digit(radix))
+            };
+            ch != '_' || (is_digit(i.checked_sub(1)) && is_digit(Some(i + 1)))
+        })
+    }
+
+    /// Read an integer literal in another base, starting at its "0x"/"0o"/"0b" prefix
+    /// Examples: "0xFF" -> 255.0, "0o17" -> 15.0, "0b1010" -> 10.0
+    /// 
+    /// The value is built up as an f64 like every other number, so it can't
+    /// overflow, though past 2^53 not every integer can be represented exactly.
+    /// A missing or invalid digit, as in "0x" or "0b102", is a malformed number,
+    /// and so is a misplaced separator, as in "0x_FF" ("0xFF_FF" is fine).
+    fn read_radix_number(&mut self, radix: u32, start: Position) -> CalcResult<f64> {
+        let mut number_str = String::new();
+        for _ in 0..2 {
+            number_str.extend(self.current_char); // The '0' and the base letter
+            self.advance();
+        }
+
+        // Read every letter and digit, so a bad digit is part of the malformed number
+        let mut value = 0.0;
+        let mut valid = true;
+        let mut has_digits = false;
+        let is_number_char = |ch: &char| ch.is_ascii_alphanumeric() || *ch == '_';
+        while let Some(ch) = self.current_char.filter(is_number_char) {
+            match ch.to_digit(radix) {
+                Some(digit) => {
+                    value = value * radix as f64 + digit as f64;
+                    has_digits = true;
+                }
+                None => valid &= ch == '_',
+            }
+            number_str.push(ch);
+            self.advance();
+        }
+
+        if valid && has_digits && Self::separators_are_valid(&number_str[2..], radix) {
+            Ok(value)
+        } else {
+            Err(CalcError::MalformedNumber {
+                text: number_str,
+                span: Span {
+                    start,
+                    end: self.location(),
+                },
+            })
+        }
+    }
+
+    /// Read a complete identifier (variable name)
+    /// Examples: "x" -> "x", "my_var" -> "my_var", "foo123" -> "foo123"
+    /// Rules: Must start with letter or underscore, then can contain letters, digits, underscores
+    fn read_identifier(&mut self) -> String {
+        let mut identifier = String::new();
+        
+        // Keep reading valid identifier characters
+        while let Some(ch) = self.current_char {
+            if ch.is_ascii_alphabetic() || ch.is_ascii_digit() || ch == '_' {
+                identifier.push(ch);
+                self.advance();
+            } else {
+                break; // Stop when we hit an invalid identifier character
+            }
+        }
+        
+        identifier
+    }
+
+    /// Where the lexer currently is in the input
+    fn location(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    /// Get the next token from the input, along with the span it covers
+    /// Example: in "x + 3.14", the third call returns Number(3.14) spanning
+    ///          line 1, columns 5 to 9
+    /// At the end of input, EOF gets an empty span just past the last character
+    ///
+    /// ```
+    /// use rust_calculator::{Lexer, Token};
+    ///
+    /// let mut lexer = Lexer::new("x + 3.14");
+    /// assert_eq!(lexer.next_token().unwrap().token, Token::Identifier("x".to_string()));
+    /// assert_eq!(lexer.next_token().unwrap().token, Token::Plus);
+    /// assert_eq!(lexer.next_token().unwrap().token, Token::Number(3.14));
+    /// assert_eq!(lexer.next_token().unwrap().token, Token::EOF);
+    /// ```
+    pub fn next_token(&mut self) -> CalcResult<SpannedToken> {
+        self.skip_whitespace();
+        let start = self.location();
+        let token = self.read_token()?;
+        self.note_token(&token);
+        Ok(SpannedToken {
+            token,
+            span: Span {
+                start,
+                end: self.location(),
+            },
+        })
+    }
+
+    /// Keep track of whether a newline after `token` ends the statement
+    /// 
+    /// A newline separates statements like ';' does, unless the statement
+    /// clearly goes on: inside parentheses, or after a token that can't end a
+    /// statement, such as an operator, ',' or '='. Several newlines in a row
+    /// count as one.
+    /// 
+    /// Examples:
+    ///   - "x = 5\nx * 2" → two statements
+    ///   - "x = 5 +\n2" and "max(1,\n2)" → one statement each
+    fn note_token(&mut self, token: &Token) {
+        match token {
+            Token::LeftParen => self.open_parens += 1,
+            Token::RightParen => self.open_parens = self.open_parens.saturating_sub(1),
+            _ => {}
+        }
+        self.line_continues = !matches!(
+            token,
+            Token::Number(_) | Token::Identifier(_) | Token::RightParen | Token::Bang
+        );
+    }
+
+    /// Read one token starting at the current character
+    /// This is the main method that identifies what kind of token we're looking at
+    /// and returns the appropriate Token enum variant, or an error for a
+    /// character that can't start any token
+    fn read_token(&mut self) -> CalcResult<Token> {
+        // Keep processing characters until we find a token or reach end of input
+        while let Some(ch) = self.current_char {
+            match ch {
+                // Whitespace: skip it and continue
+                ' ' | '\t' => {
+                    self.skip_whitespace();
+                    continue;
+                }
+                
+                // A newline that wasn't skipped as whitespace ends the statement
+                '\n' => {
+                    self.advance();
+                    return Ok(Token::Newline);
+                }
+                
+                // Single-character operators: recognize and advance
+                '+' => {
+                    self.advance();
+                    return Ok(Token::Plus);
+                }
+                '-' => {
+                    self.advance();
+                    return Ok(Token::Minus);
+                }
+                '*' => {
+                    self.advance();
+                    return Ok(Token::Multiply);
+                }
+                '/' => {
+                    self.advance();
+                    return Ok(Token::Divide);
+                }
+                '^' => {
+                    self.advance();
+                    return Ok(Token::Power);
+                }
+                '%' => {
+                    self.advance();
+                    return Ok(Token::Modulo);
+                }
+                '(' => {
+                    self.advance();
+                    return Ok(Token::LeftParen);
+                }
+                ')' => {
+                    self.advance();
+                    return Ok(Token::RightParen);
+                }
+                
+                // One or two-character operators: '=' vs '==', '<' vs '<=', ...
+                '=' => return Ok(self.read_operator(Token::Assign, Token::EqEq)),
+                '<' if self.peek() == Some('<') => {
+                    self.advance();
+                    self.advance();
+                    return Ok(Token::ShiftLeft);
+                }
+                '>' if self.peek() == Some('>') => {
+                    self.advance();
+                    self.advance();
+                    return Ok(Token::ShiftRight);
+                }
+                '<' => return Ok(self.read_operator(Token::Less, Token::LessEq)),
+                '>' => return Ok(self.read_operator(Token::Greater, Token::GreaterEq)),
+                '&' => {
+                    self.advance();
+                    return Ok(Token::Ampersand);
+                }
+                '|' => {
+                    self.advance();
+                    return Ok(Token::Pipe);
+                }
+                '~' => {
+                    self.advance();
+                    return Ok(Token::Tilde);
+                }
+                // '!=' is not-equal; '!' on its own is factorial
+                '!' => return Ok(self.read_operator(Token::Bang, Token::NotEq)),
+                
+
+                ';' => {
+                    self.advance();
+                    return Ok(Token::Semicolon);
+                }
+                ',' => {
+                    self.advance();
+                    return Ok(Token::Comma);
+                }
+                '?' => {
+                    self.advance();
+                    return Ok(Token::Question);
+                }
+                ':' => {
+                    self.advance();
+                    return Ok(Token::Colon);
+                }
+                
+                // Multi-character tokens: use helper methods
+                _ if ch.is_ascii_digit() || ch == '.' => {
+                    // Found a digit or decimal point, read the complete number
+                    let number = self.read_number()?;
+                    return Ok(Token::Number(number));
+                }
+                _ if ch.is_ascii_alphabetic() || ch == '_' => {
+                    // Found a letter or underscore, read the complete identifier
+                    let identifier = self.read_identifier();
+                    
+                    // Check if this is a keyword or a known function name
+                    return Ok(match identifier.as_str() {
+                        // Logical keywords
+                        "and" => Token::And,
+                        "or" => Token::Or,
+                        "not" => Token::Not,
+                        // Trigonometric functions
+                        "sin" | "cos" | "tan" | "asin" | "acos" | "atan" |
+                        // Mathematical functions
+                        "sqrt" | "abs" | "floor" | "ceil" | "round" | "trunc" | "fract" | "sign" |
+                        "clamp" | "cbrt" | "hypot" | "root" |
+                        // Logarithmic and exponential functions
+                        "ln" | "log10" | "log2" | "exp" |
+                        // Mathematical constants (zero-argument functions)
+                        "pi" | "e" |
+                        // Multi-argument functions
+                        "min" | "max" | "pow" | "atan2" | "gcd" | "lcm" |
+                        // Functions of any number of arguments
+                        "sum" | "avg" | "product" |
+                        // Random numbers
+                        "random" | "randint" | "seed" => Token::Function(identifier),
+                        _ => Token::Identifier(identifier),
+                    });
+                }
+                
+                // Unknown character: this is an error
+                _ => {
+                    let start = self.location();
+                    self.advance();
+                    let span = Span {
+                        start,
+                        end: self.location(),
+                    };
+                    return Err(CalcError::UnexpectedCharacter { ch, span });
+                }
+            }
+        }
+        
+        // No more characters to process
+        Ok(Token::EOF)
+    }
+}
+
+use std::collections::{HashMap, VecDeque};
+
+// ============================================================================
+// ABSTRACT SYNTAX TREE (AST)
+// ============================================================================
+// The parser doesn't compute anything itself. It turns tokens into a tree
+// that records the structure of the input, and the evaluator walks that tree
+// to get a value. Keeping the two apart means a tree can be inspected,
+// rewritten or evaluated again without re-parsing.
+//
+// Example: "2 + 3 * 4" becomes
+//
+//        BinaryOp(+)
+//        /         \
+//   Number(2)   BinaryOp(*)
+//               /         \
+//          Number(3)   Number(4)
+
+/// Operators that combine two values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperator {
+    Add,      // +
+    Subtract, // -
+    Multiply, // *
+    Divide,   // /
+    Modulo,   // %
+    Power,    // ^
+
+    // Bitwise operators work on whole numbers that fit in 64 bits
+    BitAnd,     // &
+    BitOr,      // |
+    ShiftLeft,  // <<
+    ShiftRight, // >>
+
+    // Comparisons give 1.0 for true and 0.0 for false
+    Equal,        // ==
+    NotEqual,     // !=
+    Less,         // <
+    LessEqual,    // <=
+    Greater,      // >
+    GreaterEqual, // >=
+
+    // Logical operators give 1.0 or 0.0, and only evaluate the right side
+    // when the left side doesn't already decide the result
+    And, // and
+    Or,  // or
+}
+
+impl BinaryOperator {
+    /// How the operator is written
+    /// Examples: Add → "+", ShiftLeft → "<<", And → "and"
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Modulo => "%",
+            BinaryOperator::Power => "^",
+            BinaryOperator::BitAnd => "&",
+            BinaryOperator::BitOr => "|",
+            BinaryOperator::ShiftLeft => "<<",
+            BinaryOperator::ShiftRight => ">>",
+            BinaryOperator::Equal => "==",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::Less => "<",
+            BinaryOperator::LessEqual => "<=",
+            BinaryOperator::Greater => ">",
+            BinaryOperator::GreaterEqual => ">=",
+            BinaryOperator::And => "and",
+            BinaryOperator::Or => "or",
+        }
+    }
+}
+
+/// Operators that apply to a single value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOperator {
+    Negate,    // -x
+    Not,       // not x
+    BitNot,    // ~x
+    Factorial, // x!
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),                                        // 42
+    Variable(String),                                   // x
+    BinaryOp {                                          // 2 + 3
+        op: BinaryOperator,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    UnaryOp { op: UnaryOperator, operand: Box<Expr> },  // -x
+    FunctionCall { name: String, args: Vec<Expr> },     // min(1, 2)
+    Assignment { name: String, value: Box<Expr> },      // x = 5
+    FunctionDef {                                       // f(x) = x^2 + 1
+        name: String,
+        params: Vec<String>,
+        body: Box<Expr>,
+    },
+    Conditional {                                       // x > 0 ? x : -x
+        condition: Box<Expr>,
+        then: Box<Expr>,
+        otherwise: Box<Expr>,
+    },
+}
+
+impl Expr {
+    /// Shorthand for building a BinaryOp node
+    pub fn binary(op: BinaryOperator, left: Expr, right: Expr) -> Expr {
+        Expr::BinaryOp {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    /// Shorthand for building a UnaryOp node
+    pub fn unary(op: UnaryOperator, operand: Expr) -> Expr {
+        Expr::UnaryOp {
+            op,
+            operand: Box::new(operand),
+        }
+    }
+}
+
+// ============================================================================
+// PARSER (RECURSIVE DESCENT)
+// ============================================================================
+// The parser takes tokens from the lexer and builds an AST describing the
+// program structure. It uses "recursive descent" - each grammar rule becomes
+// a method that calls other methods.
+//
+// Our grammar (in order of precedence, lowest to highest):
+//   program    → statement ((';' | NEWLINE) statement)*
+//   statement  → definition | assignment | expression
+//   definition → IDENTIFIER '(' params ')' '=' conditional
+//   params     → (IDENTIFIER (',' IDENTIFIER)*)?
+//   assignment  → IDENTIFIER '=' conditional
+//   conditional → or ('?' conditional ':' conditional)?
+//   or         → and ('or' and)*
+//   and        → not ('and' not)*
+//   not        → 'not' not | comparison
+//   comparison → bit_or (('==' | '!=' | '<' | '<=' | '>' | '>=') bit_or)*
+//   bit_or     → bit_and ('|' bit_and)*
+//   bit_and    → shift ('&' shift)*
+//   shift      → expression (('<<' | '>>') expression)*
+//   expression → term (('+' | '-') term)*
+//   term       → unary (('*' | '/' | '%' | <implicit>) unary)*
+//   unary      → ('+' | '-' | '~') unary | power
+//   power      → postfix ('^' unary)?
+//   postfix    → factor '!'*
+//   factor     → NUMBER | IDENTIFIER | '(' expression ')'
+
+pub struct Parser {
+    lexer: Lexer,                    // Source of tokens
+    current_token: Token,            // The token we're currently looking at
+    current_span: Span,              // Where current_token is in the input
+    previous_token: TokenKind,       // The kind of token just before current_token
+    lookahead: VecDeque<CalcResult<SpannedToken>>, // Tokens read past current_token
+    variables: HashMap<String, f64>, // Variable values used by parse() (symbol table)
+}
+
+impl Parser {
+    /// Create a new parser with the given lexer
+    /// Gets the first token to start parsing
+    /// Fails if the very first token can't be read (e.g. input starting with '$')
+    pub fn new(mut lexer: Lexer) -> CalcResult<Self> {
+        let first = lexer.next_token()?; // Prime the parser with first token
+        Ok(Parser {
+            lexer,
+            current_token: first.token,
+            current_span: first.span,
+            previous_token: TokenKind::EOF, // Nothing comes before the first token
+            lookahead: VecDeque::new(),     // Nothing read ahead yet
+            variables: HashMap::new(), // Start with no variables defined
+        })
+    }
+
+    /// Build the error for finding the current token where `expected` should be
+    /// Running out of input gets its own variant, since "2 +" is usually just unfinished
+    fn unexpected(&self, expected: &str) -> CalcError {
+        let expected = expected.to_string();
+        let span = self.current_span;
+        match &self.current_token {
+            Token::EOF => CalcError::UnexpectedEof { expected, span },
+            found => CalcError::UnexpectedToken { expected, found: found.clone(), span },
+        }
+    }
+
+    /// Move on to the next token, taking it from the lookahead if it was peeked
+    fn advance(&mut self) -> CalcResult<()> {
+        let next = match self.lookahead.pop_front() {
+            Some(peeked) => peeked?,
+            None => self.lexer.next_token()?,
+        };
+        self.previous_token = std::mem::replace(&mut self.current_token, next.token).kind();
+        self.current_span = next.span;
+        Ok(())
+    }
+
+    /// Look at a token after current_token without moving past it
+    /// `distance` 0 is the token right after current_token, 1 the one after that...
+    /// 
+    /// Tokens are read from the lexer once and kept until advance() reaches them,
+    /// so peeking never rescans the input.
+    /// Returns None if the token there can't be read; advancing onto it
+    /// reports the error, just as if it hadn't been peeked.
+    fn peek_token(&mut self, distance: usize) -> Option<&Token> {
+        while self.lookahead.len() <= distance {
+            // Don't read past a lexer error: it stays next in line for advance()
+            if matches!(self.lookahead.back(), Some(Err(_))) {
+                return None;
+            }
+            self.lookahead.push_back(self.lexer.next_token());
+        }
+        self.lookahead[distance].as_ref().ok().map(|spanned| &spanned.token)
+    }
+
+    /// Check whether the next operand follows the previous one with no operator,
+    /// which means multiplication: a number or ')' directly followed by '(',
+    /// a variable or a function
+    /// 
+    /// Examples: "2(3 + 4)", "2pi()", "3x", "(1 + 2)(3 + 4)"
+    /// Not "sin(1)" or "f(1)": a name followed by '(' is a function call
+    fn at_implicit_multiplication(&self) -> bool {
+        matches!(self.previous_token, TokenKind::Number | TokenKind::RightParen)
+            && matches!(
+                self.current_token,
+                Token::LeftParen | Token::Identifier(_) | Token::Function(_)
+            )
+    }
+
+    /// "Eat" a token - verify it's what we expect, then move to next token
+    /// This is a common parser pattern for consuming expected tokens
+    /// 
+    /// Only the kind is compared, so any number matches TokenKind::Number.
+    /// 
+    /// Example: if we expect a '+' and see a '+', advance to next token
+    ///          if we expect a '+' but see a '*', return an error
+    fn eat(&mut self, expected: TokenKind) -> CalcResult<()> {
+        if self.current_token.kind() == expected {
+            self.advance()
+        } else {
+            Err(self.unexpected(&expected.to_string()))
+        }
+    }
+
+    /// Parse a function's argument list, after the '(' and up to the ')'
+    /// args → (expression (',' expression)*)?
+    /// 
+    /// Examples:
+    ///   - "" (as in "pi()") → []
+    ///   - "5, 3" (as in "min(5, 3)") → [Number(5.0), Number(3.0)]
+    fn args(&mut self) -> CalcResult<Vec<Expr>> {
+        let mut args = Vec::new();
+        if matches!(self.current_token, Token::RightParen) {
+            return Ok(args); // No arguments at all
+        }
+
+        args.push(self.conditional()?); // Parse first argument
+        while matches!(self.current_token, Token::Comma) {
+            self.eat(TokenKind::Comma)?; // Consume ','
+            args.push(self.conditional()?); // Parse the next argument
+        }
+        Ok(args)
+    }
+
+    /// Parse a factor: the highest precedence elements
+    /// factor → NUMBER | IDENTIFIER | FUNCTION '(' args ')' | '(' expression ')'
+    /// 
+    /// Examples:
+    ///   - "42" → Number(42.0)
+    ///   - "x" → Variable("x")
+    ///   - "sin(3.14)" → FunctionCall("sin", [Number(3.14)])
+    ///   - "max(min(1, 2), 3)" → FunctionCall("max", [FunctionCall("min", ...), Number(3.0)])
+    ///   - "(2 + 3)" → recursively parses "2 + 3"; the parentheses leave no node
+    ///   - "f(1)" → FunctionCall("f", [Number(1.0)]), for a user-defined f
+    fn factor(&mut self) -> CalcResult<Expr> {
+        // Names are moved out of the token rather than copied;
+        // eating it replaces the emptied token straight away
+        match &mut self.current_token {
+            Token::Number(value) => {
+                // Found a number literal
+                let value = *value;
+                self.eat(TokenKind::Number)?; // Consume the number token
+                Ok(Expr::Number(value))
+            }
+            Token::Identifier(name) => {
+                // Found a variable reference
+                let name = std::mem::take(name);
+                self.eat(TokenKind::Identifier)?; // Consume the identifier token
+
+                // An identifier called like a function is a user-defined function;
+                // whether it exists is checked when the tree is evaluated
+                if matches!(self.current_token, Token::LeftParen) {
+                    self.eat(TokenKind::LeftParen)?;  // Consume '('
+                    let args = self.args()?;          // Parse the arguments
+                    self.eat(TokenKind::RightParen)?; // Consume ')'
+                    return Ok(Expr::FunctionCall { name, args });
+                }
+                
+                // The value is looked up when the tree is evaluated
+                Ok(Expr::Variable(name))
+            }
+            Token::Function(name) => {
+                // Found a function call
+                let name = std::mem::take(name);
+                self.eat(TokenKind::Function)?;  // Consume the function name
+                self.eat(TokenKind::LeftParen)?; // Consume '('
+                
+                // Any number of arguments; the evaluator checks the count
+                let args = self.args()?;
+                
+                self.eat(TokenKind::RightParen)?; // Consume ')'
+                Ok(Expr::FunctionCall { name, args })
+            }
+            Token::LeftParen => {
+                // Found parentheses - parse the expression inside
+                self.eat(TokenKind::LeftParen)?;  // Consume '('
+                let result = self.conditional()?; // Recursively parse the expression inside
+                self.eat(TokenKind::RightParen)?; // Consume ')'
+                Ok(result)
+            }
+            _ => Err(self.unexpected("a number, variable, function or '('")),
+        }
+    }
+
+    /// Parse postfix operators: factorial
+    /// postfix → factor '!'*
+    /// 
+    /// Factorial binds tighter than '^', so "3!^2" is (3!)^2 = 36.
+    /// 
+    /// Examples:
+    ///   - "5!" → Factorial(5)
+    ///   - "(2 + 3)!" → Factorial(Add(2, 3))
+    ///   - "3!!" → Factorial(Factorial(3)) = 6! = 720
+    fn postfix(&mut self) -> CalcResult<Expr> {
+        let mut result = self.factor()?; // Get the operand
+
+        // Each '!' applies to everything before it
+        while matches!(self.current_token, Token::Bang) {
+            self.eat(TokenKind::Bang)?;
+            result = Expr::unary(UnaryOperator::Factorial, result);
+        }
+
+        Ok(result)
+    }
+
+    /// Parse power operations: exponentiation
+    /// power → postfix ('^' unary)?
+    /// 
+    /// Note: Power is RIGHT associative, meaning 2^3^2 = 2^(3^2) = 512, not (2^3)^2 = 64
+    /// This is the mathematical convention for exponentiation.
+    /// The exponent may have its own sign: "2^-1" is 2^(-1) = 0.5.
+    /// 
+    /// Examples:
+    ///   - "2 ^ 3" → Power(2, 3)
+    ///   - "2 ^ 3 ^ 2" → Power(2, Power(3, 2))
+    fn power(&mut self) -> CalcResult<Expr> {
+        let mut result = self.postfix()?; // Get the base
+
+        // Right associative: if we see ^, recursively parse the right side
+        if matches!(self.current_token, Token::Power) {
+            self.eat(TokenKind::Power)?;
+            // Recursive call for right associativity: a^b^c = a^(b^c)
+            result = Expr::binary(BinaryOperator::Power, result, self.unary()?);
+        }
+
+        Ok(result)
+    }
+
+    /// Parse prefix operators: unary plus, unary minus and bitwise not
+    /// unary → ('+' | '-' | '~') unary | power
+    /// 
+    /// Prefix operators bind more loosely than '^', as in math: "-2^2" is
+    /// -(2^2) = -4, and "(-2)^2" is needed to square -2. They can be stacked,
+    /// and '+' leaves its operand unchanged, so "--5" and "-+-5" are both 5.
+    /// 
+    /// Examples:
+    ///   - "-5" → UnaryOp(Negate, Number(5.0))
+    ///   - "-3!" → UnaryOp(Negate, UnaryOp(Factorial, Number(3.0))), like -(3!)
+    ///   - "+5" → Number(5.0)
+    ///   - "~0" → UnaryOp(BitNot, Number(0.0))
+    fn unary(&mut self) -> CalcResult<Expr> {
+        let op = match self.current_token {
+            Token::Plus => None,
+            Token::Minus => Some(UnaryOperator::Negate),
+            Token::Tilde => Some(UnaryOperator::BitNot),
+            _ => return self.power(), // No prefix operator: parse the operand itself
+        };
+        self.advance()?; // Consume the operator
+
+        let operand = self.unary()?; // Recursively parse what it applies to
+        Ok(match op {
+            Some(op) => Expr::unary(op, operand),
+            None => operand, // Unary plus leaves no node
+        })
+    }
+
+    /// Parse term operations: multiplication, division, modulo
+    /// term → unary (('*' | '/' | '%' | <implicit>) unary)*
+    /// 
+    /// These operators have the same precedence and are left associative.
+    /// Left associative means: 10 / 2 / 5 = (10 / 2) / 5 = 1, not 10 / (2 / 5) = 25
+    /// 
+    /// Leaving out the '*' after a number or ')' also multiplies, at the same
+    /// precedence: "1/2x" is (1/2)*x, and "2x^2" is 2*(x^2).
+    /// 
+    /// Examples:
+    ///   - "2 * 3" → Multiply(2, 3)
+    ///   - "10 % 3" → Modulo(10, 3)
+    ///   - "2 * 3 * 4" → Multiply(Multiply(2, 3), 4) (left to right)
+    ///   - "2(3 + 4)" → Multiply(2, Add(3, 4))
+    fn term(&mut self) -> CalcResult<Expr> {
+        let mut result = self.unary()?; // Get the first operand
+
+        // Keep processing * / % operators (left associative)
+        loop {
+            let op = match self.current_token {
+                Token::Multiply => BinaryOperator::Multiply,
+                Token::Divide => BinaryOperator::Divide,
+                Token::Modulo => BinaryOperator::Modulo,
+                _ if self.at_implicit_multiplication() => {
+                    // No operator to consume: the next operand starts right here
+                    result = Expr::binary(BinaryOperator::Multiply, result, self.unary()?);
+                    continue;
+                }
+                _ => break,
+            };
+            self.advance()?; // Consume the operator
+            result = Expr::binary(op, result, self.unary()?); // Combine with the next operand
+        }
+
+        Ok(result)
+    }
+
+    /// Parse expression operations: addition and subtraction
+    /// expression → term (('+' | '-') term)*
+    /// 
+    /// These have the lowest precedence of the arithmetic operators.
+    /// Left associative: 10 - 3 - 2 = (10 - 3) - 2 = 5, not 10 - (3 - 2) = 9
+    /// 
+    /// Examples:
+    ///   - "2 + 3" → Add(2, 3)
+    ///   - "2 + 3 * 4" → Add(2, Multiply(3, 4)) (* has higher precedence)
+    fn expr(&mut self) -> CalcResult<Expr> {
+        let mut result = self.term()?; // Get the first operand
+
+        // Keep processing + - operators (left associative)
+        while matches!(self.current_token, Token::Plus | Token::Minus) {
+            let op = match self.current_token {
+                Token::Plus => BinaryOperator::Add,
+                _ => BinaryOperator::Subtract,
+            };
+            self.advance()?; // Consume the operator
+            result = Expr::binary(op, result, self.term()?); // Combine with the next operand
+        }
+
+        Ok(result)
+    }
+
+    /// Parse bit shifts
+    /// shift → expression (('<<' | '>>') expression)*
+    /// 
+    /// Shifts bind more loosely than arithmetic, so "1 + 1 << 2" is (1 + 1) << 2 = 8.
+    /// 
+    /// Examples:
+    ///   - "1 << 4" → ShiftLeft(1, 4), which evaluates to 16.0
+    ///   - "0xFF >> 4" → ShiftRight(255, 4), which evaluates to 15.0
+    fn shift(&mut self) -> CalcResult<Expr> {
+        let mut result = self.expr()?; // Get the first operand
+
+        // Keep processing << >> operators (left associative)
+        loop {
+            let op = match self.current_token {
+                Token::ShiftLeft => BinaryOperator::ShiftLeft,
+                Token::ShiftRight => BinaryOperator::ShiftRight,
+                _ => break,
+            };
+            self.advance()?; // Consume the operator
+            result = Expr::binary(op, result, self.expr()?); // Combine with the next operand
+        }
+
+        Ok(result)
+    }
+
+    /// Parse bitwise and
+    /// bit_and → shift ('&' shift)*
+    /// 
+    /// Examples:
+    ///   - "0xFF & 0x0F" → BitAnd(255, 15), which evaluates to 15.0
+    ///   - "6 & 1 << 1" → BitAnd(6, ShiftLeft(1, 1)) (shifts first)
+    fn bit_and(&mut self) -> CalcResult<Expr> {
+        let mut result = self.shift()?; // Get the first operand
+
+        // Keep processing '&' (left associative)
+        while matches!(self.current_token, Token::Ampersand) {
+            self.eat(TokenKind::Ampersand)?;
+            result = Expr::binary(BinaryOperator::BitAnd, result, self.shift()?);
+        }
+
+        Ok(result)
+    }
+
+    /// Parse bitwise or
+    /// bit_or → bit_and ('|' bit_and)*
+    /// 
+    /// Examples:
+    ///   - "8 | 1" → BitOr(8, 1), which evaluates to 9.0
+    ///   - "1 | 6 & 2" → BitOr(1, BitAnd(6, 2)) ('&' first)
+    fn bit_or(&mut self) -> CalcResult<Expr> {
+        let mut result = self.bit_and()?; // Get the first operand
+
+        // Keep processing '|' (left associative)
+        while matches!(self.current_token, Token::Pipe) {
+            self.eat(TokenKind::Pipe)?;
+            result = Expr::binary(BinaryOperator::BitOr, result, self.bit_and()?);
+        }
+
+        Ok(result)
+    }
+
+    /// Parse comparisons: equality and ordering
+    /// comparison → bit_or (('==' | '!=' | '<' | '<=' | '>' | '>=') bit_or)*
+    /// 
+    /// Comparisons bind more loosely than arithmetic, so "1 + 2 > 2" compares 3 with 2.
+    /// A chain is evaluated left to right like any other left associative operator:
+    /// "3 > 2 > 1" is (3 > 2) > 1, which is 1 > 1, which is 0.
+    /// 
+    /// Examples:
+    ///   - "3 > 2" → Greater(3, 2), which evaluates to 1.0
+    ///   - "x == 5" → Equal(Variable("x"), 5)
+    fn comparison(&mut self) -> CalcResult<Expr> {
+        let mut result = self.bit_or()?; // Get the first operand
+
+        // Keep processing comparison operators (left associative)
+        loop {
+            let op = match self.current_token {
+                Token::EqEq => BinaryOperator::Equal,
+                Token::NotEq => BinaryOperator::NotEqual,
+                Token::Less => BinaryOperator::Less,
+                Token::LessEq => BinaryOperator::LessEqual,
+                Token::Greater => BinaryOperator::Greater,
+                Token::GreaterEq => BinaryOperator::GreaterEqual,
+                _ => break,
+            };
+            self.advance()?; // Consume the operator
+            result = Expr::binary(op, result, self.bit_or()?); // Compare with the next operand
+        }
+
+        Ok(result)
+    }
+
+    /// Parse logical negation
+    /// not → 'not' not | comparison
+    /// 
+    /// 'not' binds more loosely than comparisons, so "not 2 > 3" is not (2 > 3).
+    /// 
+    /// Examples:
+    ///   - "not 0" → Not(0), which evaluates to 1.0
+    ///   - "not not x" → Not(Not(Variable("x")))
+    fn not(&mut self) -> CalcResult<Expr> {
+        if matches!(self.current_token, Token::Not) {
+            self.eat(TokenKind::Not)?; // Consume 'not'
+            let operand = self.not()?; // Recursively parse what is negated
+            return Ok(Expr::unary(UnaryOperator::Not, operand));
+        }
+        self.comparison()
+    }
+
+    /// Parse logical and
+    /// and → not ('and' not)*
+    /// 
+    /// Examples:
+    ///   - "1 and 0" → And(1, 0), which evaluates to 0.0
+    ///   - "1 + 1 and 0" → And(Add(1, 1), 0)
+    fn and(&mut self) -> CalcResult<Expr> {
+        let mut result = self.not()?; // Get the first operand
+
+        // Keep processing 'and' (left associative)
+        while matches!(self.current_token, Token::And) {
+            self.eat(TokenKind::And)?;
+            result = Expr::binary(BinaryOperator::And, result, self.not()?);
+        }
+
+        Ok(result)
+    }
+
+    /// Parse logical or
+    /// or → and ('or' and)*
+    /// 
+    /// 'and' binds tighter than 'or', so "1 or 0 and 0" is 1 or (0 and 0).
+    /// 
+    /// Examples:
+    ///   - "0 or 2" → Or(0, 2), which evaluates to 1.0
+    ///   - "x > 0 or x < -10" → Or(Greater(x, 0), Less(x, Negate(10)))
+    fn or(&mut self) -> CalcResult<Expr> {
+        let mut result = self.and()?; // Get the first operand
+
+        // Keep processing 'or' (left associative)
+        while matches!(self.current_token, Token::Or) {
+            self.eat(TokenKind::Or)?;
+            result = Expr::binary(BinaryOperator::Or, result, self.and()?);
+        }
+
+        Ok(result)
+    }
+
+    /// Parse a conditional: the lowest precedence of all operators
+    /// conditional → or ('?' conditional ':' conditional)?
+    /// 
+    /// The condition picks a branch: non-zero picks the first, zero the second.
+    /// Right associative, so "a ? b : c ? d : e" is a ? b : (c ? d : e).
+    /// 
+    /// Examples:
+    ///   - "x > 0 ? x : -x" → Conditional(Greater(x, 0), Variable(x), Negate(x))
+    ///   - "1 ? 2" → error: expected ':'
+    fn conditional(&mut self) -> CalcResult<Expr> {
+        let condition = self.or()?; // Parse the condition (or the whole expression)
+        if !matches!(self.current_token, Token::Question) {
+            return Ok(condition);
+        }
+
+        self.eat(TokenKind::Question)?;      // Consume '?'
+        let then = self.conditional()?;      // Branch for a true condition
+        self.eat(TokenKind::Colon)?;         // Consume ':'
+        let otherwise = self.conditional()?; // Branch for a false condition (may nest)
+        Ok(Expr::Conditional {
+            condition: Box::new(condition),
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+        })
+    }
+
+    /// Parse variable assignment: IDENTIFIER '=' conditional
+    /// assignment → IDENTIFIER '=' conditional
+    /// 
+    /// Examples:
+    ///   - "x = 5" → Assignment("x", Number(5.0))
+    ///   - "y = x + 2" → Assignment("y", Add(Variable("x"), Number(2.0)))
+    ///   - "ans = 5" → error: ans is set by the calculator, not by hand
+    fn assignment(&mut self) -> CalcResult<Expr> {
+        if let Token::Identifier(name) = &mut self.current_token {
+            if name == ANS {
+                return Err(CalcError::ReservedName(name.clone()));
+            }
+            let name = std::mem::take(name);  // Save the variable name
+            self.eat(TokenKind::Identifier)?; // Consume identifier
+            self.eat(TokenKind::Assign)?;     // Consume '='
+            let value = self.conditional()?;  // Parse the right-hand side
+            Ok(Expr::Assignment { name, value: Box::new(value) })
+        } else {
+            // This shouldn't happen if called correctly
+            self.conditional()
+        }
+    }
+
+    /// Check whether the tokens ahead read NAME '(' params ')' '='
+    /// Only peeks, so the parser itself doesn't move
+    /// 
+    /// Examples:
+    ///   - "f(x, y) = x + y" → true
+    ///   - "f(2) + 1" → false (a call, since 2 isn't a parameter name)
+    fn at_function_definition(&mut self) -> bool {
+        // A lexer error just means this isn't a definition; parsing reports it
+        if !matches!(self.peek_token(0), Some(Token::LeftParen)) {
+            return false;
+        }
+        let mut distance = 1;
+        if matches!(self.peek_token(distance), Some(Token::Identifier(_))) {
+            distance += 1;
+            while matches!(self.peek_token(distance), Some(Token::Comma)) {
+                if !matches!(self.peek_token(distance + 1), Some(Token::Identifier(_))) {
+                    return false;
+                }
+                distance += 2;
+            }
+        }
+        matches!(self.peek_token(distance), Some(Token::RightParen))
+            && matches!(self.peek_token(distance + 1), Some(Token::Assign))
+    }
+
+    /// Parse a function definition
+    /// definition → IDENTIFIER '(' params ')' '=' conditional
+    /// 
+    /// The body isn't evaluated here; it's stored and evaluated on each call.
+    /// 
+    /// Examples:
+    ///   - "f(x) = x^2 + 1" → FunctionDef("f", ["x"], Add(Power(x, 2), 1))
+    ///   - "area(w, h) = w * h" → FunctionDef("area", ["w", "h"], Multiply(w, h))
+    fn function_definition(&mut self) -> CalcResult<Expr> {
+        let name = match &mut self.current_token {
+            Token::Identifier(name) => std::mem::take(name),
+            _ => return Err(self.unexpected("a function name")),
+        };
+        self.eat(TokenKind::Identifier)?; // Consume the function name
+        self.eat(TokenKind::LeftParen)?;  // Consume '('
+
+        // Collect the parameter names
+        let mut params = Vec::new();
+        while let Token::Identifier(param) = &mut self.current_token {
+            params.push(std::mem::take(param));
+            self.eat(TokenKind::Identifier)?;
+            if !matches!(self.current_token, Token::Comma) {
+                break;
+            }
+            self.eat(TokenKind::Comma)?;
+        }
+
+        self.eat(TokenKind::RightParen)?; // Consume ')'
+        self.eat(TokenKind::Assign)?;     // Consume '='
+        let body = self.conditional()?;
+        Ok(Expr::FunctionDef { name, params, body: Box::new(body) })
+    }
+
+    /// Parse a statement: a function definition, an assignment or an expression
+    /// statement → definition | assignment | expression
+    /// 
+    /// We need to look ahead to distinguish between:
+    ///   - "f(x) = x + 1" (function definition)
+    ///   - "x = 5" (assignment)
+    ///   - "x + 2" (expression using variable x)
+    /// 
+    /// All start with an identifier, so we peek at the next tokens to decide.
+    fn statement(&mut self) -> CalcResult<Expr> {
+        // Built-in functions can't be redefined: "sin(x) = x" is an error
+        if matches!(self.current_token, Token::Function(_))
+            && self.at_function_definition()
+            && let Token::Function(name) = &self.current_token
+        {
+            return Err(CalcError::ReservedName(name.clone()));
+        }
+
+        // Look ahead to see if this is a function definition
+        if matches!(self.current_token, Token::Identifier(_)) && self.at_function_definition() {
+            return self.function_definition();
+        }
+
+        // Look ahead to see if this is an assignment (identifier followed by '=')
+        if matches!(self.current_token, Token::Identifier(_))
+            && matches!(self.peek_token(0), Some(Token::Assign))
+        {
+            return self.assignment(); // Parse as assignment
+        }
+        
+        // Not an assignment, parse as regular expression
+        self.conditional()
+    }
+
+    /// Parse the entire program into one tree per statement
+    /// program → statement ((';' | NEWLINE) statement)*
+    /// 
+    /// Handles multiple statements separated by semicolons or line breaks.
+    /// Anything left over after the last statement is an error.
+    /// 
+    /// Examples:
+    ///   - "2 + 3 * 4" → [Add(Number(2), Multiply(Number(3), Number(4)))]
+    ///   - "x = 5; x + 2" → [Assignment("x", Number(5)), Add(Variable("x"), Number(2))]
+    ///   - "x = 5\nx + 2" → the same two statements
+    ///   - "2 3" → error: expected ';', end of line or end of input, found number 3
+    ///
+    /// ```
+    /// use rust_calculator::{format_program, Lexer, Parser};
+    ///
+    /// let program = Parser::new(Lexer::new("x = 5; x + 2"))?.parse_ast()?;
+    /// assert_eq!(program.len(), 2);
+    /// assert_eq!(format_program(&program), "x = 5; x + 2");
+    /// # Ok::<(), rust_calculator::CalcError>(())
+    /// ```
+    pub fn parse_ast(&mut self) -> CalcResult<Vec<Expr>> {
+        let mut statements = Vec::new();
+        
+        // Parse statements separated by semicolons or line breaks
+        loop {
+            statements.push(self.statement()?); // Parse one statement
+            
+            // Check if there's a separator (indicating more statements)
+            if matches!(self.current_token, Token::Semicolon | Token::Newline) {
+                self.advance()?; // Consume the ';' or line break
+                
+                // If there's more input after the semicolon, continue parsing
+                if !matches!(self.current_token, Token::EOF) {
+                    continue;
+                }
+            }
+            
+            // No more statements to parse
+            break;
+        }
+
+        if !matches!(self.current_token, Token::EOF) {
+            return Err(self.unexpected("';', end of line or end of input"));
+        }
+        
+        Ok(statements)
+    }
+
+    /// Parse the entire program, then evaluate it with this parser's variables
+    /// 
+    /// Returns the value of the last statement, or the first error found.
+    /// 
+    /// Examples:
+    ///   - "5" → returns 5.0
+    ///   - "x = 5; x + 2" → returns 7.0 (x gets 5, then evaluate x + 2)
+    ///   - "a = 2; b = 3; a * b" → returns 6.0
+    pub fn parse(&mut self) -> CalcResult<f64> {
+        let program = self.parse_ast()?;
+
+        let mut evaluator = Evaluator::with_variables(std::mem::take(&mut self.variables));
+        let result = evaluator.evaluate_program(&program);
+        self.variables = evaluator.into_variables();
+        result
+    }
+
+    /// Get a copy of the current variables (for CLI persistence)
+    pub fn get_variables(&self) -> HashMap<String, f64> {
+        self.variables.clone()
+    }
+
+    /// Set variables from external source (for CLI persistence)
+    pub fn set_variables(&mut self, variables: HashMap<String, f64>) {
+        self.variables = variables;
+    }
+}
+
+// ============================================================================
+// EVALUATOR (TREE WALKER)
+// ============================================================================
+// The evaluator computes the value of an AST. Each node's value comes from
+// the values of its children, so evaluation is a recursive walk from the
+// leaves (numbers and variables) up to the root.
+//
+// It owns the variable environment: assignments store values in it, and
+// Variable nodes look them up. User-defined functions are kept alongside,
+// as is the random number generator behind random() and randint().
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// The variable holding the result of the previous calculation in the REPL,
+/// so "ans * 2" doubles it. Only the calculator sets it; assigning it is an error.
+pub const ANS: &str = "ans";
+
+/// How deeply user-defined functions may call each other (or themselves)
+/// before evaluation gives up, instead of overflowing the stack
+pub const MAX_CALL_DEPTH: usize = 100;
+
+/// A function defined with "f(x) = x^2 + 1": parameter names and the body
+/// that is evaluated with them bound to the call's arguments
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserFunction {
+    pub params: Vec<String>,
+    pub body: Expr,
+}
+
+#[derive(Clone)]
+pub struct Evaluator {
+    variables: HashMap<String, f64>,          // Storage for variable values (symbol table)
+    functions: HashMap<String, UserFunction>, // User-defined functions by name
+    depth: usize,                             // How many user function calls are in progress
+    strict: bool,                             // Report division by zero and domain errors
+    rng: ChaCha8Rng,                          // Source of random(); seed() makes it repeatable
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Evaluator {
+    /// Create an evaluator with no variables defined
+    pub fn new() -> Self {
+        Self::with_variables(HashMap::new())
+    }
+
+    /// Create an evaluator that starts from existing variables
+    pub fn with_variables(variables: HashMap<String, f64>) -> Self {
+        Evaluator {
+            variables,
+            functions: HashMap::new(),
+            depth: 0,
+            strict: true,
+            rng: ChaCha8Rng::from_entropy(), // Different numbers every run until seeded
+        }
+    }
+
+    /// Whether division by zero and arguments outside a function's domain are errors
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Choose between errors (strict, the default) and plain IEEE float results,
+    /// where 1/0 is inf and sqrt(-1) is NaN
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Restart the random number generator from `seed`, so random() and
+    /// randint() give the same sequence every time; the same as calling seed(N)
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    /// Forget all variables and user-defined functions
+    /// Settings stay, and so does the random number generator, so a seeded
+    /// sequence carries on where it was
+    pub fn clear(&mut self) {
+        self.variables.clear();
+        self.functions.clear();
+    }
+
+    /// The variables currently defined
+    pub fn variables(&self) -> &HashMap<String, f64> {
+        &self.variables
+    }
+
+    /// Set a variable directly, without evaluating an assignment
+    /// Used to store the previous result in `ans`
+    pub fn set_variable(&mut self, name: &str, value: f64) {
+        self.variables.insert(name.to_string(), value);
+    }
+
+    /// The user-defined functions currently defined
+    pub fn functions(&self) -> &HashMap<String, UserFunction> {
+        &self.functions
+    }
+
+    /// Give back the variables, including any assigned during evaluation
+    pub fn into_variables(self) -> HashMap<String, f64> {
+        self.variables
+    }
+
+    /// Evaluate each statement in order and return the value of the last one
+    /// An empty program evaluates to 0
+    pub fn evaluate_program(&mut self, program: &[Expr]) -> CalcResult<f64> {
+        let mut result = 0.0;
+        for statement in program {
+            result = self.evaluate(statement)?;
+        }
+        Ok(result)
+    }
+
+    /// Compute the value of one tree
+    /// 
+    /// Examples:
+    ///   - Add(Number(2), Multiply(Number(3), Number(4))) → 14.0
+    ///   - Assignment("x", Number(5)) → stores 5.0 in x, returns 5.0
+    ///   - FunctionDef("f", ["x"], ...) → stores f, returns 0.0
+    ///   - Variable("y") with y undefined → error
+    pub fn evaluate(&mut self, expr: &Expr) -> CalcResult<f64> {
+        match expr {
+            Expr::Number(value) => Ok(*value),
+            Expr::Variable(name) => {
+                // Look up the variable's value in our symbol table
+                self.variables
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| CalcError::UndefinedVariable(name.clone()))
+            }
+            Expr::BinaryOp { op: BinaryOperator::And, left, right } => {
+                // The right side only matters when the left side is true
+                Ok(truth(is_true(self.evaluate(left)?) && is_true(self.evaluate(right)?)))
+            }
+            Expr::BinaryOp { op: BinaryOperator::Or, left, right } => {
+                // The right side only matters when the left side is false
+                Ok(truth(is_true(self.evaluate(left)?) || is_true(self.evaluate(right)?)))
+            }
+            Expr::BinaryOp { op, left, right } => {
+                let left = self.evaluate(left)?;
+                let right = self.evaluate(right)?;
+                Ok(match op {
+                    BinaryOperator::Add => left + right,
+                    BinaryOperator::Subtract => left - right,
+                    BinaryOperator::Multiply => left * right,
+                    BinaryOperator::Divide | BinaryOperator::Modulo
+                        if self.strict && right == 0.0 =>
+                    {
+                        let operator = if *op == BinaryOperator::Divide { '/' } else { '%' };
+                        return Err(CalcError::DivisionByZero { dividend: left, operator });
+                    }
+                    BinaryOperator::Divide => left / right,
+                    BinaryOperator::Modulo => left % right,
+                    BinaryOperator::Power => left.powf(right),
+                    BinaryOperator::BitAnd
+                    | BinaryOperator::BitOr
+                    | BinaryOperator::ShiftLeft
+                    | BinaryOperator::ShiftRight => bitwise(*op, left, right)?,
+                    BinaryOperator::Equal => truth(left == right),
+                    BinaryOperator::NotEqual => truth(left != right),
+                    BinaryOperator::Less => truth(left < right),
+                    BinaryOperator::LessEqual => truth(left <= right),
+                    BinaryOperator::Greater => truth(left > right),
+                    BinaryOperator::GreaterEqual => truth(left >= right),
+                    BinaryOperator::And => truth(is_true(left) && is_true(right)),
+                    BinaryOperator::Or => truth(is_true(left) || is_true(right)),
+                })
+            }
+            Expr::UnaryOp { op, operand } => {
+                let value = self.evaluate(operand)?;
+                match op {
+                    UnaryOperator::Negate => Ok(-value),
+                    UnaryOperator::Not => Ok(truth(!is_true(value))),
+                    UnaryOperator::BitNot => Ok(!to_integer("~", value)? as f64),
+                    UnaryOperator::Factorial => factorial(value),
+                }
+            }
+            Expr::FunctionCall { name, args } => {
+                // Evaluate the arguments first, then call the function with them
+                let args = args
+                    .iter()
+                    .map(|arg| self.evaluate(arg))
+                    .collect::<CalcResult<Vec<f64>>>()?;
+
+                // User-defined functions are looked up first; built-ins can't be shadowed
+                match self.functions.get(name).cloned() {
+                    Some(function) => self.call_user_function(name, &function, &args),
+                    None => self.call_function_n(name, &args),
+                }
+            }
+            Expr::FunctionDef { name, params, body } => {
+                let function = UserFunction {
+                    params: params.clone(),
+                    body: (**body).clone(),
+                };
+                self.functions.insert(name.clone(), function);
+                Ok(0.0)
+            }
+            Expr::Assignment { name, value } => {
+                let value = self.evaluate(value)?; // Evaluate the right-hand side
+
+                // Store the variable in our symbol table
+                self.variables.insert(name.clone(), value);
+                Ok(value) // Return the assigned value
+            }
+            Expr::Conditional { condition, then, otherwise } => {
+                // Only the chosen branch is evaluated
+                if is_true(self.evaluate(condition)?) {
+                    self.evaluate(then)
+                } else {
+                    self.evaluate(otherwise)
+                }
+            }
+        }
+    }
+
+    /// Call a user-defined function: evaluate its body with each parameter set
+    /// to the matching argument, then put back whatever those names held before
+    /// 
+    /// Example: with f(x) = x^2 + 1, call_user_function("f", f, &[3.0]) → 10.0
+    fn call_user_function(
+        &mut self,
+        name: &str,
+        function: &UserFunction,
+        args: &[f64],
+    ) -> CalcResult<f64> {
+        if args.len() != function.params.len() {
+            return Err(CalcError::WrongArgumentCount {
+                name: name.to_string(),
+                expected: Arity::Exactly(function.params.len()),
+                found: args.len(),
+            });
+        }
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(CalcError::RecursionLimit(name.to_string()));
+        }
+
+        // Bind the parameters, remembering the values they shadow
+        let shadowed: Vec<(String, Option<f64>)> = function
+            .params
+            .iter()
+            .zip(args)
+            .map(|(param, arg)| (param.clone(), self.variables.insert(param.clone(), *arg)))
+            .collect();
+
+        self.depth += 1;
+        let result = self.evaluate(&function.body);
+        self.depth -= 1;
+
+        // Restore in reverse, so a repeated parameter name ends up as it started
+        for (param, previous) in shadowed.into_iter().rev() {
+            match previous {
+                Some(value) => self.variables.insert(param, value),
+                None => self.variables.remove(&param),
+            };
+        }
+        result
+    }
+
+    /// How many arguments a built-in function takes
+    /// Examples: arity("pi") → Some(Exactly(0)), arity("atan2") → Some(Exactly(2)),
+    ///           arity("sum") → Some(AtLeast(1)), arity("foo") → None
+    fn arity(name: &str) -> Option<Arity> {
+        match name {
+            "pi" | "e" => Some(Arity::Exactly(0)),
+            "min" | "max" | "pow" | "atan2" | "gcd" | "lcm" | "hypot" | "root" => {
+                Some(Arity::Exactly(2))
+            }
+            "sin" | "cos" | "tan" | "asin" | "acos" | "atan" | "sqrt" | "abs" | "floor"
+            | "ceil" | "round" | "trunc" | "fract" | "sign" | "cbrt" | "ln" | "log10" | "log2"
+            | "exp" => Some(Arity::Exactly(1)),
+            "clamp" => Some(Arity::Exactly(3)),
+            "sum" | "avg" | "product" => Some(Arity::AtLeast(1)),
+            "random" => Some(Arity::Exactly(0)),
+            "seed" => Some(Arity::Exactly(1)),
+            "randint" => Some(Arity::Exactly(2)),
+            _ => None,
+        }
+    }
+
+    /// Call a built-in function with any number of arguments
+    /// Checks the argument count, then dispatches to the table for variadic
+    /// functions or to the one for that count
+    /// 
+    /// Examples:
+    ///   - call_function_n("pi", &[]) → returns π
+    ///   - call_function_n("min", &[5.0, 3.0]) → returns 3.0
+    ///   - call_function_n("sum", &[1.0, 2.0, 3.0]) → returns 6.0
+    ///   - call_function_n("sqrt", &[1.0, 2.0]) → error: sqrt() takes 1 argument, got 2
+    ///   - call_function_n("avg", &[]) → error: avg() takes at least 1 argument, got 0
+    fn call_function_n(&mut self, name: &str, args: &[f64]) -> CalcResult<f64> {
+        let Some(expected) = Self::arity(name) else {
+            return Err(CalcError::UnknownFunction(name.to_string()));
+        };
+        if !expected.accepts(args.len()) {
+            return Err(CalcError::WrongArgumentCount {
+                name: name.to_string(),
+                expected,
+                found: args.len(),
+            });
+        }
+
+        match *args {
+            _ if matches!(expected, Arity::AtLeast(_)) => self.call_variadic_function(name, args),
+            _ if is_random_function(name) => self.call_random_function(name, args),
+            [] => self.call_constant(name),
+            [arg] => self.call_function(name, arg),
+            [arg1, arg2] => self.call_two_arg_function(name, arg1, arg2),
+            [arg1, arg2, arg3] => self.call_three_arg_function(name, arg1, arg2, arg3),
+            _ => Err(CalcError::UnknownFunction(name.to_string())),
+        }
+    }
+
+    /// Call a built-in function with the given argument
+    /// This is our function table - maps function names to implementations
+    /// 
+    /// Function categories:
+    ///   - Trigonometric: sin, cos, tan (input in radians)
+    ///   - Inverse trig: asin, acos, atan (output in radians)
+    ///   - Mathematical: sqrt, cbrt, abs, floor, ceil, round, trunc, fract, sign
+    /// 
+    /// Examples:
+    ///   - call_function("sqrt", 16.0) → returns 4.0
+    ///   - call_function("abs", -5.0) → returns 5.0
+    ///   - call_function("floor", 3.7) → returns 3.0
+    ///   - call_function("trunc", -2.7) → returns -2.0
+    ///   - call_function("ln", 0.0) → error: ln() needs a positive argument, got 0
+    fn call_function(&self, name: &str, arg: f64) -> CalcResult<f64> {
+        // Outside its domain a function would silently give NaN or -inf
+        if self.strict
+            && let Some(requirement) = Self::domain_requirement(name, arg)
+        {
+            return Err(CalcError::DomainError {
+                name: name.to_string(),
+                arg,
+                requirement,
+            });
+        }
+
+        Ok(match name {
+            // Basic trigonometric functions
+            "sin" => arg.sin(),
+            "cos" => arg.cos(),
+            "tan" => arg.tan(),
+            
+            // Inverse trigonometric functions
+            "asin" => arg.asin(),   // Returns value in [-π/2, π/2]
+            "acos" => arg.acos(),   // Returns value in [0, π]
+            "atan" => arg.atan(),   // Returns value in (-π/2, π/2)
+            
+            // Mathematical functions
+            "sqrt" => arg.sqrt(),   // Square root
+            "cbrt" => arg.cbrt(),   // Cube root, negative for a negative argument
+            "abs" => arg.abs(),     // Absolute value
+            "floor" => arg.floor(), // Round down to nearest integer
+            "ceil" => arg.ceil(),   // Round up to nearest integer
+            "round" => arg.round(), // Round to nearest integer
+            "trunc" => arg.trunc(), // Drop the fraction, rounding toward zero
+            "fract" => arg.fract(), // The fraction alone, with x's sign: x - trunc(x)
+            "sign" => sign(arg),    // -1, 0 or 1
+            
+            // Logarithmic and exponential functions
+            "ln" => arg.ln(),       // Natural logarithm (base e)
+            "log10" => arg.log10(), // Base-10 logarithm
+            "log2" => arg.log2(),   // Base-2 logarithm
+            "exp" => arg.exp(),     // e^x (exponential function)
+            
+            _ => return Err(CalcError::UnknownFunction(name.to_string())),
+        })
+    }
+
+    /// What a single-argument function needs of its argument, if `arg` doesn't have it
+    /// Examples: domain_requirement("sqrt", -1.0) → Some("an argument that isn't negative"),
+    /// domain_requirement("sqrt", 4.0) → None
+    fn domain_requirement(name: &str, arg: f64) -> Option<&'static str> {
+        match name {
+            "ln" | "log10" | "log2" if arg <= 0.0 => Some("a positive argument"),
+            "sqrt" if arg < 0.0 => Some("an argument that isn't negative"),
+            "asin" | "acos" if !(-1.0..=1.0).contains(&arg) => Some("an argument from -1 to 1"),
+            _ => None,
+        }
+    }
+
+    /// Call a mathematical constant (zero-argument function)
+    /// These are functions that take no arguments and return constant values
+    /// 
+    /// Examples:
+    ///   - call_constant("pi") → returns π ≈ 3.14159
+    ///   - call_constant("e") → returns e ≈ 2.71828
+    fn call_constant(&self, name: &str) -> CalcResult<f64> {
+        match name {
+            "pi" => Ok(std::f64::consts::PI),  // π ≈ 3.14159265359
+            "e" => Ok(std::f64::consts::E),    // e ≈ 2.71828182846
+            _ => Err(CalcError::UnknownFunction(name.to_string())),
+        }
+    }
+
+    /// Call a two-argument function
+    /// These functions take two arguments and return a result
+    /// 
+    /// Examples:
+    ///   - call_two_arg_function("min", 5.0, 3.0) → returns 3.0
+    ///   - call_two_arg_function("max", 5.0, 3.0) → returns 5.0
+    ///   - call_two_arg_function("pow", 2.0, 3.0) → returns 8.0
+    ///   - call_two_arg_function("lcm", 4.0, 6.0) → returns 12.0
+    ///   - call_two_arg_function("hypot", 3.0, 4.0) → returns 5.0
+    ///   - call_two_arg_function("root", -8.0, 3.0) → returns -2.0
+    ///   - call_two_arg_function("gcd", 1.5, 3.0) → error: gcd() needs whole numbers...
+    fn call_two_arg_function(&self, name: &str, arg1: f64, arg2: f64) -> CalcResult<f64> {
+        Ok(match name {
+            "min" => arg1.min(arg2),        // Minimum of two values
+            "max" => arg1.max(arg2),        // Maximum of two values
+            "pow" => arg1.powf(arg2),       // arg1 raised to power arg2
+            "atan2" => arg1.atan2(arg2),    // Two-argument arctangent (y, x)
+            "hypot" => arg1.hypot(arg2),    // sqrt(x² + y²) without overflowing on the way
+            "root" => root(arg1, arg2)?,    // The arg2-th root of arg1
+            // Greatest common divisor and least common multiple of whole numbers
+            "gcd" => gcd(natural(name, arg1)?, natural(name, arg2)?) as f64,
+            "lcm" => lcm(natural(name, arg1)?, natural(name, arg2)?),
+            _ => return Err(CalcError::UnknownFunction(name.to_string())),
+        })
+    }
+
+    /// Call a three-argument function
+    /// 
+    /// Examples:
+    ///   - call_three_arg_function("clamp", 15.0, 0.0, 10.0) → returns 10.0
+    ///   - call_three_arg_function("clamp", -3.0, 0.0, 10.0) → returns 0.0
+    ///   - call_three_arg_function("clamp", 5.0, 10.0, 0.0) → error: the range is empty
+    fn call_three_arg_function(
+        &self,
+        name: &str,
+        arg1: f64,
+        arg2: f64,
+        arg3: f64,
+    ) -> CalcResult<f64> {
+        match name {
+            // Keep arg1 within arg2..=arg3
+            "clamp" => {
+                // A NaN bound fails too, since f64::clamp would panic on it
+                if arg2 > arg3 || arg2.is_nan() || arg3.is_nan() {
+                    return Err(CalcError::DomainError {
+                        name: name.to_string(),
+                        arg: arg2,
+                        requirement: "a low end no higher than the high end",
+                    });
+                }
+                Ok(arg1.clamp(arg2, arg3))
+            }
+            _ => Err(CalcError::UnknownFunction(name.to_string())),
+        }
+    }
+
+    /// Call a function that uses the random number generator
+    /// 
+    /// Examples:
+    ///   - call_random_function("random", &[]) → a number from 0 up to (not including) 1
+    ///   - call_random_function("randint", &[1.0, 6.0]) → one of 1, 2, 3, 4, 5, 6
+    ///   - call_random_function("seed", &[42.0]) → 42, and restarts the sequence
+    ///   - call_random_function("randint", &[6.0, 1.0]) → error: the range is empty
+    fn call_random_function(&mut self, name: &str, args: &[f64]) -> CalcResult<f64> {
+        match (name, args) {
+            ("random", []) => Ok(self.rng.gen_range(0.0..1.0)),
+            ("randint", &[low, high]) => {
+                let low = whole_number(name, low)?;
+                let high = whole_number(name, high)?;
+                if low > high {
+                    return Err(CalcError::DomainError {
+                        name: name.to_string(),
+                        arg: low as f64,
+                        requirement: "a low end no higher than the high end",
+                    });
+                }
+                Ok(self.rng.gen_range(low..=high) as f64)
+            }
+            ("seed", &[seed]) => {
+                self.set_seed(natural(name, seed)?);
+                Ok(seed)
+            }
+            _ => Err(CalcError::UnknownFunction(name.to_string())),
+        }
+    }
+
+    /// Call a function that takes any number of arguments
+    /// The argument count has already been checked, so there's at least one
+    /// 
+    /// Examples:
+    ///   - call_variadic_function("sum", &[1.0, 2.0, 3.0]) → returns 6.0
+    ///   - call_variadic_function("avg", &[2.0, 4.0]) → returns 3.0
+    ///   - call_variadic_function("product", &[2.0, 3.0, 4.0]) → returns 24.0
+    fn call_variadic_function(&self, name: &str, args: &[f64]) -> CalcResult<f64> {
+        Ok(match name {
+            "sum" => args.iter().sum(),                             // a + b + ...
+            "avg" => args.iter().sum::<f64>() / args.len() as f64, // Arithmetic mean
+            "product" => args.iter().product(),                     // a * b * ...
+            _ => return Err(CalcError::UnknownFunction(name.to_string())),
+        })
+    }
+}
+
+/// The sign of x: -1 for negative numbers, 1 for positive ones and 0 for zero
+/// Examples: sign(-2.5) → -1, sign(0) → 0, sign(-0.0) → 0 (unlike f64::signum)
+fn sign(x: f64) -> f64 {
+    if x > 0.0 {
+        1.0
+    } else if x < 0.0 {
+        -1.0
+    } else if x == 0.0 {
+        0.0 // Positive zero, for -0.0 too
+    } else {
+        x // NaN has no sign
+    }
+}
+
+/// Turn a condition into a number: 1.0 for true, 0.0 for false
+fn truth(condition: bool) -> f64 {
+    if condition { 1.0 } else { 0.0 }
+}
+
+/// n! = 1 * 2 * ... * n, for whole numbers n >= 0 (and 0! = 1)
+/// Examples: factorial(5.0) → 120.0, factorial(171.0) → infinity (too big for f64),
+///           factorial(2.5) → error
+fn factorial(n: f64) -> CalcResult<f64> {
+    if n < 0.0 || n.fract() != 0.0 || n.is_nan() {
+        return Err(CalcError::DomainError {
+            name: "factorial".to_string(),
+            arg: n,
+            requirement: "a whole number that isn't negative",
+        });
+    }
+
+    let mut result = 1.0;
+    let mut i = 2.0;
+    // Once the product overflows to infinity it stays there, so stop early
+    while i <= n && result != f64::INFINITY {
+        result *= i;
+        i += 1.0;
+    }
+    Ok(result)
+}
+
+/// The largest whole number up to which an f64 holds every whole number exactly: 2^53
+const MAX_EXACT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// Whether a built-in function uses the random number generator, so calling it
+/// twice with the same arguments can give different results
+fn is_random_function(name: &str) -> bool {
+    matches!(name, "random" | "randint" | "seed")
+}
+
+/// Convert an argument of function `name` to a whole number, which may be negative
+/// Example: whole_number("randint", -3.0) → -3, whole_number("randint", 0.5) → error
+fn whole_number(name: &str, arg: f64) -> CalcResult<i64> {
+    if arg.fract() != 0.0 || !(-MAX_EXACT_INTEGER..=MAX_EXACT_INTEGER).contains(&arg) {
+        return Err(CalcError::DomainError {
+            name: name.to_string(),
+            arg,
+            requirement: "whole numbers from -2^53 to 2^53",
+        });
+    }
+    Ok(arg as i64)
+}
+
+/// Convert an argument of function `name` to a whole number that isn't negative
+/// Example: natural("gcd", 12.0) → 12, natural("gcd", -4.0) → error
+/// Past 2^53 an f64 skips whole numbers, so larger arguments are rejected too.
+fn natural(name: &str, arg: f64) -> CalcResult<u64> {
+    // NaN and infinity have no whole part, so fract() rules them out
+    if arg.fract() != 0.0 || !(0.0..=MAX_EXACT_INTEGER).contains(&arg) {
+        return Err(CalcError::DomainError {
+            name: name.to_string(),
+            arg,
+            requirement: "whole numbers from 0 to 2^53",
+        });
+    }
+    Ok(arg as u64)
+}
+
+/// The greatest common divisor, by Euclid's algorithm
+/// Examples: gcd(12, 18) → 6, gcd(7, 5) → 1, gcd(0, 4) → 4, gcd(0, 0) → 0
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple, or 0 if either number is 0
+/// Examples: lcm(4, 6) → 12, lcm(7, 5) → 35, lcm(0, 3) → 0
+/// Dividing by the gcd before multiplying keeps the intermediate value small;
+/// a result past 2^53 is rounded like any other large f64.
+fn lcm(a: u64, b: u64) -> f64 {
+    if a == 0 || b == 0 {
+        return 0.0;
+    }
+    (a / gcd(a, b)) as f64 * b as f64
+}
+
+/// The nth root of x
+/// Examples: root(27, 3) → 3, root(-8, 3) → -2, root(16, 4) → 2, root(-16, 4) → error
+/// x^(1/n) is NaN for any negative x, because 1/n is rarely exactly representable and
+/// powf only takes roots of negative numbers for whole exponents. An odd root of a
+/// negative number is real, so it's taken of -x and given back the sign.
+fn root(x: f64, n: f64) -> CalcResult<f64> {
+    let odd = n.fract() == 0.0 && n % 2.0 != 0.0;
+    let (arg, requirement) = if n == 0.0 {
+        (n, "a degree that isn't 0")
+    } else if x < 0.0 && !odd {
+        (x, "an odd whole-number degree to take the root of a negative number")
+    } else {
+        // 1000^(1/3) comes out as 9.999999999999998, so snap to a whole root that fits
+        let magnitude = x.abs().powf(1.0 / n);
+        let whole = magnitude.round();
+        let magnitude = if whole.powf(n) == x.abs() { whole } else { magnitude };
+        return Ok(magnitude.copysign(x));
+    };
+    Err(CalcError::DomainError {
+        name: "root".to_string(),
+        arg,
+        requirement,
+    })
+}
+
+/// Convert an operand of a bitwise operator to a 64-bit integer
+/// Example: to_integer("&", 12.0) → 12, to_integer("&", 1.5) → error
+/// Fractions are rejected rather than silently cut off.
+fn to_integer(operator: &str, value: f64) -> CalcResult<i64> {
+    // i64 covers -2^63 up to (but not including) 2^63
+    let in_range = value >= -(2f64.powi(63)) && value < 2f64.powi(63);
+    if value.fract() != 0.0 || !in_range {
+        return Err(CalcError::InvalidOperand {
+            operator: operator.to_string(),
+            value,
+            requirement: "whole numbers that fit in 64 bits",
+        });
+    }
+    Ok(value as i64)
+}
+
+/// Apply a bitwise operator to two numbers, working on them as integers
+/// Examples: bitwise(BitAnd, 12.0, 10.0) → 8.0, bitwise(ShiftLeft, 1.0, 4.0) → 16.0
+fn bitwise(op: BinaryOperator, left: f64, right: f64) -> CalcResult<f64> {
+    let symbol = op.symbol();
+    let left = to_integer(symbol, left)?;
+    let right_value = right;
+    let right = to_integer(symbol, right)?;
+
+    let result = match op {
+        BinaryOperator::BitAnd => left & right,
+        BinaryOperator::BitOr => left | right,
+        _ => {
+            // Shifting by 64 or more (or a negative amount) has no meaningful result
+            if !(0..64).contains(&right) {
+                return Err(CalcError::InvalidOperand {
+                    operator: symbol.to_string(),
+                    value: right_value,
+                    requirement: "a shift amount from 0 to 63",
+                });
+            }
+            if op == BinaryOperator::ShiftLeft {
+                left << right
+            } else {
+                left >> right // Arithmetic shift: negative numbers stay negative
+            }
+        }
+    };
+    Ok(result as f64)
+}
+
+/// Read a number as a condition: 0 is false, anything else is true
+fn is_true(value: f64) -> bool {
+    value != 0.0
+}
+
+// ============================================================================
+// PRETTY PRINTER
+// ============================================================================
+// The reverse of parsing: turn a tree back into text. Spacing is made uniform,
+// implicit multiplication is written out with '*', and parentheses appear only
+// where precedence needs them, so parsing the text again gives the same tree.
+//
+// Example: "2+3  *4" prints as "2 + 3 * 4", while "(2+3)*4" keeps its parentheses
+// as "(2 + 3) * 4".
+
+// How tightly each kind of expression binds, loosest first, following the grammar
+const STATEMENT: u8 = 0;   // x = ..., f(x) = ...
+const CONDITIONAL: u8 = 1; // ? :
+const OR: u8 = 2;          // or
+const AND: u8 = 3;         // and
+const NOT: u8 = 4;         // not
+const COMPARISON: u8 = 5;  // == != < <= > >=
+const BIT_OR: u8 = 6;      // |
+const BIT_AND: u8 = 7;     // &
+const SHIFT: u8 = 8;       // << >>
+const SUM: u8 = 9;         // + -
+const PRODUCT: u8 = 10;    // * / %
+const UNARY: u8 = 11;      // -x ~x
+const POWER: u8 = 12;      // ^
+const POSTFIX: u8 = 13;    // x!
+const ATOM: u8 = 14;       // Numbers, variables, calls and parentheses
+
+/// Write an expression back out as text
+/// 
+/// Examples:
+///   - Add(2, Multiply(3, 4)) → "2 + 3 * 4"
+///   - Multiply(Add(2, 3), 4) → "(2 + 3) * 4"
+///   - Power(Negate(2), 2) → "(-2) ^ 2", while Negate(Power(2, 2)) → "-2 ^ 2"
+///   - FunctionCall("max", [1, 2]) → "max(1, 2)"
+pub fn format_expr(expr: &Expr) -> String {
+    format_with_precedence(expr, STATEMENT)
+}
+
+/// Write a whole program back out, with statements separated by "; "
+/// Example: [Assignment("x", 2), Multiply(2, x)] → "x = 2; 2 * x"
+pub fn format_program(program: &[Expr]) -> String {
+    program.iter().map(format_expr).collect::<Vec<_>>().join("; ")
+}
+
+/// Write an expression that sits where the grammar expects at least `min`
+/// precedence, wrapping it in parentheses if it binds more loosely than that
+fn format_with_precedence(expr: &Expr, min: u8) -> String {
+    let text = match expr {
+        Expr::Number(value) => value.to_string(),
+        Expr::Variable(name) => name.clone(),
+        Expr::BinaryOp { op, left, right } => {
+            // Left associative operators need parentheses for a right operand at
+            // their own level: "10 - (3 - 2)". '^' is right associative, with a
+            // postfix base and an exponent that may have a sign: "(2 ^ 3) ^ -1".
+            let (left_min, right_min) = match op {
+                BinaryOperator::Power => (POSTFIX, UNARY),
+                _ => (binary_precedence(*op), binary_precedence(*op) + 1),
+            };
+            format!(
+                "{} {} {}",
+                format_with_precedence(left, left_min),
+                op.symbol(),
+                format_with_precedence(right, right_min)
+            )
+        }
+        Expr::UnaryOp { op, operand } => match op {
+            UnaryOperator::Negate => format!("-{}", format_with_precedence(operand, UNARY)),
+            UnaryOperator::BitNot => format!("~{}", format_with_precedence(operand, UNARY)),
+            UnaryOperator::Not => format!("not {}", format_with_precedence(operand, NOT)),
+            UnaryOperator::Factorial => format!("{}!", format_with_precedence(operand, POSTFIX)),
+        },
+        Expr::FunctionCall { name, args } => {
+            let args: Vec<String> = args
+                .iter()
+                .map(|arg| format_with_precedence(arg, CONDITIONAL))
+                .collect();
+            format!("{}({})", name, args.join(", "))
+        }
+        Expr::Assignment { name, value } => {
+            format!("{} = {}", name, format_with_precedence(value, CONDITIONAL))
+        }
+        Expr::FunctionDef { name, params, body } => {
+            let body = format_with_precedence(body, CONDITIONAL);
+            format!("{}({}) = {}", name, params.join(", "), body)
+        }
+        Expr::Conditional { condition, then, otherwise } => format!(
+            "{} ? {} : {}",
+            format_with_precedence(condition, OR),
+            format_with_precedence(then, CONDITIONAL),
+            format_with_precedence(otherwise, CONDITIONAL)
+        ),
+    };
+
+    if precedence(expr) < min {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
+
+/// How tightly an expression binds, as one of the levels above
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Assignment { .. } | Expr::FunctionDef { .. } => STATEMENT,
+        Expr::Conditional { .. } => CONDITIONAL,
+        Expr::BinaryOp { op, .. } => binary_precedence(*op),
+        Expr::UnaryOp { op: UnaryOperator::Not, .. } => NOT,
+        Expr::UnaryOp { op: UnaryOperator::Factorial, .. } => POSTFIX,
+        Expr::UnaryOp { .. } => UNARY,
+        Expr::Number(value) if value.is_sign_negative() => UNARY, // Written with a '-'
+        Expr::Number(_) | Expr::Variable(_) | Expr::FunctionCall { .. } => ATOM,
+    }
+}
+
+/// How tightly a binary operator binds
+fn binary_precedence(op: BinaryOperator) -> u8 {
+    match op {
+        BinaryOperator::Or => OR,
+        BinaryOperator::And => AND,
+        BinaryOperator::Equal
+        | BinaryOperator::NotEqual
+        | BinaryOperator::Less
+        | BinaryOperator::LessEqual
+        | BinaryOperator::Greater
+        | BinaryOperator::GreaterEqual => COMPARISON,
+        BinaryOperator::BitOr => BIT_OR,
+        BinaryOperator::BitAnd => BIT_AND,
+        BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight => SHIFT,
+        BinaryOperator::Add | BinaryOperator::Subtract => SUM,
+        BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulo => PRODUCT,
+        BinaryOperator::Power => POWER,
+    }
+}
+
+// ============================================================================
+// SIMPLIFIER
+// ============================================================================
+// Rewrites a tree into a simpler one with the same value:
+// - Operations on numbers alone are worked out: "2 * 3 + x" becomes "6 + x"
+// - Operations that change nothing are dropped: "x + 0", "x * 1", "x ^ 1"
+// - Double negation cancels out: "--x" becomes "x"
+// - A conditional with a known condition becomes the branch it picks
+//
+// Anything whose evaluation fails or gives inf or NaN, like "1 / 0", is left
+// as it is, so evaluating the simplified tree still reports the problem.
+
+/// Simplify an expression without changing its value
+/// 
+/// Examples:
+///   - "2 * 3 + x" → Add(6, x)
+///   - "x * 1 + 0" → Variable("x")
+///   - "-(-x)" → Variable("x")
+///   - "1 / 0 + x" → unchanged
+pub fn simplify(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Number(_) | Expr::Variable(_) => expr.clone(),
+        Expr::BinaryOp { op, left, right } => simplify_binary(*op, simplify(left), simplify(right)),
+        Expr::UnaryOp { op, operand } => match (op, simplify(operand)) {
+            (UnaryOperator::Negate, Expr::UnaryOp { op: UnaryOperator::Negate, operand }) => {
+                *operand
+            }
+            (op, operand) => fold(Expr::unary(*op, operand)),
+        },
+        Expr::FunctionCall { name, args } => fold(Expr::FunctionCall {
+            name: name.clone(),
+            args: args.iter().map(simplify).collect(),
+        }),
+        Expr::Assignment { name, value } => Expr::Assignment {
+            name: name.clone(),
+            value: Box::new(simplify(value)),
+        },
+        Expr::FunctionDef { name, params, body } => Expr::FunctionDef {
+            name: name.clone(),
+            params: params.clone(),
+            body: Box::new(simplify(body)),
+        },
+        Expr::Conditional { condition, then, otherwise } => match simplify(condition) {
+            // Only the chosen branch would ever be evaluated
+            Expr::Number(value) => simplify(if is_true(value) { then } else { otherwise }),
+            condition => Expr::Conditional {
+                condition: Box::new(condition),
+                then: Box::new(simplify(then)),
+                otherwise: Box::new(simplify(otherwise)),
+            },
+        },
+    }
+}
+
+/// Simplify a binary operation whose operands are already simplified
+/// Examples: (Add, x, 0) → x, (Multiply, 1, x) → x, (Multiply, 2, 3) → 6
+fn simplify_binary(op: BinaryOperator, left: Expr, right: Expr) -> Expr {
+    let is = |expr: &Expr, number: f64| matches!(expr, Expr::Number(value) if *value == number);
+    match op {
+        BinaryOperator::Add if is(&left, 0.0) => right,
+        BinaryOperator::Add | BinaryOperator::Subtract if is(&right, 0.0) => left,
+        BinaryOperator::Multiply if is(&left, 1.0) => right,
+        BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Power
+            if is(&right, 1.0) =>
+        {
+            left
+        }
+        _ => fold(Expr::binary(op, left, right)),
+    }
+}
+
+/// Replace an operation on numbers alone with its value, unless evaluating it
+/// fails or gives inf or NaN
+/// Calls to random functions are kept, since each evaluation should draw anew.
+/// Examples: Multiply(2, 3) → 6, sqrt(16) → 4, Divide(1, 0), Add(x, 1) and
+///           randint(1, 6) → unchanged
+fn fold(expr: Expr) -> Expr {
+    let is_number = |expr: &Expr| matches!(expr, Expr::Number(_));
+    let operands_are_numbers = match &expr {
+        Expr::BinaryOp { left, right, .. } => is_number(left) && is_number(right),
+        Expr::UnaryOp { operand, .. } => is_number(operand),
+        Expr::FunctionCall { name, args } => {
+            !is_random_function(name) && args.iter().all(is_number)
+        }
+        _ => false,
+    };
+    if !operands_are_numbers {
+        return expr;
+    }
+
+    // A fresh evaluator knows only the built-in functions, so calls to
+    // user-defined ones fail here and are kept
+    match Evaluator::new().evaluate(&expr) {
+        Ok(value) if value.is_finite() => Expr::Number(value),
+        _ => expr,
+    }
+}
+
+// ============================================================================
+// WASM MODULE
+// ============================================================================
+#[cfg(feature = "wasm")]
+mod wasm;
+
+#[cfg(feature = "wasm")]
+pub use wasm::CalculatorJs;
+
+// ============================================================================
+// LIBRARY API
+// ============================================================================
+// The easiest way to use the calculator from another program: evaluate() for
+// a one-off calculation, or a Calculator that remembers variables and
+// functions from one input to the next.
+
+/// Lex, parse and evaluate one input with no variables defined beforehand
+/// Examples: evaluate("2 + 3 * 4") → Ok(14.0), evaluate("2 +") → Err(UnexpectedEof)
+///
+/// ```
+/// use rust_calculator::evaluate;
+///
+/// assert_eq!(evaluate("2 + 3 * 4"), Ok(14.0));
+/// assert_eq!(evaluate("x = 5; x ^ 2"), Ok(25.0));
+/// assert_eq!(evaluate("2 +").unwrap_err().kind(), "UnexpectedEof");
+/// ```
+pub fn evaluate(input: &str) -> CalcResult<f64> {
+    Parser::new(Lexer::new(input))?.parse()
+}
+
+/// A calculator that keeps variables and user-defined functions between inputs
+/// 
+/// Example:
+///   - eval("x = 2") → Ok(2.0)
+///   - eval("f(n) = n * x") → Ok(0.0)
+///   - eval("f(3)") → Ok(6.0)
+///
+/// ```
+/// use rust_calculator::Calculator;
+///
+/// let mut calculator = Calculator::new();
+/// calculator.eval("x = 2").unwrap();
+/// calculator.eval("f(n) = n * x").unwrap();
+/// assert_eq!(calculator.eval("f(3)"), Ok(6.0));
+/// assert_eq!(calculator.variables().get("x"), Some(&2.0));
+/// ```
+#[derive(Clone, Default)]
+pub struct Calculator {
+    evaluator: Evaluator, // Holds the variables and functions
+}
+
+impl Calculator {
+    /// Create a calculator with no variables or functions defined
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluate one or more statements and return the value of the last one
+    /// A failing input changes nothing: assignments made before the error are discarded
+    ///
+    /// ```
+    /// use rust_calculator::Calculator;
+    ///
+    /// let mut calculator = Calculator::new();
+    /// assert!(calculator.eval("y = 1; 1 / 0").is_err());
+    /// assert!(calculator.variables().get("y").is_none());
+    /// ```
+    pub fn eval(&mut self, input: &str) -> CalcResult<f64> {
+        let program = Parser::new(Lexer::new(input))?.parse_ast()?;
+
+        // Evaluate on a copy, so a failing input leaves everything as it was
+        let mut evaluator = self.evaluator.clone();
+        let value = evaluator.evaluate_program(&program)?;
+        self.evaluator = evaluator;
+        Ok(value)
+    }
+
+    /// The variables currently defined
+    pub fn variables(&self) -> &HashMap<String, f64> {
+        self.evaluator.variables()
+    }
+
+    /// Set a variable directly, as if "name = value" had been evaluated
+    pub fn set_variable(&mut self, name: &str, value: f64) {
+        self.evaluator.set_variable(name, value);
+    }
+
+    /// The evaluator underneath, for settings like strict mode
+    ///
+    /// ```
+    /// use rust_calculator::Calculator;
+    ///
+    /// let mut calculator = Calculator::new();
+    /// calculator.evaluator_mut().set_strict(false);
+    /// assert_eq!(calculator.eval("1 / 0"), Ok(f64::INFINITY));
+    /// ```
+    pub fn evaluator_mut(&mut self) -> &mut Evaluator {
+        &mut self.evaluator
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+// Each group of tests below pins down one feature, errors included. Run them
+// with `cargo test`.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Evaluate `input` on a fresh calculator
+    fn eval(input: &str) -> CalcResult<f64> {
+        Calculator::new().eval(input)
+    }
+
+    /// The statements of `input` as trees
+    fn parse(input: &str) -> CalcResult<Vec<Expr>> {
+        Parser::new(Lexer::new(input))?.parse_ast()
+    }
+
+    /// Every token of `input` with its span, up to but not including EOF
+    fn lex(input: &str) -> CalcResult<Vec<SpannedToken>> {
+        let mut lexer = Lexer::new(input);
+        let mut tokens = Vec::new();
+        loop {
+            let spanned = lexer.next_token()?;
+            if spanned.token == Token::EOF {
+                return Ok(tokens);
+            }
+            tokens.push(spanned);
+        }
+    }
+
+    /// The tokens of `input` without their spans
+    fn tokens(input: &str) -> CalcResult<Vec<Token>> {
+        Ok(lex(input)?.into_iter().map(|spanned| spanned.token).collect())
+    }
+
+    /// The number `input` lexes to, if it's a single number
+    fn number(input: &str) -> CalcResult<f64> {
+        match tokens(input)?.as_slice() {
+            [Token::Number(value)] => Ok(*value),
+            other => panic!("{:?} should be one number, but lexed as {:?}", input, other),
+        }
+    }
+
+    /// The error for a malformed number at the start of the input
+    fn malformed(text: &str) -> CalcResult<Vec<Token>> {
+        Err(CalcError::MalformedNumber {
+            text: text.to_string(),
+            span: span(1, 1, text.chars().count() + 1),
+        })
+    }
+
+    /// A span on one line, from column `start` up to (not including) `end`
+    fn span(line: usize, start: usize, end: usize) -> Span {
+        Span {
+            start: Position { line, column: start },
+            end: Position { line, column: end },
+        }
+    }
+
+    /// Evaluate `input` on a fresh calculator with strict mode off
+    fn eval_lenient(input: &str) -> CalcResult<f64> {
+        let mut calculator = Calculator::new();
+        calculator.evaluator_mut().set_strict(false);
+        calculator.eval(input)
+    }
+
+    /// The kind of error `input` fails with
+    fn error_kind(input: &str) -> &'static str {
+        match eval(input) {
+            Ok(value) => panic!("{:?} should fail, but gave {}", input, value),
+            Err(err) => err.kind(),
+        }
+    }
+
+    /// The kind of error `input` fails with when strict mode is off
+    fn error_kind_lenient(input: &str) -> &'static str {
+        match eval_lenient(input) {
+            Ok(value) => panic!("{:?} should fail, but gave {}", input, value),
+            Err(err) => err.kind(),
+        }
+    }
+
+    // ---- Errors instead of panics ----
+
+    #[test]
+    fn parse_returns_value() {
+        let mut parser = Parser::new(Lexer::new("2 + 3 * 4")).unwrap();
+        assert_eq!(parser.parse(), Ok(14.0));
+    }
+
+    #[test]
+    fn incomplete_input_is_an_error() {
+        assert_eq!(error_kind("2 +"), "UnexpectedEof");
+        assert_eq!(error_kind("(2 + 3"), "UnexpectedEof");
+        assert_eq!(error_kind("sin("), "UnexpectedEof");
+    }
+
+    #[test]
+    fn unexpected_input_is_an_error() {
+        assert_eq!(error_kind("2 $ 3"), "UnexpectedCharacter");
+        assert_eq!(error_kind("(2 + 3))"), "UnexpectedToken");
+        assert_eq!(error_kind("* 2"), "UnexpectedToken");
+    }
+
+    #[test]
+    fn unknown_names_are_errors() {
+        assert_eq!(eval("y + 1"), Err(CalcError::UndefinedVariable("y".to_string())));
+        assert_eq!(eval("foo(1)"), Err(CalcError::UnknownFunction("foo".to_string())));
+    }
+
+    // ---- Token positions ----
+
+    #[test]
+    fn tokens_know_where_they_are() {
+        let spans: Vec<Span> = lex("x + 3.14").unwrap().iter().map(|t| t.span).collect();
+        assert_eq!(spans, vec![span(1, 1, 2), span(1, 3, 4), span(1, 5, 9)]);
+    }
+
+    #[test]
+    fn positions_count_lines_and_columns() {
+        let err = lex("(1 +\n  $)").unwrap_err();
+        assert_eq!(err.span(), Some(span(2, 3, 4)));
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_token() {
+        let err = eval("2 + $ 3").unwrap_err();
+        assert_eq!(
+            err.report("2 + $ 3"),
+            "2 + $ 3\n    ^\nError: Unexpected character '$' at line 1, column 5"
+        );
+
+        let input = "x = 1\ny = 2 +";
+        let err = eval(input).unwrap_err();
+        assert_eq!(err.span().map(|span| span.start.line), Some(2));
+        assert!(err.report(input).starts_with("y = 2 +\n"));
+    }
+
+    #[test]
+    fn errors_without_a_position_have_no_carets() {
+        let err = eval("y + 1").unwrap_err();
+        assert_eq!(err.span(), None);
+        assert_eq!(err.report("y + 1"), "Error: Undefined variable: y");
+    }
+
+    // ---- Syntax trees ----
+
+    #[test]
+    fn parser_builds_a_tree_with_precedence() {
+        let expected = Expr::binary(
+            BinaryOperator::Add,
+            Expr::Number(2.0),
+            Expr::binary(BinaryOperator::Multiply, Expr::Number(3.0), Expr::Number(4.0)),
+        );
+        assert_eq!(parse("2 + 3 * 4"), Ok(vec![expected]));
+    }
+
+    #[test]
+    fn parser_builds_assignments_and_calls() {
+        let expected = Expr::Assignment {
+            name: "x".to_string(),
+            value: Box::new(Expr::FunctionCall {
+                name: "sqrt".to_string(),
+                args: vec![Expr::Variable("y".to_string())],
+            }),
+        };
+        assert_eq!(parse("x = sqrt(y)"), Ok(vec![expected]));
+    }
+
+    #[test]
+    fn a_tree_can_be_evaluated_again() {
+        let program = parse("x = x + 1").unwrap();
+        let mut evaluator = Evaluator::new();
+        evaluator.set_variable("x", 1.0);
+        assert_eq!(evaluator.evaluate_program(&program), Ok(2.0));
+        assert_eq!(evaluator.evaluate_program(&program), Ok(3.0));
+        assert_eq!(evaluator.variables().get("x"), Some(&3.0));
+    }
+
+    #[test]
+    fn evaluator_reports_errors_from_the_tree() {
+        let mut evaluator = Evaluator::new();
+        let tree = Expr::binary(
+            BinaryOperator::Add,
+            Expr::Variable("y".to_string()),
+            Expr::Number(1.0),
+        );
+        assert_eq!(evaluator.evaluate(&tree), Err(CalcError::UndefinedVariable("y".to_string())));
+        assert_eq!(evaluator.evaluate_program(&[]), Ok(0.0));
+    }
+
+    // ---- Multi-argument functions ----
+
+    #[test]
+    fn functions_take_several_arguments() {
+        assert_eq!(eval("min(5, 3)"), Ok(3.0));
+        assert_eq!(eval("max(5, 3)"), Ok(5.0));
+        assert_eq!(eval("pow(2, 10)"), Ok(1024.0));
+        assert_eq!(eval("atan2(1, 1)"), Ok(std::f64::consts::FRAC_PI_4));
+        assert_eq!(eval("max(min(1, 2), pow(2, 2) - 1)"), Ok(3.0));
+    }
+
+    #[test]
+    fn argument_lists_are_parsed_into_the_call() {
+        let expected = Expr::FunctionCall {
+            name: "min".to_string(),
+            args: vec![Expr::Number(1.0), Expr::Number(2.0)],
+        };
+        assert_eq!(parse("min(1, 2)"), Ok(vec![expected]));
+        assert_eq!(error_kind("min(1,)"), "UnexpectedToken");
+        assert_eq!(error_kind("min(1 2"), "UnexpectedToken");
+        assert_eq!(error_kind("min(1, 2"), "UnexpectedEof");
+    }
+
+    #[test]
+    fn wrong_argument_counts_are_errors() {
+        let expected = |name: &str, count: usize, found: usize| {
+            Err(CalcError::WrongArgumentCount {
+                name: name.to_string(),
+                expected: Arity::Exactly(count),
+                found,
+            })
+        };
+        assert_eq!(eval("min(1)"), expected("min", 2, 1));
+        assert_eq!(eval("max(1, 2, 3)"), expected("max", 2, 3));
+        assert_eq!(eval("sqrt(1, 2)"), expected("sqrt", 1, 2));
+        assert_eq!(eval("pi(1)"), expected("pi", 0, 1));
+        assert_eq!(
+            eval("min(1)").unwrap_err().to_string(),
+            "min() takes 2 arguments, got 1"
+        );
+    }
+
+    // ---- Logarithms and exponentials ----
+
+    #[test]
+    fn logarithms_and_exponentials() {
+        assert_eq!(eval("ln(e())"), Ok(1.0));
+        assert_eq!(eval("log10(1000)"), Ok(3.0));
+        assert_eq!(eval("log2(8)"), Ok(3.0));
+        assert_eq!(eval("exp(0)"), Ok(1.0));
+        assert_eq!(eval("exp(1)"), Ok(std::f64::consts::E));
+    }
+
+    #[test]
+    fn logarithms_of_zero_and_negatives_are_domain_errors() {
+        for input in ["ln(0)", "log10(-1)", "log2(-8)"] {
+            assert_eq!(error_kind(input), "DomainError", "{}", input);
+        }
+        assert_eq!(
+            eval("ln(0)").unwrap_err().to_string(),
+            "ln() needs a positive argument, got 0"
+        );
+    }
+
+    #[test]
+    fn logarithms_outside_strict_mode_follow_ieee() {
+        assert_eq!(eval_lenient("ln(0)"), Ok(f64::NEG_INFINITY));
+        assert!(eval_lenient("log2(-1)").unwrap().is_nan());
+    }
+
+    // ---- Comparisons ----
+
+    #[test]
+    fn comparisons_give_one_or_zero() {
+        assert_eq!(eval("3 > 2"), Ok(1.0));
+        assert_eq!(eval("2 == 3"), Ok(0.0));
+        assert_eq!(eval("2 != 3"), Ok(1.0));
+        assert_eq!(eval("1 <= 1"), Ok(1.0));
+        assert_eq!(eval("1 < 1"), Ok(0.0));
+        assert_eq!(eval("2 >= 3"), Ok(0.0));
+    }
+
+    #[test]
+    fn comparisons_bind_looser_than_arithmetic() {
+        assert_eq!(eval("1 + 1 == 2"), Ok(1.0));
+        assert_eq!(eval("2 * 3 >= 6"), Ok(1.0));
+        assert_eq!(eval("x = 3 > 2; x"), Ok(1.0));
+        // Left to right: (3 < 2) < 1 is 0 < 1
+        assert_eq!(eval("3 < 2 < 1"), Ok(1.0));
+    }
+
+    #[test]
+    fn incomplete_comparisons_are_errors() {
+        assert_eq!(error_kind("1 !="), "UnexpectedEof");
+        assert_eq!(error_kind("1 = 2"), "UnexpectedToken");
+        assert_eq!(error_kind("< 2"), "UnexpectedToken");
+    }
+
+    // ---- Logical operators ----
+
+    #[test]
+    fn logical_operators_give_one_or_zero() {
+        assert_eq!(eval("2 and 3"), Ok(1.0));
+        assert_eq!(eval("1 and 0"), Ok(0.0));
+        assert_eq!(eval("0 or 0"), Ok(0.0));
+        assert_eq!(eval("0 or -2"), Ok(1.0));
+        assert_eq!(eval("not 0"), Ok(1.0));
+        assert_eq!(eval("not 5"), Ok(0.0));
+    }
+
+    #[test]
+    fn logical_operators_short_circuit() {
+        // y is never defined, so evaluating it would be an error
+        assert_eq!(eval("0 and y"), Ok(0.0));
+        assert_eq!(eval("1 or y"), Ok(1.0));
+        assert_eq!(eval("1 and y"), Err(CalcError::UndefinedVariable("y".to_string())));
+        assert_eq!(eval("0 or y"), Err(CalcError::UndefinedVariable("y".to_string())));
+    }
+
+    #[test]
+    fn logical_operator_precedence() {
+        // not binds looser than ==, and binds tighter than or
+        assert_eq!(eval("not 1 == 2"), Ok(1.0));
+        assert_eq!(eval("1 and 0 or 1"), Ok(1.0));
+        assert_eq!(eval("1 or 1 and 0"), Ok(1.0));
+        assert_eq!(error_kind("not"), "UnexpectedEof");
+        assert_eq!(error_kind("1 and"), "UnexpectedEof");
+    }
+
+    // ---- Conditionals ----
+
+    #[test]
+    fn conditionals_pick_a_branch() {
+        assert_eq!(eval("1 ? 2 : 3"), Ok(2.0));
+        assert_eq!(eval("0 ? 2 : 3"), Ok(3.0));
+        assert_eq!(eval("x = -5; x > 0 ? x : -x"), Ok(5.0));
+        assert_eq!(eval("1 + 1 ? 10 : 20"), Ok(10.0));
+        assert_eq!(eval("1 ? 2 : 3 + 4"), Ok(2.0));
+    }
+
+    #[test]
+    fn conditionals_only_evaluate_the_chosen_branch() {
+        assert_eq!(eval("1 ? 2 : y"), Ok(2.0));
+        assert_eq!(eval("0 ? y : 3"), Ok(3.0));
+        assert_eq!(eval("0 ? 2 : y"), Err(CalcError::UndefinedVariable("y".to_string())));
+    }
+
+    #[test]
+    fn conditionals_are_right_associative() {
+        let conditional = |condition, then, otherwise| Expr::Conditional {
+            condition: Box::new(Expr::Number(condition)),
+            then: Box::new(Expr::Number(then)),
+            otherwise: Box::new(otherwise),
+        };
+        let expected = conditional(0.0, 1.0, conditional(1.0, 2.0, Expr::Number(3.0)));
+        assert_eq!(parse("0 ? 1 : 1 ? 2 : 3"), Ok(vec![expected]));
+        assert_eq!(eval("0 ? 1 : 1 ? 2 : 3"), Ok(2.0));
+    }
+
+    #[test]
+    fn incomplete_conditionals_are_errors() {
+        assert_eq!(error_kind("1 ? 2"), "UnexpectedEof");
+        assert_eq!(error_kind("1 ? 2 3"), "UnexpectedToken");
+        assert_eq!(error_kind("1 ? : 3"), "UnexpectedToken");
+    }
+
+    // ---- User-defined functions ----
+
+    #[test]
+    fn user_functions_can_be_defined_and_called() {
+        assert_eq!(eval("f(x) = x^2 + 1; f(3)"), Ok(10.0));
+        assert_eq!(eval("f(x, y) = x * y; f(2, 3)"), Ok(6.0));
+        assert_eq!(eval("f() = 42; f()"), Ok(42.0));
+        assert_eq!(eval("fact(n) = n <= 1 ? 1 : n * fact(n - 1); fact(5)"), Ok(120.0));
+    }
+
+    #[test]
+    fn user_functions_persist_between_inputs() {
+        let mut calculator = Calculator::new();
+        assert_eq!(calculator.eval("f(n) = n * 2"), Ok(0.0));
+        assert_eq!(calculator.eval("f(4)"), Ok(8.0));
+        assert!(calculator.evaluator_mut().functions().contains_key("f"));
+    }
+
+    #[test]
+    fn parameters_shadow_variables_without_changing_them() {
+        assert_eq!(eval("x = 10; f(x) = x * 2; f(3)"), Ok(6.0));
+        assert_eq!(eval("x = 10; f(x) = x * 2; f(3); x"), Ok(10.0));
+        // Other names in the body are looked up when the function is called
+        assert_eq!(eval("f(a) = a + b; b = 2; f(1)"), Ok(3.0));
+        assert_eq!(eval("f(a) = a + b; f(1)"), Err(CalcError::UndefinedVariable("b".to_string())));
+    }
+
+    #[test]
+    fn user_function_errors() {
+        assert_eq!(
+            eval("f(x) = x; f(1, 2)"),
+            Err(CalcError::WrongArgumentCount {
+                name: "f".to_string(),
+                expected: Arity::Exactly(1),
+                found: 2,
+            })
+        );
+        assert_eq!(eval("sin(x) = x"), Err(CalcError::ReservedName("sin".to_string())));
+        assert_eq!(
+            eval("f(x) = f(x); f(1)"),
+            Err(CalcError::RecursionLimit("f".to_string()))
+        );
+        assert_eq!(error_kind("f(x = x"), "UnexpectedToken");
+    }
+
+    // ---- The previous result ----
+
+    #[test]
+    fn ans_is_undefined_until_set() {
+        assert_eq!(eval("ans"), Err(CalcError::UndefinedVariable(ANS.to_string())));
+
+        let mut calculator = Calculator::new();
+        calculator.set_variable(ANS, 5.0);
+        assert_eq!(calculator.eval("ans * 2"), Ok(10.0));
+    }
+
+    #[test]
+    fn ans_cant_be_assigned() {
+        assert_eq!(eval("ans = 5"), Err(CalcError::ReservedName(ANS.to_string())));
+
+        let mut calculator = Calculator::new();
+        calculator.set_variable(ANS, 5.0);
+        assert_eq!(calculator.eval("x = 1; ans = 2").unwrap_err().kind(), "ReservedName");
+        assert_eq!(calculator.variables().get(ANS), Some(&5.0));
+    }
+
+    // ---- Factorial ----
+
+    #[test]
+    fn factorial_of_whole_numbers() {
+        assert_eq!(eval("0!"), Ok(1.0));
+        assert_eq!(eval("1!"), Ok(1.0));
+        assert_eq!(eval("5!"), Ok(120.0));
+        assert_eq!(eval("5! / 3!"), Ok(20.0));
+    }
+
+    #[test]
+    fn factorial_binds_tighter_than_power_and_minus() {
+        assert_eq!(eval("2^3!"), Ok(64.0));
+        assert_eq!(eval("-3!"), Ok(-6.0));
+        assert_eq!(eval("3!!"), Ok(720.0));
+    }
+
+    #[test]
+    fn factorial_of_fractions_and_negatives_is_an_error() {
+        let expected = |arg| {
+            Err(CalcError::DomainError {
+                name: "factorial".to_string(),
+                arg,
+                requirement: "a whole number that isn't negative",
+            })
+        };
+        assert_eq!(eval("3.5!"), expected(3.5));
+        assert_eq!(eval("(-1)!"), expected(-1.0));
+        // Unlike the domain errors of functions, this one doesn't depend on strict mode
+        assert_eq!(eval_lenient("3.5!"), expected(3.5));
+    }
+
+    #[test]
+    fn factorial_overflows_to_infinity() {
+        assert!(eval("170!").unwrap().is_finite());
+        assert_eq!(eval("171!"), Ok(f64::INFINITY));
+        assert_eq!(eval("1000!"), Ok(f64::INFINITY));
+    }
+
+    // ---- Implicit multiplication ----
+
+    #[test]
+    fn operands_side_by_side_are_multiplied() {
+        assert_eq!(eval("2(3 + 4)"), Ok(14.0));
+        assert_eq!(eval("2pi()"), Ok(2.0 * std::f64::consts::PI));
+        assert_eq!(eval("(1 + 1)(2 + 2)"), Ok(8.0));
+        assert_eq!(eval("x = 4; 3x"), Ok(12.0));
+        assert_eq!(eval("-2(3)"), Ok(-6.0));
+
+        let expected = Expr::binary(
+            BinaryOperator::Multiply,
+            Expr::Number(2.0),
+            Expr::Variable("x".to_string()),
+        );
+        assert_eq!(parse("2x"), Ok(vec![expected]));
+    }
+
+    #[test]
+    fn implicit_multiplication_has_the_precedence_of_star() {
+        // Left to right like 6 / 2 * 3, and below ^
+        assert_eq!(eval("6 / 2(3)"), Ok(9.0));
+        assert_eq!(eval("2^2(3)"), Ok(12.0));
+    }
+
+    #[test]
+    fn two_numbers_in_a_row_are_still_an_error() {
+        assert_eq!(error_kind("2 3"), "UnexpectedToken");
+        assert_eq!(error_kind("(2)3"), "UnexpectedToken");
+    }
+
+    // ---- Scientific notation ----
+
+    #[test]
+    fn numbers_can_have_an_exponent() {
+        assert_eq!(number("1.5e3"), Ok(1500.0));
+        assert_eq!(number("2E-2"), Ok(0.02));
+        assert_eq!(number("1e+3"), Ok(1000.0));
+        assert_eq!(number(".5e1"), Ok(5.0));
+        assert_eq!(number("1.e2"), Ok(100.0));
+        assert_eq!(number("1e400"), Ok(f64::INFINITY));
+        assert_eq!(number("1e-400"), Ok(0.0));
+    }
+
+    #[test]
+    fn an_exponent_needs_digits() {
+        assert_eq!(tokens("2e"), malformed("2e"));
+        assert_eq!(tokens("2e+"), malformed("2e+"));
+        assert_eq!(tokens("2e-x"), malformed("2e-"));
+    }
+
+    // ---- Hexadecimal, octal and binary literals ----
+
+    #[test]
+    fn prefixed_literals_lex_in_their_base() {
+        assert_eq!(number("0xFF"), Ok(255.0));
+        assert_eq!(number("0XfF"), Ok(255.0));
+        assert_eq!(number("0o17"), Ok(15.0));
+        assert_eq!(number("0b1010"), Ok(10.0));
+        assert_eq!(number("0x0"), Ok(0.0));
+    }
+
+    #[test]
+    fn prefixed_literals_need_valid_digits() {
+        assert_eq!(tokens("0x"), malformed("0x"));
+        assert_eq!(tokens("0b102"), malformed("0b102"));
+        assert_eq!(tokens("0o8"), malformed("0o8"));
+        assert_eq!(tokens("0xfg"), malformed("0xfg"));
+    }
+
+    #[test]
+    fn prefixed_literals_mix_with_arithmetic() {
+        assert_eq!(eval("0xFF + 1"), Ok(256.0));
+        assert_eq!(eval("0xFF + 1 == 256"), Ok(1.0));
+        assert_eq!(eval("0b1010 * 0o10 - 0x10"), Ok(64.0));
+    }
+
+    // ---- Bitwise operators ----
+
+    #[test]
+    fn bitwise_operators_work_on_whole_numbers() {
+        assert_eq!(eval("0xFF & 0x0F"), Ok(15.0));
+        assert_eq!(eval("0b1100 | 0b0011"), Ok(15.0));
+        assert_eq!(eval("~0"), Ok(-1.0));
+        assert_eq!(eval("1 << 4"), Ok(16.0));
+        assert_eq!(eval("256 >> 4"), Ok(16.0));
+        assert_eq!(eval("(-8) >> 1"), Ok(-4.0));
+    }
+
+    #[test]
+    fn bitwise_precedence_sits_below_arithmetic() {
+        // Shifts bind tighter than '&', which binds tighter than '|'
+        assert_eq!(eval("6 | 1 & 3"), Ok(7.0));
+        assert_eq!(eval("6 & 1 << 1"), Ok(2.0));
+        // Arithmetic binds tighter than shifts, and '^' is still a power
+        assert_eq!(eval("1 + 1 << 2"), Ok(8.0));
+        assert_eq!(eval("1 << 2 + 1"), Ok(8.0));
+        assert_eq!(eval("2 ^ 3 & 12"), Ok(8.0));
+    }
+
+    #[test]
+    fn bitwise_operators_reject_fractions() {
+        let invalid = |operator: &str, value: f64, requirement: &'static str| {
+            Err(CalcError::InvalidOperand { operator: operator.to_string(), value, requirement })
+        };
+        let whole = "whole numbers that fit in 64 bits";
+        assert_eq!(eval("1.5 & 2"), invalid("&", 1.5, whole));
+        assert_eq!(eval("2 | 0.5"), invalid("|", 0.5, whole));
+        assert_eq!(eval("~1.5"), invalid("~", 1.5, whole));
+        assert_eq!(eval("1e20 & 1"), invalid("&", 1e20, whole));
+    }
+
+    #[test]
+    fn shift_amounts_must_be_in_range() {
+        let out_of_range = |operator: &str, value: f64| {
+            Err(CalcError::InvalidOperand {
+                operator: operator.to_string(),
+                value,
+                requirement: "a shift amount from 0 to 63",
+            })
+        };
+        assert_eq!(eval("1 << 64"), out_of_range("<<", 64.0));
+        assert_eq!(eval("1 >> -1"), out_of_range(">>", -1.0));
+        assert_eq!(eval("1 << 62"), Ok(2f64.powi(62)));
+    }
+
+    // ---- Digit separators ----
+
+    #[test]
+    fn underscores_separate_digits() {
+        assert_eq!(number("1_000_000"), Ok(1_000_000.0));
+        assert_eq!(number("1_0.5_5"), Ok(10.55));
+        assert_eq!(number("1e1_0"), Ok(1e10));
+        assert_eq!(number("0xFF_FF"), Ok(65535.0));
+        assert_eq!(number("0b1_0"), Ok(2.0));
+    }
+
+    #[test]
+    fn misplaced_underscores_are_malformed() {
+        for text in ["100_", "1__0", "1_.5", "1._5", "1_e3", "1e3_", "0x_FF", "0xFF_", "0b1__0"] {
+            assert_eq!(tokens(text), malformed(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn a_leading_underscore_starts_a_name() {
+        assert_eq!(tokens("_100"), Ok(vec![Token::Identifier("_100".to_string())]));
+        assert_eq!(eval("_100"), Err(CalcError::UndefinedVariable("_100".to_string())));
+    }
+
+    // ---- Division by zero and domain errors ----
+
+    #[test]
+    fn dividing_by_zero_is_an_error() {
+        assert_eq!(eval("1 / 0"), Err(CalcError::DivisionByZero { dividend: 1.0, operator: '/' }));
+        assert_eq!(eval("5 % 0"), Err(CalcError::DivisionByZero { dividend: 5.0, operator: '%' }));
+        assert_eq!(error_kind("0 / (2 - 2)"), "DivisionByZero");
+    }
+
+    #[test]
+    fn functions_reject_arguments_outside_their_domain() {
+        assert_eq!(error_kind("sqrt(0 - 1)"), "DomainError");
+        assert_eq!(error_kind("ln(0)"), "DomainError");
+        assert_eq!(error_kind("asin(2)"), "DomainError");
+        assert_eq!(error_kind("acos(0 - 2)"), "DomainError");
+        assert_eq!(
+            eval("sqrt(0 - 1)"),
+            Err(CalcError::DomainError {
+                name: "sqrt".to_string(),
+                arg: -1.0,
+                requirement: "an argument that isn't negative",
+            })
+        );
+        assert_eq!(eval("asin(1)"), Ok(std::f64::consts::FRAC_PI_2));
+    }
+
+    #[test]
+    fn a_failed_assignment_stores_nothing() {
+        let mut calculator = Calculator::new();
+        assert_eq!(
+            calculator.eval("x = 1 / 0"),
+            Err(CalcError::DivisionByZero { dividend: 1.0, operator: '/' })
+        );
+        assert!(!calculator.variables().contains_key("x"));
+        assert_eq!(calculator.eval("x"), Err(CalcError::UndefinedVariable("x".to_string())));
+    }
+
+    #[test]
+    fn lenient_mode_follows_ieee_arithmetic() {
+        assert_eq!(eval_lenient("1 / 0"), Ok(f64::INFINITY));
+        assert_eq!(eval_lenient("0 - 1 / 0"), Ok(f64::NEG_INFINITY));
+        assert!(eval_lenient("sqrt(0 - 1)").unwrap().is_nan());
+        assert!(eval_lenient("5 % 0").unwrap().is_nan());
+        assert_eq!(eval_lenient("ln(0)"), Ok(f64::NEG_INFINITY));
+    }
+
+    // ---- Malformed decimal literals ----
+
+    #[test]
+    fn a_second_decimal_point_is_malformed() {
+        assert_eq!(tokens("1.2.3"), malformed("1.2.3"));
+        assert_eq!(tokens("1..2"), malformed("1..2"));
+        assert_eq!(tokens("."), malformed("."));
+        assert_eq!(error_kind("1.2.3 + 1"), "MalformedNumber");
+        assert_eq!(error_kind(". + 1"), "MalformedNumber");
+    }
+
+    #[test]
+    fn a_decimal_point_may_start_or_end_a_number() {
+        assert_eq!(number("5."), Ok(5.0));
+        assert_eq!(number(".5"), Ok(0.5));
+        assert_eq!(eval("5. + 1"), Ok(6.0));
+    }
+
+    // ---- Unary plus and stacked prefix operators ----
+
+    #[test]
+    fn unary_plus_is_a_no_op() {
+        assert_eq!(parse("+5"), Ok(vec![Expr::Number(5.0)]));
+        assert_eq!(eval("+5"), Ok(5.0));
+        assert_eq!(eval("2 * +3"), Ok(6.0));
+    }
+
+    #[test]
+    fn prefix_operators_stack() {
+        let negate = |operand| Expr::unary(UnaryOperator::Negate, operand);
+        assert_eq!(parse("--5"), Ok(vec![negate(negate(Expr::Number(5.0)))]));
+        assert_eq!(parse("-+-5"), Ok(vec![negate(negate(Expr::Number(5.0)))]));
+        assert_eq!(eval("--5"), Ok(5.0));
+        assert_eq!(eval("-+-5"), Ok(5.0));
+        assert_eq!(eval("- - -5"), Ok(-5.0));
+        assert_eq!(eval("~-1"), Ok(0.0));
+        assert_eq!(eval("not -1"), Ok(0.0));
+    }
+
+    #[test]
+    fn a_prefix_minus_applies_after_a_power() {
+        // -2^2 is -(2^2), as in mathematics
+        let power = Expr::binary(BinaryOperator::Power, Expr::Number(2.0), Expr::Number(2.0));
+        assert_eq!(parse("-2^2"), Ok(vec![Expr::unary(UnaryOperator::Negate, power)]));
+        assert_eq!(eval("-2^2"), Ok(-4.0));
+        assert_eq!(eval("(-2)^2"), Ok(4.0));
+        assert_eq!(eval("2^-1"), Ok(0.5));
+        assert_eq!(eval("--2^2"), Ok(4.0));
+    }
+
+    // ---- Library API ----
+
+    #[test]
+    fn evaluate_starts_from_nothing_each_time() {
+        assert_eq!(evaluate("x = 2; x * 3"), Ok(6.0));
+        assert_eq!(evaluate("x"), Err(CalcError::UndefinedVariable("x".to_string())));
+    }
+
+    #[test]
+    fn a_calculator_remembers_between_inputs() {
+        let mut calculator = Calculator::new();
+        calculator.set_variable("rate", 0.5);
+        assert_eq!(calculator.eval("cost(n) = n * rate"), Ok(0.0));
+        assert_eq!(calculator.eval("cost(10)"), Ok(5.0));
+        assert_eq!(calculator.eval("rate = 2; cost(10)"), Ok(20.0));
+        assert_eq!(calculator.variables().get("rate"), Some(&2.0));
+    }
+
+    #[test]
+    fn the_pieces_can_be_used_separately() {
+        let program = parse("x = 4; sqrt(x)").unwrap();
+        let mut evaluator = Evaluator::new();
+        assert_eq!(evaluator.evaluate_program(&program), Ok(2.0));
+        assert_eq!(evaluator.into_variables().get("x"), Some(&4.0));
+    }
+
+    // ---- Errors as JSON ----
+
+    #[test]
+    fn errors_serialize_with_kind_message_and_position() {
+        let error = evaluate("2 + $ 3").unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "kind": "UnexpectedCharacter",
+                "message": "Unexpected character '$' at line 1, column 5",
+                "position": {"line": 1, "column": 5},
+            })
+        );
+    }
+
+    #[test]
+    fn errors_without_a_position_serialize_it_as_null() {
+        let error = evaluate("1 / 0").unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "kind": "DivisionByZero",
+                "message": "Division by zero: 1 / 0",
+                "position": null,
+            })
+        );
+    }
+
+    #[test]
+    fn positions_on_later_lines_are_serialized() {
+        let error = evaluate("x = 1\nx +").unwrap_err();
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["kind"], "UnexpectedEof");
+        assert_eq!(json["position"], serde_json::json!({"line": 2, "column": 4}));
+    }
+
+    // ---- Pretty-printer ----
+
+    /// `input` parsed and written back out
+    fn reformat(input: &str) -> String {
+        format_program(&parse(input).unwrap())
+    }
+
+    /// Any tree the parser could build, from small numbers and the variables x, y and z
+    fn any_expr() -> impl Strategy<Value = Expr> {
+        use BinaryOperator::*;
+        use UnaryOperator::*;
+
+        let leaf = prop_oneof![
+            (0..100u32).prop_map(|n| Expr::Number(f64::from(n) / 2.0)),
+            prop::sample::select(vec!["x", "y", "z"]).prop_map(|name| Expr::Variable(name.into())),
+        ];
+        leaf.prop_recursive(4, 32, 3, |inner| {
+            let binary_ops = vec![
+                Add, Subtract, Multiply, Divide, Modulo, Power, BitAnd, BitOr, ShiftLeft,
+                ShiftRight, Equal, NotEqual, Less, LessEqual, Greater, GreaterEqual, And, Or,
+            ];
+            let unary_ops = vec![Negate, Not, BitNot, Factorial];
+            prop_oneof![
+                (prop::sample::select(binary_ops), inner.clone(), inner.clone())
+                    .prop_map(|(op, left, right)| Expr::binary(op, left, right)),
+                (prop::sample::select(unary_ops), inner.clone())
+                    .prop_map(|(op, operand)| Expr::unary(op, operand)),
+                prop::collection::vec(inner.clone(), 1..4)
+                    .prop_map(|args| Expr::FunctionCall { name: "max".into(), args }),
+                (inner.clone(), inner.clone(), inner).prop_map(|(condition, then, otherwise)| {
+                    Expr::Conditional {
+                        condition: Box::new(condition),
+                        then: Box::new(then),
+                        otherwise: Box::new(otherwise),
+                    }
+                }),
+            ]
+        })
+    }
+
+    #[test]
+    fn printing_normalizes_spacing() {
+        assert_eq!(reformat("2+3  *4"), "2 + 3 * 4");
+        assert_eq!(reformat("x=5;x*2"), "x = 5; x * 2");
+        assert_eq!(reformat("max( 1 ,2,3 )"), "max(1, 2, 3)");
+        assert_eq!(reformat("f(a,b)=a+b"), "f(a, b) = a + b");
+        assert_eq!(reformat("x>0?x:-x"), "x > 0 ? x : -x");
+    }
+
+    #[test]
+    fn printing_keeps_only_needed_parentheses() {
+        assert_eq!(reformat("(2+3)*4"), "(2 + 3) * 4");
+        assert_eq!(reformat("2+(3*4)"), "2 + 3 * 4");
+        assert_eq!(reformat("10-(3-2)"), "10 - (3 - 2)");
+        assert_eq!(reformat("(10-3)-2"), "10 - 3 - 2");
+        assert_eq!(reformat("((x))"), "x");
+    }
+
+    #[test]
+    fn printing_handles_signs_and_powers() {
+        assert_eq!(reformat("-2^2"), "-2 ^ 2");
+        assert_eq!(reformat("(-2)^2"), "(-2) ^ 2");
+        assert_eq!(reformat("2^3^2"), "2 ^ 3 ^ 2");
+        assert_eq!(reformat("(2^3)^2"), "(2 ^ 3) ^ 2");
+        assert_eq!(reformat("2^-1"), "2 ^ -1");
+        assert_eq!(reformat("--x"), "--x");
+        assert_eq!(reformat("(-3)!"), "(-3)!");
+    }
+
+    proptest! {
+        #[test]
+        fn printed_trees_parse_back_the_same(expr in any_expr()) {
+            let text = format_expr(&expr);
+            prop_assert_eq!(parse(&text), Ok(vec![expr]), "printed as {}", text);
+        }
+    }
+
+    // ---- Simplifier ----
+
+    /// `input` simplified and written back out
+    fn simplified(input: &str) -> String {
+        let program: Vec<Expr> = parse(input).unwrap().iter().map(simplify).collect();
+        format_program(&program)
+    }
+
+    #[test]
+    fn constants_are_folded() {
+        assert_eq!(simplified("2 * 3 + x"), "6 + x");
+        assert_eq!(simplified("x * (1 + 1)"), "x * 2");
+        assert_eq!(simplified("sqrt(16) + x"), "4 + x");
+        assert_eq!(simplified("2 ^ 10"), "1024");
+        assert_eq!(simplified("1 < 2 ? x : y"), "x");
+        assert_eq!(simplified("1 > 2 ? x : y"), "y");
+    }
+
+    #[test]
+    fn identity_operations_are_dropped() {
+        assert_eq!(simplified("x + 0"), "x");
+        assert_eq!(simplified("0 + x"), "x");
+        assert_eq!(simplified("x - 0"), "x");
+        assert_eq!(simplified("x * 1"), "x");
+        assert_eq!(simplified("1 * x"), "x");
+        assert_eq!(simplified("x / 1"), "x");
+        assert_eq!(simplified("x ^ 1"), "x");
+        assert_eq!(simplified("--x"), "x");
+        assert_eq!(simplified("-(-(x + 1))"), "x + 1");
+    }
+
+    #[test]
+    fn failing_subtrees_are_left_alone() {
+        assert_eq!(simplified("1 / 0 + x"), "1 / 0 + x");
+        assert_eq!(simplified("1 / 0 * 1"), "1 / 0");
+        // The parts that can be worked out still are
+        assert_eq!(simplified("x + sqrt(0 - 1)"), "x + sqrt(-1)");
+        assert_eq!(simplified("5 % (2 - 2)"), "5 % 0");
+        assert_eq!(error_kind(&simplified("5 % (2 - 2)")), "DivisionByZero");
+    }
+
+    proptest! {
+        #[test]
+        fn simplifying_keeps_the_value(
+            expr in any_expr(),
+            x in -10.0..10.0f64,
+            y in -10.0..10.0f64,
+            z in -10.0..10.0f64,
+        ) {
+            let mut evaluator = Evaluator::new();
+            evaluator.set_variable("x", x.round()); // A whole number, for '!' and bitwise operators
+            evaluator.set_variable("y", y);
+            evaluator.set_variable("z", z);
+
+            // Compared as text, so NaN matches NaN, in results and in errors alike
+            let original = format!("{:?}", evaluator.clone().evaluate(&expr));
+            let simpler = format!("{:?}", evaluator.evaluate(&simplify(&expr)));
+            prop_assert_eq!(
+                original,
+                simpler,
+                "{} simplified to {}",
+                format_expr(&expr),
+                format_expr(&simplify(&expr))
+            );
+        }
+    }
+
+    // ---- Lookahead ----
+
+    #[test]
+    fn peeking_does_not_move_the_parser() {
+        let mut parser = Parser::new(Lexer::new("x = 5")).unwrap();
+        assert_eq!(parser.peek_token(1), Some(&Token::Number(5.0)));
+        assert_eq!(parser.peek_token(0), Some(&Token::Assign));
+        assert_eq!(parser.peek_token(2), Some(&Token::EOF));
+        assert_eq!(parser.current_token, Token::Identifier("x".to_string()));
+
+        // Advancing hands out the peeked tokens in order
+        parser.advance().unwrap();
+        assert_eq!(parser.current_token, Token::Assign);
+        parser.advance().unwrap();
+        assert_eq!(parser.current_token, Token::Number(5.0));
+    }
+
+    #[test]
+    fn peeking_stops_at_a_lexer_error() {
+        let mut parser = Parser::new(Lexer::new("x $ 1")).unwrap();
+        assert_eq!(parser.peek_token(0), None);
+        assert_eq!(parser.peek_token(1), None);
+        assert_eq!(
+            parser.advance(),
+            Err(CalcError::UnexpectedCharacter { ch: '$', span: span(1, 3, 4) })
+        );
+    }
+
+    #[test]
+    fn a_name_alone_is_an_expression() {
+        assert_eq!(parse("x"), Ok(vec![Expr::Variable("x".to_string())]));
+        let compare = Expr::binary(
+            BinaryOperator::Equal,
+            Expr::Variable("x".to_string()),
+            Expr::Number(5.0),
+        );
+        assert_eq!(parse("x == 5"), Ok(vec![compare]));
+    }
+
+    #[test]
+    fn a_name_and_equals_sign_is_an_assignment() {
+        let assign = Expr::Assignment { name: "x".to_string(), value: Box::new(Expr::Number(5.0)) };
+        assert_eq!(parse("x = 5"), Ok(vec![assign.clone()]));
+        assert_eq!(parse("x = 5; x"), Ok(vec![assign, Expr::Variable("x".to_string())]));
+        assert_eq!(error_kind("x ="), "UnexpectedEof");
+        assert_eq!(
+            parse("x = $"),
+            Err(CalcError::UnexpectedCharacter { ch: '$', span: span(1, 5, 6) })
+        );
+    }
+
+    #[test]
+    fn lookahead_tells_definitions_from_calls() {
+        assert!(matches!(parse("f(x, y) = x * y").unwrap()[..], [Expr::FunctionDef { .. }]));
+        assert!(matches!(parse("f(x, y)").unwrap()[..], [Expr::FunctionCall { .. }]));
+        assert!(matches!(parse("f(x, 1)").unwrap()[..], [Expr::FunctionCall { .. }]));
+        assert_eq!(error_kind("f(x, 1) = 2"), "UnexpectedToken");
+        assert_eq!(error_kind("sin(x) = x"), "ReservedName");
+    }
+
+    #[test]
+    fn long_scripts_parse_in_one_pass() {
+        // Each statement only looks a few tokens ahead, so this stays fast
+        let script: String = (0..10_000).map(|i| format!("x{} = {}; x{} * 2\n", i, i, i)).collect();
+        let program = parse(&script).unwrap();
+        assert_eq!(program.len(), 20_000);
+        assert_eq!(evaluate(&script), Ok(19_998.0));
+    }
+
+    // ---- Token kinds ----
+
+    #[test]
+    fn tokens_with_values_share_a_kind() {
+        assert_eq!(Token::Number(1.0).kind(), Token::Number(2.5).kind());
+        assert_eq!(Token::Identifier("x".to_string()).kind(), TokenKind::Identifier);
+        assert_eq!(Token::Function("sin".to_string()).kind(), TokenKind::Function);
+        assert_ne!(Token::Identifier("x".to_string()).kind(), TokenKind::Function);
+        assert_eq!(Token::EOF.kind(), TokenKind::EOF);
+    }
+
+    #[test]
+    fn kinds_describe_themselves_for_errors() {
+        assert_eq!(TokenKind::RightParen.to_string(), "')'");
+        assert_eq!(TokenKind::ShiftLeft.to_string(), "'<<'");
+        assert_eq!(TokenKind::Identifier.to_string(), "a name");
+        assert_eq!(TokenKind::Newline.to_string(), "end of line");
+        assert_eq!(TokenKind::EOF.to_string(), "end of input");
+    }
+
+    #[test]
+    fn eat_consumes_only_the_expected_kind() {
+        let mut parser = Parser::new(Lexer::new("(x")).unwrap();
+        assert_eq!(parser.eat(TokenKind::LeftParen), Ok(()));
+        assert_eq!(parser.current_token, Token::Identifier("x".to_string()));
+
+        assert_eq!(
+            parser.eat(TokenKind::Comma),
+            Err(CalcError::UnexpectedToken {
+                expected: "','".to_string(),
+                found: Token::Identifier("x".to_string()),
+                span: span(1, 2, 3),
+            })
+        );
+        // A failed eat leaves the token in place
+        assert_eq!(parser.eat(TokenKind::Identifier), Ok(()));
+        assert_eq!(
+            parser.eat(TokenKind::RightParen),
+            Err(CalcError::UnexpectedEof { expected: "')'".to_string(), span: span(1, 3, 3) })
+        );
+    }
+
+    #[test]
+    fn expected_tokens_are_named_in_errors() {
+        let message = |input: &str| eval(input).unwrap_err().to_string();
+        assert_eq!(message("(1 + 2"), "Expected ')', but the input ended at line 1, column 7");
+        assert_eq!(message("x > 0 ? 1"), "Expected ':', but the input ended at line 1, column 10");
+        assert_eq!(message("max(1 2)"), "Expected ')', found number 2 at line 1, column 7");
+    }
+
+    // ---- Comments ----
+
+    #[test]
+    fn comments_run_to_the_end_of_the_line() {
+        let x = || Token::Identifier("x".to_string());
+        assert_eq!(
+            tokens("x = 5 # initial value"),
+            Ok(vec![x(), Token::Assign, Token::Number(5.0)])
+        );
+        assert_eq!(tokens("x // also a comment"), Ok(vec![x()]));
+        assert_eq!(tokens("# x = 5"), Ok(vec![]));
+        assert_eq!(eval("x = 5 # initial value"), Ok(5.0));
+    }
+
+    #[test]
+    fn full_line_comments_sit_between_statements() {
+        assert_eq!(eval("x = 1 # one\n# now double it\nx * 2"), Ok(2.0));
+        assert_eq!(eval("// start\n3 + 4\n// end"), Ok(7.0));
+        assert_eq!(eval("1 + # more below\n2"), Ok(3.0));
+    }
+
+    #[test]
+    fn a_single_slash_still_divides() {
+        assert_eq!(eval("8 / 2"), Ok(4.0));
+        assert_eq!(eval("8 / 2 // halved"), Ok(4.0));
+    }
+
+    #[test]
+    fn blank_input_holds_only_whitespace_and_comments() {
+        assert!(Lexer::is_blank(""));
+        assert!(Lexer::is_blank("   \t"));
+        assert!(Lexer::is_blank("# just a note"));
+        assert!(Lexer::is_blank("  // a note\n# another"));
+        assert!(!Lexer::is_blank("1 # one"));
+        assert!(!Lexer::is_blank("$"));
+    }
+
+    // ---- Newlines between statements ----
+
+    #[test]
+    fn a_line_break_after_a_complete_statement_is_a_token() {
+        let x = || Token::Identifier("x".to_string());
+        let expected = vec![
+            x(),
+            Token::Assign,
+            Token::Number(5.0),
+            Token::Newline,
+            x(),
+            Token::Multiply,
+            Token::Number(2.0),
+        ];
+        assert_eq!(tokens("x = 5\nx * 2"), Ok(expected));
+        // Blank lines in between add nothing, and neither do leading ones
+        assert_eq!(
+            tokens("\n\n1\n\n\n2"),
+            Ok(vec![Token::Number(1.0), Token::Newline, Token::Number(2.0)])
+        );
+    }
+
+    #[test]
+    fn newlines_and_semicolons_both_separate_statements() {
+        assert_eq!(eval("x = 5\nx * 2"), Ok(10.0));
+        assert_eq!(eval("x = 1; y = 2\nx + y"), Ok(3.0));
+        assert_eq!(eval("x = 1\ny = 2; x + y\n"), Ok(3.0));
+        assert_eq!(parse("x = 5\nx * 2"), parse("x = 5; x * 2"));
+        assert_eq!(eval("\n\n3\n\n"), Ok(3.0));
+    }
+
+    #[test]
+    fn unfinished_lines_continue_on_the_next() {
+        // After an operator or '=', or inside parentheses
+        assert_eq!(tokens("1 +\n2"), Ok(vec![Token::Number(1.0), Token::Plus, Token::Number(2.0)]));
+        assert_eq!(eval("1 +\n2"), Ok(3.0));
+        assert_eq!(eval("x = 2 *\n\n3"), Ok(6.0));
+        assert_eq!(eval("max(1,\n2)"), Ok(2.0));
+        assert_eq!(eval("(1\n+ 2)"), Ok(3.0));
+        assert_eq!(eval("1 ?\n2 :\n3"), Ok(2.0));
+        assert_eq!(eval("f(x) =\nx + 1\nf(2)"), Ok(3.0));
+    }
+
+    #[test]
+    fn a_complete_line_is_not_continued() {
+        // "+ 2" on its own line is a statement of its own
+        assert_eq!(eval("1\n+ 2"), Ok(2.0));
+        assert_eq!(
+            parse("1\n2 3"),
+            Err(CalcError::UnexpectedToken {
+                expected: "';', end of line or end of input".to_string(),
+                found: Token::Number(3.0),
+                span: span(2, 3, 4),
+            })
+        );
+    }
+
+    // ---- sum, avg and product ----
+
+    #[test]
+    fn aggregates_of_one_value_are_that_value() {
+        assert_eq!(eval("sum(5)"), Ok(5.0));
+        assert_eq!(eval("avg(4)"), Ok(4.0));
+        assert_eq!(eval("product(7)"), Ok(7.0));
+    }
+
+    #[test]
+    fn aggregates_combine_many_values() {
+        assert_eq!(eval("sum(1, 2, 3, 4)"), Ok(10.0));
+        assert_eq!(eval("avg(1, 2, 3, 4)"), Ok(2.5));
+        assert_eq!(eval("product(1, 2, 3, 4)"), Ok(24.0));
+        assert_eq!(eval("sum(1.5, -2)"), Ok(-0.5));
+    }
+
+    #[test]
+    fn aggregates_nest_with_other_expressions() {
+        assert_eq!(eval("x = 2; avg(x, x^2, 10) * 3"), Ok(16.0));
+        assert_eq!(eval("sum(1, sum(2, 3), product(2, 2))"), Ok(10.0));
+        assert_eq!(error_kind("sum(1, 1 / 0)"), "DivisionByZero");
+    }
+
+    #[test]
+    fn aggregates_need_an_argument() {
+        for name in ["sum", "avg", "product"] {
+            assert_eq!(
+                eval(&format!("{}()", name)),
+                Err(CalcError::WrongArgumentCount {
+                    name: name.to_string(),
+                    expected: Arity::AtLeast(1),
+                    found: 0,
+                })
+            );
+        }
+        let message = eval("avg()").unwrap_err().to_string();
+        assert_eq!(message, "avg() takes at least 1 argument, got 0");
+    }
+
+    // ---- gcd and lcm ----
+
+    /// The error gcd() or lcm() gives for an argument that isn't a small enough whole number
+    fn not_natural(name: &str, arg: f64) -> CalcResult<f64> {
+        Err(CalcError::DomainError {
+            name: name.to_string(),
+            arg,
+            requirement: "whole numbers from 0 to 2^53",
+        })
+    }
+
+    #[test]
+    fn gcd_and_lcm_of_whole_numbers() {
+        assert_eq!(eval("gcd(12, 18)"), Ok(6.0));
+        assert_eq!(eval("lcm(4, 6)"), Ok(12.0));
+        // Coprime pairs
+        assert_eq!(eval("gcd(7, 5)"), Ok(1.0));
+        assert_eq!(eval("lcm(7, 5)"), Ok(35.0));
+    }
+
+    #[test]
+    fn gcd_and_lcm_with_zero() {
+        assert_eq!(eval("gcd(0, 0)"), Ok(0.0));
+        assert_eq!(eval("gcd(0, 5)"), Ok(5.0));
+        assert_eq!(eval("gcd(5, 0)"), Ok(5.0));
+        assert_eq!(eval("lcm(0, 5)"), Ok(0.0));
+        assert_eq!(eval("lcm(0, 0)"), Ok(0.0));
+    }
+
+    #[test]
+    fn gcd_and_lcm_reject_fractions_and_negatives() {
+        assert_eq!(eval("gcd(1.5, 3)"), not_natural("gcd", 1.5));
+        assert_eq!(eval("lcm(2, 2.5)"), not_natural("lcm", 2.5));
+        assert_eq!(eval("gcd(-12, 18)"), not_natural("gcd", -12.0));
+        assert_eq!(eval("lcm(4, -6)"), not_natural("lcm", -6.0));
+        assert_eq!(eval("gcd(1e20, 5)"), not_natural("gcd", 1e20));
+        assert_eq!(eval_lenient("gcd(1 / 0, 5)"), not_natural("gcd", f64::INFINITY));
+    }
+
+    #[test]
+    fn large_lcms_do_not_overflow() {
+        // Dividing by the gcd first keeps the arithmetic in range
+        let big = 2f64.powi(53);
+        assert_eq!(lcm(1 << 53, 1 << 52), big);
+        assert_eq!(eval("lcm(2^53, 2^52)"), Ok(big));
+        // A result past 2^53 is rounded like any other large number
+        assert_eq!(eval("lcm(2^53, 2^53 - 1)"), Ok(big * (big - 1.0)));
+    }
+
+    // ---- random and randint ----
+
+    /// The values of `input` evaluated `count` times by one evaluator started from `seed`
+    fn draws(seed: u64, input: &str, count: usize) -> Vec<f64> {
+        let expr = &parse(input).unwrap()[0];
+        let mut evaluator = Evaluator::new();
+        evaluator.set_seed(seed);
+        (0..count).map(|_| evaluator.evaluate(expr).unwrap()).collect()
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_sequence() {
+        assert_eq!(draws(42, "random()", 20), draws(42, "random()", 20));
+        assert_eq!(draws(42, "randint(1, 100)", 20), draws(42, "randint(1, 100)", 20));
+        assert_ne!(draws(42, "random()", 20), draws(43, "random()", 20));
+        // ChaCha8 gives the same numbers on every platform
+        assert_eq!(draws(42, "random()", 1), [0.6818961923066713]);
+    }
+
+    #[test]
+    fn seed_restarts_the_sequence() {
+        assert_eq!(eval("seed(42)"), Ok(42.0));
+        assert_eq!(eval("seed(42); random()"), Ok(0.6818961923066713));
+        assert_eq!(eval("seed(7); a = random(); seed(7); a == random()"), Ok(1.0));
+        assert_eq!(eval("seed(7); a = random(); a == random()"), Ok(0.0));
+    }
+
+    #[test]
+    fn random_stays_below_one() {
+        for value in draws(1, "random()", 1000) {
+            assert!((0.0..1.0).contains(&value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn randint_includes_both_ends() {
+        let values = draws(1, "randint(1, 3)", 1000);
+        assert!(values.iter().all(|value| [1.0, 2.0, 3.0].contains(value)));
+        assert!(values.contains(&1.0) && values.contains(&3.0));
+        assert_eq!(draws(1, "randint(-2, -2)", 5), [-2.0; 5]);
+    }
+
+    #[test]
+    fn randint_checks_its_range() {
+        assert_eq!(
+            eval("randint(5, 1)"),
+            Err(CalcError::DomainError {
+                name: "randint".to_string(),
+                arg: 5.0,
+                requirement: "a low end no higher than the high end",
+            })
+        );
+        assert_eq!(
+            eval("randint(1.5, 3)"),
+            Err(CalcError::DomainError {
+                name: "randint".to_string(),
+                arg: 1.5,
+                requirement: "whole numbers from -2^53 to 2^53",
+            })
+        );
+        assert_eq!(error_kind("randint(1, 2.5)"), "DomainError");
+        assert_eq!(eval("seed(-1)"), not_natural("seed", -1.0));
+        assert_eq!(eval("seed(1.5)"), not_natural("seed", 1.5));
+    }
+
+    // ---- clamp, sign, trunc and fract ----
+
+    #[test]
+    fn clamp_keeps_a_value_in_range() {
+        assert_eq!(eval("clamp(15, 0, 10)"), Ok(10.0));
+        assert_eq!(eval("clamp(-3, 0, 10)"), Ok(0.0));
+        assert_eq!(eval("clamp(5, 0, 10)"), Ok(5.0));
+        assert_eq!(eval("clamp(5, 3, 3)"), Ok(3.0));
+        assert_eq!(eval("clamp(-5, -10, -1)"), Ok(-5.0));
+    }
+
+    #[test]
+    fn clamp_rejects_an_empty_range() {
+        assert_eq!(
+            eval("clamp(5, 10, 0)"),
+            Err(CalcError::DomainError {
+                name: "clamp".to_string(),
+                arg: 10.0,
+                requirement: "a low end no higher than the high end",
+            })
+        );
+        // A NaN bound can't make a range either
+        assert_eq!(error_kind_lenient("clamp(5, 0 / 0, 1)"), "DomainError");
+        assert_eq!(error_kind_lenient("clamp(5, 0, 0 / 0)"), "DomainError");
+    }
+
+    #[test]
+    fn sign_is_minus_one_zero_or_one() {
+        assert_eq!(eval("sign(-5)"), Ok(-1.0));
+        assert_eq!(eval("sign(2.5)"), Ok(1.0));
+        assert_eq!(eval("sign(0)"), Ok(0.0));
+        // Negative zero has no sign either
+        let zero = eval("sign(-0)").unwrap();
+        assert_eq!(zero, 0.0);
+        assert!(zero.is_sign_positive());
+        assert!(eval_lenient("sign(0 / 0)").unwrap().is_nan());
+    }
+
+    #[test]
+    fn trunc_rounds_toward_zero() {
+        assert_eq!(eval("trunc(2.7)"), Ok(2.0));
+        assert_eq!(eval("trunc(-2.7)"), Ok(-2.0));
+        assert_eq!(eval("floor(-2.7)"), Ok(-3.0));
+        assert_eq!(eval("trunc(-3)"), Ok(-3.0));
+    }
+
+    #[test]
+    fn fract_keeps_the_sign_of_its_argument() {
+        assert_eq!(eval("fract(2.75)"), Ok(0.75));
+        assert_eq!(eval("fract(-2.75)"), Ok(-0.75));
+        assert_eq!(eval("fract(-3)"), Ok(0.0));
+        // x is always trunc(x) + fract(x)
+        assert_eq!(eval("x = -7.25; trunc(x) + fract(x) == x"), Ok(1.0));
+    }
+
+    // ---- hypot, cbrt and root ----
+
+    #[test]
+    fn hypot_and_cbrt() {
+        assert_eq!(eval("hypot(3, 4)"), Ok(5.0));
+        assert_eq!(eval("hypot(-5, 12)"), Ok(13.0));
+        // No overflow on the way, even though 1e300^2 is out of range
+        assert_eq!(eval("hypot(1e300, 1e300)"), Ok(1e300 * std::f64::consts::SQRT_2));
+        assert_eq!(eval("cbrt(27)"), Ok(3.0));
+        assert_eq!(eval("cbrt(-8)"), Ok(-2.0));
+    }
+
+    #[test]
+    fn root_takes_whole_roots_exactly() {
+        assert_eq!(eval("root(27, 3)"), Ok(3.0));
+        assert_eq!(eval("root(16, 4)"), Ok(2.0));
+        assert_eq!(eval("root(1000, 3)"), Ok(10.0));
+        assert_eq!(eval("root(4, 0.5)"), Ok(16.0));
+        assert_eq!(eval("root(-8, -3)"), Ok(-0.5));
+    }
+
+    #[test]
+    fn an_odd_root_of_a_negative_number_is_negative() {
+        assert_eq!(eval("root(-8, 3)"), Ok(-2.0));
+        assert_eq!(eval("root(-32, 5)"), Ok(-2.0));
+        // Unlike a fractional power, which has no real value for a negative base
+        assert!(eval("(-8) ^ (1 / 3)").unwrap().is_nan());
+    }
+
+    #[test]
+    fn root_rejects_even_roots_of_negatives_and_degree_zero() {
+        let negative = "an odd whole-number degree to take the root of a negative number";
+        let domain_error = |arg: f64, requirement: &'static str| {
+            Err(CalcError::DomainError { name: "root".to_string(), arg, requirement })
+        };
+        assert_eq!(eval("root(-16, 4)"), domain_error(-16.0, negative));
+        assert_eq!(eval("root(-8, 1.5)"), domain_error(-8.0, negative));
+        assert_eq!(eval("root(8, 0)"), domain_error(0.0, "a degree that isn't 0"));
+        assert_eq!(eval("root(-8, 0)"), domain_error(0.0, "a degree that isn't 0"));
+    }
+
+    #[test]
+    fn hypot_cbrt_and_root_check_their_arity() {
+        let wrong_count = |name: &str, expected: usize, found: usize| {
+            Err(CalcError::WrongArgumentCount {
+                name: name.to_string(),
+                expected: Arity::Exactly(expected),
+                found,
+            })
+        };
+        assert_eq!(eval("hypot(1)"), wrong_count("hypot", 2, 1));
+        assert_eq!(eval("root(8)"), wrong_count("root", 2, 1));
+        assert_eq!(eval("cbrt(1, 2)"), wrong_count("cbrt", 1, 2));
+    }
+}
@@ -1,648 +1,30 @@
 // ============================================================================
-// RUST CALCULATOR - A LEARNING PROJECT FOR LEXERS AND PARSERS
+// RUST CALCULATOR - COMMAND LINE TOOL
 // ============================================================================
 //
-// This is a complete implementation of a calculator that demonstrates how to
-// build a lexer (tokenizer) and parser for a simple programming language.
+// The calculator itself (lexer, parser and evaluator) lives in the library,
+// lib.rs. This binary adds the ways to run it: the interactive REPL, script
+// files, piped input, single expressions and a demonstration.
 //
-// FEATURES:
-// - Arithmetic operators: + - * / % ^ (with correct precedence)
-// - Variables: x = 5; y = x + 2
-// - Parentheses for grouping: (2 + 3) * 4
-// - Multiple statements: x = 5; y = x + 2; x * y
-//
-// ARCHITECTURE:
-// 1. LEXER: Converts text "2 + 3" into tokens [Number(2), Plus, Number(3)]
-// 2. PARSER: Uses recursive descent to build understanding and evaluate
-//
-// PRECEDENCE (highest to lowest):
-// - Parentheses: ()
-// - Power: ^ (right associative)
-// - Multiply/Divide/Modulo: * / %
-// - Add/Subtract: + -
-//
-// This is an excellent starting point for learning compiler/interpreter design!
-//
-// ============================================================================
-// TOKEN DEFINITION
-// ============================================================================
-// Tokens are the "words" of our programming language. The lexer breaks down
-// source code into these atomic units that the parser can understand.
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum Token {
-    // Literals and identifiers
-    Number(f64),         // Numbers like 3.14, 42, -5.0
-    Identifier(String),  // Variable names like "x", "foo", "my_var"
-    
-    // Arithmetic operators (in order of precedence, lowest to highest)
-    Plus,                // + addition
-    Minus,               // - subtraction  
-    Multiply,            // * multiplication
-    Divide,              // / division
-    Modulo,              // % remainder (e.g., 10 % 3 = 1)
-    Power,               // ^ exponentiation (e.g., 2^3 = 8)
-    
-    // Grouping and structure
-    LeftParen,           // ( for grouping expressions
-    RightParen,          // ) for grouping expressions
-    Comma,               // , for function arguments (future use)
-    Assign,              // = for variable assignment
-    Semicolon,           // ; to separate statements
-    
-    // Functions
-    Function(String),    // Function names like "sin", "cos", "tan"
-    
-    // Special
-    EOF,                 // End of file/input marker
-}
-
-// ============================================================================
-// LEXER (TOKENIZER)
-// ============================================================================
-// The lexer's job is to take raw text like "x = 2 + 3" and break it into
-// tokens like [Identifier("x"), Assign, Number(2.0), Plus, Number(3.0)]
-//
-// Think of it like reading a sentence and identifying: noun, verb, adjective, etc.
-
-#[derive(Clone)]
-pub struct Lexer {
-    input: Vec<char>,           // The source code as individual characters
-    position: usize,            // Current position in the input
-    current_char: Option<char>, // The character we're currently looking at
-}
-
-impl Lexer {
-    /// Create a new lexer from input string
-    /// Example: Lexer::new("2 + 3") sets up lexer to tokenize "2 + 3"
-    pub fn new(input: &str) -> Self {
-        let chars: Vec<char> = input.chars().collect();
-        let current_char = chars.get(0).copied(); // Start at first character
-        
-        Lexer {
-            input: chars,
-            position: 0,
-            current_char,
-        }
-    }
-
-    /// Move to the next character in the input
-    /// Like moving a cursor forward when reading text
-    fn advance(&mut self) {
-        self.position += 1;
-        self.current_char = self.input.get(self.position).copied();
-    }
-
-    /// Skip over whitespace characters (spaces, tabs, newlines)
-    /// We ignore whitespace since it doesn't affect meaning in our language
-    fn skip_whitespace(&mut self) {
-        while let Some(ch) = self.current_char {
-            if ch.is_whitespace() {
-                self.advance();
-            } else {
-                break;
-            }
-        }
-    }
-
-    /// Read a complete number (including decimals)
-    /// Examples: "42" -> 42.0, "3.14" -> 3.14, "0.5" -> 0.5
-    fn read_number(&mut self) -> f64 {
-        let mut number_str = String::new();
-        
-        // Keep reading digits and decimal points
-        while let Some(ch) = self.current_char {
-            if ch.is_ascii_digit() || ch == '.' {
-                number_str.push(ch);
-                self.advance();
-            } else {
-                break; // Stop when we hit a non-digit, non-decimal character
-            }
-        }
-        
-        // Convert string to number, default to 0.0 if parsing fails
-        number_str.parse().unwrap_or(0.0)
-    }
-
-    /// Read a complete identifier (variable name)
-    /// Examples: "x" -> "x", "my_var" -> "my_var", "foo123" -> "foo123"
-    /// Rules: Must start with letter or underscore, then can contain letters, digits, underscores
-    fn read_identifier(&mut self) -> String {
-        let mut identifier = String::new();
-        
-        // Keep reading valid identifier characters
-        while let Some(ch) = self.current_char {
-            if ch.is_ascii_alphabetic() || ch.is_ascii_digit() || ch == '_' {
-                identifier.push(ch);
-                self.advance();
-            } else {
-                break; // Stop when we hit an invalid identifier character
-            }
-        }
-        
-        identifier
-    }
-
-    /// Get the next token from the input
-    /// This is the main method that identifies what kind of token we're looking at
-    /// and returns the appropriate Token enum variant
-    pub fn next_token(&mut self) -> Token {
-        // Keep processing characters until we find a token or reach end of input
-        while let Some(ch) = self.current_char {
-            match ch {
-                // Whitespace: skip it and continue
-                ' ' | '\t' | '\n' => {
-                    self.skip_whitespace();
-                    continue;
-                }
-                
-                // Single-character operators: recognize and advance
-                '+' => {
-                    self.advance();
-                    return Token::Plus;
-                }
-                '-' => {
-                    self.advance();
-                    return Token::Minus;
-                }
-                '*' => {
-                    self.advance();
-                    return Token::Multiply;
-                }
-                '/' => {
-                    self.advance();
-                    return Token::Divide;
-                }
-                '^' => {
-                    self.advance();
-                    return Token::Power;
-                }
-                '%' => {
-                    self.advance();
-                    return Token::Modulo;
-                }
-                '(' => {
-                    self.advance();
-                    return Token::LeftParen;
-                }
-                ')' => {
-                    self.advance();
-                    return Token::RightParen;
-                }
-                '=' => {
-                    self.advance();
-                    return Token::Assign;
-                }
-                ';' => {
-                    self.advance();
-                    return Token::Semicolon;
-                }
-                ',' => {
-                    self.advance();
-                    return Token::Comma;
-                }
-                
-                // Multi-character tokens: use helper methods
-                _ if ch.is_ascii_digit() => {
-                    // Found a digit, read the complete number
-                    let number = self.read_number();
-                    return Token::Number(number);
-                }
-                _ if ch.is_ascii_alphabetic() || ch == '_' => {
-                    // Found a letter or underscore, read the complete identifier
-                    let identifier = self.read_identifier();
-                    
-                    // Check if this is a known function name
-                    return match identifier.as_str() {
-                        // Trigonometric functions
-                        "sin" | "cos" | "tan" | "asin" | "acos" | "atan" |
-                        // Mathematical functions
-                        "sqrt" | "abs" | "floor" | "ceil" | "round" |
-                        // Logarithmic and exponential functions
-                        "ln" | "log10" | "log2" | "exp" |
-                        // Mathematical constants (zero-argument functions)
-                        "pi" | "e" |
-                        // Multi-argument functions
-                        "min" | "max" | "pow" | "atan2" => Token::Function(identifier),
-                        _ => Token::Identifier(identifier),
-                    };
-                }
-                
-                // Unknown character: this is an error
-                _ => {
-                    panic!("Unexpected character: {}", ch);
-                }
-            }
-        }
-        
-        // No more characters to process
-        Token::EOF
-    }
-}
-
-use std::collections::HashMap;
-
-// ============================================================================
-// PARSER (RECURSIVE DESCENT)
-// ============================================================================
-// The parser takes tokens from the lexer and builds an understanding of the
-// program structure. It uses "recursive descent" - each grammar rule becomes
-// a method that calls other methods.
-//
-// Our grammar (in order of precedence, lowest to highest):
-//   program    → statement (';' statement)*
-//   statement  → assignment | expression
-//   assignment → IDENTIFIER '=' expression
-//   expression → term (('+' | '-') term)*
-//   term       → power (('*' | '/' | '%') power)*
-//   power      → factor ('^' factor)*
-//   factor     → NUMBER | IDENTIFIER | '(' expression ')'
-
-pub struct Parser {
-    lexer: Lexer,                    // Source of tokens
-    current_token: Token,            // The token we're currently looking at
-    variables: HashMap<String, f64>, // Storage for variable values (symbol table)
-}
-
-impl Parser {
-    /// Create a new parser with the given lexer
-    /// Gets the first token to start parsing
-    pub fn new(mut lexer: Lexer) -> Self {
-        let current_token = lexer.next_token(); // Prime the parser with first token
-        Parser {
-            lexer,
-            current_token,
-            variables: HashMap::new(), // Start with no variables defined
-        }
-    }
-
-    /// "Eat" a token - verify it's what we expect, then move to next token
-    /// This is a common parser pattern for consuming expected tokens
-    /// 
-    /// Example: if we expect a '+' and see a '+', advance to next token
-    ///          if we expect a '+' but see a '*', panic with error
-    fn eat(&mut self, expected_token: Token) {
-        // Use discriminant to compare token types without comparing values
-        // (e.g., Number(5.0) matches Number(0.0) for type checking)
-        if std::mem::discriminant(&self.current_token) == std::mem::discriminant(&expected_token) {
-            self.current_token = self.lexer.next_token();
-        } else {
-            panic!("Expected {:?}, got {:?}", expected_token, self.current_token);
-        }
-    }
-
-    /// Call a built-in function with the given argument
-    /// This is our function table - maps function names to implementations
-    /// 
-    /// Function categories:
-    ///   - Trigonometric: sin, cos, tan (input in radians)
-    ///   - Inverse trig: asin, acos, atan (output in radians)
-    ///   - Mathematical: sqrt, abs, floor, ceil, round
-    /// 
-    /// Examples:
-    ///   - call_function("sqrt", 16.0) → returns 4.0
-    ///   - call_function("abs", -5.0) → returns 5.0
-    ///   - call_function("floor", 3.7) → returns 3.0
-    fn call_function(&self, name: &str, arg: f64) -> f64 {
-        match name {
-            // Basic trigonometric functions
-            "sin" => arg.sin(),
-            "cos" => arg.cos(),
-            "tan" => arg.tan(),
-            
-            // Inverse trigonometric functions
-            "asin" => arg.asin(),   // Returns value in [-π/2, π/2]
-            "acos" => arg.acos(),   // Returns value in [0, π]
-            "atan" => arg.atan(),   // Returns value in (-π/2, π/2)
-            
-            // Mathematical functions
-            "sqrt" => arg.sqrt(),   // Square root
-            "abs" => arg.abs(),     // Absolute value
-            "floor" => arg.floor(), // Round down to nearest integer
-            "ceil" => arg.ceil(),   // Round up to nearest integer
-            "round" => arg.round(), // Round to nearest integer
-            
-            // Logarithmic and exponential functions
-            "ln" => arg.ln(),       // Natural logarithm (base e)
-            "log10" => arg.log10(), // Base-10 logarithm
-            "log2" => arg.log2(),   // Base-2 logarithm
-            "exp" => arg.exp(),     // e^x (exponential function)
-            
-            _ => panic!("Unknown function: {}", name),
-        }
-    }
-
-    /// Call a mathematical constant (zero-argument function)
-    /// These are functions that take no arguments and return constant values
-    /// 
-    /// Examples:
-    ///   - call_constant("pi") → returns π ≈ 3.14159
-    ///   - call_constant("e") → returns e ≈ 2.71828
-    fn call_constant(&self, name: &str) -> f64 {
-        match name {
-            "pi" => std::f64::consts::PI,  // π ≈ 3.14159265359
-            "e" => std::f64::consts::E,    // e ≈ 2.71828182846
-            _ => panic!("Unknown constant: {}", name),
-        }
-    }
-
-    /// Call a two-argument function
-    /// These functions take two arguments and return a result
-    /// 
-    /// Examples:
-    ///   - call_two_arg_function("min", 5.0, 3.0) → returns 3.0
-    ///   - call_two_arg_function("max", 5.0, 3.0) → returns 5.0
-    ///   - call_two_arg_function("pow", 2.0, 3.0) → returns 8.0
-    fn call_two_arg_function(&self, name: &str, arg1: f64, arg2: f64) -> f64 {
-        match name {
-            "min" => arg1.min(arg2),        // Minimum of two values
-            "max" => arg1.max(arg2),        // Maximum of two values
-            "pow" => arg1.powf(arg2),       // arg1 raised to power arg2
-            "atan2" => arg1.atan2(arg2),    // Two-argument arctangent (y, x)
-            _ => panic!("Unknown two-argument function: {}", name),
-        }
-    }
-
-    /// Parse a factor: the highest precedence elements
-    /// factor → NUMBER | IDENTIFIER | FUNCTION '(' expression ')' | '(' expression ')' | '-' factor
-    /// 
-    /// Examples:
-    ///   - "42" → returns 42.0
-    ///   - "-5" → returns -5.0 (unary minus)
-    ///   - "x" → looks up variable x and returns its value
-    ///   - "sin(3.14)" → calls sin function with 3.14 and returns result
-    ///   - "(2 + 3)" → recursively parses "2 + 3" and returns 5.0
-    fn factor(&mut self) -> f64 {
-        let token = self.current_token.clone();
-        
-        match token {
-            Token::Number(value) => {
-                // Found a number literal
-                self.eat(Token::Number(0.0)); // Consume the number token
-                value
-            }
-            Token::Identifier(name) => {
-                // Found a variable reference
-                self.eat(Token::Identifier(String::new())); // Consume the identifier token
-                
-                // Look up the variable's value in our symbol table
-                *self.variables.get(&name).unwrap_or_else(|| {
-                    panic!("Undefined variable: {}", name);
-                })
-            }
-            Token::Function(name) => {
-                // Found a function call
-                self.eat(Token::Function(String::new())); // Consume the function name
-                self.eat(Token::LeftParen);               // Consume '('
-                
-                // Determine function type and parse arguments accordingly
-                let result = match name.as_str() {
-                    "pi" | "e" => {
-                        // Zero-argument function (constant)
-                        self.call_constant(&name)
-                    }
-                    "min" | "max" | "pow" | "atan2" => {
-                        // Two-argument function
-                        let arg1 = self.expr();           // Parse first argument
-                        self.eat(Token::Comma);           // Consume ','
-                        let arg2 = self.expr();           // Parse second argument
-                        self.call_two_arg_function(&name, arg1, arg2)
-                    }
-                    _ => {
-                        // Single-argument function
-                        let arg = self.expr();            // Parse the argument
-                        self.call_function(&name, arg)
-                    }
-                };
-                
-                self.eat(Token::RightParen);              // Consume ')'
-                result
-            }
-            Token::Minus => {
-                // Found unary minus (negative number)
-                self.eat(Token::Minus);       // Consume the '-'
-                -self.factor()                // Recursively parse the factor and negate it
-            }
-            Token::LeftParen => {
-                // Found parentheses - parse the expression inside
-                self.eat(Token::LeftParen);   // Consume '('
-                let result = self.expr();     // Recursively parse the expression inside
-                self.eat(Token::RightParen);  // Consume ')'
-                result
-            }
-            _ => panic!("Unexpected token in factor: {:?}", token),
-        }
-    }
-
-    /// Parse power operations: exponentiation
-    /// power → factor ('^' factor)*
-    /// 
-    /// Note: Power is RIGHT associative, meaning 2^3^2 = 2^(3^2) = 512, not (2^3)^2 = 64
-    /// This is the mathematical convention for exponentiation.
-    /// 
-    /// Examples:
-    ///   - "2 ^ 3" → returns 8.0
-    ///   - "2 ^ 3 ^ 2" → returns 512.0 (2^(3^2))
-    fn power(&mut self) -> f64 {
-        let mut result = self.factor(); // Get the base
-
-        // Right associative: if we see ^, recursively parse the right side
-        if matches!(self.current_token, Token::Power) {
-            self.eat(Token::Power);
-            // Recursive call for right associativity: a^b^c = a^(b^c)
-            result = result.powf(self.power());
-        }
-
-        result
-    }
-
-    /// Parse term operations: multiplication, division, modulo
-    /// term → power (('*' | '/' | '%') power)*
-    /// 
-    /// These operators have the same precedence and are left associative.
-    /// Left associative means: 10 / 2 / 5 = (10 / 2) / 5 = 1, not 10 / (2 / 5) = 25
-    /// 
-    /// Examples:
-    ///   - "2 * 3" → returns 6.0
-    ///   - "10 / 2" → returns 5.0
-    ///   - "10 % 3" → returns 1.0 (remainder)
-    ///   - "2 * 3 * 4" → returns 24.0 (left to right: (2*3)*4)
-    fn term(&mut self) -> f64 {
-        let mut result = self.power(); // Get the first operand
-
-        // Keep processing * / % operators (left associative)
-        while matches!(self.current_token, Token::Multiply | Token::Divide | Token::Modulo) {
-            let token = self.current_token.clone();
-            match token {
-                Token::Multiply => {
-                    self.eat(Token::Multiply);
-                    result *= self.power(); // Get next operand and multiply
-                }
-                Token::Divide => {
-                    self.eat(Token::Divide);
-                    result /= self.power(); // Get next operand and divide
-                }
-                Token::Modulo => {
-                    self.eat(Token::Modulo);
-                    result %= self.power(); // Get next operand and take remainder
-                }
-                _ => break,
-            }
-        }
-
-        result
-    }
-
-    /// Parse expression operations: addition and subtraction
-    /// expression → term (('+' | '-') term)*
-    /// 
-    /// These have the lowest precedence, so they're evaluated last.
-    /// Left associative: 10 - 3 - 2 = (10 - 3) - 2 = 5, not 10 - (3 - 2) = 9
-    /// 
-    /// Examples:
-    ///   - "2 + 3" → returns 5.0
-    ///   - "10 - 3" → returns 7.0
-    ///   - "2 + 3 * 4" → returns 14.0 (not 20, because * has higher precedence)
-    fn expr(&mut self) -> f64 {
-        let mut result = self.term(); // Get the first operand
-
-        // Keep processing + - operators (left associative)
-        while matches!(self.current_token, Token::Plus | Token::Minus) {
-            let token = self.current_token.clone();
-            match token {
-                Token::Plus => {
-                    self.eat(Token::Plus);
-                    result += self.term(); // Get next operand and add
-                }
-                Token::Minus => {
-                    self.eat(Token::Minus);
-                    result -= self.term(); // Get next operand and subtract
-                }
-                _ => break,
-            }
-        }
-
-        result
-    }
-
-    /// Parse variable assignment: IDENTIFIER '=' expression
-    /// assignment → IDENTIFIER '=' expression
-    /// 
-    /// Stores the result of the expression in the variable and returns the value.
-    /// 
-    /// Examples:
-    ///   - "x = 5" → stores 5.0 in variable x, returns 5.0
-    ///   - "y = x + 2" → evaluates x + 2, stores result in y, returns the result
-    fn assignment(&mut self) -> f64 {
-        if let Token::Identifier(name) = &self.current_token {
-            let var_name = name.clone();           // Save the variable name
-            self.eat(Token::Identifier(String::new())); // Consume identifier
-            self.eat(Token::Assign);               // Consume '='
-            let value = self.expr();               // Evaluate the right-hand side
-            
-            // Store the variable in our symbol table
-            self.variables.insert(var_name, value);
-            value // Return the assigned value
-        } else {
-            // This shouldn't happen if called correctly
-            self.expr()
-        }
-    }
-
-    /// Parse a statement: either an assignment or an expression
-    /// statement → assignment | expression
-    /// 
-    /// We need to look ahead to distinguish between:
-    ///   - "x = 5" (assignment)
-    ///   - "x + 2" (expression using variable x)
-    /// 
-    /// Both start with an identifier, so we peek at the next token to decide.
-    fn statement(&mut self) -> f64 {
-        // Look ahead to see if this is an assignment (identifier followed by '=')
-        if let Token::Identifier(_) = &self.current_token {
-            // Save current parser state so we can restore it
-            let saved_lexer = self.lexer.clone();
-            let saved_token = self.current_token.clone();
-            
-            // Look ahead: consume identifier and check if next token is '='
-            self.current_token = self.lexer.next_token();
-            let is_assignment = matches!(self.current_token, Token::Assign);
-            
-            // Restore parser state (backtrack)
-            self.lexer = saved_lexer;
-            self.current_token = saved_token;
-            
-            if is_assignment {
-                return self.assignment(); // Parse as assignment
-            }
-        }
-        
-        // Not an assignment, parse as regular expression
-        self.expr()
-    }
-
-    /// Parse the entire program: a sequence of statements
-    /// program → statement (';' statement)*
-    /// 
-    /// Handles multiple statements separated by semicolons.
-    /// Returns the value of the last statement.
-    /// 
-    /// Examples:
-    ///   - "5" → returns 5.0
-    ///   - "x = 5; x + 2" → returns 7.0 (x gets 5, then evaluate x + 2)
-    ///   - "a = 2; b = 3; a * b" → returns 6.0
-    pub fn parse(&mut self) -> f64 {
-        let mut result;
-        
-        // Parse statements separated by semicolons
-        loop {
-            result = self.statement(); // Parse one statement
-            
-            // Check if there's a semicolon (indicating more statements)
-            if matches!(self.current_token, Token::Semicolon) {
-                self.eat(Token::Semicolon); // Consume the ';'
-                
-                // If there's more input after the semicolon, continue parsing
-                if !matches!(self.current_token, Token::EOF) {
-                    continue;
-                }
-            }
-            
-            // No more statements to parse
-            break;
-        }
-        
-        // Return the value of the last statement
-        result
-    }
-
-    /// Get a copy of the current variables (for CLI persistence)
-    pub fn get_variables(&self) -> HashMap<String, f64> {
-        self.variables.clone()
-    }
-
-    /// Set variables from external source (for CLI persistence)
-    pub fn set_variables(&mut self, variables: HashMap<String, f64>) {
-        self.variables = variables;
-    }
-}
-
 // ============================================================================
 // CLI MODULE
 // ============================================================================
 mod cli;
 
 use clap::{Arg, Command};
-use cli::CalculatorCLI;
+use cli::{CalculatorCLI, Outcome};
+use rust_calculator::{evaluate, Calculator};
+use std::io::IsTerminal;
 
 // ============================================================================
 // MAIN FUNCTION - CLI AND DEMONSTRATION
 // ============================================================================
-// Supports both interactive CLI mode and demonstration mode
+// Supports interactive CLI mode, script files, batch mode for piped input,
+// and demonstration mode
 
 fn main() {
     let matches = Command::new("Rust Calculator")
-        .version("1.0")
+        .version(env!("CARGO_PKG_VERSION"))
         .author("Your Name")
         .about("A mathematical calculator with lexer and parser")
         .arg(
@@ -657,20 +39,58 @@ fn main() {
                 .short('e')
                 .long("eval")
                 .value_name("EXPR")
-                .help("Evaluate a single expression")
-                .action(clap::ArgAction::Set),
+                .help("Evaluate an expression; repeat to evaluate several, sharing variables")
+                .action(clap::ArgAction::Append),
         )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print each result or error as a line of JSON")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .value_name("N")
+                .help("Seed random() and randint(), so every run gives the same numbers")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("script")
+                .value_name("FILE")
+                .help("Run a script file (same as `run FILE`)")
+                .conflicts_with_all(["interactive", "expression"]),
+        )
+        .subcommand(
+            Command::new("run")
+                .about("Run a script file, printing the value of each calculation")
+                .arg(Arg::new("file").value_name("FILE").required(true)),
+        )
+        .args_conflicts_with_subcommands(true)
         .get_matches();
 
-    // Check for single expression evaluation
-    if let Some(expr) = matches.get_one::<String>("expression") {
-        evaluate_single_expression(expr);
+    let json = matches.get_flag("json");
+    let seed = matches.get_one::<u64>("seed").copied();
+
+    // Check for a script to run, given as `run FILE` or just `FILE`
+    let script = match matches.subcommand() {
+        Some(("run", run)) => run.get_one::<String>("file"),
+        _ => matches.get_one::<String>("script"),
+    };
+    if let Some(path) = script {
+        run_script_file(path, json, seed);
+        return;
+    }
+
+    // Check for expressions to evaluate
+    if let Some(exprs) = matches.get_many::<String>("expression") {
+        evaluate_expressions(exprs.map(String::as_str), json, seed);
         return;
     }
 
     // Check for interactive mode
     if matches.get_flag("interactive") {
-        match CalculatorCLI::new() {
+        match CalculatorCLI::new(json, seed) {
             Ok(mut cli) => {
                 if let Err(e) = cli.run() {
                     eprintln!("CLI Error: {}", e);
@@ -683,23 +103,68 @@ fn main() {
         return;
     }
 
+    // Piped input, as in `echo "2 + 2" | rust-calculator`: evaluate it line by line
+    if !std::io::stdin().is_terminal() {
+        if !cli::run_batch(std::io::stdin().lock(), json, seed) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Default: run demonstration
     run_demonstration();
 }
 
-/// Evaluate a single expression from command line
-fn evaluate_single_expression(expr: &str) {
-    let lexer = Lexer::new(expr);
-    let mut parser = Parser::new(lexer);
-    
-    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        parser.parse()
-    })) {
-        Ok(result) => println!("{}", result),
-        Err(_) => {
-            eprintln!("Error: Invalid expression");
+/// Run a script file, exiting with status 1 if it can't be read or fails
+fn run_script_file(path: &str, json: bool, seed: Option<u64>) {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!("Can't read {}: {}", path, error);
             std::process::exit(1);
         }
+    };
+    if !cli::run_script(path, &source, json, seed) {
+        std::process::exit(1);
+    }
+}
+
+/// Evaluate expressions from the command line in order, sharing variables and
+/// functions between them, and print the value of the last one
+/// Example: -e "x = 2" -e "x * 3" prints 6
+/// 
+/// With `json` set, the last expression (or the one that failed) is printed as
+/// a line of JSON on stdout instead. With `seed` set, random numbers start from it.
+fn evaluate_expressions<'a>(
+    exprs: impl IntoIterator<Item = &'a str>,
+    json: bool,
+    seed: Option<u64>,
+) {
+    let mut calculator = Calculator::new();
+    if let Some(seed) = seed {
+        calculator.evaluator_mut().set_seed(seed);
+    }
+    let mut last = ("", 0.0);
+
+    for expr in exprs {
+        match calculator.eval(expr) {
+            Ok(value) => last = (expr, value),
+            Err(error) if json => {
+                cli::print_json(expr, &Err(error), calculator.variables());
+                std::process::exit(1);
+            }
+            Err(error) => {
+                eprintln!("{}", error.report(expr));
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let (expr, result) = last;
+    if json {
+        cli::print_json(expr, &Ok(Outcome::Value(result)), calculator.variables());
+    } else {
+        println!("{}", cli::format_number(result, cli::DEFAULT_PRECISION));
     }
 }
 
@@ -717,16 +182,54 @@ fn run_demonstration() {
         "2 + 3 ^ 2",                  // Power before addition: 2 + (3^2) = 2 + 9 = 11
         "2 * 3 ^ 2",                  // Power before multiplication: 2 * (3^2) = 2 * 9 = 18
         "(2 + 3) ^ 2",                // Parentheses override precedence: (2+3)^2 = 5^2 = 25
+        "5!",                         // Factorial: 5! = 1*2*3*4*5 = 120
+        "3! ^ 2",                     // Factorial before power: (3!)^2 = 6^2 = 36
         
         // Mixed operations showing precedence hierarchy
         "10 % 3 + 2",                 // Modulo before addition: (10%3) + 2 = 1 + 2 = 3
         "2 ^ 3 * 4",                  // Power before multiplication: (2^3) * 4 = 8 * 4 = 32
         "100 / 2 ^ 3",                // Power before division: 100 / (2^3) = 100 / 8 = 12.5
+        "-2 ^ 2",                     // Power before negation: -(2^2) = -4
+        "-+-5",                       // Stacked unary operators: -(+(-5)) = 5
+        
+        // Number literals
+        "1.5e3",                      // Scientific notation: 1.5 * 10^3 = 1500
+        "0xFF + 1",                   // Hexadecimal: 255 + 1 = 256
+        "0b1010 + 0o17",              // Binary and octal: 10 + 15 = 25
+        "1_000_000 / 0b1111_1010",    // Digit separators: 1000000 / 250 = 4000
+        
+        // Implicit multiplication - a number or ')' followed by '(', a variable or a function
+        "2(3 + 4)",                   // 2 * (3 + 4) = 14
+        "(1 + 2)(3 + 4)",             // 3 * 7 = 21
+        "x = 4; 1/2x",                // Same precedence as *: (1/2) * x = 2
         
         // Variables with operators
         "x = 2; y = 3; x ^ y",        // Assign variables, then use: 2^3 = 8
         "a = 10; b = 3; a % b",       // Variables with modulo: 10 % 3 = 1
-        "base = 2; exp = 8; base ^ exp", // More descriptive variable names: 2^8 = 256
+        "base = 2; n = 8; base ^ n",  // More descriptive variable names: 2^8 = 256
+        "r = 3 # radius",             // A comment runs to the end of the line: 3
+        "x = 2\ny = x +\n3\nx * y",   // One statement per line; "+" carries on: 2 * 5 = 10
+        
+        // Bitwise operators - whole numbers only
+        "0xFF & 0x0F",                // Keep the low four bits: 15
+        "1 + 1 << 2",                 // Arithmetic before shifts: 2 << 2 = 8
+        "1 | 6 & 2",                  // '&' before '|': 1 | 2 = 3
+        
+        // Comparisons - true is 1, false is 0
+        "3 > 2",                      // 3 > 2 is true: 1
+        "1 + 2 > 2",                  // Arithmetic before comparison: 3 > 2 = 1
+        "x = 5; x == 5",              // Equality test on a variable: 1
+        "3 > 2 > 1",                  // Left to right: (3 > 2) > 1 = 1 > 1 = 0
+        
+        // Logic - 0 is false, anything else is true
+        "1 and 0",                    // Both must be true: 0
+        "not (2 > 3)",                // 2 > 3 is false, so: 1
+        "1 + 1 and 0",                // Arithmetic before logic: (1+1) and 0 = 0
+        "x = 5; x > 0 and not (x > 10)", // x is between 0 and 10: 1
+        
+        // Conditionals - cond ? then : else
+        "x = -3; x > 0 ? x : -x",     // Absolute value of x: 3
+        "a = 0; a ? 1 : a + 1 ? 2 : 3", // Nested, right associative: 2
         
         // Basic trigonometric functions
         "sin(0)",                     // sin(0) = 0
@@ -754,6 +257,14 @@ fn run_demonstration() {
         "ceil(-2.7)",                 // ceil(-2.7) = -2
         "round(3.4)",                 // round(3.4) = 3
         "round(3.6)",                 // round(3.6) = 4
+        "trunc(-2.7)",                // trunc(-2.7) = -2 (toward zero)
+        "fract(2.25)",                // fract(2.25) = 0.25
+        "sign(-0.0)",                 // sign(-0.0) = 0 (zero has no sign)
+        "clamp(15, 0, 10)",           // clamp(15, 0, 10) = 10
+        "cbrt(-27)",                  // cbrt(-27) = -3
+        "root(-8, 3)",                // root(-8, 3) = -2, where (-8)^(1/3) is NaN
+        "root(10000, 4)",             // root(10000, 4) = 10
+        "hypot(3, 4)",                // hypot(3, 4) = 5
         
         // Mathematical constants
         "pi()",                       // π ≈ 3.14159
@@ -779,41 +290,67 @@ fn run_demonstration() {
         "pow(4, 0.5)",                // pow(4, 0.5) = 2 (square root)
         "atan2(1, 1)",                // atan2(1, 1) = π/4 ≈ 0.7854
         
+        // Aggregate functions - any number of arguments, at least one
+        "sum(1, 2, 3, 4)",            // 1 + 2 + 3 + 4 = 10
+        "avg(5)",                     // The average of one value is itself: 5
+        "x = 3; avg(x, x^2, 10)",     // (3 + 9 + 10) / 3 ≈ 7.333
+        "product(1, 2, 3, 4)",        // 1 * 2 * 3 * 4 = 24
+        
+        // Whole number functions
+        "gcd(12, 18)",                // Greatest common divisor: 6
+        "lcm(4, 6)",                  // Least common multiple: 12
+        "gcd(0, 0)",                  // No divisor to speak of: 0
+        
+        // Random numbers - seeded, so the demonstration gives the same ones each run
+        "seed(42); randint(1, 6)",    // A roll of a die: 5
+        "seed(42); random()",         // From 0 up to 1: 0.6818961923066713
+        
         // Functions with expressions
         "min(2 + 3, 4 * 2)",          // min(5, 8) = 5
         "max(sqrt(16), abs(-3))",     // max(4, 3) = 4
         "pow(sin(pi()/2), 2)",        // pow(1, 2) = 1
         "x = 10; y = 3; min(x, y)",   // Using variables with multi-arg functions
+        
+        // User-defined functions
+        "f(x) = x^2 + 1; f(3)",       // Define f, then call it: 3^2 + 1 = 10
+        "area(w, h) = w * h; area(3, 4)", // Several parameters: 12
+        "fact(n) = n <= 1 ? 1 : n * fact(n - 1); fact(5)", // Recursion: 120
     ];
 
     println!("=== RUST CALCULATOR DEMONSTRATION ===");
     println!("This calculator supports:");
     println!("- Variables: x = 5");
-    println!("- Arithmetic: + - * / % ^");
+    println!("- Arithmetic: + - * / % ^ and factorial !");
     println!("- Trigonometric functions: sin(x), cos(x), tan(x), asin(x), acos(x), atan(x)");
     println!("- Mathematical functions: sqrt(x), abs(x), floor(x), ceil(x), round(x)");
+    println!("- More rounding: trunc(x), fract(x), sign(x), clamp(x, lo, hi)");
+    println!("- Roots: cbrt(x), root(x, n), hypot(x, y)");
     println!("- Logarithmic/exponential: ln(x), log10(x), log2(x), exp(x)");
     println!("- Mathematical constants: pi(), e()");
     println!("- Multi-argument functions: min(x,y), max(x,y), pow(x,y), atan2(y,x)");
+    println!("- Aggregate functions: sum(...), avg(...), product(...)");
+    println!("- Whole number functions: gcd(a,b), lcm(a,b)");
+    println!("- Random numbers: random(), randint(lo, hi), seed(N)");
+    println!("- Bitwise operators: & | ~ << >> on whole numbers");
+    println!("- Comparisons: == != < <= > >= (1 for true, 0 for false)");
+    println!("- Logic: and, or, not");
+    println!("- Conditionals: x > 0 ? x : -x");
+    println!("- User-defined functions: f(x) = x^2 + 1");
     println!("- Proper precedence: 2 + 3 * 4 = 14 (not 20)");
     println!("- Parentheses: (2 + 3) * 4 = 20");
-    println!("- Multiple statements: x = 5; y = x + 2; x * y");
+    println!("- Implicit multiplication: 2(3 + 4), 2pi(), 3x");
+    println!("- Multiple statements: x = 5; y = x + 2; x * y, or one per line");
+    println!("- Comments: x = 5 # initial value, or // initial value");
     println!();
 
     // Test each case
     for input in test_cases {
         println!("Evaluating: {}", input);
         
-        // Create lexer and parser for this input
-        let lexer = Lexer::new(input);
-        let mut parser = Parser::new(lexer);
-        
-        // Parse and evaluate, catching any panics
-        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            parser.parse()
-        })) {
+        // Parse and evaluate, reporting any error
+        match evaluate(input) {
             Ok(result) => println!("Result: {}\n", result),
-            Err(_) => println!("Error parsing expression\n"),
+            Err(error) => println!("{}\n", error.report(input)),
         }
     }
 }
@@ -0,0 +1,78 @@
+// ============================================================================
+// WASM MODULE - JavaScript Bindings
+// ============================================================================
+// With the "wasm" feature, this module exposes the calculator to JavaScript
+// through wasm-bindgen, so it can run in a web page:
+//
+//   const calc = new CalculatorJs();
+//   calc.eval("x = 2");     // 2
+//   calc.eval("x * 3");     // 6
+//   calc.variables();       // { x: 2 }
+//   calc.eval("1 / 0");     // throws Error("Division by zero: 1 / 0")
+
+use crate::Calculator;
+use wasm_bindgen::prelude::*;
+
+/// A calculator for JavaScript that keeps variables and functions between calls
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct CalculatorJs {
+    calculator: Calculator,
+}
+
+#[wasm_bindgen]
+impl CalculatorJs {
+    /// Create a calculator with no variables or functions defined
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> CalculatorJs {
+        Self::default()
+    }
+
+    /// Evaluate one or more statements and return the value of the last one
+    /// A failure is thrown as a JavaScript Error with the calculator's message
+    pub fn eval(&mut self, expr: &str) -> Result<f64, JsValue> {
+        self.calculator
+            .eval(expr)
+            .map_err(|error| js_sys::Error::new(&error.to_string()).into())
+    }
+
+    /// The variables currently defined, as an object mapping names to values
+    pub fn variables(&self) -> JsValue {
+        let variables = js_sys::Object::new();
+        for (name, value) in self.calculator.variables() {
+            js_sys::Reflect::set(&variables, &JsValue::from_str(name), &JsValue::from_f64(*value))
+                .expect("setting a property on a plain object can't fail");
+        }
+        variables.into()
+    }
+}
+
+// Run with: wasm-pack test --node -- --no-default-features --features wasm
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn eval_returns_the_value() {
+        let mut calc = CalculatorJs::new();
+        assert_eq!(calc.eval("2 + 3 * 4"), Ok(14.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn eval_keeps_variables_between_calls() {
+        let mut calc = CalculatorJs::new();
+        assert_eq!(calc.eval("x = 2"), Ok(2.0));
+        assert_eq!(calc.eval("x * 3"), Ok(6.0));
+
+        let x = js_sys::Reflect::get(&calc.variables(), &JsValue::from_str("x")).unwrap();
+        assert_eq!(x.as_f64(), Some(2.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn eval_throws_an_error_with_the_message() {
+        let mut calc = CalculatorJs::new();
+        let error = js_sys::Error::from(calc.eval("1 / 0").unwrap_err());
+        assert_eq!(String::from(error.message()), "Division by zero: 1 / 0");
+    }
+}
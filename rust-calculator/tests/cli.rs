@@ -0,0 +1,288 @@
+// ============================================================================
+// COMMAND LINE TESTS
+// ============================================================================
+// These run the rust-calculator binary the way a shell would, checking what it
+// prints and the status it exits with.
+
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// Run the calculator with `args`, feeding it `stdin`, and wait for it to finish
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rust-calculator"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the calculator binary starts");
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(stdin.as_bytes())
+        .expect("the calculator reads its input");
+    child.wait_with_output().expect("the calculator finishes")
+}
+
+/// Everything the calculator printed to stdout
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// Everything the calculator printed to stderr
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// The path of one of the example scripts
+fn example(name: &str) -> String {
+    format!("{}/scripts/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// Write `source` to a script file in the temporary directory and return its path
+fn script(name: &str, source: &str) -> PathBuf {
+    let file_name = format!("rust-calculator-{}-{}", std::process::id(), name);
+    let path = std::env::temp_dir().join(file_name);
+    std::fs::write(&path, source).expect("the script can be written");
+    path
+}
+
+/// Each line of stdout, parsed as JSON
+fn json_lines(output: &Output) -> Vec<Value> {
+    stdout(output)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every line is JSON"))
+        .collect()
+}
+
+// ---- Piped input ----
+
+#[test]
+fn piped_lines_print_one_result_each() {
+    let output = run(&[], "2 + 2\nx = 3\nx * 2\nans + 1\n");
+    assert_eq!(stdout(&output), "4\n3\n6\n7\n");
+    assert_eq!(stderr(&output), "");
+    assert!(output.status.success());
+}
+
+#[test]
+fn piped_blank_and_comment_lines_are_skipped() {
+    let output = run(&[], "\n# a comment\n1 + 1\n\n   \n# another\n");
+    assert_eq!(stdout(&output), "2\n");
+    assert!(output.status.success());
+}
+
+#[test]
+fn a_failing_piped_line_sets_the_exit_status() {
+    let output = run(&[], "1 / 0\n2\n");
+    // Later lines still run, but the failure is reported and remembered
+    assert_eq!(stdout(&output), "2\n");
+    assert!(stderr(&output).contains("In line 1:"));
+    assert!(stderr(&output).contains("Division by zero"));
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn piped_results_are_rounded_like_the_repl() {
+    let output = run(&[], "sin(pi())\n2 / 3\nprecision 3\n2 / 3\nprecision 99\n");
+    assert_eq!(stdout(&output), "0\n0.6666666667\n0.667\n");
+    assert!(stderr(&output).contains("In line 5:"));
+    assert!(stderr(&output).contains("Precision must be from 1 to 17 digits."));
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn empty_piped_input_succeeds() {
+    let output = run(&[], "");
+    assert_eq!(stdout(&output), "");
+    assert!(output.status.success());
+}
+
+// ---- Expressions from the command line ----
+
+#[test]
+fn eval_prints_the_value() {
+    let output = run(&["-e", "sqrt(16) + 1"], "");
+    assert_eq!(stdout(&output), "5\n");
+    assert_eq!(stderr(&output), "");
+    assert!(output.status.success());
+}
+
+#[test]
+fn eval_rounds_the_value_like_the_repl() {
+    let output = run(&["-e", "sqrt(2)^2"], "");
+    assert_eq!(stdout(&output), "2\n");
+
+    let output = run(&["-e", "2 / 3"], "");
+    assert_eq!(stdout(&output), "0.6666666667\n");
+}
+
+#[test]
+fn repeated_evals_share_variables_and_print_the_last_value() {
+    let output = run(&["-e", "x = 2", "-e", "x * 3"], "");
+    assert_eq!(stdout(&output), "6\n");
+    assert!(output.status.success());
+
+    let output = run(&["--eval", "f(n) = n * n", "--eval", "f(4)"], "");
+    assert_eq!(stdout(&output), "16\n");
+}
+
+#[test]
+fn a_failing_eval_prints_to_stderr_and_exits_with_1() {
+    let output = run(&["-e", "1 / 0"], "");
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("Division by zero: 1 / 0"));
+    assert_eq!(output.status.code(), Some(1));
+
+    // Nothing after the failing expression runs
+    let output = run(&["-e", "1 / 0", "-e", "2"], "");
+    assert_eq!(stdout(&output), "");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn help_and_version_are_available() {
+    let output = run(&["--version"], "");
+    assert_eq!(stdout(&output), format!("Rust Calculator {}\n", env!("CARGO_PKG_VERSION")));
+    assert!(output.status.success());
+
+    let output = run(&["--help"], "");
+    assert!(stdout(&output).contains("--eval <EXPR>"));
+    assert!(output.status.success());
+}
+
+// ---- Script files ----
+
+#[test]
+fn run_prints_each_calculation_in_a_script() {
+    let output = run(&["run", &example("circle.calc")], "");
+    assert_eq!(stdout(&output), "78.53981634\n31.41592654\n2.5\n");
+    assert!(output.status.success());
+
+    // A bare file name does the same
+    let output = run(&[&example("functions.calc")], "");
+    assert_eq!(stdout(&output), "5\n3628800\n30\n");
+    assert!(output.status.success());
+}
+
+#[test]
+fn script_values_are_rounded_like_the_repl() {
+    let path = script("precision.calc", "sin(pi())\nprecision 3\n2 / 3\nprecision 0\n1\n");
+    let output = run(&["run", path.to_str().unwrap()], "");
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(stdout(&output), "0\n0.667\n");
+    assert!(stderr(&output).contains(&format!("In {}, line 4:", path.display())));
+    assert!(stderr(&output).contains("Precision must be from 1 to 17 digits."));
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn a_script_error_names_the_file_and_line_and_stops() {
+    let path = script("error.calc", "a = 1\n# comment\n\na + 1\nb\na + 5\n");
+    let output = run(&["run", path.to_str().unwrap()], "");
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(stdout(&output), "2\n");
+    assert!(stderr(&output).contains(&format!("In {}, line 5:", path.display())));
+    assert!(stderr(&output).contains("Undefined variable: b"));
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn a_missing_script_exits_with_1() {
+    let output = run(&["run", "no-such-script.calc"], "");
+    assert!(stderr(&output).starts_with("Can't read no-such-script.calc"));
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn json_output_has_one_record_per_line() {
+    let path = script("json.calc", "a = 1\na + 1\nb\n");
+    let output = run(&["--json", path.to_str().unwrap()], "");
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        json_lines(&output),
+        [
+            json!({"input": "a = 1", "result": 1.0, "variables": {"a": 1.0}}),
+            json!({"input": "a + 1", "result": 2.0, "variables": {"a": 1.0}}),
+            json!({
+                "input": "b",
+                "error": {
+                    "kind": "UndefinedVariable",
+                    "message": "Undefined variable: b",
+                    "position": null,
+                },
+            }),
+        ]
+    );
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn json_eval_prints_the_last_result_with_variables() {
+    let output = run(&["--json", "-e", "x = 2", "-e", "x * 3"], "");
+    assert_eq!(
+        json_lines(&output),
+        [json!({"input": "x * 3", "result": 6.0, "variables": {"x": 2.0}})]
+    );
+    assert!(output.status.success());
+}
+
+#[test]
+fn json_errors_go_to_stdout_with_their_position() {
+    let output = run(&["--json", "-e", "2 +"], "");
+    let records = json_lines(&output);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["input"], "2 +");
+    assert_eq!(records[0]["error"]["kind"], "UnexpectedEof");
+    assert_eq!(records[0]["error"]["position"], json!({"line": 1, "column": 4}));
+    assert_eq!(stderr(&output), "");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn json_piped_lines_include_ans() {
+    let output = run(&["--json"], "a = 1\nf(x) = x + a\n");
+    assert_eq!(
+        json_lines(&output),
+        [
+            json!({"input": "a = 1", "result": 1.0, "variables": {"a": 1.0, "ans": 1.0}}),
+            json!({
+                "input": "f(x) = x + a",
+                "defined": "f(x)",
+                "variables": {"a": 1.0, "ans": 1.0},
+            }),
+        ]
+    );
+}
+
+// ---- Seeded random numbers ----
+
+#[test]
+fn seed_makes_runs_repeatable() {
+    let first = run(&["--seed", "42"], "random()\nrandint(1, 100)\nrandom()\n");
+    let second = run(&["--seed", "42"], "random()\nrandint(1, 100)\nrandom()\n");
+    assert!(first.status.success());
+    assert_eq!(stdout(&first).lines().count(), 3);
+    assert_eq!(stdout(&first), stdout(&second));
+}
+
+#[test]
+fn seed_flag_matches_the_seed_function() {
+    let flag = run(&["--seed", "42", "-e", "random()"], "");
+    let function = run(&["-e", "seed(42); random()"], "");
+    assert_eq!(stdout(&flag), "0.6818961923\n");
+    assert_eq!(stdout(&flag), stdout(&function));
+}
+
+#[test]
+fn seed_must_be_a_whole_number() {
+    let output = run(&["--seed", "x", "-e", "1"], "");
+    assert!(stderr(&output).contains("invalid value 'x' for '--seed <N>'"));
+    assert_eq!(output.status.code(), Some(2));
+}